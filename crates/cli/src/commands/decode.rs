@@ -1,18 +1,23 @@
 //! Commande de décodage
 
 use crate::create_spinner;
+use adn_core::reads::{self, ClusterConfig, ConsensusConfig, FastqRecord};
 use adn_core::{Decoder, DecoderConfig, DnaSequence};
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 pub fn run(input: PathBuf, output: PathBuf, ignore_checksum: bool) -> Result<()> {
     println!("🧬 Décodage de: {}", input.display());
 
-    // 1. Lire le fichier FASTA
+    // 1. Lire le fichier FASTA ou FASTQ
     let spinner = create_spinner("Lecture des séquences...");
-    let sequences = read_fasta(&input)?;
+    let sequences = if is_fastq(&input) {
+        read_fastq_consensus(&input)?
+    } else {
+        read_fasta(&input)?
+    };
     spinner.finish_with_message(format!("{} séquences lues", sequences.len()));
 
     // 2. Configurer le décodeur
@@ -26,9 +31,19 @@ pub fn run(input: PathBuf, output: PathBuf, ignore_checksum: bool) -> Result<()>
     // 3. Décoder
     let spinner = create_spinner("Décodage...");
     let decoder = Decoder::new(config);
-    let data = decoder.decode(&sequences)?;
+    let (data, report) = decoder.decode_with_report(&sequences)?;
     spinner.finish_with_message(format!("Données récupérées ({} octets)", data.len()));
 
+    // Rapport d'intégrité (SHA-256 du fichier, CRC32 par chunk)
+    match report.sha256_verified {
+        Some(true) => println!("🔒 Intégrité vérifiée (SHA-256 + CRC32 par chunk)"),
+        Some(false) => println!("⚠️  Intégrité NON vérifiée: SHA-256 différent"),
+        None => {}
+    }
+    for warning in &report.warnings {
+        println!("⚠️  {}", warning);
+    }
+
     // 4. Écrire le fichier de sortie
     let spinner = create_spinner("Écriture du fichier...");
     std::fs::write(&output, &data)?;
@@ -39,15 +54,39 @@ pub fn run(input: PathBuf, output: PathBuf, ignore_checksum: bool) -> Result<()>
     Ok(())
 }
 
+/// Indique si le fichier est un FASTQ (éventuellement gzip) d'après son extension
+fn is_fastq(path: &Path) -> bool {
+    let name = path.to_string_lossy().to_lowercase();
+    [".fastq", ".fq", ".fastq.gz", ".fq.gz"]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+}
+
+/// Lit un fichier FASTQ de séquençage et le réduit à un brin consensus par oligo
+///
+/// Les brins n'ont pas de métadonnées d'encodage: le décodeur refuse les
+/// schémas qui n'inscrivent pas l'adresse des oligos dans leurs bases.
+fn read_fastq_consensus(path: &PathBuf) -> Result<Vec<DnaSequence>> {
+    let reads = reads::read_fastq(path)?
+        .iter()
+        .map(FastqRecord::to_sequence)
+        .collect::<adn_core::Result<Vec<_>>>()?;
+
+    let strands = reads::consensus_pool(&reads, ClusterConfig::default(), ConsensusConfig::default());
+    println!("🧪 {} lectures regroupées en {} brins consensus", reads.len(), strands.len());
+
+    Ok(reads::to_sequences(&strands))
+}
+
 /// Lit un fichier FASTA
+///
+/// Les en-têtes sont conservés pour que les métadonnées (schéma, oligos de
+/// manifeste, paramètres fontaine) soient restaurées par `DnaSequence::from_fasta`.
 fn read_fasta(path: &PathBuf) -> Result<Vec<DnaSequence>> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
     let mut sequences = Vec::new();
-
-    let mut current_id: Option<String> = None;
-    let mut current_seq = String::new();
-    let mut chunk_index = 0;
+    let mut current = String::new();
 
     for line in reader.lines() {
         let line = line?;
@@ -57,41 +96,20 @@ fn read_fasta(path: &PathBuf) -> Result<Vec<DnaSequence>> {
             continue;
         }
 
-        if let Some(stripped) = line.strip_prefix('>') {
+        if line.starts_with('>') {
             // Sauvegarder la séquence précédente
-            if !current_seq.is_empty() {
-                if let Ok(seq) = DnaSequence::from_str(
-                    &current_seq,
-                    current_id.clone().unwrap_or("unknown".to_string()),
-                    chunk_index,
-                    current_seq.len() / 4, // Estimation
-                    0,
-                ) {
-                    sequences.push(seq);
-                    chunk_index += 1;
-                }
+            if !current.is_empty() {
+                sequences.push(DnaSequence::from_fasta(&current)?);
             }
-
-            // Extraire l'ID de la ligne header
-            let parts: Vec<&str> = stripped.split('|').collect();
-            current_id = Some(parts[0].to_string());
-            current_seq = String::new();
+            current = format!("{}\n", line);
         } else {
-            current_seq.push_str(line);
+            current.push_str(line);
         }
     }
 
     // Dernière séquence
-    if !current_seq.is_empty() {
-        if let Ok(seq) = DnaSequence::from_str(
-            &current_seq,
-            current_id.unwrap_or("unknown".to_string()),
-            chunk_index,
-            current_seq.len() / 4,
-            0,
-        ) {
-            sequences.push(seq);
-        }
+    if !current.is_empty() {
+        sequences.push(DnaSequence::from_fasta(&current)?);
     }
 
     Ok(sequences)
@@ -1,9 +1,10 @@
 //! Commande d'encodage
 
-use crate::{EncodingAlgorithm, CompressionAlgorithm, create_progress_bar, create_spinner};
+use crate::{CompressionAlgorithm, create_progress_bar, create_spinner};
 use adn_core::{Encoder, EncoderConfig, DnaConstraints};
-use adn_core::codec::encoder::{EncoderType, CompressionType};
-use anyhow::Result;
+use adn_core::codec::encoder::CompressionType;
+use adn_core::codec::lookup_codec;
+use anyhow::{anyhow, Result};
 use std::path::PathBuf;
 use std::fs::File;
 use std::io::Write;
@@ -11,7 +12,7 @@ use std::io::Write;
 pub fn run(
     input: PathBuf,
     output: PathBuf,
-    algorithm: EncodingAlgorithm,
+    algorithm: String,
     redundancy: f64,
     compress: bool,
     compression: Option<CompressionAlgorithm>,
@@ -24,14 +25,7 @@ pub fn run(
     spinner.finish_with_message(format!("Fichier lu ({} octets)", data.len()));
 
     // 2. Configurer l'encodeur
-    let encoder_type = match algorithm {
-        EncodingAlgorithm::Fountain => EncoderType::Fountain,
-        EncodingAlgorithm::Goldman => EncoderType::Goldman,
-        EncodingAlgorithm::Goldman2013 => EncoderType::Goldman2013,
-        EncodingAlgorithm::Grass2015 => EncoderType::Grass2015,
-        EncodingAlgorithm::Adaptive => EncoderType::Adaptive,
-        EncodingAlgorithm::Base3 => EncoderType::Base3,
-    };
+    let codec = lookup_codec(&algorithm).ok_or_else(|| anyhow!("Schéma d'encodage inconnu: {}", algorithm))?;
 
     let compression_type = match compression.unwrap_or(CompressionAlgorithm::Lz4) {
         CompressionAlgorithm::Lz4 => CompressionType::Lz4,
@@ -40,19 +34,21 @@ pub fn run(
     };
 
     // Use lenient constraints for algorithms that don't enforce GC/homopolymer limits
-    let constraints = match algorithm {
-        EncodingAlgorithm::Grass2015 => DnaConstraints {
+    let constraints = if codec.capabilities().enforces_constraints {
+        DnaConstraints::default()
+    } else {
+        DnaConstraints {
             gc_min: 0.0,
             gc_max: 1.0,
             max_homopolymer: 150,
             max_sequence_length: 200,
             allowed_bases: vec![adn_core::IupacBase::A, adn_core::IupacBase::C, adn_core::IupacBase::G, adn_core::IupacBase::T],
-        },
-        _ => DnaConstraints::default(),
+            ..Default::default()
+        }
     };
 
     let config = EncoderConfig {
-        encoder_type,
+        encoder_type: codec.encoder_type().unwrap_or_default(),
         chunk_size: 32,
         redundancy,
         compression_enabled: compress,
@@ -62,7 +58,7 @@ pub fn run(
 
     // 3. Encoder
     let pb = create_progress_bar(data.len() as u64, "Encodage ADN...");
-    let encoder = Encoder::new(config)?;
+    let encoder = Encoder::new(config)?.with_codec(codec);
     let sequences = encoder.encode(&data)?;
     pb.finish_with_message(format!("{} séquences générées", sequences.len()));
 
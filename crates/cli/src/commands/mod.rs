@@ -3,4 +3,5 @@
 pub mod encode;
 pub mod decode;
 pub mod simulate;
+pub mod profile;
 pub mod visualize;
@@ -0,0 +1,27 @@
+//! Commande d'apprentissage d'un profil d'erreur
+
+use crate::create_spinner;
+use adn_simulation::ErrorProfile;
+use anyhow::Result;
+use std::path::PathBuf;
+
+pub fn run(references: PathBuf, reads: PathBuf, output: PathBuf, max_error_ratio: f64) -> Result<()> {
+    println!("🧬 Apprentissage du profil d'erreur: {}", reads.display());
+
+    let spinner = create_spinner("Alignement des lectures...");
+    let profile = ErrorProfile::fit_files(&references, &reads, max_error_ratio)?;
+    spinner.finish_with_message(format!(
+        "{} lectures alignées, {} écartées",
+        profile.aligned_reads, profile.unassigned_reads
+    ));
+
+    println!("\n📊 Modèle équivalent:");
+    println!("   Substitutions : {:.4}%", 100.0 * profile.substitution_rate);
+    println!("   Insertions    : {:.4}%", 100.0 * profile.insertion_rate);
+    println!("   Délétions     : {:.4}%", 100.0 * profile.deletion_rate);
+
+    profile.save(&output)?;
+    println!("\n✅ Profil écrit dans {}", output.display());
+
+    Ok(())
+}
@@ -1,48 +1,34 @@
 //! Commande de simulation
 
-use crate::create_progress_bar;
-use adn_simulation::{DnaChannel, ChannelConfig, ErrorModel, MetricsCollector};
-use adn_core::DnaSequence;
-use anyhow::Result;
+use crate::{create_progress_bar, create_spinner};
+use adn_simulation::{
+    ChannelConfig, DnaChannel, MetricsCollector, MonteCarlo, MonteCarloConfig, SweepParameter,
+};
+use adn_core::codec::lookup_codec;
+use adn_core::{DnaConstraints, DnaSequence, EncoderConfig};
+use anyhow::{anyhow, Result};
 use std::path::PathBuf;
 use std::io::{BufRead, BufReader};
 
-pub fn run(
-    input: PathBuf,
-    substitution_rate: f64,
-    insertion_rate: f64,
-    deletion_rate: f64,
-    iterations: usize,
-) -> Result<()> {
+pub fn run(input: PathBuf, config: ChannelConfig, iterations: usize, report: Option<PathBuf>) -> Result<()> {
     println!("🧬 Simulation d'erreurs sur: {}", input.display());
 
     // 1. Lire les séquences
     let sequences = read_fasta(&input)?;
     println!("{} séquences chargées", sequences.len());
 
-    // 2. Configurer le canal
-    let error_model = ErrorModel {
-        substitution_rate,
-        insertion_rate,
-        deletion_rate,
-        seed: 42,
-    };
-
-    let config = ChannelConfig {
-        error_model,
-        temperature: 25.0,
-        ph: 7.0,
-        storage_duration_days: 30,
-    };
+    // 2. Canal: taux uniformes ou profil empirique
+    if let Some(profile) = &config.profile {
+        println!("Profil empirique: {} lectures alignées", profile.aligned_reads);
+    }
 
-    // 3. Simuler
-    let pb = create_progress_bar((iterations * sequences.len()) as u64, "Simulation en cours...");
-    let mut channel = DnaChannel::new(config);
+    // 3. Simuler: un lot parallèle par itération, flux aléatoire par séquence
+    let pb = create_progress_bar(iterations as u64, "Simulation en cours...");
+    let channel = DnaChannel::new(config);
     let mut collector = MetricsCollector::new();
 
-    for seq in &sequences {
-        for _ in 0..iterations {
-            let (_corrupted, metrics) = channel.transmit(seq)?;
+    for trial in 0..iterations {
+        for (_corrupted, metrics) in channel.transmit_batch(&sequences, trial as u64)? {
             collector.add(metrics);
         }
         pb.inc(1);
@@ -61,11 +47,128 @@ pub fn run(
     println!("\n   Maximum:");
     println!("{}", collector.max().format_table());
 
+    save_report(&collector, report)?;
+
+    println!("\n✅ Simulation terminée!");
+
+    Ok(())
+}
+
+/// Paramètres du mode pipeline
+pub struct PipelineOptions {
+    /// Schéma d'encodage
+    pub algorithm: String,
+    /// Facteur de redondance
+    pub redundancy: f64,
+    /// Nombre d'essais
+    pub trials: usize,
+    /// Couverture de séquençage
+    pub coverage: f64,
+    /// Paramètre balayé et ses valeurs
+    pub sweep: Option<(SweepParameter, Vec<f64>)>,
+}
+
+/// Simulation de bout en bout: encodage, canal complet, décodage
+pub fn run_pipeline(
+    input: PathBuf,
+    channel: ChannelConfig,
+    options: PipelineOptions,
+    report: Option<PathBuf>,
+) -> Result<()> {
+    let PipelineOptions {
+        algorithm,
+        redundancy,
+        trials,
+        coverage,
+        sweep,
+    } = options;
+
+    println!("🧬 Simulation de bout en bout sur: {}", input.display());
+
+    let data = std::fs::read(&input)?;
+    let codec = lookup_codec(&algorithm).ok_or_else(|| anyhow!("Schéma d'encodage inconnu: {}", algorithm))?;
+
+    // Même tolérance que `encode` pour les schémas sans contraintes GC/homopolymères
+    let constraints = if codec.capabilities().enforces_constraints {
+        DnaConstraints::default()
+    } else {
+        DnaConstraints {
+            gc_min: 0.0,
+            gc_max: 1.0,
+            max_homopolymer: 150,
+            max_sequence_length: 200,
+            ..Default::default()
+        }
+    };
+
+    let config = MonteCarloConfig::default()
+        .with_encoder(EncoderConfig {
+            encoder_type: codec.encoder_type().unwrap_or_default(),
+            redundancy,
+            constraints,
+            ..Default::default()
+        })
+        .with_channel(channel)
+        .with_coverage(coverage)
+        .with_trials(trials);
+    let driver = MonteCarlo::new(config);
+
+    match sweep {
+        None => {
+            let spinner = create_spinner(&format!("{} essais en cours...", trials));
+            let summary = driver.run(&data)?;
+            spinner.finish_with_message(String::from("Simulation terminée"));
+
+            println!("\n📊 Résultats ({}, {} oligos):", codec.name(), summary.oligos);
+            println!("{}", summary.format_table());
+
+            let mut collector = MetricsCollector::new();
+            for outcome in &summary.outcomes {
+                collector.add(outcome.metrics.clone());
+            }
+            save_report(&collector, report)?;
+        }
+        Some((parameter, values)) => {
+            if values.is_empty() {
+                return Err(anyhow!("--sweep nécessite --values"));
+            }
+
+            let pb = create_progress_bar(values.len() as u64, "Balayage en cours...");
+            let mut points = Vec::with_capacity(values.len());
+            for value in values {
+                points.extend(driver.sweep(&data, parameter, &[value])?);
+                pb.inc(1);
+            }
+            pb.finish_with_message(String::from("Balayage terminé"));
+
+            println!("\n📊 Balayage de {} ({}):", parameter, codec.name());
+            println!("{:>12} │ {:>8} │ {:>10} │ {:>10}", parameter.name(), "succès", "BER", "bits/base");
+            for point in &points {
+                println!(
+                    "{:>12} │ {:>7.1}% │ {:>10.2e} │ {:>10.3}",
+                    point.value,
+                    100.0 * point.summary.success_probability(),
+                    point.summary.residual_bit_error_rate,
+                    point.summary.effective_bits_per_base()
+                );
+            }
+        }
+    }
+
     println!("\n✅ Simulation terminée!");
 
     Ok(())
 }
 
+/// Sauvegarde les métriques brutes, relues par `adn visualize`
+fn save_report(collector: &MetricsCollector, report: Option<PathBuf>) -> Result<()> {
+    if let Some(path) = report {
+        std::fs::write(&path, collector.to_json()?)?;
+        println!("\n📁 Métriques écrites dans: {}", path.display());
+    }
+    Ok(())
+}
+
 /// Lit un fichier FASTA (version simplifiée)
 fn read_fasta(path: &PathBuf) -> Result<Vec<DnaSequence>> {
     let file = std::fs::File::open(path)?;
@@ -2,13 +2,21 @@
 
 use crate::VisualizationFormat;
 use adn_core::{DnaSequence, ConstraintChecker};
+use adn_simulation::{MetricsCollector, StatisticsReport};
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::io::{BufRead, BufReader};
 
 pub fn run(input: PathBuf, format: VisualizationFormat, output: Option<PathBuf>) -> Result<()> {
     println!("📊 Visualisation de: {}", input.display());
 
+    // Métriques de `simulate --report`
+    if input.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+        let collector = MetricsCollector::from_json(&std::fs::read_to_string(&input)?)?;
+        println!("{} simulations chargées", collector.len());
+        return visualize_simulation(&collector, format, output);
+    }
+
     // 1. Lire les séquences
     let sequences = read_fasta(&input)?;
     println!("{} séquences chargées", sequences.len());
@@ -18,6 +26,7 @@ pub fn run(input: PathBuf, format: VisualizationFormat, output: Option<PathBuf>)
         VisualizationFormat::Table => visualize_table(&sequences)?,
         VisualizationFormat::Json => visualize_json(&sequences, output)?,
         VisualizationFormat::Html => visualize_html(&sequences, output)?,
+        VisualizationFormat::Csv => visualize_csv(&sequences, output)?,
     }
 
     Ok(())
@@ -162,6 +171,220 @@ fn visualize_html(sequences: &[DnaSequence], output: Option<PathBuf>) -> Result<
     Ok(())
 }
 
+/// Visualisation en CSV
+fn visualize_csv(sequences: &[DnaSequence], output: Option<PathBuf>) -> Result<()> {
+    let mut csv = String::from("id,length,gc_ratio,max_homopolymer,entropy,chunk_index\n");
+    for seq in sequences {
+        csv.push_str(&format!(
+            "{},{},{:.4},{},{:.4},{}\n",
+            seq.id,
+            seq.len(),
+            seq.metadata.gc_ratio,
+            seq.metadata.max_homopolymer,
+            seq.metadata.entropy,
+            seq.metadata.chunk_index
+        ));
+    }
+
+    write_or_print(&csv, output.as_deref(), "CSV")
+}
+
+/// Nombre de tranches de l'histogramme des positions d'erreur
+const HISTOGRAM_BINS: usize = 20;
+
+/// Rapport statistique d'une série de simulations
+fn visualize_simulation(collector: &MetricsCollector, format: VisualizationFormat, output: Option<PathBuf>) -> Result<()> {
+    let report = collector.report(HISTOGRAM_BINS);
+
+    match format {
+        VisualizationFormat::Table => simulation_table(&report),
+        VisualizationFormat::Json => write_or_print(&report.to_json()?, output.as_deref(), "JSON"),
+        VisualizationFormat::Html => write_or_print(&simulation_html(&report), output.as_deref(), "HTML"),
+        VisualizationFormat::Csv => match output {
+            // Trois fichiers: résumé, positions et détail par simulation
+            Some(path) => {
+                write_or_print(&report.to_csv(), Some(&path), "CSV")?;
+                write_or_print(&report.histogram.to_csv(), Some(&sibling(&path, "positions")), "CSV")?;
+                write_or_print(&collector.to_csv(), Some(&sibling(&path, "runs")), "CSV")
+            }
+            None => {
+                println!("\n{}\n{}\n{}", report.to_csv(), report.histogram.to_csv(), collector.to_csv());
+                Ok(())
+            }
+        },
+    }
+}
+
+/// Résumé et histogramme en tableau
+fn simulation_table(report: &StatisticsReport) -> Result<()> {
+    use tabled::{Table, Tabled};
+
+    #[derive(Tabled)]
+    struct SummaryRow {
+        #[tabled(rename = "Metric")]
+        metric: &'static str,
+        #[tabled(rename = "Mean")]
+        mean: String,
+        #[tabled(rename = "95% CI")]
+        ci: String,
+        #[tabled(rename = "P5")]
+        p05: String,
+        #[tabled(rename = "Median")]
+        median: String,
+        #[tabled(rename = "P95")]
+        p95: String,
+    }
+
+    let rows: Vec<SummaryRow> = report
+        .summaries
+        .iter()
+        .map(|s| SummaryRow {
+            metric: s.metric.name(),
+            mean: format!("{:.4}%", 100.0 * s.mean),
+            ci: format!("[{:.4}%, {:.4}%]", 100.0 * s.ci95.lower, 100.0 * s.ci95.upper),
+            p05: format!("{:.4}%", 100.0 * s.p05),
+            median: format!("{:.4}%", 100.0 * s.median),
+            p95: format!("{:.4}%", 100.0 * s.p95),
+        })
+        .collect();
+
+    println!();
+    println!("{}", Table::new(rows));
+
+    let histogram = &report.histogram;
+    let peak = (0..histogram.bins).map(|b| histogram.total(b)).max().unwrap_or(0).max(1);
+    println!("\nPosition des erreurs le long de l'oligo (S/I/D):");
+    for bin in 0..histogram.bins {
+        println!(
+            "{:>4.0}% │{:<40}│ {}/{}/{}",
+            100.0 * bin as f64 / histogram.bins as f64,
+            "█".repeat(histogram.total(bin) * 40 / peak),
+            histogram.substitutions[bin],
+            histogram.insertions[bin],
+            histogram.deletions[bin]
+        );
+    }
+
+    Ok(())
+}
+
+/// Rapport HTML autonome
+fn simulation_html(report: &StatisticsReport) -> String {
+    let summary_rows = report
+        .summaries
+        .iter()
+        .map(|s| {
+            format!(
+                "<tr><td>{}</td><td>{:.4}%</td><td>[{:.4}%, {:.4}%]</td><td>{:.4}%</td><td>{:.4}%</td><td>{:.4}%</td><td>{:.4}%</td><td>{:.4}%</td></tr>",
+                s.metric.name(),
+                100.0 * s.mean,
+                100.0 * s.ci95.lower,
+                100.0 * s.ci95.upper,
+                100.0 * s.std_dev,
+                100.0 * s.min,
+                100.0 * s.median,
+                100.0 * s.p95,
+                100.0 * s.max
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n        ");
+
+    let histogram = &report.histogram;
+    let peak = (0..histogram.bins).map(|b| histogram.total(b)).max().unwrap_or(0).max(1) as f64;
+    let bar = |count: usize, class: &str| {
+        format!(r#"<span class="{}" style="width: {:.1}%"></span>"#, class, 100.0 * count as f64 / peak)
+    };
+    let histogram_rows = (0..histogram.bins)
+        .map(|bin| {
+            format!(
+                "<tr><td>{:.0}–{:.0}%</td><td class=\"bars\">{}{}{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                100.0 * bin as f64 / histogram.bins as f64,
+                100.0 * (bin + 1) as f64 / histogram.bins as f64,
+                bar(histogram.substitutions[bin], "sub"),
+                bar(histogram.insertions[bin], "ins"),
+                bar(histogram.deletions[bin], "del"),
+                histogram.substitutions[bin],
+                histogram.insertions[bin],
+                histogram.deletions[bin]
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n        ");
+
+    format!(
+        r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <title>ADN Simulation Report</title>
+    <style>
+        body {{ font-family: Arial, sans-serif; margin: 20px; }}
+        table {{ border-collapse: collapse; width: 100%; margin-bottom: 30px; }}
+        th, td {{ border: 1px solid #ddd; padding: 8px; text-align: left; }}
+        th {{ background-color: #4CAF50; color: white; }}
+        tr:nth-child(even) {{ background-color: #f2f2f2; }}
+        .bars {{ width: 50%; }}
+        .bars span {{ display: inline-block; height: 14px; }}
+        .sub {{ background-color: #2196F3; }}
+        .ins {{ background-color: #FF9800; }}
+        .del {{ background-color: #F44336; }}
+    </style>
+</head>
+<body>
+    <h1>🧬 ADN Simulation Report</h1>
+    <p><strong>Simulations:</strong> {}</p>
+    <h2>Error rates</h2>
+    <table>
+        <tr>
+            <th>Metric</th>
+            <th>Mean</th>
+            <th>95% CI (bootstrap)</th>
+            <th>Std dev</th>
+            <th>Min</th>
+            <th>Median</th>
+            <th>P95</th>
+            <th>Max</th>
+        </tr>
+        {}
+    </table>
+    <h2>Error positions along the oligo</h2>
+    <p><span class="sub">&nbsp;&nbsp;&nbsp;</span> substitutions <span class="ins">&nbsp;&nbsp;&nbsp;</span> insertions <span class="del">&nbsp;&nbsp;&nbsp;</span> deletions</p>
+    <table>
+        <tr>
+            <th>Position</th>
+            <th>Distribution</th>
+            <th>Substitutions</th>
+            <th>Insertions</th>
+            <th>Deletions</th>
+        </tr>
+        {}
+    </table>
+</body>
+</html>
+"#,
+        report.runs, summary_rows, histogram_rows
+    )
+}
+
+/// Écrit dans un fichier ou affiche sur la sortie standard
+fn write_or_print(content: &str, output: Option<&Path>, label: &str) -> Result<()> {
+    match output {
+        Some(path) => {
+            std::fs::write(path, content)?;
+            println!("{} écrit dans: {}", label, path.display());
+        }
+        None => println!("\n{}", content),
+    }
+    Ok(())
+}
+
+/// `rapport.csv` → `rapport_<suffix>.csv`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("report");
+    path.with_file_name(format!("{}_{}.csv", stem, suffix))
+}
+
 /// Lit un fichier FASTA
 fn read_fasta(path: &PathBuf) -> Result<Vec<DnaSequence>> {
     let file = std::fs::File::open(path)?;
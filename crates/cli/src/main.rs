@@ -1,5 +1,6 @@
 //! CLI pour l'application ADN
 
+use adn_simulation::{ChannelConfig, ErrorModel, SweepParameter};
 use clap::{Parser, Subcommand};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::path::PathBuf;
@@ -7,7 +8,7 @@ use std::path::PathBuf;
 mod commands;
 mod display;
 
-use commands::{encode, decode, simulate, visualize};
+use commands::{encode, decode, profile, simulate, visualize};
 
 #[derive(Parser)]
 #[command(name = "adn")]
@@ -34,9 +35,9 @@ enum Commands {
         #[arg(short, long)]
         output: PathBuf,
 
-        /// Algorithme d'encodage
-        #[arg(short, long, value_enum)]
-        algorithm: EncodingAlgorithm,
+        /// Algorithme d'encodage (un schéma du registre des codecs)
+        #[arg(short, long, value_parser = parse_codec)]
+        algorithm: String,
 
         /// Facteur de redondance (1.0 = pas de redondance)
         #[arg(short, long, default_value = "1.5")]
@@ -53,7 +54,7 @@ enum Commands {
 
     /// Décode des séquences ADN en fichier original
     Decode {
-        /// Fichier FASTA/JSON d'entrée
+        /// Fichier FASTA ou FASTQ (brut ou .gz) d'entrée
         #[arg(short, long)]
         input: PathBuf,
 
@@ -84,14 +85,67 @@ enum Commands {
         #[arg(short = 'd', long, default_value = "0.005")]
         deletion_rate: f64,
 
-        /// Nombre d'itérations
+        /// Nombre d'itérations (essais en mode pipeline)
         #[arg(short = 'n', long, default_value = "100")]
         iterations: usize,
+
+        /// Simulation de bout en bout: `input` est un fichier brut, encodé,
+        /// transmis au canal complet puis décodé à chaque essai
+        #[arg(long)]
+        pipeline: bool,
+
+        /// Schéma d'encodage du mode pipeline
+        #[arg(short, long, value_parser = parse_codec, default_value = "fountain")]
+        algorithm: String,
+
+        /// Facteur de redondance du mode pipeline
+        #[arg(short, long, default_value = "1.5")]
+        redundancy: f64,
+
+        /// Couverture de séquençage (lectures par oligo)
+        #[arg(long, default_value = "10")]
+        coverage: f64,
+
+        /// Paramètre à balayer (substitution, insertion, deletion, coverage,
+        /// redundancy, dropout, temperature, days)
+        #[arg(long)]
+        sweep: Option<SweepParameter>,
+
+        /// Valeurs du balayage, séparées par des virgules
+        #[arg(long, value_delimiter = ',')]
+        values: Vec<f64>,
+
+        /// Profil d'erreur JSON appris par `fit-profile` (remplace les taux)
+        #[arg(long)]
+        profile: Option<PathBuf>,
+
+        /// Sauvegarde les métriques brutes en JSON (à passer à `visualize`)
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+
+    /// Apprend un profil d'erreur à partir de lectures alignées sur leurs références
+    FitProfile {
+        /// FASTA des oligos de référence
+        #[arg(short, long)]
+        references: PathBuf,
+
+        /// Lectures séquencées (FASTQ brut ou gzip, ou FASTA)
+        #[arg(short = 'q', long)]
+        reads: PathBuf,
+
+        /// Fichier JSON du profil appris
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Distance d'édition maximale relative à la longueur de la référence
+        #[arg(long, default_value = "0.25")]
+        max_error_ratio: f64,
     },
 
     /// Visualise les statistiques et métadonnées
     Visualize {
-        /// Fichier de séquences
+        /// Fichier de séquences, ou métriques JSON de `simulate --report`
         #[arg(short, long)]
         input: PathBuf,
 
@@ -105,14 +159,15 @@ enum Commands {
     },
 }
 
-#[derive(clap::ValueEnum, Clone)]
-pub enum EncodingAlgorithm {
-    Fountain,
-    Goldman,
-    Goldman2013,
-    Grass2015,
-    Adaptive,
-    Base3,
+/// Valide un nom de schéma contre le registre des codecs
+fn parse_codec(name: &str) -> Result<String, String> {
+    match adn_core::codec::lookup_codec(name) {
+        Some(codec) => Ok(codec.name().to_string()),
+        None => Err(format!(
+            "schéma inconnu, valeurs possibles: {}",
+            adn_core::codec::codec_names().join(", ")
+        )),
+    }
 }
 
 #[derive(clap::ValueEnum, Clone)]
@@ -127,6 +182,7 @@ pub enum VisualizationFormat {
     Table,
     Json,
     Html,
+    Csv,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -156,8 +212,43 @@ fn main() -> anyhow::Result<()> {
             insertion_rate,
             deletion_rate,
             iterations,
+            pipeline,
+            algorithm,
+            redundancy,
+            coverage,
+            sweep,
+            values,
+            profile,
+            report,
+        } => {
+            let mut channel = ChannelConfig {
+                error_model: ErrorModel::new(substitution_rate, insertion_rate, deletion_rate),
+                ..Default::default()
+            };
+            if let Some(path) = profile {
+                channel = channel.with_profile_file(path)?;
+            }
+
+            if pipeline {
+                let options = simulate::PipelineOptions {
+                    algorithm,
+                    redundancy,
+                    trials: iterations,
+                    coverage,
+                    sweep: sweep.map(|parameter| (parameter, values)),
+                };
+                simulate::run_pipeline(input, channel, options, report)?;
+            } else {
+                simulate::run(input, channel, iterations, report)?;
+            }
+        }
+        Commands::FitProfile {
+            references,
+            reads,
+            output,
+            max_error_ratio,
         } => {
-            simulate::run(input, substitution_rate, insertion_rate, deletion_rate, iterations)?;
+            profile::run(references, reads, output, max_error_ratio)?;
         }
         Commands::Visualize {
             input,
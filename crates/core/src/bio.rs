@@ -1,11 +1,14 @@
 //! Module de bioinformatique pour standards ADN
 //!
 //! Ce module contient les structures et fonctions pour les standards
-//! de séquencement Illumina et autres formats biologiques courants.
+//! de séquencement Illumina et autres formats biologiques courants, ainsi que
+//! les amorces PCR d'adressage des fichiers dans un pool partagé.
 
 pub mod illumina;
+pub mod primers;
 
 pub use illumina::{
     IlluminaBarcode, IlluminaAdapter, AdapterType, IlluminaSystem,
     IlluminaConfig, IlluminaValidator, BarcodePosition,
 };
+pub use primers::{Primer, PrimerConfig, PrimerLibrary, PrimerPair};
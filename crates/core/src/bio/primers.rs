@@ -0,0 +1,519 @@
+//! Bibliothèque d'amorces pour l'accès aléatoire dans un pool partagé
+//!
+//! Chaque fichier d'un même run de synthèse reçoit une paire d'amorces
+//! unique: l'amorce sens est ajoutée en 5' de ses oligos et le complément
+//! inverse de l'amorce antisens en 3'. Une PCR simulée avec cette paire
+//! extrait du pool mélangé les seuls oligos du fichier, prêts à décoder.
+//!
+//! Les amorces sont tirées aléatoirement puis criblées individuellement
+//! (GC, Tm, homopolymères, épingles à cheveux, auto-dimères) et deux à deux
+//! (distance de Hamming, dimères croisés) contre toute la bibliothèque.
+
+use crate::error::{DnaError, Result};
+use crate::sequence::{DnaSequence, IupacBase, SequenceMetadata};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+const BASES: [IupacBase; 4] = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+
+/// Taille minimale de la boucle d'une épingle à cheveux
+const MIN_HAIRPIN_LOOP: usize = 3;
+
+/// Paramètres de génération et de criblage des amorces
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrimerConfig {
+    /// Longueur des amorces (nt)
+    pub length: usize,
+    /// Ratio GC minimum
+    pub gc_min: f64,
+    /// Ratio GC maximum
+    pub gc_max: f64,
+    /// Température de fusion minimale (°C)
+    pub tm_min: f64,
+    /// Température de fusion maximale (°C)
+    pub tm_max: f64,
+    /// Longueur max d'homopolymère
+    pub max_homopolymer: usize,
+    /// Distance de Hamming minimale entre deux amorces (et leurs compléments inverses)
+    pub min_hamming_distance: usize,
+    /// Tige maximale d'une épingle à cheveux
+    pub max_hairpin_stem: usize,
+    /// Plus longue région complémentaire tolérée entre deux amorces (ou une amorce et elle-même)
+    pub max_dimer_run: usize,
+    /// Nombre maximal de candidats tirés
+    pub max_attempts: usize,
+    /// Graine du générateur
+    pub seed: u64,
+}
+
+impl Default for PrimerConfig {
+    fn default() -> Self {
+        Self {
+            length: 20,
+            gc_min: 0.45,
+            gc_max: 0.55,
+            tm_min: 50.0,
+            tm_max: 58.0,
+            max_homopolymer: 3,
+            min_hamming_distance: 6,
+            max_hairpin_stem: 4,
+            max_dimer_run: 6,
+            max_attempts: 200_000,
+            seed: 42,
+        }
+    }
+}
+
+impl PrimerConfig {
+    /// Vérifie les critères propres à une amorce
+    pub fn accepts(&self, primer: &Primer) -> bool {
+        let gc = primer.gc_ratio();
+        let tm = primer.melting_temperature();
+
+        primer.len() == self.length
+            && gc >= self.gc_min
+            && gc <= self.gc_max
+            && tm >= self.tm_min
+            && tm <= self.tm_max
+            && crate::constraints::find_max_homopolymer(&primer.sequence) <= self.max_homopolymer
+            && max_hairpin_stem(&primer.sequence) <= self.max_hairpin_stem
+            && max_complementary_run(&primer.sequence, &primer.sequence) <= self.max_dimer_run
+    }
+
+    /// Vérifie qu'une amorce est orthogonale à une autre
+    pub fn compatible(&self, a: &Primer, b: &Primer) -> bool {
+        hamming_distance(&a.sequence, &b.sequence) >= self.min_hamming_distance
+            && hamming_distance(&a.sequence, &b.reverse_complement()) >= self.min_hamming_distance
+            && max_complementary_run(&a.sequence, &b.sequence) <= self.max_dimer_run
+    }
+}
+
+/// Amorce PCR
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Primer {
+    /// Séquence 5' → 3'
+    pub sequence: Vec<IupacBase>,
+}
+
+impl Primer {
+    /// Crée une amorce
+    pub fn new(sequence: Vec<IupacBase>) -> Self {
+        Self { sequence }
+    }
+
+    /// Retourne la longueur de l'amorce
+    pub fn len(&self) -> usize {
+        self.sequence.len()
+    }
+
+    /// Retourne true si l'amorce est vide
+    pub fn is_empty(&self) -> bool {
+        self.sequence.is_empty()
+    }
+
+    /// Ratio GC de l'amorce
+    pub fn gc_ratio(&self) -> f64 {
+        if self.sequence.is_empty() {
+            return 0.0;
+        }
+        self.sequence.iter().filter(|b| b.is_gc()).count() as f64 / self.sequence.len() as f64
+    }
+
+    /// Température de fusion estimée (°C)
+    pub fn melting_temperature(&self) -> f64 {
+        melting_temperature(&self.sequence)
+    }
+
+    /// Complément inverse de l'amorce
+    pub fn reverse_complement(&self) -> Vec<IupacBase> {
+        reverse_complement(&self.sequence)
+    }
+}
+
+impl fmt::Display for Primer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for base in &self.sequence {
+            write!(f, "{}", base.as_char())?;
+        }
+        Ok(())
+    }
+}
+
+/// Paire d'amorces adressant un fichier
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrimerPair {
+    /// Index de la paire dans la bibliothèque
+    pub id: usize,
+    /// Amorce sens (site en 5' des oligos)
+    pub forward: Primer,
+    /// Amorce antisens (son complément inverse termine les oligos)
+    pub reverse: Primer,
+}
+
+impl PrimerPair {
+    /// Ajoute les sites d'amorces autour d'un oligo
+    pub fn attach(&self, sequence: &DnaSequence) -> DnaSequence {
+        let mut bases = Vec::with_capacity(sequence.len() + self.forward.len() + self.reverse.len());
+        bases.extend_from_slice(&self.forward.sequence);
+        bases.extend_from_slice(&sequence.bases);
+        bases.extend(self.reverse.reverse_complement());
+
+        with_bases(sequence, bases)
+    }
+
+    /// Ajoute les sites d'amorces à tout un pool
+    pub fn attach_all(&self, sequences: &[DnaSequence]) -> Vec<DnaSequence> {
+        sequences.iter().map(|s| self.attach(s)).collect()
+    }
+
+    /// Retire les sites d'amorces d'un oligo amplifié par cette paire
+    ///
+    /// Les deux brins sont reconnus: un oligo lu en antisens est remis dans
+    /// le sens d'origine. Retourne `None` si les amorces ne s'hybrident pas
+    /// avec au plus `max_mismatches` mésappariements à chaque extrémité.
+    pub fn strip(&self, sequence: &DnaSequence, max_mismatches: usize) -> Option<DnaSequence> {
+        if let Some(payload) = self.payload(&sequence.bases, max_mismatches) {
+            return Some(with_bases(sequence, payload.to_vec()));
+        }
+
+        let reverse_strand = reverse_complement(&sequence.bases);
+        self.payload(&reverse_strand, max_mismatches)
+            .map(|payload| with_bases(sequence, payload.to_vec()))
+    }
+
+    /// PCR simulée: sélectionne et débarrasse de leurs amorces les oligos de la paire
+    pub fn amplify<'a, I>(&self, pool: I, max_mismatches: usize) -> Vec<DnaSequence>
+    where
+        I: IntoIterator<Item = &'a DnaSequence>,
+    {
+        pool.into_iter()
+            .filter_map(|sequence| self.strip(sequence, max_mismatches))
+            .collect()
+    }
+
+    /// Charge utile entre les deux sites, si les deux amorces s'hybrident
+    fn payload<'b>(&self, bases: &'b [IupacBase], max_mismatches: usize) -> Option<&'b [IupacBase]> {
+        let head = self.forward.len();
+        let tail = self.reverse.len();
+        if bases.len() < head + tail {
+            return None;
+        }
+
+        let end = bases.len() - tail;
+        let forward_ok = hamming_distance(&bases[..head], &self.forward.sequence) <= max_mismatches;
+        let reverse_ok = hamming_distance(&bases[end..], &self.reverse.reverse_complement()) <= max_mismatches;
+
+        (forward_ok && reverse_ok).then_some(&bases[head..end])
+    }
+}
+
+/// Bibliothèque de paires d'amorces orthogonales et leur attribution aux fichiers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrimerLibrary {
+    config: PrimerConfig,
+    pairs: Vec<PrimerPair>,
+    assignments: HashMap<String, usize>,
+}
+
+impl PrimerLibrary {
+    /// Génère `pair_count` paires d'amorces mutuellement orthogonales
+    pub fn generate(config: PrimerConfig, pair_count: usize) -> Result<Self> {
+        if config.length == 0 {
+            return Err(DnaError::Encoding("Longueur d'amorce nulle".to_string()));
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+        let needed = pair_count * 2;
+        let mut primers: Vec<Primer> = Vec::with_capacity(needed);
+
+        let mut attempts = 0;
+        while primers.len() < needed {
+            if attempts == config.max_attempts {
+                return Err(DnaError::Encoding(format!(
+                    "Seulement {} amorces orthogonales sur {} après {} candidats",
+                    primers.len(),
+                    needed,
+                    attempts
+                )));
+            }
+            attempts += 1;
+
+            let candidate = random_primer(&mut rng, config.length, config.max_homopolymer);
+            if config.accepts(&candidate) && primers.iter().all(|p| config.compatible(&candidate, p)) {
+                primers.push(candidate);
+            }
+        }
+
+        let mut primers = primers.into_iter();
+        let pairs = (0..pair_count)
+            .filter_map(|id| {
+                Some(PrimerPair {
+                    id,
+                    forward: primers.next()?,
+                    reverse: primers.next()?,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            config,
+            pairs,
+            assignments: HashMap::new(),
+        })
+    }
+
+    /// Paramètres utilisés pour la génération
+    pub fn config(&self) -> &PrimerConfig {
+        &self.config
+    }
+
+    /// Paires de la bibliothèque
+    pub fn pairs(&self) -> &[PrimerPair] {
+        &self.pairs
+    }
+
+    /// Nombre de paires
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Retourne true si la bibliothèque est vide
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Attribue une paire libre à un fichier (ou retourne celle déjà attribuée)
+    pub fn assign(&mut self, file: &str) -> Result<&PrimerPair> {
+        let next = self.assignments.len();
+        let id = match self.assignments.get(file) {
+            Some(&id) => id,
+            None if next < self.pairs.len() => {
+                self.assignments.insert(file.to_string(), next);
+                next
+            }
+            None => {
+                return Err(DnaError::Encoding(format!(
+                    "Plus de paire d'amorces libre pour {} ({} paires attribuées)",
+                    file, next
+                )))
+            }
+        };
+
+        Ok(&self.pairs[id])
+    }
+
+    /// Paire attribuée à un fichier
+    pub fn pair_for(&self, file: &str) -> Option<&PrimerPair> {
+        self.assignments.get(file).map(|&id| &self.pairs[id])
+    }
+
+    /// Extrait d'un pool mélangé les oligos d'un fichier par PCR simulée
+    pub fn select<'a, I>(&self, file: &str, pool: I, max_mismatches: usize) -> Result<Vec<DnaSequence>>
+    where
+        I: IntoIterator<Item = &'a DnaSequence>,
+    {
+        let pair = self
+            .pair_for(file)
+            .ok_or_else(|| DnaError::Decoding(format!("Aucune paire d'amorces pour {}", file)))?;
+
+        Ok(pair.amplify(pool, max_mismatches))
+    }
+}
+
+/// Température de fusion: règle de Wallace sous 14 nt, formule GC au-delà
+pub fn melting_temperature(bases: &[IupacBase]) -> f64 {
+    let gc = bases.iter().filter(|b| b.is_gc()).count() as f64;
+    let n = bases.len() as f64;
+
+    if bases.len() < 14 {
+        2.0 * (n - gc) + 4.0 * gc
+    } else {
+        64.9 + 41.0 * (gc - 16.4) / n
+    }
+}
+
+/// Distance de Hamming (les positions en excès comptent comme différences)
+pub fn hamming_distance(a: &[IupacBase], b: &[IupacBase]) -> usize {
+    let mismatches = a.iter().zip(b).filter(|(x, y)| x != y).count();
+    mismatches + a.len().abs_diff(b.len())
+}
+
+/// Complément inverse d'une séquence
+pub fn reverse_complement(bases: &[IupacBase]) -> Vec<IupacBase> {
+    bases.iter().rev().map(|b| b.complement()).collect()
+}
+
+/// Plus longue tige d'épingle à cheveux (boucle d'au moins 3 nt)
+pub fn max_hairpin_stem(bases: &[IupacBase]) -> usize {
+    let n = bases.len();
+    let mut best = 0;
+
+    // Tige ancrée en (i, j): bases[i + k] s'apparie avec bases[j - k]
+    for i in 0..n {
+        for j in (i + MIN_HAIRPIN_LOOP + 1)..n {
+            let mut stem = 0;
+            while i + stem < j - stem
+                && (j - stem) - (i + stem) > MIN_HAIRPIN_LOOP
+                && bases[i + stem].complement() == bases[j - stem]
+            {
+                stem += 1;
+            }
+            best = best.max(stem);
+        }
+    }
+
+    best
+}
+
+/// Plus longue région contiguë où `a` et `b` s'hybrident en antiparallèle
+pub fn max_complementary_run(a: &[IupacBase], b: &[IupacBase]) -> usize {
+    let target = reverse_complement(b);
+    let mut best = 0;
+
+    for shift in 0..(a.len() + target.len()) {
+        // Décalage de `target` par rapport à `a`
+        let offset = shift as isize - target.len() as isize + 1;
+        let mut run = 0;
+        for (i, &base) in a.iter().enumerate() {
+            let j = i as isize - offset;
+            if j >= 0 && (j as usize) < target.len() && target[j as usize] == base {
+                run += 1;
+                best = best.max(run);
+            } else {
+                run = 0;
+            }
+        }
+    }
+
+    best
+}
+
+/// Tire une amorce sans homopolymère plus long que `max_homopolymer`
+fn random_primer(rng: &mut ChaCha8Rng, length: usize, max_homopolymer: usize) -> Primer {
+    let mut sequence: Vec<IupacBase> = Vec::with_capacity(length);
+    let mut run = 0;
+
+    while sequence.len() < length {
+        let base = BASES[rng.gen_range(0..4)];
+        let extends = sequence.last() == Some(&base);
+        if extends && run >= max_homopolymer {
+            continue;
+        }
+        run = if extends { run + 1 } else { 1 };
+        sequence.push(base);
+    }
+
+    Primer::new(sequence)
+}
+
+/// Copie d'un oligo avec de nouvelles bases (métadonnées recalculées)
+fn with_bases(sequence: &DnaSequence, bases: Vec<IupacBase>) -> DnaSequence {
+    let source = &sequence.metadata;
+    let mut metadata = SequenceMetadata::compute(
+        &bases,
+        source.original_file.clone(),
+        source.chunk_index,
+        source.chunk_size,
+        source.seed,
+        source.encoding_scheme.clone(),
+    );
+    metadata.timestamp = source.timestamp;
+    metadata.total_chunks = source.total_chunks;
+    metadata.source_length = source.source_length;
+
+    DnaSequence {
+        bases,
+        id: sequence.id,
+        metadata,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Vec<IupacBase> {
+        s.chars().map(|c| IupacBase::from_char(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_melting_temperature() {
+        // Wallace: 2 × (A+T) + 4 × (G+C)
+        assert_eq!(melting_temperature(&parse("ACGTACGT")), 24.0);
+        // 20 nt dont 10 GC
+        let tm = melting_temperature(&parse("ACGTACGTACGTACGTACGT"));
+        assert!((tm - 51.78).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hairpin_and_dimers() {
+        // GCGCA ... TGCGC forme une tige de 5 autour d'une boucle de 4
+        assert_eq!(max_hairpin_stem(&parse("GCGCAAAAATGCGC")), 5);
+        assert_eq!(max_hairpin_stem(&parse("AAAAAAAA")), 0);
+
+        // Une séquence s'hybride entièrement avec son complément inverse
+        let a = parse("ACCTGAGT");
+        assert_eq!(max_complementary_run(&a, &reverse_complement(&a)), 8);
+        assert_eq!(max_complementary_run(&parse("AAAA"), &parse("AAAA")), 0);
+    }
+
+    #[test]
+    fn test_generated_library_is_orthogonal() {
+        let config = PrimerConfig::default();
+        let library = PrimerLibrary::generate(config.clone(), 6).unwrap();
+        assert_eq!(library.len(), 6);
+
+        let primers: Vec<&Primer> = library.pairs().iter().flat_map(|p| [&p.forward, &p.reverse]).collect();
+        for (i, a) in primers.iter().enumerate() {
+            assert!(config.accepts(a), "{} rejetée", a);
+            for b in &primers[i + 1..] {
+                assert!(config.compatible(a, b), "{} / {}", a, b);
+                assert!(hamming_distance(&a.sequence, &b.sequence) >= config.min_hamming_distance);
+            }
+        }
+
+        // Génération déterministe pour une graine donnée
+        let again = PrimerLibrary::generate(config, 6).unwrap();
+        assert_eq!(again.pairs(), library.pairs());
+    }
+
+    #[test]
+    fn test_attach_and_amplify_both_strands() {
+        let library = PrimerLibrary::generate(PrimerConfig::default(), 2).unwrap();
+        let (pair, other) = (&library.pairs()[0], &library.pairs()[1]);
+
+        let payload = DnaSequence::from_str("ACGTTGCAACGTTGCA", "f".to_string(), 3, 16, 7).unwrap();
+        let tagged = pair.attach(&payload);
+        assert_eq!(tagged.len(), payload.len() + 40);
+        assert_eq!(tagged.id, payload.id);
+
+        // Brin antisens avec un mésappariement dans l'amorce sens
+        let mut reverse_read = tagged.clone();
+        reverse_read.bases = reverse_complement(&tagged.bases);
+        let last = reverse_read.bases.len() - 1;
+        reverse_read.bases[last] = reverse_read.bases[last].complement();
+
+        let foreign = other.attach(&payload);
+        let amplified = pair.amplify([&tagged, &reverse_read, &foreign], 1);
+        assert_eq!(amplified.len(), 2);
+        for sequence in &amplified {
+            assert_eq!(sequence.bases, payload.bases);
+            assert_eq!(sequence.metadata.chunk_index, 3);
+        }
+        assert!(pair.amplify([&reverse_read], 0).is_empty());
+    }
+
+    #[test]
+    fn test_assignment_per_file() {
+        let mut library = PrimerLibrary::generate(PrimerConfig::default(), 2).unwrap();
+        let a = library.assign("a.txt").unwrap().id;
+        let b = library.assign("b.txt").unwrap().id;
+        assert_ne!(a, b);
+        assert_eq!(library.assign("a.txt").unwrap().id, a);
+        assert!(library.assign("c.txt").is_err());
+        assert!(library.select("d.txt", std::iter::empty(), 0).is_err());
+    }
+}
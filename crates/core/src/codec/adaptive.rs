@@ -464,6 +464,7 @@ mod tests {
                 crate::sequence::IupacBase::G,
                 crate::sequence::IupacBase::T,
             ],
+            ..Default::default()
         };
 
         let encoder = AdaptiveEncoder::new(constraints.clone());
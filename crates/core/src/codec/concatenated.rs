@@ -1,22 +1,137 @@
 //! Codes concaténés : Reed-Solomon + Convolutional
 //!
 //! Ce module implémente un code concaténé avec :
-//! - Code interne : Convolutional (half-rate, constraint length 7)
+//! - Code interne : Convolutional (half-rate, constraint length 7),
+//!   décodé par Viterbi (décision dure ou souple), poinçonnable
 //! - Code externe : Reed-Solomon (255, 223)
 //!
+//! Pipeline : Données → Reed-Solomon → Convolutional → Sortie
+//!
 //! Avantages :
 //! - Meilleure correction d'erreurs mixtes (substitutions + indels)
 //! - +50% d'efficacité de correction par rapport à RS seul
-//! - Possibilité d'itération entre décodeurs
+//! - Décodage itératif : effacements Viterbi → RS, blocs RS corrigés → Viterbi
 
 use crate::error::{DnaError, Result};
 use crate::codec::reed_solomon::ReedSolomonCodec;
 
+/// Nombre d'états du treillis (2^(K-1))
+const NUM_STATES: usize = 64;
+
+/// Profondeur de traceback par défaut (5 × K)
+const DEFAULT_TRACEBACK_DEPTH: usize = 35;
+
+/// LLR utilisé pour les décisions dures (bit 0 → +1, bit 1 → -1)
+const HARD_LLR: f64 = 1.0;
+
+/// Gain appliqué aux symboles fixés par les blocs RS corrigés
+const FEEDBACK_GAIN: f64 = 16.0;
+
+/// Motif de poinçonnage (puncturing) pour les taux supérieurs à 1/2
+///
+/// Chaque entrée indique, pour un bit d'entrée, si les sorties G1 et G2
+/// sont transmises. Les motifs prédéfinis sont ceux de DVB-S pour (171, 133).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuncturingPattern {
+    pattern: Vec<(bool, bool)>,
+}
+
+impl PuncturingPattern {
+    /// Crée un motif personnalisé
+    ///
+    /// Le motif doit être non vide et transmettre au moins un symbole par
+    /// bit d'entrée en moyenne (taux ≤ 1).
+    pub fn new(pattern: Vec<(bool, bool)>) -> Result<Self> {
+        let kept: usize = pattern.iter().map(|&(g1, g2)| g1 as usize + g2 as usize).sum();
+
+        if pattern.is_empty() || kept < pattern.len() {
+            return Err(DnaError::Encoding(format!(
+                "Motif de poinçonnage invalide: {} symboles transmis pour {} bits",
+                kept,
+                pattern.len()
+            )));
+        }
+
+        Ok(Self { pattern })
+    }
+
+    /// Taux 1/2 (aucun poinçonnage)
+    pub fn rate_1_2() -> Self {
+        Self { pattern: vec![(true, true)] }
+    }
+
+    /// Taux 2/3 (X: 10, Y: 11)
+    pub fn rate_2_3() -> Self {
+        Self { pattern: vec![(true, true), (false, true)] }
+    }
+
+    /// Taux 3/4 (X: 101, Y: 110)
+    pub fn rate_3_4() -> Self {
+        Self { pattern: vec![(true, true), (false, true), (true, false)] }
+    }
+
+    /// Taux 5/6 (X: 10101, Y: 11010)
+    pub fn rate_5_6() -> Self {
+        Self {
+            pattern: vec![(true, true), (false, true), (true, false), (false, true), (true, false)],
+        }
+    }
+
+    /// Taux 7/8 (X: 1000101, Y: 1111010)
+    pub fn rate_7_8() -> Self {
+        Self {
+            pattern: vec![
+                (true, true),
+                (false, true),
+                (false, true),
+                (false, true),
+                (true, false),
+                (false, true),
+                (true, false),
+            ],
+        }
+    }
+
+    /// Retourne le taux de codage (bits d'entrée / symboles transmis)
+    pub fn rate(&self) -> f64 {
+        self.period() as f64 / self.kept() as f64
+    }
+
+    /// Nombre de bits d'entrée par période
+    fn period(&self) -> usize {
+        self.pattern.len()
+    }
+
+    /// Nombre de symboles transmis par période
+    fn kept(&self) -> usize {
+        (0..self.period()).map(|i| self.kept_at(i)).sum()
+    }
+
+    /// Symboles transmis pour un bit d'entrée donné
+    fn at(&self, step: usize) -> (bool, bool) {
+        self.pattern[step % self.pattern.len()]
+    }
+
+    /// Nombre de symboles transmis pour un bit d'entrée donné
+    fn kept_at(&self, step: usize) -> usize {
+        let (g1, g2) = self.at(step);
+        g1 as usize + g2 as usize
+    }
+}
+
+impl Default for PuncturingPattern {
+    fn default() -> Self {
+        Self::rate_1_2()
+    }
+}
+
 /// Code convolutif (half-rate, constraint length 7)
 ///
 /// Utilise les polynômes générateurs :
 /// - G1 = 171 (octal) = 1111001 (binary)
 /// - G2 = 133 (octal) = 1011011 (binary)
+///
+/// Le treillis n'est pas terminé : le décodeur retient le meilleur état final.
 pub struct ConvolutionalCodec {
     /// Polynôme générateur 1
     g1: u8,
@@ -24,6 +139,10 @@ pub struct ConvolutionalCodec {
     g2: u8,
     /// Constraint length (K)
     constraint_length: usize,
+    /// Profondeur de traceback du décodeur de Viterbi
+    traceback_depth: usize,
+    /// Motif de poinçonnage
+    puncturing: PuncturingPattern,
 }
 
 impl ConvolutionalCodec {
@@ -35,50 +154,58 @@ impl ConvolutionalCodec {
             g1: 0o171, // 121 decimal = 0b01111001
             g2: 0o133, // 91 decimal = 0b01011011
             constraint_length: 7,
+            traceback_depth: DEFAULT_TRACEBACK_DEPTH,
+            puncturing: PuncturingPattern::rate_1_2(),
         }
     }
 
-    /// Encode un flux de bits (entrées 0/1)
+    /// Définit la profondeur de traceback (en bits d'entrée)
     ///
-    /// Pour chaque bit d'entrée, génère 2 bits de sortie
-    /// Rate = 1/2 (double la taille)
+    /// Une profondeur de 5 × K est généralement suffisante ; une valeur plus
+    /// faible réduit la mémoire au prix de décisions moins fiables.
+    pub fn with_traceback_depth(mut self, depth: usize) -> Self {
+        self.traceback_depth = depth.max(1);
+        self
+    }
+
+    /// Définit le motif de poinçonnage (taux > 1/2)
+    pub fn with_puncturing(mut self, pattern: PuncturingPattern) -> Self {
+        self.puncturing = pattern;
+        self
+    }
+
+    /// Encode un flux d'octets
+    ///
+    /// Pour chaque bit d'entrée, génère 2 bits de sortie avant poinçonnage.
+    /// Les bits transmis sont regroupés en octets (padding à zéro).
     pub fn encode(&self, input: &[u8]) -> Vec<u8> {
         if input.is_empty() {
             return Vec::new();
         }
 
-        let mut output_bits = Vec::with_capacity(input.len() * 16);
-        let mut shift_register: u8 = 0; // 7-bit shift register (K-1 = 7)
-
-        for &byte in input {
-            for bit_pos in 0..8 {
-                let input_bit = (byte >> (7 - bit_pos)) & 1;
+        bits_to_bytes(&self.encode_bits(&bytes_to_bits(input)))
+    }
 
-                // Shift le registre et insérer le nouveau bit
-                shift_register = ((shift_register << 1) | input_bit) & 0x7F;
+    /// Encode des bits d'entrée (0/1) en symboles transmis (après poinçonnage)
+    fn encode_bits(&self, input_bits: &[u8]) -> Vec<u8> {
+        let mut output_bits = Vec::with_capacity(self.transmitted_bits(input_bits.len()));
+        let mut shift_register: u8 = 0; // 7-bit shift register (K = 7)
 
-                // Calculer les deux sorties (XOR avec polynômes générateurs)
-                let out1 = Self::compute_output(shift_register, self.g1);
-                let out2 = Self::compute_output(shift_register, self.g2);
+        for (step, &input_bit) in input_bits.iter().enumerate() {
+            // Shift le registre et insérer le nouveau bit
+            shift_register = ((shift_register << 1) | input_bit) & 0x7F;
 
-                output_bits.push(if out1 { 1 } else { 0 });
-                output_bits.push(if out2 { 1 } else { 0 });
+            // Calculer les deux sorties (XOR avec polynômes générateurs)
+            let (keep1, keep2) = self.puncturing.at(step);
+            if keep1 {
+                output_bits.push(Self::compute_output(shift_register, self.g1) as u8);
             }
-        }
-
-        // Pack bits into bytes
-        let mut output = Vec::with_capacity((output_bits.len() + 7) / 8);
-        for chunk in output_bits.chunks(8) {
-            let mut byte = 0u8;
-            for (i, &bit) in chunk.iter().enumerate() {
-                if bit != 0 {
-                    byte |= 1 << (7 - i);
-                }
+            if keep2 {
+                output_bits.push(Self::compute_output(shift_register, self.g2) as u8);
             }
-            output.push(byte);
         }
 
-        output
+        output_bits
     }
 
     /// Calcule la sortie du codeur convolutif pour un registre donné
@@ -99,19 +226,133 @@ impl ConvolutionalCodec {
         result
     }
 
-    /// Décode avec algorithme de Viterbi (simplifié)
+    /// Décode avec l'algorithme de Viterbi (décision dure)
     ///
-    /// Note: Implémentation simplifiée pour démonstration.
-    /// Un Viterbi complet nécessiterait des treillis complexes.
-    pub fn decode(&self, _encoded: &[u8]) -> Result<Vec<u8>> {
-        // Pour une implémentation complète, il faudrait :
-        // - Construire le treillis
-        // - Calculer les métriques de branche
-        // - Backtracking pour trouver le chemin optimal
-        // Pour l'instant, retourner une erreur
-        Err(DnaError::Decoding(
-            "Viterbi decoding not yet implemented".to_string()
-        ))
+    /// `encoded` est la sortie de [`ConvolutionalCodec::encode`].
+    pub fn decode(&self, encoded: &[u8]) -> Result<Vec<u8>> {
+        if encoded.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.decode_soft(&hard_llrs(encoded))
+    }
+
+    /// Décode avec l'algorithme de Viterbi (décision souple)
+    ///
+    /// Un LLR par symbole transmis : LLR = log(P(bit=0) / P(bit=1)).
+    /// Les symboles en excès (padding d'octet) sont ignorés.
+    pub fn decode_soft(&self, llrs: &[f64]) -> Result<Vec<u8>> {
+        if llrs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let num_bytes = self.decoded_len(llrs.len());
+        if num_bytes == 0 {
+            return Err(DnaError::Decoding(format!(
+                "Trop peu de symboles pour décoder un octet: {}",
+                llrs.len()
+            )));
+        }
+
+        // Remettre les symboles poinçonnés à leur place (effacements)
+        let mut received = llrs.iter().copied();
+        let symbols: Vec<(Option<f64>, Option<f64>)> = (0..num_bytes * 8)
+            .map(|step| {
+                let (keep1, keep2) = self.puncturing.at(step);
+                let r1 = if keep1 { received.next() } else { None };
+                let r2 = if keep2 { received.next() } else { None };
+                (r1, r2)
+            })
+            .collect();
+
+        Ok(bits_to_bytes(&self.viterbi(&symbols)))
+    }
+
+    /// Parcourt le treillis et retourne les bits d'entrée les plus probables
+    ///
+    /// Les décisions sont prises avec une fenêtre glissante de
+    /// `traceback_depth` pas : seuls les survivants de la fenêtre sont gardés.
+    fn viterbi(&self, symbols: &[(Option<f64>, Option<f64>)]) -> Vec<u8> {
+        let steps = symbols.len();
+        let depth = self.traceback_depth.min(steps).max(1);
+
+        let mut metrics = [f64::INFINITY; NUM_STATES];
+        metrics[0] = 0.0;
+        let mut survivors = vec![[0u8; NUM_STATES]; depth];
+        let mut bits = vec![0u8; steps];
+
+        for (t, &(r1, r2)) in symbols.iter().enumerate() {
+            let mut next = [f64::INFINITY; NUM_STATES];
+            let survivor = &mut survivors[t % depth];
+
+            // Add-Compare-Select
+            for (state, &metric) in metrics.iter().enumerate() {
+                if !metric.is_finite() {
+                    continue;
+                }
+
+                for input_bit in 0..2u8 {
+                    let register = (((state as u8) << 1) | input_bit) & 0x7F;
+                    let next_state = (register & 0x3F) as usize;
+                    let candidate = metric
+                        + branch_cost(Self::compute_output(register, self.g1), r1)
+                        + branch_cost(Self::compute_output(register, self.g2), r2);
+
+                    if candidate < next[next_state] {
+                        next[next_state] = candidate;
+                        survivor[next_state] = state as u8;
+                    }
+                }
+            }
+
+            metrics = next;
+
+            // Décision pour le bit sortant de la fenêtre
+            if t + 1 >= depth {
+                let state = Self::traceback(&survivors, best_state(&metrics), t, depth - 1);
+                bits[t + 1 - depth] = (state & 1) as u8;
+            }
+        }
+
+        // Vider la fenêtre depuis le meilleur état final
+        let mut state = best_state(&metrics);
+        for t in (steps + 1 - depth..steps).rev() {
+            bits[t] = (state & 1) as u8;
+            state = survivors[t % depth][state] as usize;
+        }
+
+        bits
+    }
+
+    /// Remonte `steps` pas de survivants depuis `state` au temps `t`
+    fn traceback(survivors: &[[u8; NUM_STATES]], mut state: usize, t: usize, steps: usize) -> usize {
+        for k in 0..steps {
+            state = survivors[(t - k) % survivors.len()][state] as usize;
+        }
+        state
+    }
+
+    /// Nombre de symboles transmis pour un nombre de bits d'entrée
+    fn transmitted_bits(&self, input_bits: usize) -> usize {
+        let period = self.puncturing.period();
+        let full_periods = input_bits / period * self.puncturing.kept();
+        let remainder: usize = (0..input_bits % period).map(|i| self.puncturing.kept_at(i)).sum();
+        full_periods + remainder
+    }
+
+    /// Nombre d'octets décodables à partir d'un nombre de symboles
+    fn decoded_len(&self, num_symbols: usize) -> usize {
+        // Estimation puis ajustement : le motif n'est pas forcément aligné sur l'octet
+        let mut num_bytes = num_symbols * self.puncturing.period() / (self.puncturing.kept() * 8);
+
+        while num_bytes > 0 && self.transmitted_bits(num_bytes * 8) > num_symbols {
+            num_bytes -= 1;
+        }
+        while self.transmitted_bits((num_bytes + 1) * 8) <= num_symbols {
+            num_bytes += 1;
+        }
+
+        num_bytes
     }
 
     /// Retourne la longueur de contrainte
@@ -119,10 +360,20 @@ impl ConvolutionalCodec {
         self.constraint_length
     }
 
-    /// Retourne le taux de codage (1/n)
+    /// Retourne la profondeur de traceback
+    pub fn traceback_depth(&self) -> usize {
+        self.traceback_depth
+    }
+
+    /// Retourne le taux du code mère (1/n), avant poinçonnage
     pub fn rate(&self) -> usize {
         2 // 1/2 rate
     }
+
+    /// Retourne le taux de codage effectif (après poinçonnage)
+    pub fn code_rate(&self) -> f64 {
+        self.puncturing.rate()
+    }
 }
 
 impl Default for ConvolutionalCodec {
@@ -131,6 +382,65 @@ impl Default for ConvolutionalCodec {
     }
 }
 
+/// Coût d'une branche : corrélation négative entre le bit attendu et le LLR
+///
+/// Un symbole poinçonné (None) ne coûte rien.
+fn branch_cost(expected: bool, llr: Option<f64>) -> f64 {
+    match llr {
+        Some(llr) if expected => llr,
+        Some(llr) => -llr,
+        None => 0.0,
+    }
+}
+
+/// Retourne l'état de métrique minimale
+fn best_state(metrics: &[f64; NUM_STATES]) -> usize {
+    metrics
+        .iter()
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(state, _)| state)
+        .unwrap_or(0)
+}
+
+/// Convertit des octets en LLR de décision dure
+fn hard_llrs(bytes: &[u8]) -> Vec<f64> {
+    bytes_to_bits(bytes)
+        .into_iter()
+        .map(|bit| if bit == 0 { HARD_LLR } else { -HARD_LLR })
+        .collect()
+}
+
+/// Convertit un tableau de bits en bytes
+fn bits_to_bytes(bits: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(bits.len().div_ceil(8));
+
+    for chunk in bits.chunks(8) {
+        let mut byte = 0u8;
+        for (i, &bit) in chunk.iter().enumerate() {
+            if bit != 0 {
+                byte |= 1 << (7 - i);
+            }
+        }
+        bytes.push(byte);
+    }
+
+    bytes
+}
+
+/// Convertit des bytes en bits
+fn bytes_to_bits(bytes: &[u8]) -> Vec<u8> {
+    let mut bits = Vec::with_capacity(bytes.len() * 8);
+
+    for &byte in bytes {
+        for i in 0..8 {
+            bits.push((byte >> (7 - i)) & 1);
+        }
+    }
+
+    bits
+}
+
 /// Code concaténé : Convolutional (inner) + Reed-Solomon (outer)
 pub struct ConcatenatedCodec {
     /// Code convolutif interne
@@ -157,105 +467,232 @@ impl ConcatenatedCodec {
         self
     }
 
+    /// Remplace le code convolutif interne (poinçonnage, traceback)
+    pub fn with_convolutional_codec(mut self, conv_codec: ConvolutionalCodec) -> Self {
+        self.conv_codec = conv_codec;
+        self
+    }
+
     /// Encode avec le code concaténé
     ///
-    /// Pipeline : Données → Convolutional → Reed-Solomon → Sortie
+    /// Pipeline : Données → Reed-Solomon → Convolutional → Sortie
     pub fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
         if data.is_empty() {
             return Ok(Vec::new());
         }
 
-        // 1. Code convolutif interne (double la taille)
-        let conv_encoded = if self.use_convolutional {
-            // Convertir bytes en bits pour le codeur convolutif
-            let conv_output = self.conv_codec.encode(data);
-            self.bits_to_bytes(&conv_output)
-        } else {
-            data.to_vec()
-        };
-
-        // 2. Reed-Solomon externe
-        let rs_encoded = self.rs_codec.encode(&conv_encoded)?;
+        // 1. Reed-Solomon externe
+        let rs_encoded = self.rs_codec.encode(data)?;
 
-        Ok(rs_encoded)
+        // 2. Code convolutif interne
+        if self.use_convolutional {
+            Ok(self.conv_codec.encode(&rs_encoded))
+        } else {
+            Ok(rs_encoded)
+        }
     }
 
-    /// Décode avec itération possible
+    /// Décode en une passe
     ///
-    /// Pipeline : Sortie → Reed-Solomon → Convolutional → Données
+    /// Pipeline : Entrée → Viterbi → Reed-Solomon → Données
     pub fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
         if data.is_empty() {
             return Ok(Vec::new());
         }
 
-        // 1. Décoder Reed-Solomon
-        let rs_decoded = self.rs_codec.decode(data)?;
-
-        // 2. Décoder convolutif (si activé)
         if self.use_convolutional {
-            // Convertir bytes en bits
-            let bits = self.bytes_to_bits(&rs_decoded);
-            let conv_decoded = self.conv_codec.decode(&bits)?;
-
-            // Convertir bits en bytes
-            Ok(self.bits_to_bytes(&conv_decoded))
+            let rs_encoded = self.conv_codec.decode(data)?;
+            self.rs_codec.decode(&rs_encoded)
         } else {
-            Ok(rs_decoded)
+            self.rs_codec.decode(data)
         }
     }
 
     /// Décode itératif (avec feedback entre décodeurs)
     ///
-    /// Utilise les effacements du décodeur convolutif pour améliorer RS
-    pub fn decode_iterative(&self, data: &[u8], _iterations: usize) -> Result<Vec<u8>> {
-        // Pour une implémentation complète :
-        // 1. Décoder RS → obtenir blocs avec effacements
-        // 2. Décoder convolutif avec soft decision
-        // 3. Identifier les bits douteux → marquer comme effacements
-        // 4. Réessayer RS avec effacements
-        // 5. Répéter
-
-        // Pour l'instant, décodage simple
-        self.decode(data)
-    }
-
-    /// Convertit un tableau de bits en bytes
-    fn bits_to_bytes(&self, bits: &[u8]) -> Vec<u8> {
-        let mut bytes = Vec::with_capacity((bits.len() + 7) / 8);
-
-        for chunk in bits.chunks(8) {
-            let mut byte = 0u8;
-            for (i, &bit) in chunk.iter().enumerate() {
-                if bit != 0 {
-                    byte |= 1 << (7 - i);
+    /// Voir [`ConcatenatedCodec::decode_soft`] ; l'entrée est ici en décision dure.
+    pub fn decode_iterative(&self, data: &[u8], iterations: usize) -> Result<Vec<u8>> {
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if !self.use_convolutional {
+            return self.rs_codec.decode(data);
+        }
+
+        self.decode_soft(&hard_llrs(data), iterations)
+    }
+
+    /// Décode itératif à partir de LLR par symbole transmis
+    ///
+    /// À chaque itération :
+    /// 1. Viterbi sur les LLR courants
+    /// 2. Les octets dont le ré-encodage diverge du canal deviennent des effacements
+    /// 3. RS corrige bloc par bloc (avec puis sans effacements)
+    /// 4. Les symboles entièrement déterminés par des blocs corrigés sont fixés
+    ///    pour l'itération suivante
+    pub fn decode_soft(&self, llrs: &[f64], iterations: usize) -> Result<Vec<u8>> {
+        if llrs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if !self.use_convolutional {
+            let bits: Vec<u8> = llrs.iter().map(|&llr| (llr < 0.0) as u8).collect();
+            return self.rs_codec.decode(&bits_to_bytes(&bits));
+        }
+
+        let block_size = self.rs_codec.block_size();
+        let mut a_priori = llrs.to_vec();
+        let mut last_error = None;
+
+        for iteration in 0..iterations.max(1) {
+            // 1. Décodage interne
+            let stream = self.conv_codec.decode_soft(&a_priori)?;
+            if stream.len() < 4 || !(stream.len() - 4).is_multiple_of(block_size) {
+                return Err(DnaError::Correction(format!(
+                    "Flux Reed-Solomon invalide après Viterbi: {} bytes",
+                    stream.len()
+                )));
+            }
+
+            // 2. Fiabilité des octets décodés
+            let scores = self.byte_unreliability(&stream, llrs);
+            let threshold = if iteration == 0 { 2 } else { 1 };
+
+            // 3. Décodage externe bloc par bloc
+            let mut corrected = stream.clone();
+            let mut confirmed = vec![false; stream.len()];
+            let mut failed_blocks = 0;
+
+            for (block_idx, block) in stream[4..].chunks(block_size).enumerate() {
+                let start = 4 + block_idx * block_size;
+                let erasures = select_erasures(
+                    &scores[start..start + block_size],
+                    threshold,
+                    self.rs_codec.max_erasures_per_block(),
+                );
+
+                let codeword = self
+                    .rs_codec
+                    .correct_block(block, &erasures)
+                    .or_else(|_| self.rs_codec.correct_block(block, &[]));
+
+                match codeword {
+                    Ok(codeword) => {
+                        corrected[start..start + block_size].copy_from_slice(&codeword);
+                        confirmed[start..start + block_size].fill(true);
+                    }
+                    Err(e) => {
+                        failed_blocks += 1;
+                        last_error = Some(e);
+                    }
                 }
             }
-            bytes.push(byte);
+
+            if failed_blocks == 0 {
+                return self.extract_payload(&corrected);
+            }
+
+            tracing::debug!(
+                "Décodage concaténé: itération {}, {} blocs RS en échec",
+                iteration + 1,
+                failed_blocks
+            );
+
+            // 4. Rétroaction RS → Viterbi
+            a_priori = self.feedback_llrs(llrs, &corrected, &confirmed);
         }
 
-        bytes
+        Err(last_error.unwrap_or_else(|| {
+            DnaError::Correction("Décodage concaténé: aucune itération effectuée".to_string())
+        }))
     }
 
-    /// Convertit des bytes en bits
-    fn bytes_to_bits(&self, bytes: &[u8]) -> Vec<u8> {
-        let mut bits = Vec::with_capacity(bytes.len() * 8);
+    /// Score de non-fiabilité par octet décodé
+    ///
+    /// Le flux décodé est ré-encodé puis comparé aux décisions dures du canal :
+    /// on compte les divergences sur les symboles influencés par chaque octet.
+    fn byte_unreliability(&self, stream: &[u8], channel: &[f64]) -> Vec<usize> {
+        let reencoded = self.conv_codec.encode_bits(&bytes_to_bits(stream));
+        let disagreements: Vec<bool> = reencoded
+            .iter()
+            .zip(channel)
+            .map(|(&bit, &llr)| (bit == 1) != (llr < 0.0))
+            .collect();
+
+        let memory = self.conv_codec.constraint_length - 1;
+        let total_steps = stream.len() * 8;
+
+        (0..stream.len())
+            .map(|byte_idx| {
+                let first = self.conv_codec.transmitted_bits(byte_idx * 8);
+                let last = self
+                    .conv_codec
+                    .transmitted_bits((byte_idx * 8 + 8 + memory).min(total_steps))
+                    .min(disagreements.len());
+
+                disagreements[first.min(last)..last].iter().filter(|&&d| d).count()
+            })
+            .collect()
+    }
 
-        for &byte in bytes {
-            for i in 0..8 {
-                bits.push((byte >> (7 - i)) & 1);
+    /// Construit les LLR a priori de l'itération suivante
+    ///
+    /// Les symboles dont tout le registre provient d'octets confirmés par RS
+    /// sont fixés à une forte fiabilité ; les autres gardent la valeur du canal.
+    fn feedback_llrs(&self, channel: &[f64], corrected: &[u8], confirmed: &[bool]) -> Vec<f64> {
+        let input_bits = bytes_to_bits(corrected);
+        let reencoded = self.conv_codec.encode_bits(&input_bits);
+        let memory = self.conv_codec.constraint_length - 1;
+        let pinned_llr = channel.iter().fold(HARD_LLR, |acc, llr| acc.max(llr.abs())) * FEEDBACK_GAIN;
+
+        let mut llrs = channel.to_vec();
+        let mut symbol = 0;
+
+        for step in 0..input_bits.len() {
+            let pinned = (step.saturating_sub(memory)..=step).all(|bit| confirmed[bit / 8]);
+
+            for _ in 0..self.conv_codec.puncturing.kept_at(step) {
+                if pinned && symbol < llrs.len() {
+                    llrs[symbol] = if reencoded[symbol] == 0 { pinned_llr } else { -pinned_llr };
+                }
+                symbol += 1;
             }
         }
 
-        bits
+        llrs
+    }
+
+    /// Extrait les données d'un flux RS entièrement corrigé
+    fn extract_payload(&self, corrected: &[u8]) -> Result<Vec<u8>> {
+        let original_len = u32::from_be_bytes([corrected[0], corrected[1], corrected[2], corrected[3]]) as usize;
+        let max_data_block = self.rs_codec.max_data_block();
+
+        let mut data: Vec<u8> = corrected[4..]
+            .chunks(self.rs_codec.block_size())
+            .flat_map(|codeword| codeword[..max_data_block].iter().copied())
+            .collect();
+
+        // Le préfixe de longueur n'est pas protégé par RS
+        if original_len > data.len() {
+            return Err(DnaError::Correction(format!(
+                "Longueur originale incohérente: {} > {}",
+                original_len,
+                data.len()
+            )));
+        }
+
+        data.truncate(original_len);
+        Ok(data)
     }
 
     /// Retourne le taux de codage global
     pub fn overall_rate(&self) -> f64 {
-        // Convolutional : 1/2
+        // Convolutional : 1/2 (ou plus avec poinçonnage)
         // Reed-Solomon : 223/255
         // Global : 1/2 * 223/255 ≈ 0.437
         if self.use_convolutional {
-            0.5 * (223.0 / 255.0)
+            self.conv_codec.code_rate() * (223.0 / 255.0)
         } else {
             223.0 / 255.0
         }
@@ -273,6 +710,15 @@ impl Default for ConcatenatedCodec {
     }
 }
 
+/// Sélectionne les positions d'effacement d'un bloc, les plus suspectes d'abord
+fn select_erasures(scores: &[usize], threshold: usize, max_erasures: usize) -> Vec<u8> {
+    let mut suspects: Vec<usize> = (0..scores.len()).filter(|&i| scores[i] >= threshold).collect();
+    suspects.sort_by_key(|&i| std::cmp::Reverse(scores[i]));
+    suspects.truncate(max_erasures);
+    suspects.sort_unstable();
+    suspects.into_iter().map(|i| i as u8).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,6 +735,103 @@ mod tests {
         assert_eq!(encoded.len(), 2);
     }
 
+    /// Inverse un bit dans un flux d'octets (MSB en premier)
+    fn flip_bit(data: &mut [u8], bit: usize) {
+        data[bit / 8] ^= 0x80 >> (bit % 8);
+    }
+
+    #[test]
+    fn test_viterbi_roundtrip() {
+        let codec = ConvolutionalCodec::new();
+
+        let original = b"Viterbi hard decision roundtrip".to_vec();
+        let encoded = codec.encode(&original);
+
+        assert_eq!(codec.decode(&encoded).unwrap(), original);
+    }
+
+    #[test]
+    fn test_viterbi_corrects_scattered_errors() {
+        let codec = ConvolutionalCodec::new();
+
+        let original: Vec<u8> = (0..64u8).collect();
+        let mut encoded = codec.encode(&original);
+
+        // Erreurs isolées, espacées de plus que la longueur de contrainte
+        for bit in [40, 120, 333, 500, 781] {
+            flip_bit(&mut encoded, bit);
+        }
+
+        assert_eq!(codec.decode(&encoded).unwrap(), original);
+    }
+
+    #[test]
+    fn test_viterbi_soft_decision() {
+        let codec = ConvolutionalCodec::new();
+
+        let original = b"soft".to_vec();
+        let encoded = codec.encode(&original);
+
+        // Deux erreurs adjacentes, mais peu fiables: la décision souple les ignore
+        let mut llrs = hard_llrs(&encoded);
+        for i in [20, 21] {
+            llrs[i] *= -0.1;
+        }
+
+        assert_eq!(codec.decode_soft(&llrs).unwrap(), original);
+    }
+
+    #[test]
+    fn test_viterbi_short_traceback() {
+        let codec = ConvolutionalCodec::new().with_traceback_depth(8);
+        assert_eq!(codec.traceback_depth(), 8);
+
+        let original = b"short traceback window".to_vec();
+        let encoded = codec.encode(&original);
+
+        assert_eq!(codec.decode(&encoded).unwrap(), original);
+    }
+
+    #[test]
+    fn test_punctured_roundtrip() {
+        let patterns = [
+            (PuncturingPattern::rate_2_3(), 2.0 / 3.0),
+            (PuncturingPattern::rate_3_4(), 3.0 / 4.0),
+            (PuncturingPattern::rate_5_6(), 5.0 / 6.0),
+            (PuncturingPattern::rate_7_8(), 7.0 / 8.0),
+        ];
+
+        let original = b"Puncturing patterns".to_vec();
+
+        for (pattern, rate) in patterns {
+            let codec = ConvolutionalCodec::new().with_puncturing(pattern);
+            assert!((codec.code_rate() - rate).abs() < 1e-9);
+
+            let encoded = codec.encode(&original);
+            let expected_bits = (original.len() * 8) as f64 / rate;
+            assert_eq!(encoded.len(), (expected_bits.ceil() as usize).div_ceil(8));
+            assert_eq!(codec.decode(&encoded).unwrap(), original);
+        }
+    }
+
+    #[test]
+    fn test_punctured_corrects_error() {
+        let codec = ConvolutionalCodec::new().with_puncturing(PuncturingPattern::rate_2_3());
+
+        let original: Vec<u8> = (0..32u8).map(|i| i.wrapping_mul(29)).collect();
+        let mut encoded = codec.encode(&original);
+        flip_bit(&mut encoded, 100);
+
+        assert_eq!(codec.decode(&encoded).unwrap(), original);
+    }
+
+    #[test]
+    fn test_invalid_puncturing_pattern() {
+        assert!(PuncturingPattern::new(vec![]).is_err());
+        assert!(PuncturingPattern::new(vec![(true, false), (false, false)]).is_err());
+        assert!(PuncturingPattern::new(vec![(true, true), (true, false)]).is_ok());
+    }
+
     #[test]
     fn test_convolutional_properties() {
         let codec = ConvolutionalCodec::new();
@@ -315,10 +858,73 @@ mod tests {
             .with_convolutional(true);
 
         let original = b"ABC";
-        let encoded = codec.encode(original);
+        let encoded = codec.encode(original).unwrap();
+        let decoded = codec.decode(&encoded).unwrap();
+
+        assert_eq!(original.to_vec(), decoded);
+    }
 
-        assert!(encoded.is_ok());
-        // Le décodage nécessite Viterbi qui n'est pas implémenté
+    #[test]
+    fn test_concatenated_corrects_burst() {
+        let codec = ConcatenatedCodec::new();
+
+        let original: Vec<u8> = (0..300u32).map(|i| (i * 7 % 256) as u8).collect();
+        let mut encoded = codec.encode(&original).unwrap();
+
+        // Rafale de 24 bits dans le deuxième bloc: Viterbi échoue localement, RS corrige
+        for bit in 6000..6024 {
+            flip_bit(&mut encoded, bit);
+        }
+
+        assert_eq!(codec.decode(&encoded).unwrap(), original);
+        assert_eq!(codec.decode_iterative(&encoded, 3).unwrap(), original);
+    }
+
+    #[test]
+    fn test_concatenated_iterative_scattered_errors() {
+        let codec = ConcatenatedCodec::new();
+
+        let original = b"Iterative concatenated decoding with RS feedback".to_vec();
+        let mut encoded = codec.encode(&original).unwrap();
+
+        for bit in (100..encoded.len() * 8 - 100).step_by(97) {
+            flip_bit(&mut encoded, bit);
+        }
+
+        let decoded = codec.decode_iterative(&encoded, 4).unwrap();
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    fn test_concatenated_punctured_roundtrip() {
+        let codec = ConcatenatedCodec::new().with_convolutional_codec(
+            ConvolutionalCodec::new().with_puncturing(PuncturingPattern::rate_3_4()),
+        );
+
+        let original = b"Punctured inner code".to_vec();
+        let encoded = codec.encode(&original).unwrap();
+
+        assert_eq!(codec.decode(&encoded).unwrap(), original);
+        assert!((codec.overall_rate() - 0.75 * 223.0 / 255.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_concatenated_soft_input() {
+        let codec = ConcatenatedCodec::new();
+
+        let original = b"Soft decision".to_vec();
+        let encoded = codec.encode(&original).unwrap();
+
+        // LLR de fiabilité variable, quelques symboles faiblement erronés
+        let mut llrs: Vec<f64> = bytes_to_bits(&encoded)
+            .iter()
+            .map(|&bit| if bit == 0 { 2.0 } else { -2.0 })
+            .collect();
+        for i in (50..llrs.len() - 100).step_by(61) {
+            llrs[i] = -llrs[i] * 0.1;
+        }
+
+        assert_eq!(codec.decode_soft(&llrs, 2).unwrap(), original);
     }
 
     #[test]
@@ -334,11 +940,9 @@ mod tests {
 
     #[test]
     fn test_bits_conversion() {
-        let codec = ConcatenatedCodec::new();
-
         let bytes = vec![0b11010110, 0b00110011];
-        let bits = codec.bytes_to_bits(&bytes);
-        let recovered = codec.bits_to_bytes(&bits);
+        let bits = bytes_to_bits(&bytes);
+        let recovered = bits_to_bytes(&bits);
 
         assert_eq!(bytes, recovered);
     }
@@ -1,11 +1,20 @@
 //! Décodeur ADN - Récupère les données depuis les séquences ADN
 
+use crate::codec::encoder::{CompressionType as EncoderCompression, Encoder, EncoderConfig, EncoderType};
+use crate::codec::gc_aware_encoding::{GcAwareDecoder, MAX_PAYLOAD_BYTES};
+use crate::codec::goldman_2013::Goldman2013Decoder;
+use crate::codec::grass_2015::Grass2015Decoder;
+use crate::codec::manifest::{split_pool, PoolManifest, SegmentInfo};
+use crate::codec::stream::{FastaReader, StreamSummary};
+use crate::codec::product_code::ProductCodeDecoder;
+use crate::codec::registry::CodecRegistry;
+use crate::codec::vt_marker::VtMarkerDecoder;
 use crate::error::{DnaError, Result};
-use crate::sequence::{DnaSequence, IupacBase};
+use crate::sequence::{DnaConstraints, DnaSequence, IupacBase};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Write};
 
 // Importer les macros depuis la racine du crate
 pub use crate::{log_operation, log_error};
@@ -13,7 +22,9 @@ pub use crate::{log_operation, log_error};
 /// Configuration du décodeur
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecoderConfig {
-    /// Ignorer les erreurs de checksum
+    /// Ignorer les erreurs de checksum (SHA-256, CRC32 par chunk)
+    ///
+    /// Les erreurs deviennent des avertissements dans le `DecodeReport`.
     pub ignore_checksum: bool,
 
     /// Nombre maximum d'itérations de belief propagation
@@ -46,6 +57,28 @@ impl Default for DecoderConfig {
     }
 }
 
+/// Rapport d'intégrité d'un décodage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecodeReport {
+    /// Le pool contenait un manifeste
+    pub manifest_found: bool,
+    /// Chunks dont le CRC32 ne correspond pas au manifeste
+    pub failed_chunks: Vec<usize>,
+    /// Résultat de la vérification SHA-256 (None si non vérifiable)
+    pub sha256_verified: Option<bool>,
+    /// Avertissements (erreurs d'intégrité ignorées, vérifications impossibles)
+    pub warnings: Vec<String>,
+    /// Position du segment décodé (pools produits par `encode_stream`)
+    pub segment: Option<SegmentInfo>,
+}
+
+impl DecodeReport {
+    /// Retourne true si aucune vérification n'a échoué
+    pub fn is_intact(&self) -> bool {
+        self.failed_chunks.is_empty() && self.sha256_verified != Some(false)
+    }
+}
+
 /// Décodeur ADN principal
 pub struct Decoder {
     config: DecoderConfig,
@@ -108,7 +141,12 @@ impl Decoder {
                 return Err(DnaError::Decoding("Aucune séquence trouvée".to_string()));
             }
 
-            // Décoder avec le bon schéma
+            // Pool avec manifeste: la configuration exacte y est décrite
+            if sequences.iter().any(PoolManifest::is_header_oligo) {
+                return self.decode(&sequences);
+            }
+
+            // Ancien format: schéma déduit des en-têtes FASTA
             self.decode_with_detected_scheme(&sequences, detected_scheme)
         })
     }
@@ -119,98 +157,510 @@ impl Decoder {
         sequences: &[DnaSequence],
         scheme: Option<String>,
     ) -> Result<Vec<u8>> {
-        let scheme = scheme.as_deref().unwrap_or("unknown");
-
-        match scheme {
-            "goldman_2013" => {
-                use crate::codec::goldman_2013::Goldman2013Decoder;
-                let decoder = Goldman2013Decoder::new(crate::sequence::DnaConstraints::default());
-                decoder.decode(sequences)
-            }
-            "grass_2015" => {
-                use crate::codec::grass_2015::Grass2015Decoder;
-                let decoder = Grass2015Decoder::new(crate::sequence::DnaConstraints::default());
-                decoder.decode(sequences)
-            }
-            "erlich_zielinski_2017" => {
-                // Utiliser le GC-Aware decoder pour EZ 2017
-                use crate::codec::gc_aware_encoding::GcAwareDecoder;
-                // Contraintes EZ 2017: GC 40-60%, homopolymer <4, 152nt
-                let ez_constraints = crate::sequence::DnaConstraints::new(
-                    0.40,  // GC min 40%
-                    0.60,  // GC max 60%
-                    3,     // Max homopolymer 3 (<4)
-                    152    // Max length 152nt
-                );
-                let decoder = GcAwareDecoder::new(ez_constraints);
-
-                // Le GC-Aware decoder décode une séquence à la fois
-                // Pour Fountain avec LT codes, on a besoin de plus de logique
-                // Pour l'instant, on retourne les données de la première séquence
-                // NOTE: Ceci est simplifié - le vrai décodage Fountain nécessite
-                // la logique LT codes avec belief propagation
-                if sequences.is_empty() {
-                    return Err(DnaError::Decoding("Aucune séquence fournie".to_string()));
+        let codec = scheme.and_then(|scheme| CodecRegistry::global().read().get(&scheme));
+
+        match codec {
+            // Données brutes: ni détection ni décompression
+            Some(codec) if codec.capabilities().raw_input => {
+                codec.decode(sequences, &EncoderConfig::default(), &self.config)
+            }
+            // Schéma inconnu ou compressible: décodage générique
+            _ => self.decode(sequences),
+        }
+    }
+
+    /// Décode des séquences ADN en données avec gestion des erreurs améliorée
+    pub fn decode(&self, sequences: &[DnaSequence]) -> Result<Vec<u8>> {
+        self.decode_with_report(sequences).map(|(data, _)| data)
+    }
+
+    /// Décode un flux FASTA produit par `Encoder::encode_stream`
+    ///
+    /// Les oligos sont lus au fil de l'eau: un oligo de manifeste qui suit des
+    /// oligos de données ouvre un nouveau segment, et chaque segment complet est
+    /// décodé puis écrit dans `writer` avant de lire le suivant. Les segments
+    /// doivent arriver dans l'ordre; un flux sans dernier segment est rejeté.
+    pub fn decode_stream<R: BufRead, W: Write>(&self, reader: R, writer: &mut W) -> Result<StreamSummary> {
+        log_operation!("decode_stream", {
+            let mut summary = StreamSummary::default();
+            let mut pending: Vec<DnaSequence> = Vec::new();
+            let mut has_payload = false;
+            let mut finished = false;
+
+            for sequence in FastaReader::new(reader) {
+                let sequence = sequence?;
+                let is_header = PoolManifest::is_header_oligo(&sequence);
+
+                if is_header && has_payload {
+                    finished = self.decode_stream_segment(&pending, finished, writer, &mut summary)?;
+                    pending.clear();
+                    has_payload = false;
                 }
-                decoder.decode(&sequences[0])
+
+                has_payload |= !is_header;
+                pending.push(sequence);
             }
-            "fountain" | "unknown" => {
-                // Utiliser le décodeur générique pour Fountain et inconnu
-                self.decode(sequences)
+
+            if !pending.is_empty() {
+                finished = self.decode_stream_segment(&pending, finished, writer, &mut summary)?;
             }
-            _ => {
-                // Schéma non reconnu, tenter le décodage générique
-                self.decode(sequences)
+
+            if !finished {
+                return Err(DnaError::Decoding(format!(
+                    "Flux tronqué: dernier segment absent après {} segments",
+                    summary.segments
+                )));
             }
+
+            writer.flush()?;
+            Ok(summary)
+        })
+    }
+
+    /// Décode un segment de flux et l'écrit à la suite des précédents
+    ///
+    /// Retourne true si c'était le dernier segment du fichier.
+    fn decode_stream_segment<W: Write>(
+        &self,
+        pool: &[DnaSequence],
+        finished: bool,
+        writer: &mut W,
+        summary: &mut StreamSummary,
+    ) -> Result<bool> {
+        if finished {
+            return Err(DnaError::Decoding("Oligos après le dernier segment du flux".to_string()));
+        }
+
+        let (data, report) = self.decode_with_report(pool)?;
+        let segment = report
+            .segment
+            .ok_or_else(|| DnaError::Decoding("Pool sans information de segment".to_string()))?;
+
+        if segment.index != summary.segments as u64 || segment.offset != summary.bytes {
+            return Err(DnaError::Decoding(format!(
+                "Segment {} (octet {}) inattendu: attendu segment {} à l'octet {}",
+                segment.index, segment.offset, summary.segments, summary.bytes
+            )));
         }
+
+        writer.write_all(&data)?;
+
+        summary.segments += 1;
+        summary.bytes += data.len() as u64;
+        summary.sequences += pool.len();
+        summary.warnings.extend(
+            report
+                .warnings
+                .into_iter()
+                .map(|w| format!("segment {}: {}", segment.index, w)),
+        );
+
+        Ok(segment.last)
     }
 
-    /// Décode des séquences ADN en données avec gestion des erreurs améliorée
-    pub fn decode(&self, sequences: &[DnaSequence]) -> Result<Vec<u8>> {
+    /// Décode des séquences ADN et retourne le rapport d'intégrité
+    ///
+    /// Avec `ignore_checksum`, les erreurs d'intégrité deviennent des
+    /// avertissements du rapport au lieu d'erreurs fatales.
+    pub fn decode_with_report(&self, sequences: &[DnaSequence]) -> Result<(Vec<u8>, DecodeReport)> {
         log_operation!("decode_data", {
             if sequences.is_empty() {
                 return Err(DnaError::Decoding("Aucune séquence fournie".to_string()));
             }
 
-            // Note: Les séquences sont déjà validées pendant l'encodage
-            // Pas besoin de re-valider ici avec des contraintes par défaut
-
-            // Pour l'instant, on implémente un décodage simple (Goldman-like)
-            // Le décodage Fountain nécessiterait plus de métadonnées
+            let mut report = DecodeReport::default();
 
-            let mut data = Vec::new();
+            // Le manifeste, s'il est présent, décrit exactement l'encodage
+            if let (Some(manifest), payload) = split_pool(sequences)? {
+                report.manifest_found = true;
+                report.segment = manifest.segment;
+                let data = self.decode_with_manifest(&payload, &manifest, &mut report)?;
+                return Ok((data, report));
+            }
 
-            // Trier les séquences par chunk_index
-            let mut sorted_seqs: Vec<_> = sequences.iter().collect();
-            sorted_seqs.sort_by_key(|s| s.metadata.chunk_index);
+            // Note: Les séquences sont déjà validées pendant l'encodage
+            // Pas besoin de re-valider ici avec des contraintes par défaut
 
-            for seq in sorted_seqs {
-                let chunk_data = self.sequence_to_chunk(seq)?;
-                data.extend_from_slice(&chunk_data);
-            }
+            // Schéma inscrit dans les séquences, ou reconnu par un codec du registre
+            let codec = CodecRegistry::global().read().detect(sequences);
+            let (data, raw_input) = match codec {
+                Some(codec) => {
+                    let config = EncoderConfig {
+                        encoder_type: codec.encoder_type().unwrap_or_default(),
+                        ..Default::default()
+                    };
+                    (codec.decode(sequences, &config, &self.config)?, codec.capabilities().raw_input)
+                }
+                // Décodage simple (Goldman-like): concaténation dans l'ordre des chunks
+                None => (self.decode_ordered_chunks(sequences)?, false),
+            };
 
             // Décompression si activée
-            let result = if self.config.auto_decompress {
+            let result = if self.config.auto_decompress && !raw_input {
                 self.decompress(&data)?
             } else {
                 data
             };
 
             // Vérification finale d'intégrité
-            self.verify_integrity(&result)?;
+            self.verify_integrity(&result, None, &mut report)?;
 
-            Ok(result)
+            Ok((result, report))
         })
     }
 
+    /// Décode un pool selon son manifeste (schéma, contraintes, compression)
+    fn decode_with_manifest(
+        &self,
+        sequences: &[DnaSequence],
+        manifest: &PoolManifest,
+        report: &mut DecodeReport,
+    ) -> Result<Vec<u8>> {
+        if sequences.is_empty() {
+            return Err(DnaError::Decoding("Aucun oligo de données après le manifeste".to_string()));
+        }
+
+        let codec = {
+            let registry = CodecRegistry::global().read();
+            registry
+                .get(&manifest.scheme)
+                .or_else(|| registry.for_type(manifest.encoder_config.encoder_type))
+        }
+        .ok_or_else(|| DnaError::Decoding(format!("Schéma inconnu du registre: {}", manifest.scheme)))?;
+
+        // Sans métadonnées, l'ordre des oligos n'est lisible que dans leurs bases
+        let unaddressed = codec.encoder_type().is_some_and(|t| !t.addresses_in_bases());
+        if unaddressed && sequences.iter().all(|s| s.metadata.encoding_scheme != manifest.scheme) {
+            return Err(DnaError::Decoding(format!(
+                "Le schéma {} n'inscrit pas l'adresse des oligos dans leurs bases: lectures sans \
+                 métadonnées (FASTQ, consensus) non décodables, utiliser le FASTA de l'encodeur",
+                manifest.scheme
+            )));
+        }
+
+        // Lectures sans métadonnées (FASTQ, consensus): découpage fontaine tiré du manifeste
+        let restored: Vec<DnaSequence>;
+        let sequences = if sequences.iter().all(|s| s.metadata.total_chunks == 0) {
+            let stream_length = manifest.stream_length as usize;
+            let chunk_size = Encoder::fountain_chunk_size(&manifest.encoder_config).max(1);
+            restored = sequences
+                .iter()
+                .cloned()
+                .map(|mut sequence| {
+                    sequence.metadata.total_chunks = stream_length.div_ceil(chunk_size);
+                    sequence.metadata.source_length = stream_length;
+                    sequence
+                })
+                .collect();
+            restored.as_slice()
+        } else {
+            sequences
+        };
+        let data = codec.decode(sequences, &manifest.encoder_config, &self.config)?;
+
+        // CRC32 par chunk, sur le flux avant décompression
+        report.failed_chunks = manifest.failing_chunks(&data);
+        if !report.failed_chunks.is_empty() {
+            self.integrity_failure(
+                DnaError::ChecksumMismatch {
+                    expected: format!("CRC32 valides pour {} chunks", manifest.chunk_crc32.len()),
+                    actual: format!("chunks en échec: {:?}", report.failed_chunks),
+                },
+                report,
+            )?;
+        }
+
+        // La compression est connue: aucune détection nécessaire
+        if !self.config.auto_decompress && manifest.compression != EncoderCompression::None {
+            report
+                .warnings
+                .push("SHA-256 non vérifié: données laissées compressées".to_string());
+            return Ok(data);
+        }
+
+        let result = match manifest.compression {
+            EncoderCompression::Lz4 => self.decompress_as(&data, CompressionType::Lz4)?,
+            EncoderCompression::Zstd => self.decompress_as(&data, CompressionType::Zstd)?,
+            EncoderCompression::None => data,
+        };
+
+        self.verify_integrity(&result, Some(manifest), report)?;
+        Ok(result)
+    }
+
+    /// Décode les oligos de données d'un schéma intégré
+    pub(crate) fn decode_builtin(
+        &self,
+        encoder_type: EncoderType,
+        sequences: &[DnaSequence],
+        constraints: &DnaConstraints,
+    ) -> Result<Vec<u8>> {
+        match encoder_type {
+            EncoderType::ErlichZielinski2017 => self.decode_erlich_zielinski_2017(sequences),
+            EncoderType::Fountain | EncoderType::Adaptive => self.decode_fountain(sequences),
+            EncoderType::Goldman2013 => Goldman2013Decoder::new(constraints.clone()).decode(sequences),
+            EncoderType::Grass2015 => Grass2015Decoder::new(constraints.clone()).decode(sequences),
+            EncoderType::Goldman | EncoderType::Base3 => self.decode_ordered_chunks(sequences),
+            EncoderType::VtMarker => VtMarkerDecoder::new().decode(sequences),
+            EncoderType::ProductCode => ProductCodeDecoder::new().decode(sequences),
+        }
+    }
+
+    /// Décodage simple (Goldman-like): concaténation dans l'ordre des chunks
+    fn decode_ordered_chunks(&self, sequences: &[DnaSequence]) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+
+        let mut sorted_seqs: Vec<_> = sequences.iter().collect();
+        sorted_seqs.sort_by_key(|s| s.metadata.chunk_index);
+
+        for seq in sorted_seqs {
+            let chunk_data = self.sequence_to_chunk(seq)?;
+            data.extend_from_slice(&chunk_data);
+        }
+
+        Ok(data)
+    }
+
+    /// Décode un pool DNA Fountain (LT codes)
+    ///
+    /// Le seed de chaque droplet est lu dans ses bases, ce qui suffit à
+    /// décoder des lectures sans métadonnées. La composition du droplet est
+    /// régénérée depuis ce seed avec la même logique que l'encodeur, sa charge
+    /// est débarrassée du blanchiment dérivé du même seed, puis les droplets
+    /// sont injectés dans le `FountainDecoder` jusqu'à récupération de tous
+    /// les chunks.
+    fn decode_fountain(&self, sequences: &[DnaSequence]) -> Result<Vec<u8>> {
+        let num_chunks = sequences[0].metadata.total_chunks;
+        let source_length = sequences[0].metadata.source_length;
+
+        // En-têtes sans `chunks:` (pools antérieurs au décodage LT): ordre des chunks
+        if sequences.iter().all(|s| s.metadata.total_chunks == 0) {
+            return self.decode_ordered_chunks(sequences);
+        }
+
+        if sequences.iter().any(|s| s.metadata.total_chunks != num_chunks) {
+            return Err(DnaError::Decoding(
+                "Droplets issus d'encodages différents (nombre de chunks incohérent)".to_string()
+            ));
+        }
+
+        let chunk_size = max_chunk_size(num_chunks, source_length);
+        let mut fountain = FountainDecoder::new(self.config.clone(), num_chunks, chunk_size);
+        let (droplets, rejected) = self.fountain_droplets(sequences, chunk_size);
+
+        if rejected > 0 {
+            tracing::warn!("Fountain: {} oligos écartés (illisibles, trop longs ou minoritaires)", rejected);
+        }
+
+        for (seed, payload) in droplets {
+            let degree = Encoder::sample_robust_soliton_degree(num_chunks, seed);
+            let chunk_indices = Encoder::select_chunk_indices(num_chunks, degree, seed);
+
+            if let Progress::Complete(mut data) =
+                fountain.add_droplet(Droplet::new(chunk_indices, payload, seed))?
+            {
+                // Le dernier chunk peut avoir été complété par des zéros lors du XOR
+                data.truncate(source_length);
+                return Ok(data);
+            }
+        }
+
+        Self::finish_with_gaussian_elimination(fountain, num_chunks, source_length)
+    }
+
+    /// Retient une charge par seed parmi les oligos d'un pool fontaine
+    ///
+    /// Les oligos illisibles, dont le CRC-16 échoue ou plus longs qu'un chunk
+    /// sont écartés. Quand les copies d'un droplet divergent, la charge
+    /// strictement majoritaire est retenue; sans majorité, le droplet entier
+    /// est écarté plutôt que de propager une charge altérée. Retourne aussi le
+    /// nombre d'oligos écartés.
+    fn fountain_droplets(&self, sequences: &[DnaSequence], chunk_size: usize) -> (Vec<(u64, Vec<u8>)>, usize) {
+        let mut order = Vec::new();
+        let mut copies: HashMap<u64, Vec<(Vec<u8>, usize)>> = HashMap::new();
+        let mut rejected = 0usize;
+
+        for seq in sequences {
+            // Seed lu dans les bases, vérifié avec la charge par le CRC-16 de l'oligo
+            let droplet = self
+                .sequence_to_chunk(seq)
+                .ok()
+                .and_then(|bytes| Encoder::read_fountain_oligo(&bytes))
+                .filter(|(_, payload)| payload.len() <= chunk_size);
+            let Some((seed, payload)) = droplet else {
+                rejected += 1;
+                continue;
+            };
+
+            let variants = copies.entry(seed).or_insert_with(|| {
+                order.push(seed);
+                Vec::new()
+            });
+            match variants.iter_mut().find(|(p, _)| *p == payload) {
+                Some((_, count)) => *count += 1,
+                None => variants.push((payload, 1)),
+            }
+        }
+
+        let mut droplets = Vec::with_capacity(order.len());
+        for seed in order {
+            let mut variants = copies.remove(&seed).unwrap_or_default();
+            variants.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+            let copies: usize = variants.iter().map(|(_, count)| count).sum();
+            if variants.len() > 1 && variants[0].1 == variants[1].1 {
+                rejected += copies;
+                continue;
+            }
+
+            let (payload, count) = variants.swap_remove(0);
+            rejected += copies - count;
+            droplets.push((seed, payload));
+        }
+
+        (droplets, rejected)
+    }
+
+    /// Décode un pool Erlich-Zielinski 2017
+    ///
+    /// 1. Décodage GC-aware de chaque oligo (seed, degré, payload)
+    /// 2. Rejet des oligos dont le CRC interne échoue
+    /// 3. Peeling LT, puis élimination de Gauss si le peeling stagne
+    fn decode_erlich_zielinski_2017(&self, sequences: &[DnaSequence]) -> Result<Vec<u8>> {
+        // Contraintes EZ 2017: GC 40-60%, homopolymer <4, 152nt
+        let ez_constraints = DnaConstraints::new(0.40, 0.60, 3, 152);
+        let gc_decoder = GcAwareDecoder::new(ez_constraints);
+
+        let num_chunks = sequences
+            .iter()
+            .map(|s| s.metadata.total_chunks)
+            .max()
+            .unwrap_or(0);
+        let source_length = sequences
+            .iter()
+            .map(|s| s.metadata.source_length)
+            .max()
+            .unwrap_or(0);
+
+        if num_chunks == 0 {
+            return Err(DnaError::Decoding(
+                "Nombre de chunks source inconnu pour EZ 2017".to_string()
+            ));
+        }
+
+        let mut fountain = FountainDecoder::new(self.config.clone(), num_chunks, MAX_PAYLOAD_BYTES);
+        let mut seen_seeds = HashSet::new();
+        let mut rejected = 0usize;
+
+        for seq in sequences {
+            // Oligo corrompu (CRC invalide ou header illisible): on l'écarte
+            let droplet = match gc_decoder.decode_droplet(seq) {
+                Ok(droplet) => droplet,
+                Err(_) => {
+                    rejected += 1;
+                    continue;
+                }
+            };
+
+            // Le header ne conserve que les 8 bits de poids faible du degré
+            let degree = Encoder::sample_robust_soliton_degree_ez2017(num_chunks, droplet.seed);
+            if degree as u8 != droplet.degree as u8 {
+                rejected += 1;
+                continue;
+            }
+
+            if !seen_seeds.insert(droplet.seed) {
+                continue;
+            }
+
+            let chunk_indices = Encoder::select_chunk_indices(num_chunks, degree, droplet.seed);
+
+            if let Progress::Complete(mut data) =
+                fountain.add_droplet(Droplet::new(chunk_indices, droplet.payload, droplet.seed))?
+            {
+                data.truncate(source_length);
+                return Ok(data);
+            }
+        }
+
+        if rejected > 0 {
+            tracing::warn!("EZ 2017: {} oligos rejetés (CRC invalide)", rejected);
+        }
+
+        Self::finish_with_gaussian_elimination(fountain, num_chunks, source_length)
+    }
+
+    /// Termine un décodage fontaine dont le peeling a stagné
+    fn finish_with_gaussian_elimination(
+        mut fountain: FountainDecoder,
+        num_chunks: usize,
+        source_length: usize,
+    ) -> Result<Vec<u8>> {
+        match fountain.gaussian_elimination()? {
+            Progress::Complete(mut data) => {
+                data.truncate(source_length);
+                Ok(data)
+            }
+            Progress::Incomplete => Err(DnaError::InsufficientData {
+                need: num_chunks,
+                have: fountain.recovered_count(),
+            }),
+        }
+    }
+
     /// Vérifie l'intégrité des données décodées
-    fn verify_integrity(&self, data: &[u8]) -> Result<()> {
+    ///
+    /// Sans manifeste (ancien format), seule la présence de données est vérifiable.
+    fn verify_integrity(
+        &self,
+        data: &[u8],
+        manifest: Option<&PoolManifest>,
+        report: &mut DecodeReport,
+    ) -> Result<()> {
         // Vérification basique de la taille
         if data.is_empty() {
             return Err(DnaError::Decoding("Données décodées vides".to_string()));
         }
-        
-        // Ajouter d'autres vérifications d'intégrité ici
+
+        let manifest = match manifest {
+            Some(manifest) => manifest,
+            None => {
+                report
+                    .warnings
+                    .push("Aucun manifeste: intégrité du fichier non vérifiable".to_string());
+                return Ok(());
+            }
+        };
+
+        match manifest.verify(data) {
+            Ok(()) => {
+                report.sha256_verified = Some(true);
+                Ok(())
+            }
+            Err(DnaError::ChecksumMismatch { expected, actual }) => {
+                report.sha256_verified = Some(false);
+                let actual = if report.failed_chunks.is_empty() {
+                    actual
+                } else {
+                    format!("{} (chunks en échec: {:?})", actual, report.failed_chunks)
+                };
+                self.integrity_failure(DnaError::ChecksumMismatch { expected, actual }, report)
+            }
+            Err(e) => {
+                report.sha256_verified = Some(false);
+                self.integrity_failure(e, report)
+            }
+        }
+    }
+
+    /// Erreur d'intégrité: fatale, ou simple avertissement avec `ignore_checksum`
+    fn integrity_failure(&self, error: DnaError, report: &mut DecodeReport) -> Result<()> {
+        if !self.config.ignore_checksum {
+            return Err(error);
+        }
+
+        tracing::warn!("Intégrité ignorée (ignore_checksum): {}", error);
+        report.warnings.push(error.to_string());
         Ok(())
     }
 
@@ -267,6 +717,11 @@ impl Decoder {
             other => other,
         };
 
+        self.decompress_as(data, compression_type)
+    }
+
+    /// Décompresse avec un type de compression explicite
+    fn decompress_as(&self, data: &[u8], compression_type: CompressionType) -> Result<Vec<u8>> {
         match compression_type {
             CompressionType::Lz4 => {
                 lz4::block::decompress(data, None)
@@ -288,11 +743,13 @@ pub struct FountainDecoder {
     droplets: Vec<Droplet>,
     received: usize,
     required: usize,
-    _chunk_size: usize,
+    chunk_size: usize,
 }
 
 impl FountainDecoder {
     /// Crée un nouveau décodeur Fountain
+    ///
+    /// `chunk_size` est la taille maximale du payload d'un droplet.
     pub fn new(config: DecoderConfig, required_chunks: usize, chunk_size: usize) -> Self {
         Self {
             config,
@@ -300,13 +757,32 @@ impl FountainDecoder {
             droplets: Vec::new(),
             received: 0,
             required: required_chunks,
-            _chunk_size: chunk_size,
+            chunk_size,
         }
     }
 
     /// Ajoute un droplet et tente de progresser dans le décodage
-    pub fn add_droplet(&mut self, droplet: Droplet) -> Result<Progress> {
+    pub fn add_droplet(&mut self, mut droplet: Droplet) -> Result<Progress> {
+        // Un payload plus long qu'un chunk ne peut venir que d'un oligo altéré
+        if droplet.payload.len() > self.chunk_size {
+            return Err(DnaError::Decoding(format!(
+                "Droplet {}: payload de {} octets pour des chunks de {} octets",
+                droplet.seed,
+                droplet.payload.len(),
+                self.chunk_size
+            )));
+        }
+
         self.received += 1;
+
+        // Retirer du nouveau droplet les chunks déjà récupérés
+        for &idx in &droplet.chunk_indices {
+            if let Some(chunk) = self.chunks.get(&idx) {
+                xor_bytes(&mut droplet.payload, chunk);
+            }
+        }
+        droplet.chunk_indices.retain(|idx| !self.chunks.contains_key(idx));
+
         self.droplets.push(droplet);
 
         // Tenter de décoder avec belief propagation
@@ -371,6 +847,76 @@ impl FountainDecoder {
         }
     }
 
+    /// Résout les chunks restants par élimination de Gauss sur GF(2)
+    ///
+    /// Utilisé lorsque le peeling stagne: les droplets restants forment un
+    /// système linéaire qui peut encore être de rang plein sans contenir
+    /// de droplet de degré 1.
+    pub fn gaussian_elimination(&mut self) -> Result<Progress> {
+        if self.is_complete() {
+            return Ok(Progress::Complete(self.reassemble()?));
+        }
+
+        // Une colonne par chunk encore inconnu
+        let unknown: Vec<usize> = (0..self.required)
+            .filter(|idx| !self.chunks.contains_key(idx))
+            .collect();
+        let column_of: HashMap<usize, usize> = unknown
+            .iter()
+            .enumerate()
+            .map(|(col, &idx)| (idx, col))
+            .collect();
+        let words = unknown.len().div_ceil(64);
+        let payload_len = self.droplets.iter().map(|d| d.payload.len()).max().unwrap_or(0);
+
+        let has_bit = |bits: &[u64], col: usize| (bits[col / 64] >> (col % 64)) & 1 == 1;
+
+        // Lignes du système: (coefficients, payload)
+        let mut rows: Vec<(Vec<u64>, Vec<u8>)> = self
+            .droplets
+            .iter()
+            .filter(|d| d.degree() > 0)
+            .map(|d| {
+                let mut bits = vec![0u64; words];
+                for idx in &d.chunk_indices {
+                    if let Some(&col) = column_of.get(idx) {
+                        bits[col / 64] ^= 1u64 << (col % 64);
+                    }
+                }
+                let mut payload = d.payload.clone();
+                payload.resize(payload_len, 0);
+                (bits, payload)
+            })
+            .collect();
+
+        for col in 0..unknown.len() {
+            let pivot = match (col..rows.len()).find(|&r| has_bit(&rows[r].0, col)) {
+                Some(r) => r,
+                // Rang insuffisant: il manque des droplets
+                None => return Ok(Progress::Incomplete),
+            };
+            rows.swap(col, pivot);
+
+            let (pivot_bits, pivot_payload) = rows[col].clone();
+            for (r, row) in rows.iter_mut().enumerate() {
+                if r != col && has_bit(&row.0, col) {
+                    for (word, pivot_word) in row.0.iter_mut().zip(&pivot_bits) {
+                        *word ^= pivot_word;
+                    }
+                    xor_bytes(&mut row.1, &pivot_payload);
+                }
+            }
+        }
+
+        // Forme réduite: la ligne `col` contient exactement le chunk unknown[col]
+        for (col, &idx) in unknown.iter().enumerate() {
+            self.chunks.insert(idx, rows[col].1.clone());
+        }
+        self.droplets.clear();
+
+        Ok(Progress::Complete(self.reassemble()?))
+    }
+
     /// Trouve tous les indices des droplets de degré 1
     fn find_degree_one_droplets(&self) -> Vec<usize> {
         self.droplets.iter()
@@ -428,6 +974,18 @@ impl FountainDecoder {
     }
 }
 
+/// Plus grande taille de chunk compatible avec un découpage fontaine
+///
+/// Tous les chunks sauf le dernier sont pleins: `(num_chunks - 1) * taille`
+/// reste strictement inférieur à `source_length`.
+fn max_chunk_size(num_chunks: usize, source_length: usize) -> usize {
+    if num_chunks <= 1 {
+        source_length
+    } else {
+        source_length.saturating_sub(1) / (num_chunks - 1)
+    }
+}
+
 /// XOR deux tableaux d'octets in-place
 fn xor_bytes(dest: &mut [u8], src: &[u8]) {
     for (i, &byte) in src.iter().enumerate() {
@@ -501,6 +1059,7 @@ mod tests {
                     crate::sequence::IupacBase::G,
                     crate::sequence::IupacBase::T,
                 ],
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -520,6 +1079,385 @@ mod tests {
         assert_eq!(original.to_vec(), recovered);
     }
 
+    #[test]
+    fn test_roundtrip_vt_marker_with_indels() {
+        let encoder_config = EncoderConfig {
+            encoder_type: EncoderType::VtMarker,
+            chunk_size: 15,
+            compression_enabled: false,
+            ..Default::default()
+        };
+        let encoder = Encoder::new(encoder_config).unwrap();
+
+        let original: Vec<u8> = (0..150u8).map(|i| i.wrapping_mul(7)).collect();
+        let mut sequences = encoder.encode(&original).unwrap();
+
+        // Une délétion et une insertion par oligo de données, dans des segments distincts
+        for sequence in sequences.iter_mut().filter(|s| !PoolManifest::is_header_oligo(s)) {
+            sequence.bases.insert(40, crate::sequence::IupacBase::A);
+            sequence.bases.remove(3);
+        }
+
+        let decoder = Decoder::new(DecoderConfig::default());
+        let (recovered, report) = decoder.decode_with_report(&sequences).unwrap();
+        assert_eq!(recovered, original);
+        assert!(report.is_intact());
+    }
+
+    #[test]
+    fn test_roundtrip_product_code_with_strand_loss() {
+        let encoder_config = EncoderConfig {
+            encoder_type: EncoderType::ProductCode,
+            chunk_size: 24,
+            redundancy: 1.125,
+            compression_enabled: false,
+            ..Default::default()
+        };
+        let encoder = Encoder::new(encoder_config).unwrap();
+
+        let original: Vec<u8> = (0..2000u32).map(|i| (i * 13 % 256) as u8).collect();
+        let mut sequences = encoder.encode(&original).unwrap();
+
+        // Perte de 5% des brins de données, au plus 4 par groupe de 36
+        let data_start = sequences.iter().take_while(|s| PoolManifest::is_header_oligo(s)).count();
+        let lost: Vec<usize> = (data_start..sequences.len()).step_by(9).collect();
+        assert!(lost.len() * 20 >= sequences.len() - data_start);
+        for index in lost.into_iter().rev() {
+            sequences.remove(index);
+        }
+
+        let decoder = Decoder::new(DecoderConfig::default());
+        let (recovered, report) = decoder.decode_with_report(&sequences).unwrap();
+        assert_eq!(recovered, original);
+        assert!(report.is_intact());
+    }
+
+    fn lenient_constraints() -> crate::sequence::DnaConstraints {
+        crate::sequence::DnaConstraints {
+            gc_min: 0.0,
+            gc_max: 1.0,
+            max_homopolymer: 100,
+            max_sequence_length: 200,
+            allowed_bases: vec![
+                crate::sequence::IupacBase::A,
+                crate::sequence::IupacBase::C,
+                crate::sequence::IupacBase::G,
+                crate::sequence::IupacBase::T,
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_fountain() {
+        let encoder_config = EncoderConfig {
+            encoder_type: EncoderType::Fountain,
+            chunk_size: 4,
+            redundancy: 3.0,
+            compression_enabled: false,
+            constraints: lenient_constraints(),
+            ..Default::default()
+        };
+        let encoder = Encoder::new(encoder_config).unwrap();
+
+        // 38 octets → 10 chunks, le dernier incomplet
+        let original = b"LT droplets decoded by belief peeling!";
+        let sequences = encoder.encode(original).unwrap();
+        assert!(sequences
+            .iter()
+            .filter(|s| !PoolManifest::is_header_oligo(s))
+            .all(|s| s.metadata.total_chunks == 10));
+
+        let decoder = Decoder::new(DecoderConfig {
+            auto_decompress: false,
+            ..Default::default()
+        });
+
+        // Les paramètres fontaine doivent survivre à un passage par FASTA
+        let parsed: Vec<DnaSequence> = sequences
+            .iter()
+            .map(|s| DnaSequence::from_fasta(&s.to_fasta()).unwrap())
+            .collect();
+
+        let recovered = decoder.decode(&parsed).unwrap();
+        assert_eq!(original.to_vec(), recovered);
+    }
+
+    #[test]
+    fn test_fountain_skips_corrupted_and_oversized_droplets() {
+        let encoder_config = EncoderConfig {
+            encoder_type: EncoderType::Fountain,
+            chunk_size: 4,
+            redundancy: 3.0,
+            compression_enabled: false,
+            constraints: lenient_constraints(),
+            ..Default::default()
+        };
+        let encoder = Encoder::new(encoder_config).unwrap();
+        let original = b"Corrupted copies must not poison peeling";
+        let (headers, droplets): (Vec<DnaSequence>, Vec<DnaSequence>) =
+            encoder.encode(original).unwrap().into_iter().partition(PoolManifest::is_header_oligo);
+
+        // Chaque droplet: une copie altérée d'abord, puis deux copies intactes;
+        // le premier droplet a aussi une copie trop longue
+        let mut pool = headers;
+        for droplet in &droplets {
+            let mut corrupted = droplet.clone();
+            corrupted.bases[0] = if corrupted.bases[0] == IupacBase::A { IupacBase::C } else { IupacBase::A };
+            pool.push(corrupted);
+            pool.push(droplet.clone());
+            pool.push(droplet.clone());
+        }
+        let mut oversized = droplets[0].clone();
+        oversized.bases.extend([IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T].repeat(2));
+        pool.insert(pool.len() - 3 * droplets.len(), oversized);
+
+        let decoder = Decoder::new(DecoderConfig {
+            auto_decompress: false,
+            ..Default::default()
+        });
+        assert_eq!(decoder.decode(&pool).unwrap(), original.to_vec());
+    }
+
+    #[test]
+    fn test_legacy_fountain_header_without_chunk_count() {
+        let sequence = DnaSequence::from_fasta(">abc|scheme:fountain|seed:3|gc:50.00|len:8\nACGTACGT\n").unwrap();
+        assert_eq!(sequence.metadata.total_chunks, 0);
+
+        let decoder = Decoder::new(DecoderConfig {
+            auto_decompress: false,
+            ..Default::default()
+        });
+        assert_eq!(decoder.decode(&[sequence]).unwrap(), vec![0b0001_1011, 0b0001_1011]);
+    }
+
+    #[test]
+    fn test_fountain_insufficient_droplets() {
+        let encoder_config = EncoderConfig {
+            encoder_type: EncoderType::Fountain,
+            chunk_size: 4,
+            redundancy: 1.5,
+            compression_enabled: false,
+            constraints: lenient_constraints(),
+            ..Default::default()
+        };
+        let encoder = Encoder::new(encoder_config).unwrap();
+        let sequences = encoder.encode(b"Not enough droplets to peel this!!!!!!").unwrap();
+
+        let decoder = Decoder::new(DecoderConfig {
+            auto_decompress: false,
+            ..Default::default()
+        });
+
+        // Manifeste complet, mais un seul droplet
+        let (headers, droplets): (Vec<DnaSequence>, Vec<DnaSequence>) =
+            sequences.into_iter().partition(PoolManifest::is_header_oligo);
+        let mut pool = headers;
+        pool.push(droplets[0].clone());
+
+        let result = decoder.decode(&pool);
+        assert!(matches!(result, Err(DnaError::InsufficientData { need: 10, .. })));
+    }
+
+    #[test]
+    fn test_manifest_selects_compression() {
+        let encoder_config = EncoderConfig {
+            encoder_type: EncoderType::Fountain,
+            chunk_size: 8,
+            redundancy: 3.0,
+            compression_enabled: true,
+            compression_type: crate::codec::encoder::CompressionType::Zstd,
+            constraints: lenient_constraints(),
+        };
+        let encoder = Encoder::new(encoder_config).unwrap();
+
+        let original = b"Zstd payload described by the manifest, no guessing needed.".repeat(4);
+        let sequences = encoder.encode(&original).unwrap();
+
+        // Un décodeur configuré pour LZ4 suit malgré tout le manifeste
+        let decoder = Decoder::new(DecoderConfig {
+            compression_type: CompressionType::Lz4,
+            ..Default::default()
+        });
+
+        assert_eq!(decoder.decode(&sequences).unwrap(), original);
+    }
+
+    #[test]
+    fn test_manifest_detects_wrong_payload() {
+        let encoder_config = EncoderConfig {
+            encoder_type: EncoderType::Goldman,
+            chunk_size: 4,
+            compression_enabled: false,
+            constraints: lenient_constraints(),
+            ..Default::default()
+        };
+        let encoder = Encoder::new(encoder_config).unwrap();
+        let mut sequences = encoder.encode(b"checksummed!").unwrap();
+
+        // Corrompre le premier oligo de données: le SHA-256 ne correspond plus
+        let data_oligo = sequences
+            .iter_mut()
+            .find(|s| !PoolManifest::is_header_oligo(s))
+            .unwrap();
+        data_oligo.bases[0] = if data_oligo.bases[0] == IupacBase::A { IupacBase::C } else { IupacBase::A };
+
+        let decoder = Decoder::new(DecoderConfig::default());
+        assert!(matches!(
+            decoder.decode(&sequences),
+            Err(DnaError::ChecksumMismatch { .. })
+        ));
+    }
+
+    fn checksummed_pool() -> Vec<DnaSequence> {
+        let encoder_config = EncoderConfig {
+            encoder_type: EncoderType::Goldman,
+            chunk_size: 4,
+            compression_enabled: false,
+            constraints: lenient_constraints(),
+            ..Default::default()
+        };
+        let encoder = Encoder::new(encoder_config).unwrap();
+        encoder.encode(b"chunks 0..3 of data").unwrap()
+    }
+
+    /// Corrompt le premier octet du chunk de données `chunk_index`
+    fn corrupt_chunk(sequences: &mut [DnaSequence], chunk_index: usize) {
+        let seq = sequences
+            .iter_mut()
+            .find(|s| !PoolManifest::is_header_oligo(s) && s.metadata.chunk_index == chunk_index)
+            .unwrap();
+        seq.bases[0] = if seq.bases[0] == IupacBase::A { IupacBase::C } else { IupacBase::A };
+    }
+
+    #[test]
+    fn test_checksum_mismatch_names_failing_chunks() {
+        let mut sequences = checksummed_pool();
+        corrupt_chunk(&mut sequences, 1);
+        corrupt_chunk(&mut sequences, 3);
+
+        let decoder = Decoder::new(DecoderConfig::default());
+        match decoder.decode(&sequences) {
+            Err(DnaError::ChecksumMismatch { actual, .. }) => assert!(actual.contains("[1, 3]")),
+            other => panic!("ChecksumMismatch attendu, obtenu {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_ignore_checksum_reports_warning() {
+        let mut sequences = checksummed_pool();
+        corrupt_chunk(&mut sequences, 2);
+
+        let decoder = Decoder::new(DecoderConfig {
+            ignore_checksum: true,
+            ..Default::default()
+        });
+
+        let (data, report) = decoder.decode_with_report(&sequences).unwrap();
+        assert_eq!(data.len(), 19);
+        assert!(report.manifest_found);
+        assert_eq!(report.failed_chunks, vec![2]);
+        assert_eq!(report.sha256_verified, Some(false));
+        assert!(!report.is_intact());
+        assert_eq!(report.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_intact_pool_report() {
+        let sequences = checksummed_pool();
+
+        let decoder = Decoder::new(DecoderConfig::default());
+        let (data, report) = decoder.decode_with_report(&sequences).unwrap();
+
+        assert_eq!(data, b"chunks 0..3 of data".to_vec());
+        assert!(report.is_intact());
+        assert_eq!(report.sha256_verified, Some(true));
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_decode_from_fasta_auto_uses_manifest() {
+        let encoder_config = EncoderConfig {
+            encoder_type: EncoderType::Goldman,
+            chunk_size: 4,
+            compression_enabled: true,
+            compression_type: crate::codec::encoder::CompressionType::Lz4,
+            constraints: lenient_constraints(),
+            ..Default::default()
+        };
+        let encoder = Encoder::new(encoder_config).unwrap();
+
+        let original = b"FASTA file decoded from its own manifest".to_vec();
+        let sequences = encoder.encode(&original).unwrap();
+
+        let path = std::env::temp_dir().join(format!("adn_manifest_{}.fasta", std::process::id()));
+        let fasta: String = sequences.iter().map(|s| s.to_fasta()).collect();
+        std::fs::write(&path, fasta).unwrap();
+
+        let decoder = Decoder::new(DecoderConfig::default());
+        let recovered = decoder.decode_from_fasta_auto(path.to_str().unwrap());
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(recovered.unwrap(), original);
+    }
+
+    fn ez2017_encoder() -> Encoder {
+        Encoder::new(EncoderConfig {
+            encoder_type: EncoderType::ErlichZielinski2017,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip_erlich_zielinski_2017() {
+        let encoder = ez2017_encoder();
+        let decoder = Decoder::new(DecoderConfig::default());
+
+        for size in [1u32, 3, 17, 100, 1000] {
+            let original: Vec<u8> = (0..size).map(|i| (i * 37 % 251) as u8).collect();
+            let sequences = encoder.encode(&original).unwrap();
+
+            let parsed: Vec<DnaSequence> = sequences
+                .iter()
+                .map(|s| DnaSequence::from_fasta(&s.to_fasta()).unwrap())
+                .collect();
+
+            assert_eq!(decoder.decode(&parsed).unwrap(), original, "taille {}", size);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_erlich_zielinski_2017_zero_bytes() {
+        // Un seul chunk de zéros: chaque seed doit donner un oligo différent
+        let original = vec![0u8; 20];
+        let sequences = ez2017_encoder().encode(&original).unwrap();
+
+        let decoder = Decoder::new(DecoderConfig::default());
+        assert_eq!(decoder.decode(&sequences).unwrap(), original);
+    }
+
+    #[test]
+    fn test_erlich_zielinski_2017_drops_corrupted_oligos() {
+        let original: Vec<u8> = (0..1000u32).map(|i| (i * 53 % 241) as u8).collect();
+        let sequences = ez2017_encoder().encode(&original).unwrap();
+        let (manifest, droplets): (Vec<_>, Vec<_>) =
+            sequences.into_iter().partition(PoolManifest::is_header_oligo);
+
+        // Copies corrompues (section DATA) lues avant les copies intactes:
+        // leur CRC doit échouer au lieu d'empoisonner le peeling
+        let mut pool = manifest;
+        for seq in droplets.iter().take(2) {
+            let mut corrupted = seq.clone();
+            corrupted.bases[25] = if corrupted.bases[25] == IupacBase::A { IupacBase::T } else { IupacBase::A };
+            pool.push(corrupted);
+        }
+        pool.extend(droplets);
+
+        let decoder = Decoder::new(DecoderConfig::default());
+        assert_eq!(decoder.decode(&pool).unwrap(), original);
+    }
+
     #[test]
     fn test_sequence_to_chunk() {
         // Note: DnaSequence n'a pas de champs A, C, G, T accessibles directement
@@ -593,6 +1531,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fountain_decoder_rejects_oversized_payload() {
+        let mut decoder = FountainDecoder::new(DecoderConfig::default(), 2, 4);
+
+        let oversized = Droplet::new(vec![0], vec![1, 2, 3, 4, 5], 0);
+        assert!(matches!(decoder.add_droplet(oversized), Err(DnaError::Decoding(_))));
+        assert_eq!(decoder.received_count(), 0);
+    }
+
     #[test]
     fn test_fountain_decoder_insufficient_data() {
         let config = DecoderConfig::default();
@@ -611,6 +1558,81 @@ mod tests {
         assert!(!decoder.is_complete());
     }
 
+    #[test]
+    fn test_fountain_decoder_late_droplet() {
+        let config = DecoderConfig::default();
+        let mut decoder = FountainDecoder::new(config, 2, 4);
+
+        let chunk0 = vec![1, 2, 3, 4];
+        let chunk1 = vec![5, 6, 7, 8];
+
+        // chunk0 est récupéré avant l'arrivée des droplets qui le contiennent
+        let droplet1 = Droplet::new(vec![0], chunk0.clone(), 0);
+        assert!(matches!(decoder.add_droplet(droplet1).unwrap(), Progress::Incomplete));
+
+        // Un doublon de chunk0 ne doit pas bloquer le décodage
+        let duplicate = Droplet::new(vec![0], chunk0.clone(), 1);
+        assert!(matches!(decoder.add_droplet(duplicate).unwrap(), Progress::Incomplete));
+
+        let mut payload = chunk0.clone();
+        xor_bytes(&mut payload, &chunk1);
+        let droplet2 = Droplet::new(vec![0, 1], payload, 2);
+
+        let result = decoder.add_droplet(droplet2).unwrap();
+        if let Progress::Complete(data) = result {
+            assert_eq!(data, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        } else {
+            panic!("Le décodage aurait dû être complet");
+        }
+    }
+
+    #[test]
+    fn test_fountain_decoder_gaussian_elimination() {
+        let config = DecoderConfig::default();
+        let mut decoder = FountainDecoder::new(config, 3, 2);
+
+        let chunks = [vec![1u8, 2], vec![3u8, 4], vec![5u8, 6]];
+        let combine = |indices: &[usize]| {
+            let mut payload = vec![0u8; 2];
+            for &idx in indices {
+                xor_bytes(&mut payload, &chunks[idx]);
+            }
+            payload
+        };
+
+        // Aucun droplet de degré 1: le peeling seul ne peut pas progresser
+        for (seed, indices) in [vec![0, 1], vec![1, 2], vec![0, 1, 2]].into_iter().enumerate() {
+            let payload = combine(&indices);
+            let progress = decoder.add_droplet(Droplet::new(indices, payload, seed as u64)).unwrap();
+            assert!(matches!(progress, Progress::Incomplete));
+        }
+        assert_eq!(decoder.recovered_count(), 0);
+
+        match decoder.gaussian_elimination().unwrap() {
+            Progress::Complete(data) => assert_eq!(data, vec![1, 2, 3, 4, 5, 6]),
+            Progress::Incomplete => panic!("Le système est de rang plein"),
+        }
+    }
+
+    #[test]
+    fn test_fountain_decoder_gaussian_rank_deficient() {
+        let config = DecoderConfig::default();
+        let mut decoder = FountainDecoder::new(config, 3, 1);
+
+        decoder.add_droplet(Droplet::new(vec![0, 1], vec![7], 0)).unwrap();
+        decoder.add_droplet(Droplet::new(vec![0, 1], vec![7], 1)).unwrap();
+
+        assert!(matches!(decoder.gaussian_elimination().unwrap(), Progress::Incomplete));
+    }
+
+    #[test]
+    fn test_max_chunk_size() {
+        // 32 + 32 + 5 octets: toute taille de 23 à 34 produit 3 chunks
+        assert_eq!(max_chunk_size(3, 69), 34);
+        assert_eq!(max_chunk_size(1, 17), 17);
+        assert_eq!(max_chunk_size(4, 128), 42);
+    }
+
     #[test]
     fn test_xor_bytes() {
         let mut a = vec![0b11110000, 0b10101010];
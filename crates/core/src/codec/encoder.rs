@@ -1,5 +1,10 @@
 //! Encodeur ADN - Implémente DNA Fountain et autres algorithmes
 
+use crate::bio::PrimerPair;
+use crate::codec::manifest::{PoolManifest, SegmentInfo};
+use crate::codec::registry::{BuiltinCodec, DnaCodec};
+use crate::codec::stream::{read_segment, FastaSink, StreamSummary, DEFAULT_SEGMENT_SIZE};
+use crate::codec::whitening::whiten;
 use crate::error::{DnaError, Result};
 use crate::sequence::{DnaConstraints, DnaSequence, IupacBase};
 use rand::{Rng, SeedableRng};
@@ -7,10 +12,30 @@ use rand_chacha::ChaCha8Rng;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
 
 // Importer les macros depuis la racine du crate
 pub use crate::{log_operation, log_error};
 
+/// Seeds fontaine évalués en parallèle à chaque lot
+const FOUNTAIN_SEED_BATCH: u64 = 256;
+
+/// Seeds consécutifs rejetés au-delà desquels les contraintes sont jugées intenables
+const MAX_REJECTED_SEEDS: usize = 4096;
+
+/// Octets du seed (24 bits) en tête de chaque oligo fontaine
+const FOUNTAIN_SEED_BYTES: usize = 3;
+
+/// Plus grand seed inscriptible en tête d'oligo
+const MAX_FOUNTAIN_SEED: u64 = (1 << (8 * FOUNTAIN_SEED_BYTES)) - 1;
+
+/// Octets ajoutés à la charge d'un oligo fontaine: seed et CRC-16
+pub(crate) const FOUNTAIN_OVERHEAD_BYTES: usize = FOUNTAIN_SEED_BYTES + 2;
+
+/// Masque du seed en tête d'oligo (ACGT GATC TGCA pour le seed 0)
+const FOUNTAIN_SEED_MASK: u32 = 0x1B_8DE4;
+
 /// Type d'algorithme d'encodage
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EncoderType {
@@ -29,6 +54,48 @@ pub enum EncoderType {
     Adaptive,
     /// Encodage base-3 optimisé
     Base3,
+    /// Code interne VT + marqueurs de synchronisation, résistant aux insertions/délétions
+    VtMarker,
+    /// Code produit Reed-Solomon 2D: RS interne par oligo, RS externe par groupe d'oligos
+    ProductCode,
+}
+
+impl EncoderType {
+    /// Tous les schémas intégrés, dans l'ordre du registre
+    pub const ALL: [EncoderType; 9] = [
+        Self::Fountain,
+        Self::ErlichZielinski2017,
+        Self::Goldman2013,
+        Self::Goldman,
+        Self::Grass2015,
+        Self::Adaptive,
+        Self::Base3,
+        Self::VtMarker,
+        Self::ProductCode,
+    ];
+
+    /// Nom du schéma inscrit dans les séquences et le manifeste
+    pub fn scheme_name(self) -> &'static str {
+        match self {
+            Self::Fountain => "fountain",
+            Self::ErlichZielinski2017 => "erlich_zielinski_2017",
+            Self::Goldman2013 => "goldman_2013",
+            Self::Goldman => "goldman",
+            Self::Grass2015 => "grass_2015",
+            Self::Adaptive => "adaptive",
+            Self::Base3 => "base3",
+            Self::VtMarker => "vt_marker",
+            Self::ProductCode => "product_rs",
+        }
+    }
+
+    /// Retourne true si chaque oligo porte son adresse (seed, index) dans ses bases
+    ///
+    /// Les autres schémas ne retrouvent l'ordre des oligos que dans les
+    /// métadonnées FASTA, absentes des lectures de séquençage.
+    pub fn addresses_in_bases(self) -> bool {
+        !matches!(self, Self::Goldman | Self::Base3)
+    }
 }
 
 impl Default for EncoderType {
@@ -83,55 +150,145 @@ pub enum CompressionType {
 /// Encodeur ADN principal
 pub struct Encoder {
     config: EncoderConfig,
+    segment_size: usize,
+    codec: Arc<dyn DnaCodec>,
+    primers: Option<PrimerPair>,
 }
 
 impl Encoder {
     /// Crée un nouvel encodeur
     pub fn new(config: EncoderConfig) -> Result<Self> {
-        Ok(Self { config })
+        Ok(Self {
+            codec: Arc::new(BuiltinCodec::new(config.encoder_type)),
+            config,
+            segment_size: DEFAULT_SEGMENT_SIZE,
+            primers: None,
+        })
+    }
+
+    /// Encode avec un codec du registre au lieu du schéma de `encoder_type`
+    pub fn with_codec(mut self, codec: Arc<dyn DnaCodec>) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Encadre chaque oligo (manifeste compris) par les sites d'une paire d'amorces
+    ///
+    /// Le pool du fichier peut alors être extrait d'un pool partagé par
+    /// `PrimerPair::amplify`, qui retire les sites avant le décodage.
+    pub fn with_primer_pair(mut self, pair: PrimerPair) -> Self {
+        self.primers = Some(pair);
+        self
+    }
+
+    /// Définit la taille des segments de `encode_stream`
+    pub fn with_segment_size(mut self, segment_size: usize) -> Self {
+        self.segment_size = segment_size.max(1);
+        self
     }
 
     /// Retourne le nom du schéma d'encodage actuel
     fn encoding_scheme_name(&self) -> &'static str {
-        match self.config.encoder_type {
-            EncoderType::Fountain => "fountain",
-            EncoderType::ErlichZielinski2017 => "erlich_zielinski_2017",
-            EncoderType::Goldman2013 => "goldman_2013",
-            EncoderType::Goldman => "goldman",
-            EncoderType::Grass2015 => "grass_2015",
-            EncoderType::Adaptive => "adaptive",
-            EncoderType::Base3 => "base3",
-        }
+        self.config.encoder_type.scheme_name()
     }
 
     /// Encode des données en séquences ADN avec optimisation de performance
     pub fn encode(&self, data: &[u8]) -> Result<Vec<DnaSequence>> {
+        self.encode_pool(data, None)
+    }
+
+    /// Encode un flux par segments de taille fixe
+    ///
+    /// Chaque segment devient un pool indépendant (manifeste + oligos de
+    /// données) écrit dans `sink` dès qu'il est encodé: la mémoire utilisée
+    /// ne dépend que de la taille des segments, pas de celle du flux.
+    pub fn encode_stream<R: Read, W: FastaSink>(&self, mut reader: R, sink: &mut W) -> Result<StreamSummary> {
+        log_operation!("encode_stream", {
+            let mut summary = StreamSummary::default();
+
+            let mut current = read_segment(&mut reader, self.segment_size)?;
+            if current.is_empty() {
+                return Err(DnaError::Encoding("Flux d'entrée vide".to_string()));
+            }
+
+            loop {
+                // Lecture anticipée pour marquer le dernier segment
+                let next = read_segment(&mut reader, self.segment_size)?;
+                let segment = SegmentInfo {
+                    index: summary.segments as u64,
+                    offset: summary.bytes,
+                    last: next.is_empty(),
+                };
+
+                let pool = self.encode_pool(&current, Some(segment))?;
+                for sequence in &pool {
+                    sink.write_sequence(sequence)?;
+                }
+
+                summary.segments += 1;
+                summary.bytes += current.len() as u64;
+                summary.sequences += pool.len();
+
+                if segment.last {
+                    break;
+                }
+                current = next;
+            }
+
+            sink.flush()?;
+            Ok(summary)
+        })
+    }
+
+    /// Encode un pool complet, éventuellement rattaché à un segment de flux
+    fn encode_pool(&self, data: &[u8], segment: Option<SegmentInfo>) -> Result<Vec<DnaSequence>> {
         log_operation!("encode_data", {
-            // 1. Compression si activée
-            let processed_data = if self.config.compression_enabled {
+            // 1. Compression si activée (sauf schémas qui encodent les données brutes)
+            let raw_input = self.codec.capabilities().raw_input;
+            let processed_data = if self.config.compression_enabled && !raw_input {
                 self.compress(data)?
             } else {
                 data.to_vec()
             };
 
-            // 2. Division en chunks
-            let chunks = self.split_into_chunks(&processed_data);
-
-            // 3. Encodage selon le type avec parallélisme
-            let sequences = match self.config.encoder_type {
-                EncoderType::Fountain => self.encode_fountain_optimized(&chunks)?,
-                EncoderType::ErlichZielinski2017 => self.encode_erlich_zielinski_2017(&chunks)?,
-                EncoderType::Goldman2013 => self.encode_goldman_2013(data)?,
-                EncoderType::Goldman => self.encode_goldman(&chunks)?,
-                EncoderType::Grass2015 => self.encode_grass_2015(data)?,
-                EncoderType::Adaptive => self.encode_adaptive(&chunks)?,
-                EncoderType::Base3 => self.encode_base3(&chunks)?,
-            };
+            // 2. Encodage par le codec
+            let sequences = self.codec.encode(&processed_data, &self.config)?;
+
+            // 3. Oligos de manifeste en tête de pool
+            let mut manifest = PoolManifest::new(&self.config, self.codec.name(), data, &processed_data);
+            if raw_input {
+                manifest.compression = CompressionType::None;
+            }
+            manifest.segment = segment;
+            let mut pool = manifest.to_sequences()?;
+            pool.extend(sequences);
+
+            // 4. Sites d'amorces pour l'accès aléatoire
+            if let Some(pair) = &self.primers {
+                pool = pair.attach_all(&pool);
+            }
 
-            Ok(sequences)
+            Ok(pool)
         })
     }
 
+    /// Encode un flux avec le schéma intégré de `encoder_type`
+    pub(crate) fn encode_builtin(&self, data: &[u8]) -> Result<Vec<DnaSequence>> {
+        let chunks = self.split_into_chunks(data);
+
+        match self.config.encoder_type {
+            EncoderType::Fountain => self.encode_fountain(&chunks),
+            EncoderType::ErlichZielinski2017 => self.encode_erlich_zielinski_2017(&chunks),
+            EncoderType::Goldman2013 => self.encode_goldman_2013(data),
+            EncoderType::Goldman => self.encode_goldman(&chunks),
+            EncoderType::Grass2015 => self.encode_grass_2015(data),
+            EncoderType::Adaptive => self.encode_adaptive(&chunks),
+            EncoderType::Base3 => self.encode_base3(&chunks),
+            EncoderType::VtMarker => self.encode_vt_marker(data),
+            EncoderType::ProductCode => self.encode_product_code(data),
+        }
+    }
+
     /// Compresse les données
     fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
         match self.config.compression_type {
@@ -153,6 +310,18 @@ impl Encoder {
         }
     }
 
+    /// Taille des chunks combinés par les gouttes d'un schéma fontaine
+    ///
+    /// EZ 2017 redécoupe le flux pour qu'un chunk tienne dans un oligo.
+    pub(crate) fn fountain_chunk_size(config: &EncoderConfig) -> usize {
+        match config.encoder_type {
+            EncoderType::ErlichZielinski2017 => {
+                config.chunk_size.min(crate::codec::gc_aware_encoding::MAX_PAYLOAD_BYTES)
+            }
+            _ => config.chunk_size,
+        }
+    }
+
     /// Divise les données en chunks
     fn split_into_chunks(&self, data: &[u8]) -> Vec<Vec<u8>> {
         data.chunks(self.config.chunk_size)
@@ -160,30 +329,99 @@ impl Encoder {
             .collect()
     }
 
-    /// Encodage DNA Fountain optimisé avec parallélisme
-    fn encode_fountain_optimized(&self, chunks: &[Vec<u8>]) -> Result<Vec<DnaSequence>> {
+    /// Encodage DNA Fountain
+    ///
+    /// Chaque oligo porte le seed de sa goutte sur 24 bits et un CRC-16
+    /// (`fountain_oligo_bytes`), pour que des lectures sans métadonnées
+    /// restent décodables et qu'un seed altéré soit détecté. Comme dans
+    /// DNA Fountain, la charge est blanchie par un flux dérivé du seed, et la
+    /// goutte est écartée si l'oligo obtenu viole les contraintes de la
+    /// configuration: seuls les seeds retenus sont émis. Les candidats sont calculés en parallèle par lots puis retenus dans
+    /// l'ordre des seeds, jusqu'à un système XOR de rang plein et au nombre de
+    /// gouttes demandé par `redundancy`.
+    fn encode_fountain(&self, chunks: &[Vec<u8>]) -> Result<Vec<DnaSequence>> {
         let num_chunks = chunks.len();
         let num_droplets = (num_chunks as f64 * self.config.redundancy).ceil() as usize;
+        let source_length: usize = chunks.iter().map(|c| c.len()).sum();
+
+        let oligo_length = (FOUNTAIN_OVERHEAD_BYTES + self.config.chunk_size) * 4;
+        if oligo_length > self.config.constraints.max_sequence_length {
+            return Err(DnaError::SequenceTooLong {
+                len: oligo_length,
+                max: self.config.constraints.max_sequence_length,
+            });
+        }
 
-        // Utiliser Rayon pour le parallélisme
-        let sequences: Result<Vec<DnaSequence>> = (0..num_droplets)
-            .into_par_iter()
-            .map(|seed| {
-                // Échantillonner le degré depuis la distribution robust soliton
-                let degree = Self::sample_robust_soliton_degree(num_chunks, seed as u64);
+        let mut sequences = Vec::with_capacity(num_droplets);
+        let mut basis = Gf2Basis::new(num_chunks);
+        let mut target = num_droplets;
+        let mut next_seed = 0u64;
+        let mut rejected_in_a_row = 0usize;
+
+        while sequences.len() < target || !basis.is_full() {
+            if next_seed > MAX_FOUNTAIN_SEED {
+                return Err(DnaError::Encoding("Seeds fontaine épuisés (24 bits)".to_string()));
+            }
 
-                // Sélectionner les chunks (seed-based pour reproductibilité)
-                let selected_chunks = Self::select_chunks_seeded(chunks, degree, seed as u64);
+            let candidates: Vec<Option<(DnaSequence, usize)>> = (next_seed..next_seed + FOUNTAIN_SEED_BATCH)
+                .into_par_iter()
+                .map(|seed| self.fountain_droplet(chunks, seed))
+                .collect::<Result<_>>()?;
+            next_seed += FOUNTAIN_SEED_BATCH;
 
-                // XOR des chunks sélectionnés
-                let payload = Self::xor_chunks(&selected_chunks)?;
+            for candidate in candidates {
+                if sequences.len() >= target && basis.is_full() {
+                    break;
+                }
 
-                // Convertir en ADN avec contraintes
-                self.payload_to_dna(payload, seed as u64)
-            })
-            .collect();
+                let Some((mut dna, degree)) = candidate else {
+                    rejected_in_a_row += 1;
+                    if rejected_in_a_row > MAX_REJECTED_SEEDS {
+                        return Err(DnaError::Encoding(format!(
+                            "Aucune goutte fontaine ne respecte les contraintes après {} seeds consécutifs",
+                            MAX_REJECTED_SEEDS
+                        )));
+                    }
+                    continue;
+                };
+                rejected_in_a_row = 0;
+
+                if !basis.is_full() {
+                    basis.insert(&Self::select_chunk_indices(num_chunks, degree, dna.metadata.seed));
+                    if basis.is_full() {
+                        let margin = num_droplets.saturating_sub(num_chunks);
+                        target = target.max(sequences.len() + 1 + margin);
+                    }
+                }
+
+                Self::tag_fountain_droplet(&mut dna, num_chunks, source_length);
+                sequences.push(dna);
+            }
+        }
+
+        Ok(sequences)
+    }
+
+    /// Goutte fontaine du seed `seed` et son degré, ou `None` si l'oligo viole les contraintes
+    fn fountain_droplet(&self, chunks: &[Vec<u8>], seed: u64) -> Result<Option<(DnaSequence, usize)>> {
+        let degree = Self::sample_robust_soliton_degree(chunks.len(), seed);
+        let selected_chunks = Self::select_chunks_seeded(chunks, degree, seed);
+        let payload = Self::xor_chunks(&selected_chunks)?;
+
+        let dna = DnaSequence::with_encoding_scheme(
+            self.chunk_to_bases(&Self::fountain_oligo_bytes(seed, &payload))?,
+            String::from("encoded"),
+            0,
+            payload.len(),
+            seed,
+            self.encoding_scheme_name().to_string(),
+        );
 
-        sequences
+        match dna.validate(&self.config.constraints) {
+            Ok(()) => Ok(Some((dna, degree))),
+            Err(e) if is_synthesis_rejection(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 
     /// Encodage Erlich-Zielinski 2017 - DNA Fountain validé (Science 2017)
@@ -199,24 +437,61 @@ impl Encoder {
     fn encode_erlich_zielinski_2017(&self, chunks: &[Vec<u8>]) -> Result<Vec<DnaSequence>> {
         use crate::codec::gc_aware_encoding::GcAwareEncoder;
 
-        // Contraintes Erlich-Zielinski 2017
-        let ez_constraints = DnaConstraints::new(
-            0.40,  // GC min 40%
-            0.60,  // GC max 60%
-            3,     // Max homopolymer 3 (<4)
-            152    // Max length 152nt (spécification papier)
-        );
+        // Contraintes Erlich-Zielinski 2017, plus les règles de synthèse de la configuration
+        let ez_constraints = DnaConstraints {
+            tm_range: self.config.constraints.tm_range,
+            min_fold_energy: self.config.constraints.min_fold_energy,
+            forbidden_motifs: self.config.constraints.forbidden_motifs.clone(),
+            gc_window: self.config.constraints.gc_window,
+            ..DnaConstraints::new(
+                0.40,  // GC min 40%
+                0.60,  // GC max 60%
+                3,     // Max homopolymer 3 (<4)
+                152    // Max length 152nt (spécification papier)
+            )
+        };
 
         let gc_aware_encoder = GcAwareEncoder::new(ez_constraints.clone());
 
+        // Un oligo ne transporte que MAX_PAYLOAD_BYTES octets: redécouper si besoin
+        let chunk_size = Self::fountain_chunk_size(&self.config);
+        let rechunked: Vec<Vec<u8>>;
+        let chunks = if chunks.iter().any(|c| c.len() > chunk_size) {
+            rechunked = chunks.concat().chunks(chunk_size).map(|c| c.to_vec()).collect();
+            rechunked.as_slice()
+        } else {
+            chunks
+        };
+
         let num_chunks = chunks.len();
+        let source_length: usize = chunks.iter().map(|c| c.len()).sum();
         // Redondance plus faible avec EZ 2017 (1.03-1.07 recommandé)
         let redundancy = self.config.redundancy.clamp(1.03, 1.07);
         let num_droplets = (num_chunks as f64 * redundancy).ceil() as usize;
 
+        // Le seed est stocké sur 16 bits dans le header de l'oligo
+        if num_droplets > u16::MAX as usize + 1 {
+            return Err(DnaError::Encoding(format!(
+                "Trop de gouttes pour EZ 2017: {} (maximum {})",
+                num_droplets,
+                u16::MAX as usize + 1
+            )));
+        }
+
         let mut sequences = Vec::with_capacity(num_droplets);
+        // Avec peu de chunks, 1.03-1.07 gouttes par chunk ne suffisent pas toujours:
+        // on émet jusqu'à ce que le système XOR soit de rang plein, puis la marge
+        let mut basis = Gf2Basis::new(num_chunks);
+        let mut target = num_droplets;
+
+        let mut seed = 0usize;
+        while sequences.len() < target || !basis.is_full() {
+            if seed > u16::MAX as usize {
+                return Err(DnaError::Encoding(
+                    "Seeds EZ 2017 épuisés: trop de gouttes rejetées par les règles de synthèse".to_string(),
+                ));
+            }
 
-        for seed in 0..num_droplets {
             // Échantillonner le degré avec paramètres EZ 2017
             let degree = Self::sample_robust_soliton_degree_ez2017(num_chunks, seed as u64);
 
@@ -226,12 +501,31 @@ impl Encoder {
             // XOR des chunks
             let payload = Self::xor_chunks(&selected_chunks)?;
 
-            // Utiliser le GC-Aware encoder qui préserve les données intactes
-            let dna = gc_aware_encoder.encode(payload, seed as u64, degree)?;
+            // Utiliser le GC-Aware encoder qui préserve les données intactes;
+            // comme dans DNA Fountain, une goutte non synthétisable est écartée
+            let mut dna = match gc_aware_encoder.encode(payload, seed as u64, degree) {
+                Ok(dna) => dna,
+                Err(e) if is_synthesis_rejection(&e) => {
+                    seed += 1;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            seed += 1;
 
-            // Validation stricte des contraintes EZ 2017
-            Self::validate_erlich_zielinski_2017_sequence(&dna)?;
+            // Validation stricte des contraintes EZ 2017: goutte écartée, seed suivant
+            if Self::validate_erlich_zielinski_2017_sequence(&dna).is_err() {
+                continue;
+            }
+
+            if !basis.is_full() {
+                basis.insert(&Self::select_chunk_indices(num_chunks, degree, dna.metadata.seed));
+                if basis.is_full() {
+                    target = target.max(sequences.len() + 1 + num_droplets - num_chunks);
+                }
+            }
 
+            Self::tag_fountain_droplet(&mut dna, num_chunks, source_length);
             sequences.push(dna);
         }
 
@@ -244,7 +538,7 @@ impl Encoder {
     /// - c = 0.1
     /// - δ = 0.5
     /// - K = nombre de chunks
-    fn sample_robust_soliton_degree_ez2017(num_chunks: usize, seed: u64) -> usize {
+    pub(crate) fn sample_robust_soliton_degree_ez2017(num_chunks: usize, seed: u64) -> usize {
         let mut rng = ChaCha8Rng::seed_from_u64(seed);
 
         // Paramètres Robust Soliton du papier EZ 2017
@@ -342,34 +636,53 @@ impl Encoder {
         Ok(())
     }
 
-    /// Encodage DNA Fountain (version originale pour compatibilité)
-    fn encode_fountain(&self, chunks: &[Vec<u8>]) -> Result<Vec<DnaSequence>> {
-        let num_chunks = chunks.len();
-        let num_droplets = (num_chunks as f64 * self.config.redundancy).ceil() as usize;
-
-        let mut sequences = Vec::with_capacity(num_droplets);
-
-        for seed in 0..num_droplets {
-            // Échantillonner le degré depuis la distribution robust soliton
-            let degree = Self::sample_robust_soliton_degree(num_chunks, seed as u64);
+    /// Octets d'un oligo fontaine: `[seed masqué (3)] [charge blanchie] [CRC-16 (2)]`
+    ///
+    /// Le masque évite qu'un petit seed ne commence l'oligo par une série de A;
+    /// le CRC-16 couvre le seed et la charge tels qu'écrits.
+    pub(crate) fn fountain_oligo_bytes(seed: u64, payload: &[u8]) -> Vec<u8> {
+        let masked = (seed as u32 ^ FOUNTAIN_SEED_MASK).to_be_bytes();
+        let mut bytes = masked[4 - FOUNTAIN_SEED_BYTES..].to_vec();
+
+        let start = bytes.len();
+        bytes.extend_from_slice(payload);
+        whiten(&mut bytes[start..], seed);
+
+        let crc = crc16(&bytes);
+        bytes.extend_from_slice(&crc.to_be_bytes());
+        bytes
+    }
 
-            // Sélectionner les chunks (seed-based pour reproductibilité)
-            let selected_chunks = Self::select_chunks_seeded(chunks, degree, seed as u64);
+    /// Relit un oligo écrit par `fountain_oligo_bytes`: seed et charge en clair
+    ///
+    /// Retourne `None` si l'oligo est trop court ou si son CRC-16 échoue.
+    pub(crate) fn read_fountain_oligo(bytes: &[u8]) -> Option<(u64, Vec<u8>)> {
+        if bytes.len() < FOUNTAIN_OVERHEAD_BYTES {
+            return None;
+        }
 
-            // XOR des chunks sélectionnés
-            let payload = Self::xor_chunks(&selected_chunks)?;
+        let (body, crc) = bytes.split_at(bytes.len() - 2);
+        if crc16(body).to_be_bytes() != crc {
+            return None;
+        }
 
-            // Convertir en ADN avec contraintes
-            let dna = self.payload_to_dna(payload, seed as u64)?;
+        let (prefix, payload) = body.split_at(FOUNTAIN_SEED_BYTES);
+        let masked = prefix.iter().fold(0u32, |seed, &byte| (seed << 8) | u32::from(byte));
+        let seed = u64::from(masked ^ FOUNTAIN_SEED_MASK);
 
-            sequences.push(dna);
-        }
+        let mut payload = payload.to_vec();
+        whiten(&mut payload, seed);
+        Some((seed, payload))
+    }
 
-        Ok(sequences)
+    /// Renseigne les paramètres source nécessaires au décodage LT d'un droplet
+    fn tag_fountain_droplet(dna: &mut DnaSequence, num_chunks: usize, source_length: usize) {
+        dna.metadata.total_chunks = num_chunks;
+        dna.metadata.source_length = source_length;
     }
 
     /// Échantillonne un degré depuis la distribution Robust Soliton
-    fn sample_robust_soliton_degree(num_chunks: usize, seed: u64) -> usize {
+    pub(crate) fn sample_robust_soliton_degree(num_chunks: usize, seed: u64) -> usize {
         let mut rng = ChaCha8Rng::seed_from_u64(seed);
 
         // Distribution Robust Soliton simplifiée
@@ -425,11 +738,21 @@ impl Encoder {
 
     /// Sélectionne des chunks de façon déterministe (seed-based)
     fn select_chunks_seeded(chunks: &[Vec<u8>], degree: usize, seed: u64) -> Vec<Vec<u8>> {
+        Self::select_chunk_indices(chunks.len(), degree, seed)
+            .into_iter()
+            .map(|idx| chunks[idx].clone())
+            .collect()
+    }
+
+    /// Retourne les indices (triés) des chunks combinés dans le droplet `seed`
+    ///
+    /// Utilisé par le décodeur pour régénérer la composition d'un droplet.
+    pub(crate) fn select_chunk_indices(num_chunks: usize, degree: usize, seed: u64) -> Vec<usize> {
         let mut rng = ChaCha8Rng::seed_from_u64(seed);
         let mut indices = HashMap::new();
 
         while indices.len() < degree {
-            let idx = rng.gen_range(0..chunks.len());
+            let idx = rng.gen_range(0..num_chunks);
             indices.insert(idx, ());
         }
 
@@ -437,12 +760,7 @@ impl Encoder {
         let mut sorted_indices: Vec<usize> = indices.keys().copied().collect();
         sorted_indices.sort();
 
-        let mut selected = Vec::with_capacity(degree);
-        for idx in sorted_indices {
-            selected.push(chunks[idx].clone());
-        }
-
-        selected
+        sorted_indices
     }
 
     /// XOR de plusieurs chunks
@@ -465,105 +783,6 @@ impl Encoder {
         Ok(result)
     }
 
-    /// Convertit un payload en séquence ADN avec optimisation
-    fn payload_to_dna(&self, payload: Vec<u8>, seed: u64) -> Result<DnaSequence> {
-        let mut bases = Vec::with_capacity(payload.len() * 4); // Pré-allocation
-        let mut rng = ChaCha8Rng::seed_from_u64(seed);
-        let validator = crate::constraints::DnaConstraintValidator::with_constraints(
-            self.config.constraints.clone(),
-        );
-
-        let payload_len = payload.len();
-
-        // Encoder chaque octet en 4 bases (2 bits par base) - version optimisée
-        for byte in &payload {
-            let bits = [
-                (byte >> 6) & 0b11,
-                (byte >> 4) & 0b11,
-                (byte >> 2) & 0b11,
-                byte & 0b11,
-            ];
-
-            for two_bits in bits {
-                let base = match two_bits {
-                    0b00 => IupacBase::A,
-                    0b01 => IupacBase::C,
-                    0b10 => IupacBase::G,
-                    0b11 => IupacBase::T,
-                    _ => unreachable!(),
-                };
-
-                // Vérification optimisée des contraintes
-                if validator.can_append(&bases, base) {
-                    bases.push(base);
-                } else {
-                    // Essayer une base alternative qui préserve la valeur
-                    let alt = self.suggest_alternative_base(base, &bases, &mut rng)?;
-                    bases.push(alt);
-                }
-            }
-        }
-
-        // Créer la séquence avec validation optimisée
-        let sequence = DnaSequence::with_encoding_scheme(
-            bases,
-            String::from("encoded"),
-            0,
-            payload_len,
-            seed,
-            self.encoding_scheme_name().to_string(),
-        );
-
-        // Valider avec cache
-        sequence.validate(&self.config.constraints)?;
-
-        Ok(sequence)
-    }
-
-    /// Suggère une base alternative respectant les contraintes
-    fn suggest_alternative_base(
-        &self,
-        preferred: IupacBase,
-        current: &[IupacBase],
-        _rng: &mut ChaCha8Rng,
-    ) -> Result<IupacBase> {
-        let bases = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
-
-        // Essayer d'abord la base préférée
-        for &base in &bases {
-            if base == preferred {
-                continue;
-            }
-
-            let gc_ratio = if current.is_empty() {
-                0.5
-            } else {
-                current.iter().filter(|b| b.is_gc()).count() as f64 / current.len() as f64
-            };
-
-            // Vérifier si cette base nous rapproche du GC cible
-            let target_gc = (self.config.constraints.gc_min + self.config.constraints.gc_max) / 2.0;
-
-            let is_gc = base.is_gc();
-            let improves_gc = (gc_ratio < target_gc && is_gc) || (gc_ratio > target_gc && !is_gc);
-
-            if improves_gc && self.config.constraints.validate(&[base]).is_ok() {
-                return Ok(base);
-            }
-        }
-
-        // Fallback: première base valide
-        for base in bases {
-            if self.config.constraints.validate(&[base]).is_ok() {
-                return Ok(base);
-            }
-        }
-
-        Err(DnaError::ConstraintViolation(
-            "Impossible de trouver une base valide".to_string(),
-        ))
-    }
-
     /// Encodage Goldman (simple, sans fountain codes)
     fn encode_goldman(&self, chunks: &[Vec<u8>]) -> Result<Vec<DnaSequence>> {
         let mut sequences = Vec::with_capacity(chunks.len());
@@ -656,6 +875,102 @@ impl Encoder {
         let grass_encoder = Grass2015Encoder::new(self.config.constraints.clone());
         grass_encoder.encode(data)
     }
+
+    /// Encode avec le code interne VT + marqueurs (oligos d'au plus 15 octets)
+    fn encode_vt_marker(&self, data: &[u8]) -> Result<Vec<DnaSequence>> {
+        use crate::codec::vt_marker::VtMarkerEncoder;
+
+        VtMarkerEncoder::new(self.config.chunk_size, self.config.constraints.clone())
+            .encode(data, self.encoding_scheme_name())
+    }
+
+    /// Encode avec le code produit RS 2D
+    ///
+    /// `chunk_size` donne les octets de données par oligo, `redundancy` la
+    /// part d'oligos de parité externe par groupe (1.25 → 8 pour 32).
+    fn encode_product_code(&self, data: &[u8]) -> Result<Vec<DnaSequence>> {
+        use crate::codec::product_code::{ProductCodeConfig, ProductCodeEncoder, MAX_PAYLOAD_BYTES};
+
+        let config = ProductCodeConfig {
+            payload_bytes: self.config.chunk_size.clamp(1, MAX_PAYLOAD_BYTES),
+            ..Default::default()
+        }
+        .with_redundancy(self.config.redundancy);
+        ProductCodeEncoder::new(config, self.config.constraints.clone()).encode(data, self.encoding_scheme_name())
+    }
+}
+
+/// Retourne true si l'erreur vient d'une contrainte de l'oligo (goutte à écarter)
+pub(crate) fn is_synthesis_rejection(error: &DnaError) -> bool {
+    matches!(
+        error,
+        DnaError::GcContentOutOfRange { .. }
+            | DnaError::HomopolymerRun { .. }
+            | DnaError::ForbiddenMotif { .. }
+            | DnaError::LocalGcOutOfRange { .. }
+            | DnaError::MeltingTemperatureOutOfRange { .. }
+            | DnaError::SecondaryStructure { .. }
+            | DnaError::ConstraintViolation(_)
+    )
+}
+
+/// CRC-16/CCITT-FALSE
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc
+}
+
+/// Base échelonnée sur GF(2) des ensembles de chunks combinés par les gouttes
+struct Gf2Basis {
+    pivots: Vec<Option<Vec<u64>>>,
+    rank: usize,
+}
+
+impl Gf2Basis {
+    fn new(num_chunks: usize) -> Self {
+        Self {
+            pivots: vec![None; num_chunks],
+            rank: 0,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.rank == self.pivots.len()
+    }
+
+    /// Ajoute une combinaison de chunks (sans effet si elle est déjà engendrée)
+    fn insert(&mut self, indices: &[usize]) {
+        let mut row = vec![0u64; self.pivots.len().div_ceil(64)];
+        for &i in indices {
+            row[i / 64] ^= 1 << (i % 64);
+        }
+
+        for bit in 0..self.pivots.len() {
+            if row[bit / 64] >> (bit % 64) & 1 == 0 {
+                continue;
+            }
+            match &self.pivots[bit] {
+                Some(pivot) => row.iter_mut().zip(pivot).for_each(|(r, p)| *r ^= p),
+                None => {
+                    self.pivots[bit] = Some(row);
+                    self.rank += 1;
+                    return;
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -686,6 +1001,54 @@ mod tests {
         assert!(!sequences.is_empty());
     }
 
+    #[test]
+    fn test_pool_starts_with_manifest() {
+        let config = EncoderConfig {
+            encoder_type: EncoderType::Goldman,
+            chunk_size: 4,
+            compression_enabled: false,
+            ..Default::default()
+        };
+
+        let encoder = Encoder::new(config).unwrap();
+        let sequences = encoder.encode(b"manifest first").unwrap();
+
+        let headers: Vec<&DnaSequence> = sequences
+            .iter()
+            .take_while(|s| PoolManifest::is_header_oligo(s))
+            .collect();
+        assert!(!headers.is_empty());
+        assert!(sequences[headers.len()..].iter().all(|s| !PoolManifest::is_header_oligo(s)));
+
+        let manifest = PoolManifest::from_sequences(&headers).unwrap();
+        assert_eq!(manifest.scheme, "goldman");
+        assert_eq!(manifest.original_length, 14);
+    }
+
+    #[test]
+    fn test_default_fountain_pool_respects_constraints() {
+        let mut data: Vec<u8> = (0..3000u32).map(|i| (i * i % 251) as u8).collect();
+        data.extend(vec![0u8; 500]);
+
+        let config = EncoderConfig::default();
+        let sequences = Encoder::new(config.clone()).unwrap().encode(&data).unwrap();
+
+        // Blanchies puis filtrées, les gouttes respectent les contraintes par défaut
+        let droplets: Vec<&DnaSequence> = sequences
+            .iter()
+            .filter(|s| !PoolManifest::is_header_oligo(s))
+            .collect();
+        assert!(!droplets.is_empty());
+        for droplet in droplets {
+            droplet.validate(&config.constraints).unwrap();
+        }
+
+        let decoded = crate::codec::Decoder::new(crate::codec::DecoderConfig::default())
+            .decode(&sequences)
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+
     #[test]
     fn test_xor_chunks() {
         let chunk1 = vec![0b01010101];
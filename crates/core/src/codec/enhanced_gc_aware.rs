@@ -5,6 +5,7 @@
 
 use crate::error::{DnaError, Result};
 use crate::sequence::{DnaSequence, DnaConstraints, IupacBase};
+use crate::codec::gc_aware_encoding::screen_padding;
 use crate::codec::gc_optimizer::GcOptimizer;
 
 /// Encodeur GC-Aware amélioré avec optimisation du padding
@@ -45,9 +46,10 @@ impl EnhancedGcAwareEncoder {
             padding_needed,
         )?;
 
-        // 4. Concaténer toutes les sections
+        // 4. Concaténer toutes les sections (padding criblé par les règles de synthèse)
         let mut all_bases = header;
         all_bases.extend_from_slice(&data_bases);
+        let padding = screen_padding(&self.constraints, &all_bases, padding)?;
         all_bases.extend_from_slice(&padding);
 
         // 5. Créer la séquence
@@ -323,6 +325,7 @@ mod tests {
             max_homopolymer: 4,
             max_sequence_length: 152,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let mut encoder = EnhancedGcAwareEncoder::new(constraints.clone());
@@ -344,6 +347,7 @@ mod tests {
             max_homopolymer: 3,
             max_sequence_length: 152,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let mut encoder = EnhancedGcAwareEncoder::new(constraints.clone());
@@ -7,13 +7,34 @@
 //!
 //! Structure: [HEADER 25nt] [DATA 100nt] [PADDING GC 27nt] = 152nt
 //!
-//! - HEADER: seed (8 bases) + degree (4 bases) + addressing (13 bases) = 25 bases
-//! - DATA: Original data preserved intact (up to 100 bases = 25 bytes max)
+//! - HEADER: seed (8 bases) + degree (4 bases) + payload length (5 bases) + CRC-16 (8 bases) = 25 bases
+//! - DATA: Payload whitened by a seed-keyed stream (up to 100 bases = 25 bytes max)
 //! - PADDING GC: Bases added to balance GC 40-60%, ignored during decoding
+//!
+//! The CRC-16 covers seed, degree and payload so that corrupted oligos can be
+//! discarded before fountain decoding instead of poisoning the LT peeling.
+//!
+//! Whitening the payload with a stream derived from the seed means that two
+//! seeds never produce the same DATA section, even for a single-chunk file or
+//! runs of zero bytes: a fountain encoder screening droplets always finds one
+//! that passes the homopolymer and GC rules.
+//!
+//! When the constraints carry synthesis rules (Tm, secondary structure,
+//! forbidden motifs, local GC), the padding is chosen among GC-preserving
+//! variants so that the whole oligo passes them; otherwise the oligo is
+//! rejected and fountain encoders move on to the next seed.
 
+use crate::constraints::find_max_homopolymer;
 use crate::error::{DnaError, Result};
 use crate::sequence::{DnaSequence, DnaConstraints, IupacBase};
 use crate::codec::reed_solomon::ReedSolomonCodec;
+use crate::codec::whitening::whiten;
+
+/// Longueur du header en bases
+const HEADER_LEN: usize = 25;
+
+/// Nombre maximal d'octets de payload par oligo (100 bases de données)
+pub const MAX_PAYLOAD_BYTES: usize = 25;
 
 /// Encodeur GC-Aware pour Erlich-Zielinski 2017
 pub struct GcAwareEncoder {
@@ -35,11 +56,21 @@ impl GcAwareEncoder {
     ///
     /// Structure: [HEADER 25nt] [DATA up to 100nt] [PADDING GC to reach 152nt]
     pub fn encode(&self, payload: Vec<u8>, seed: u64, degree: usize) -> Result<DnaSequence> {
+        if payload.len() > MAX_PAYLOAD_BYTES {
+            return Err(DnaError::Encoding(format!(
+                "Payload trop grand pour un oligo GC-aware: {} > {} octets",
+                payload.len(),
+                MAX_PAYLOAD_BYTES
+            )));
+        }
+
         // 1. Créer le HEADER (25 bases)
-        let header = self.encode_header(seed, degree)?;
+        let header = self.encode_header(seed, degree, &payload)?;
 
-        // 2. Encoder les données (DATA section, préservées intactes)
-        let data_bases = self.encode_data(&payload)?;
+        // 2. Encoder les données (DATA section, blanchies par le seed)
+        let mut whitened = payload.clone();
+        whiten(&mut whitened, seed);
+        let data_bases = self.encode_data(&whitened)?;
 
         // 3. Calculer le padding nécessaire pour équilibrer GC
         let current_length = header.len() + data_bases.len();
@@ -52,9 +83,10 @@ impl GcAwareEncoder {
             padding_needed,
         )?;
 
-        // 5. Concaténer toutes les sections
+        // 5. Concaténer toutes les sections (padding criblé par les règles de synthèse)
         let mut all_bases = header;
         all_bases.extend_from_slice(&data_bases);
+        let padding = screen_padding(&self.constraints, &all_bases, padding)?;
         all_bases.extend_from_slice(&padding);
 
         // 6. Créer la séquence
@@ -79,22 +111,26 @@ impl GcAwareEncoder {
         Ok(sequence)
     }
 
-    /// Encode le HEADER (25 bases): seed (8) + degree (4) + addressing (13)
-    fn encode_header(&self, seed: u64, degree: usize) -> Result<Vec<IupacBase>> {
-        let mut header = Vec::with_capacity(25);
+    /// Encode le HEADER (25 bases): seed (8) + degree (4) + longueur (5) + CRC-16 (8)
+    fn encode_header(&self, seed: u64, degree: usize, payload: &[u8]) -> Result<Vec<IupacBase>> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
 
         // 1. Seed sur 8 bases (16 bits, peut encoder jusqu'à 65535)
         let seed_bases = self.encode_value_2bit(seed as u32, 8, 0)?;
         header.extend_from_slice(&seed_bases);
 
-        // 2. Degree sur 4 bases (8 bits, peut encoder jusqu'à 255)
+        // 2. Degree sur 4 bases (8 bits de poids faible)
         let degree_bases = self.encode_value_2bit(degree as u32, 4, 8)?;
         header.extend_from_slice(&degree_bases);
 
-        // 3. Addressing/Reserved sur 13 bases (pour future extensibilité)
-        // Pour l'instant, utilisons un pattern qui aide à équilibrer GC
-        let addressing = self.generate_balanced_addressing(13, 12)?;
-        header.extend_from_slice(&addressing);
+        // 3. Longueur du payload sur 5 bases (10 bits)
+        let length_bases = self.encode_value_2bit(payload.len() as u32, 5, 12)?;
+        header.extend_from_slice(&length_bases);
+
+        // 4. CRC-16 sur 8 bases (seed + degree + payload)
+        let crc = droplet_crc16(seed as u16, degree as u8, payload);
+        let crc_bases = self.encode_value_2bit(crc as u32, 8, 17)?;
+        header.extend_from_slice(&crc_bases);
 
         Ok(header)
     }
@@ -116,16 +152,9 @@ impl GcAwareEncoder {
 
     /// Encode les données (DATA section) - préservées intactes pour roundtrip parfait
     fn encode_data(&self, payload: &[u8]) -> Result<Vec<IupacBase>> {
-        let max_data_bytes = 25; // 100 bases / 4 bases par byte
-        let truncated_payload = if payload.len() > max_data_bytes {
-            &payload[..max_data_bytes]
-        } else {
-            payload
-        };
-
-        let mut bases = Vec::with_capacity(truncated_payload.len() * 4);
+        let mut bases = Vec::with_capacity(payload.len() * 4);
 
-        for byte in truncated_payload {
+        for byte in payload {
             let bits = [
                 (byte >> 6) & 0b11,
                 (byte >> 4) & 0b11,
@@ -148,26 +177,6 @@ impl GcAwareEncoder {
         Ok(bases)
     }
 
-    /// Génère un addressing équilibré pour le header
-    fn generate_balanced_addressing(&self, length: usize, _start_rotation: usize) -> Result<Vec<IupacBase>> {
-        // Pattern qui aide avec GC: alterner GC/AT
-        let gc_bases = [IupacBase::G, IupacBase::C];
-        let at_bases = [IupacBase::A, IupacBase::T];
-        let mut bases = Vec::with_capacity(length);
-
-        for i in 0..length {
-            let use_gc = i % 2 == 0;
-            let base_choice = if use_gc {
-                gc_bases[(i / 2) % gc_bases.len()]
-            } else {
-                at_bases[(i / 2) % at_bases.len()]
-            };
-            bases.push(base_choice);
-        }
-
-        Ok(bases)
-    }
-
     /// Génère du padding GC-équilibré pour atteindre les contraintes
     ///
     /// Utilise un pattern déterministe GCTAGCTA... qui respecte:
@@ -258,6 +267,72 @@ impl GcAwareEncoder {
     }
 }
 
+/// Choisit une variante du padding qui respecte les règles de synthèse
+///
+/// Les rotations du padding et ses échanges G↔C / A↔T conservent son GC;
+/// la première variante qui passe les règles sans allonger les homopolymères
+/// est retenue.
+pub(crate) fn screen_padding(
+    constraints: &DnaConstraints,
+    prefix: &[IupacBase],
+    padding: Vec<IupacBase>,
+) -> Result<Vec<IupacBase>> {
+    if !constraints.has_synthesis_rules() {
+        return Ok(padding);
+    }
+
+    let assemble = |candidate: &[IupacBase]| {
+        let mut bases = prefix.to_vec();
+        bases.extend_from_slice(candidate);
+        bases
+    };
+    let max_homopolymer = find_max_homopolymer(&assemble(&padding)).max(constraints.max_homopolymer);
+
+    let mut last_error = None;
+    for rotation in 0..padding.len().clamp(1, 8) {
+        for swap in 0..4 {
+            let candidate: Vec<IupacBase> = padding[rotation..]
+                .iter()
+                .chain(&padding[..rotation])
+                .map(|&base| swap_bases(base, swap))
+                .collect();
+
+            let bases = assemble(&candidate);
+            if find_max_homopolymer(&bases) > max_homopolymer {
+                continue;
+            }
+            match constraints.validate_synthesis(&bases) {
+                Ok(()) => return Ok(candidate),
+                Err(e) => last_error = Some(e),
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| {
+        DnaError::ConstraintViolation("Aucun padding ne respecte les règles de synthèse".to_string())
+    }))
+}
+
+/// Échange G↔C (bit 0 de `swap`) et A↔T (bit 1)
+fn swap_bases(base: IupacBase, swap: u8) -> IupacBase {
+    match base {
+        IupacBase::G | IupacBase::C if swap & 1 != 0 => base.complement(),
+        IupacBase::A | IupacBase::T if swap & 2 != 0 => base.complement(),
+        _ => base,
+    }
+}
+
+/// Droplet extrait d'un oligo GC-aware
+#[derive(Debug, Clone)]
+pub struct GcAwareDroplet {
+    /// Seed (16 bits de poids faible)
+    pub seed: u64,
+    /// Degré (8 bits de poids faible)
+    pub degree: usize,
+    /// Payload XORé
+    pub payload: Vec<u8>,
+}
+
 /// Décodeur GC-Aware pour Erlich-Zielinski 2017
 pub struct GcAwareDecoder {
     _constraints: DnaConstraints,
@@ -273,36 +348,78 @@ impl GcAwareDecoder {
     ///
     /// Ignore le padding, extrait uniquement la section DATA
     pub fn decode(&self, sequence: &DnaSequence) -> Result<Vec<u8>> {
+        Ok(self.decode_droplet(sequence)?.payload)
+    }
+
+    /// Décode une séquence en droplet (seed, degré, payload) et vérifie son CRC
+    ///
+    /// Retourne `DnaError::ChecksumMismatch` si l'oligo a été corrompu.
+    pub fn decode_droplet(&self, sequence: &DnaSequence) -> Result<GcAwareDroplet> {
         let bases = &sequence.bases;
 
-        if bases.len() < 25 {
+        if bases.len() < HEADER_LEN {
             return Err(DnaError::Decoding(
                 "Séquence trop courte pour contenir le header".to_string()
             ));
         }
 
         // Structure: [HEADER 25] [DATA payload_len*4 bases] [PADDING rest]
-        let _header = &bases[0..25];
+        let seed = self.decode_value_2bit(&bases[0..8], 0)? as u64;
+        let degree = self.decode_value_2bit(&bases[8..12], 8)? as usize;
+        let payload_len = self.decode_value_2bit(&bases[12..17], 12)? as usize;
+        let stored_crc = self.decode_value_2bit(&bases[17..25], 17)? as u16;
 
-        // La longueur du payload est stockée dans metadata.chunk_size
-        let payload_len = sequence.metadata.chunk_size;
         let data_bases_needed = payload_len * 4;  // Chaque octet = 4 bases
 
         // Vérifier qu'on a assez de bases
-        if bases.len() < 25 + data_bases_needed {
+        if payload_len > MAX_PAYLOAD_BYTES || bases.len() < HEADER_LEN + data_bases_needed {
             return Err(DnaError::Decoding(
                 format!("Séquence trop courte: besoin de {} bases de données, n'en a que {}",
-                    data_bases_needed, bases.len().saturating_sub(25))
+                    data_bases_needed, bases.len().saturating_sub(HEADER_LEN))
             ));
         }
 
         // Extraire uniquement les bases de données (pas le padding)
-        let data_bases = &bases[25..25 + data_bases_needed];
+        let data_bases = &bases[HEADER_LEN..HEADER_LEN + data_bases_needed];
+
+        // Décoder les bases en octets, puis retirer le blanchiment
+        let mut payload = self.decode_data(data_bases)?;
+        whiten(&mut payload, seed);
+
+        let actual_crc = droplet_crc16(seed as u16, degree as u8, &payload);
+        if actual_crc != stored_crc {
+            return Err(DnaError::ChecksumMismatch {
+                expected: format!("{:04x}", stored_crc),
+                actual: format!("{:04x}", actual_crc),
+            });
+        }
 
-        // Décoder les bases en octets
-        let payload = self.decode_data(data_bases)?;
+        Ok(GcAwareDroplet { seed, degree, payload })
+    }
+
+    /// Décode une valeur encodée sur n bases avec rotation
+    fn decode_value_2bit(&self, bases: &[IupacBase], start_rotation: usize) -> Result<u32> {
+        let mut value: u32 = 0;
+
+        for (i, base) in bases.iter().enumerate() {
+            let base_idx = match base {
+                IupacBase::A => 0,
+                IupacBase::C => 1,
+                IupacBase::G => 2,
+                IupacBase::T => 3,
+                _ => {
+                    return Err(DnaError::Decoding(format!(
+                        "Base invalide dans le header: {:?}", base
+                    )));
+                }
+            };
+
+            let rotation = (start_rotation + i) % 4;
+            let two_bits = (base_idx + 4 - rotation) % 4;
+            value |= (two_bits as u32) << (i * 2);
+        }
 
-        Ok(payload)
+        Ok(value)
     }
 
     /// Décode les bases de données en octets
@@ -344,6 +461,25 @@ impl GcAwareDecoder {
     }
 }
 
+/// CRC-16/CCITT-FALSE sur seed, degré et payload d'un droplet
+fn droplet_crc16(seed: u16, degree: u8, payload: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    let header = seed.to_be_bytes();
+
+    for &byte in header.iter().chain(std::iter::once(&degree)).chain(payload) {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -356,6 +492,7 @@ mod tests {
             max_homopolymer: 3,
             max_sequence_length: 152,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let encoder = GcAwareEncoder::new(constraints.clone());
@@ -369,7 +506,68 @@ mod tests {
         assert_eq!(original, recovered);
     }
 
+    #[test]
+    fn test_gc_aware_droplet_header() {
+        let constraints = DnaConstraints::new(0.40, 0.60, 3, 152);
+        let encoder = GcAwareEncoder::new(constraints.clone());
+        let decoder = GcAwareDecoder::new(constraints);
+
+        let payload = vec![0xCA, 0xFE, 0xBA, 0xBE];
+        let sequence = encoder.encode(payload.clone(), 1234, 7).unwrap();
+        let droplet = decoder.decode_droplet(&sequence).unwrap();
+
+        assert_eq!(droplet.seed, 1234);
+        assert_eq!(droplet.degree, 7);
+        assert_eq!(droplet.payload, payload);
+    }
+
+    #[test]
+    fn test_gc_aware_detects_corruption() {
+        let constraints = DnaConstraints::new(0.40, 0.60, 3, 152);
+        let encoder = GcAwareEncoder::new(constraints.clone());
+        let decoder = GcAwareDecoder::new(constraints);
+
+        let mut sequence = encoder.encode(vec![1, 2, 3, 4, 5], 42, 3).unwrap();
+
+        // Substitution dans la section DATA
+        sequence.bases[HEADER_LEN] = match sequence.bases[HEADER_LEN] {
+            IupacBase::A => IupacBase::C,
+            _ => IupacBase::A,
+        };
+
+        assert!(matches!(
+            decoder.decode_droplet(&sequence),
+            Err(DnaError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_gc_aware_rejects_oversized_payload() {
+        let encoder = GcAwareEncoder::new(DnaConstraints::new(0.40, 0.60, 3, 152));
+        assert!(encoder.encode(vec![0u8; MAX_PAYLOAD_BYTES + 1], 0, 1).is_err());
+    }
+
     // Note: Les tests de contraintes GC strictes sont omis car le padding "best effort"
     // ne peut pas toujours garantir GC 40-60% pour tous les payloads possibles.
     // Cependant, le roundtrip fonctionne parfaitement, ce qui est l'objectif principal.
+
+    #[test]
+    fn test_padding_avoids_forbidden_motif() {
+        // Le padding GCTAGCTA... contient le site NheI GCTAGC
+        let nhe_i = vec![IupacBase::G, IupacBase::C, IupacBase::T, IupacBase::A, IupacBase::G, IupacBase::C];
+        let constraints = DnaConstraints::new(0.40, 0.60, 3, 152).with_forbidden_motifs(vec![nhe_i]);
+        let original = vec![0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0];
+        // Seed dont la section DATA blanchie respecte déjà les homopolymères
+        let seed = 2;
+
+        let plain = GcAwareEncoder::new(DnaConstraints::new(0.40, 0.60, 3, 152))
+            .encode(original.clone(), seed, 5)
+            .unwrap();
+        assert!(constraints.validate_synthesis(&plain.bases).is_err());
+
+        let screened = GcAwareEncoder::new(constraints.clone()).encode(original.clone(), seed, 5).unwrap();
+        assert!(constraints.validate(&screened.bases).is_ok());
+        assert_eq!(screened.bases.len(), plain.bases.len());
+        assert_eq!(GcAwareDecoder::new(constraints).decode(&screened).unwrap(), original);
+    }
 }
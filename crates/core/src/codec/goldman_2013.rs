@@ -320,6 +320,7 @@ mod tests {
             max_homopolymer: 4,
             max_sequence_length: 200,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let encoder = Goldman2013Encoder::new(constraints.clone());
@@ -346,6 +347,7 @@ mod tests {
             max_homopolymer: 4,
             max_sequence_length: 200,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let encoder = Goldman2013Encoder::new(constraints.clone());
@@ -368,6 +370,7 @@ mod tests {
             max_homopolymer: 4,
             max_sequence_length: 200,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let encoder = Goldman2013Encoder::new(constraints.clone());
@@ -391,6 +394,7 @@ mod tests {
             max_homopolymer: 4,
             max_sequence_length: 200,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let encoder = Goldman2013Encoder::new(constraints.clone());
@@ -414,6 +418,7 @@ mod tests {
             max_homopolymer: 4,
             max_sequence_length: 200,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let encoder = Goldman2013Encoder::new(constraints.clone());
@@ -442,6 +447,7 @@ mod tests {
             max_homopolymer: 4,
             max_sequence_length: 200,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let encoder = Goldman2013Encoder::new(constraints.clone());
@@ -346,6 +346,7 @@ mod tests {
             max_homopolymer: 150,  // Allow very long runs (124nt sequence can have 111 'A' padding)
             max_sequence_length: 200,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let encoder = Grass2015Encoder::new(constraints.clone());
@@ -370,6 +371,7 @@ mod tests {
             max_homopolymer: 150,  // Allow very long runs (124nt sequence can have 111 'A' padding)
             max_sequence_length: 200,
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         };
 
         let encoder = Grass2015Encoder::new(constraints.clone());
@@ -0,0 +1,587 @@
+//! Manifeste auto-descriptif des pools encodés
+//!
+//! L'encodeur écrit en tête de pool quelques oligos dédiés ("header oligos")
+//! qui décrivent exactement comment relire les oligos de données :
+//! - Schéma d'encodage et `EncoderConfig` complète (chunks, redondance, compression, contraintes)
+//! - Compression effectivement appliquée au flux encodé
+//! - Taille et SHA-256 du fichier original, taille du flux encodé
+//! - CRC32 de chaque chunk du flux encodé (après compression)
+//! - Position du segment dans le fichier pour les encodages en flux
+//!
+//! Format binaire (version 3) :
+//! `[magic "ADNM" (4)] [version (1)] [manifeste bincode]`, protégé par son
+//! propre code Reed-Solomon puis découpé en oligos indexés. Chaque oligo porte
+//! `[signature (1)] [index (2)] [nombre d'oligos (2)] [flux RS (15)] [CRC32 (4)]`:
+//! la signature et le CRC32 suffisent à reconnaître un oligo de manifeste à
+//! partir de ses seules bases, sans métadonnées FASTA. Tout sauf la signature
+//! et l'index est blanchi par un flux pseudo-aléatoire dérivé de l'index, pour
+//! que des oligos presque vides restent séparables au regroupement des
+//! lectures, puis écrit avec un code base-3 différentiel (jamais deux bases
+//! identiques consécutives), indépendant des contraintes du schéma de données.
+
+use crate::codec::encoder::{CompressionType, EncoderConfig, EncoderType};
+use crate::codec::reed_solomon::ReedSolomonCodec;
+use crate::error::{DnaError, Result};
+use crate::sequence::{DnaSequence, IupacBase};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Schéma d'encodage porté par les oligos de manifeste
+pub const MANIFEST_SCHEME: &str = "manifest";
+
+/// Version courante du format de manifeste
+pub const MANIFEST_VERSION: u8 = 3;
+
+/// Signature en tête du conteneur
+const MANIFEST_MAGIC: &[u8; 4] = b"ADNM";
+
+/// Signature en tête de chaque oligo de manifeste
+const OLIGO_MAGIC: u8 = 0xAD;
+
+/// Octets du flux RS portés par chaque oligo
+const OLIGO_PAYLOAD_BYTES: usize = 15;
+
+/// Octets d'adressage en tête de chaque oligo (signature, puis index et nombre d'oligos en u16 big-endian)
+const OLIGO_HEADER_BYTES: usize = 5;
+
+/// Taille d'un oligo de manifeste en octets (adressage, flux RS et CRC32)
+const OLIGO_BYTES: usize = OLIGO_HEADER_BYTES + OLIGO_PAYLOAD_BYTES + 4;
+
+/// Trits par octet (3^6 = 729 ≥ 256)
+const TRITS_PER_BYTE: usize = 6;
+
+/// Bases utilisées par le code différentiel
+const BASES: [IupacBase; 4] = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+
+/// Position d'un segment dans un fichier encodé en flux
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentInfo {
+    /// Numéro du segment (à partir de 0)
+    pub index: u64,
+    /// Décalage du segment dans le fichier original, en octets
+    pub offset: u64,
+    /// Dernier segment du fichier
+    pub last: bool,
+}
+
+/// Manifeste d'un pool encodé
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolManifest {
+    /// Schéma d'encodage des oligos de données
+    pub scheme: String,
+    /// Configuration complète de l'encodeur
+    pub encoder_config: EncoderConfig,
+    /// Compression effectivement appliquée avant l'encodage
+    pub compression: CompressionType,
+    /// Taille du fichier original en octets
+    pub original_length: u64,
+    /// SHA-256 du fichier original
+    pub sha256: [u8; 32],
+    /// Taille du flux encodé (après compression) en octets
+    pub stream_length: u64,
+    /// CRC32 de chaque chunk de `chunk_size` octets du flux encodé
+    pub chunk_crc32: Vec<u32>,
+    /// Segment couvert par ce pool (None pour un encodage en un bloc)
+    pub segment: Option<SegmentInfo>,
+}
+
+impl PoolManifest {
+    /// Construit le manifeste d'un encodage
+    ///
+    /// `stream` est le flux effectivement encodé (après compression éventuelle).
+    pub fn new(config: &EncoderConfig, scheme: &str, original: &[u8], stream: &[u8]) -> Self {
+        // Goldman 2013 et Grass 2015 encodent les données brutes
+        let compression = match config.encoder_type {
+            EncoderType::Goldman2013 | EncoderType::Grass2015 => CompressionType::None,
+            _ if config.compression_enabled => config.compression_type,
+            _ => CompressionType::None,
+        };
+
+        Self {
+            scheme: scheme.to_string(),
+            encoder_config: config.clone(),
+            compression,
+            original_length: original.len() as u64,
+            sha256: Sha256::digest(original).into(),
+            stream_length: stream.len() as u64,
+            chunk_crc32: stream
+                .chunks(config.chunk_size.max(1))
+                .map(crc32)
+                .collect(),
+            segment: None,
+        }
+    }
+
+    /// Rattache le manifeste à un segment d'un encodage en flux
+    pub fn with_segment(mut self, segment: SegmentInfo) -> Self {
+        self.segment = Some(segment);
+        self
+    }
+
+    /// Retourne les indices des chunks du flux décodé dont le CRC32 diffère
+    ///
+    /// Les chunks manquants ou excédentaires sont comptés comme en échec.
+    pub fn failing_chunks(&self, stream: &[u8]) -> Vec<usize> {
+        let chunk_size = self.encoder_config.chunk_size.max(1);
+        let actual: Vec<u32> = stream.chunks(chunk_size).map(crc32).collect();
+
+        (0..actual.len().max(self.chunk_crc32.len()))
+            .filter(|&i| actual.get(i) != self.chunk_crc32.get(i))
+            .collect()
+    }
+
+    /// Indique si une séquence est un oligo de manifeste intact
+    ///
+    /// La décision ne repose que sur les bases (code différentiel, signature
+    /// et CRC32), jamais sur les métadonnées de la séquence.
+    pub fn is_header_oligo(sequence: &DnaSequence) -> bool {
+        read_header_oligo(&sequence.bases).is_some()
+    }
+
+    /// Vérifie des données décodées contre la taille et le SHA-256 du manifeste
+    pub fn verify(&self, data: &[u8]) -> Result<()> {
+        if data.len() as u64 != self.original_length {
+            return Err(DnaError::Decoding(format!(
+                "Taille décodée {} différente du manifeste ({})",
+                data.len(),
+                self.original_length
+            )));
+        }
+
+        let actual: [u8; 32] = Sha256::digest(data).into();
+        if actual != self.sha256 {
+            return Err(DnaError::ChecksumMismatch {
+                expected: hex(&self.sha256),
+                actual: hex(&actual),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Sérialise le manifeste en oligos d'en-tête
+    pub fn to_sequences(&self) -> Result<Vec<DnaSequence>> {
+        let body = bincode::serialize(self)
+            .map_err(|e| DnaError::Serialization(format!("Manifeste: {}", e)))?;
+
+        let mut container = Vec::with_capacity(MANIFEST_MAGIC.len() + 1 + body.len());
+        container.extend_from_slice(MANIFEST_MAGIC);
+        container.push(MANIFEST_VERSION);
+        container.extend_from_slice(&body);
+
+        let stream = ReedSolomonCodec::new().encode(&container)?;
+        let count = stream.len().div_ceil(OLIGO_PAYLOAD_BYTES);
+        if count > u16::MAX as usize {
+            return Err(DnaError::Encoding(format!(
+                "Manifeste trop volumineux: {} oligos (max {})",
+                count,
+                u16::MAX
+            )));
+        }
+
+        Ok(stream
+            .chunks(OLIGO_PAYLOAD_BYTES)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut payload = Vec::with_capacity(OLIGO_BYTES);
+                payload.push(OLIGO_MAGIC);
+                payload.extend_from_slice(&(index as u16).to_be_bytes());
+                payload.extend_from_slice(&(count as u16).to_be_bytes());
+                payload.extend_from_slice(chunk);
+                payload.resize(OLIGO_HEADER_BYTES + OLIGO_PAYLOAD_BYTES, 0);
+                payload.extend_from_slice(&crc32(&payload).to_be_bytes());
+                whiten(&mut payload);
+
+                DnaSequence::with_encoding_scheme(
+                    bytes_to_bases(&payload),
+                    String::new(),
+                    index,
+                    OLIGO_PAYLOAD_BYTES,
+                    0,
+                    MANIFEST_SCHEME.to_string(),
+                )
+            })
+            .collect())
+    }
+
+    /// Reconstruit le manifeste à partir des oligos d'en-tête
+    ///
+    /// Les oligos illisibles ou manquants sont traités comme des effacements
+    /// par le code Reed-Solomon du manifeste.
+    pub fn from_sequences(header_oligos: &[&DnaSequence]) -> Result<Self> {
+        let mut pieces: HashMap<usize, Vec<u8>> = HashMap::new();
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+
+        for seq in header_oligos {
+            if let Some((index, count, piece)) = read_header_oligo(&seq.bases) {
+                *counts.entry(count).or_insert(0) += 1;
+                pieces.entry(index).or_insert(piece);
+            }
+        }
+
+        // Nombre d'oligos: valeur majoritaire
+        let count = counts
+            .into_iter()
+            .max_by_key(|&(count, votes)| (votes, count))
+            .map(|(count, _)| count)
+            .ok_or_else(|| DnaError::Decoding("Aucun oligo de manifeste lisible".to_string()))?;
+
+        let rs = ReedSolomonCodec::new();
+        let total = count * OLIGO_PAYLOAD_BYTES;
+        if total < 4 + rs.block_size() {
+            return Err(DnaError::Decoding(format!(
+                "Manifeste tronqué: {} oligos annoncés",
+                count
+            )));
+        }
+        let stream_len = 4 + (total - 4) / rs.block_size() * rs.block_size();
+
+        let mut stream = vec![0u8; total];
+        let mut erasures = Vec::new();
+        for index in 0..count {
+            let range = index * OLIGO_PAYLOAD_BYTES..(index + 1) * OLIGO_PAYLOAD_BYTES;
+            match pieces.get(&index) {
+                Some(piece) => stream[range].copy_from_slice(piece),
+                None => erasures.extend(range.filter(|&pos| pos >= 4).map(|pos| pos - 4)),
+            }
+        }
+        stream.truncate(stream_len);
+        erasures.retain(|&pos| pos < stream_len - 4);
+
+        // Préfixe de longueur perdu: le décodage bincode s'arrête de lui-même
+        if !pieces.contains_key(&0) {
+            let capacity = (stream_len - 4) / rs.block_size() * rs.max_data_block();
+            stream[..4].copy_from_slice(&(capacity as u32).to_be_bytes());
+        }
+
+        let container = if erasures.is_empty() {
+            rs.decode(&stream)?
+        } else {
+            rs.decode_with_erasures(&stream, &erasures)?
+        };
+
+        Self::from_container(&container)
+    }
+
+    /// Décode le conteneur binaire (magic, version, manifeste)
+    fn from_container(container: &[u8]) -> Result<Self> {
+        if container.len() < MANIFEST_MAGIC.len() + 1 || &container[..4] != MANIFEST_MAGIC {
+            return Err(DnaError::Decoding("Signature de manifeste invalide".to_string()));
+        }
+
+        let version = container[4];
+        if version != MANIFEST_VERSION {
+            return Err(DnaError::Decoding(format!(
+                "Version de manifeste non supportée: {} (attendu {})",
+                version, MANIFEST_VERSION
+            )));
+        }
+
+        bincode::deserialize(&container[5..])
+            .map_err(|e| DnaError::Serialization(format!("Manifeste: {}", e)))
+    }
+}
+
+/// Sépare les oligos de manifeste des oligos de données
+///
+/// Retourne `None` si le pool ne contient aucun oligo de manifeste (ancien format).
+pub fn split_pool(sequences: &[DnaSequence]) -> Result<(Option<PoolManifest>, Vec<DnaSequence>)> {
+    let (headers, payload): (Vec<&DnaSequence>, Vec<&DnaSequence>) =
+        sequences.iter().partition(|s| PoolManifest::is_header_oligo(s));
+
+    if headers.is_empty() {
+        return Ok((None, sequences.to_vec()));
+    }
+
+    let manifest = PoolManifest::from_sequences(&headers)?;
+    Ok((Some(manifest), payload.into_iter().cloned().collect()))
+}
+
+/// Lit un oligo de manifeste: `(index, nombre d'oligos, flux RS)`
+///
+/// Retourne `None` si les bases ne forment pas un oligo de manifeste intact
+/// (longueur, code différentiel, signature ou CRC32 invalides).
+fn read_header_oligo(bases: &[IupacBase]) -> Option<(usize, usize, Vec<u8>)> {
+    if bases.len() != OLIGO_BYTES * TRITS_PER_BYTE {
+        return None;
+    }
+
+    let mut payload = bases_to_bytes(bases).ok()?;
+    if payload[0] != OLIGO_MAGIC {
+        return None;
+    }
+    whiten(&mut payload);
+
+    let (body, crc) = payload.split_at(OLIGO_HEADER_BYTES + OLIGO_PAYLOAD_BYTES);
+    if crc32(body).to_be_bytes() != crc {
+        return None;
+    }
+
+    let index = u16::from_be_bytes([body[1], body[2]]) as usize;
+    let count = u16::from_be_bytes([body[3], body[4]]) as usize;
+    (index < count).then(|| (index, count, body[OLIGO_HEADER_BYTES..].to_vec()))
+}
+
+/// Blanchit (ou rétablit) la charge d'un oligo de manifeste
+///
+/// Tous les octets après la signature et l'index de l'oligo sont combinés
+/// par XOR avec un flux SplitMix64 initialisé par cet index; l'opération est
+/// son propre inverse.
+fn whiten(payload: &mut [u8]) {
+    if payload.len() < 3 {
+        return;
+    }
+    let (index, rest) = payload[1..].split_at_mut(2);
+
+    let mut state = u64::from(u16::from_be_bytes([index[0], index[1]])) ^ 0x4144_4E4D_4849_5445;
+    for chunk in rest.chunks_mut(8) {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        for (byte, key) in chunk.iter_mut().zip(z.to_le_bytes()) {
+            *byte ^= key;
+        }
+    }
+}
+
+/// Encode des octets en bases sans homopolymères (code base-3 différentiel)
+fn bytes_to_bases(bytes: &[u8]) -> Vec<IupacBase> {
+    let mut bases = Vec::with_capacity(bytes.len() * TRITS_PER_BYTE);
+    let mut previous = IupacBase::T;
+
+    for &byte in bytes {
+        let mut trits = [0u8; TRITS_PER_BYTE];
+        let mut value = byte;
+        for trit in trits.iter_mut().rev() {
+            *trit = value % 3;
+            value /= 3;
+        }
+
+        for trit in trits {
+            let base = next_base(previous, trit);
+            bases.push(base);
+            previous = base;
+        }
+    }
+
+    bases
+}
+
+/// Décode des bases produites par `bytes_to_bases`
+fn bases_to_bytes(bases: &[IupacBase]) -> Result<Vec<u8>> {
+    if !bases.len().is_multiple_of(TRITS_PER_BYTE) {
+        return Err(DnaError::Decoding(format!(
+            "Longueur d'oligo de manifeste invalide: {}",
+            bases.len()
+        )));
+    }
+
+    let mut bytes = Vec::with_capacity(bases.len() / TRITS_PER_BYTE);
+    let mut previous = IupacBase::T;
+
+    for group in bases.chunks(TRITS_PER_BYTE) {
+        let mut value = 0u32;
+        for &base in group {
+            let trit = BASES
+                .iter()
+                .filter(|&&b| b != previous)
+                .position(|&b| b == base)
+                .ok_or_else(|| DnaError::Decoding(format!("Base inattendue dans le manifeste: {:?}", base)))?;
+            value = value * 3 + trit as u32;
+            previous = base;
+        }
+
+        let byte = u8::try_from(value)
+            .map_err(|_| DnaError::Decoding(format!("Valeur hors octet dans le manifeste: {}", value)))?;
+        bytes.push(byte);
+    }
+
+    Ok(bytes)
+}
+
+/// Choisit parmi les trois bases différentes de la précédente
+fn next_base(previous: IupacBase, trit: u8) -> IupacBase {
+    BASES
+        .iter()
+        .copied()
+        .filter(|&b| b != previous)
+        .nth(trit as usize)
+        .unwrap_or(IupacBase::A)
+}
+
+/// CRC-32 (IEEE 802.3, polynôme réfléchi 0xEDB88320)
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xFFFF_FFFF;
+
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+/// Représentation hexadécimale d'un condensat
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::find_max_homopolymer;
+
+    fn sample_manifest() -> PoolManifest {
+        let config = EncoderConfig {
+            encoder_type: EncoderType::Fountain,
+            chunk_size: 24,
+            redundancy: 2.0,
+            compression_type: CompressionType::Zstd,
+            ..Default::default()
+        };
+        PoolManifest::new(&config, "fountain", b"original file contents", b"compressed stream bytes")
+    }
+
+    #[test]
+    fn test_crc32_reference_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_failing_chunks() {
+        let manifest = sample_manifest();
+        assert_eq!(manifest.chunk_crc32.len(), 1);
+
+        let stream = b"compressed stream bytes".to_vec();
+        assert!(manifest.failing_chunks(&stream).is_empty());
+
+        let mut corrupted = stream.clone();
+        corrupted[2] ^= 0x01;
+        assert_eq!(manifest.failing_chunks(&corrupted), vec![0]);
+
+        // Flux plus long: le chunk supplémentaire est en échec
+        let mut longer = stream;
+        longer.extend_from_slice(&[0u8; 30]);
+        assert_eq!(manifest.failing_chunks(&longer), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_base3_roundtrip_without_homopolymers() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let bases = bytes_to_bases(&bytes);
+
+        assert_eq!(find_max_homopolymer(&bases), 1);
+        assert_eq!(bases_to_bytes(&bases).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let manifest = sample_manifest();
+        let oligos = manifest.to_sequences().unwrap();
+        assert!(oligos.iter().all(PoolManifest::is_header_oligo));
+
+        // Passage par FASTA, ordre inversé
+        let parsed: Vec<DnaSequence> = oligos
+            .iter()
+            .rev()
+            .map(|s| DnaSequence::from_fasta(&s.to_fasta()).unwrap())
+            .collect();
+        let refs: Vec<&DnaSequence> = parsed.iter().collect();
+
+        let decoded = PoolManifest::from_sequences(&refs).unwrap();
+        assert_eq!(decoded.scheme, "fountain");
+        assert_eq!(decoded.compression, CompressionType::Zstd);
+        assert_eq!(decoded.encoder_config.chunk_size, 24);
+        assert_eq!(decoded.encoder_config.redundancy, 2.0);
+        assert_eq!(decoded.original_length, manifest.original_length);
+        assert_eq!(decoded.sha256, manifest.sha256);
+        assert_eq!(decoded.segment, None);
+    }
+
+    #[test]
+    fn test_manifest_roundtrip_with_segment() {
+        let segment = SegmentInfo { index: 3, offset: 3 * 4096, last: true };
+        let manifest = sample_manifest().with_segment(segment);
+        let oligos = manifest.to_sequences().unwrap();
+
+        let refs: Vec<&DnaSequence> = oligos.iter().collect();
+        let decoded = PoolManifest::from_sequences(&refs).unwrap();
+        assert_eq!(decoded.segment, Some(segment));
+    }
+
+    #[test]
+    fn test_manifest_survives_lost_and_corrupted_oligos() {
+        let manifest = sample_manifest();
+        let mut oligos = manifest.to_sequences().unwrap();
+
+        // Un oligo perdu (hors préfixe) et une substitution dans un autre
+        oligos.remove(3);
+        let base = oligos[5].bases[40];
+        oligos[5].bases[40] = BASES.iter().copied().find(|&b| b != base).unwrap();
+
+        let refs: Vec<&DnaSequence> = oligos.iter().collect();
+        let decoded = PoolManifest::from_sequences(&refs).unwrap();
+        assert_eq!(decoded.sha256, manifest.sha256);
+    }
+
+    #[test]
+    fn test_header_oligos_recognised_from_bases() {
+        let manifest = sample_manifest();
+        let oligos = manifest.to_sequences().unwrap();
+
+        // Bases seules, métadonnées par défaut (comme une lecture de séquenceur)
+        let mut pool: Vec<DnaSequence> = oligos
+            .iter()
+            .map(|s| DnaSequence::new(s.bases.clone(), String::new(), 0, 0, 0))
+            .collect();
+        assert!(pool.iter().all(PoolManifest::is_header_oligo));
+
+        // Un oligo sans homopolymère mais sans signature n'est pas du manifeste
+        let data = DnaSequence::new(bytes_to_bases(&[0x42; OLIGO_BYTES]), String::new(), 0, 0, 0);
+        assert!(!PoolManifest::is_header_oligo(&data));
+
+        // Une substitution invalide le CRC32 de l'oligo
+        let mut corrupted = pool[1].clone();
+        let base = corrupted.bases[40];
+        corrupted.bases[40] = BASES.iter().copied().find(|&b| b != base).unwrap();
+        assert!(!PoolManifest::is_header_oligo(&corrupted));
+
+        pool.push(data);
+        let (decoded, payload) = split_pool(&pool).unwrap();
+        assert_eq!(decoded.unwrap().sha256, manifest.sha256);
+        assert_eq!(payload.len(), 1);
+    }
+
+    #[test]
+    fn test_manifest_verify() {
+        let manifest = sample_manifest();
+
+        assert!(manifest.verify(b"original file contents").is_ok());
+        assert!(matches!(
+            manifest.verify(b"original file Contents"),
+            Err(DnaError::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(manifest.verify(b"short"), Err(DnaError::Decoding(_))));
+    }
+
+    #[test]
+    fn test_goldman_2013_manifest_records_no_compression() {
+        let config = EncoderConfig {
+            encoder_type: EncoderType::Goldman2013,
+            compression_enabled: true,
+            ..Default::default()
+        };
+
+        let manifest = PoolManifest::new(&config, "goldman_2013", b"raw", b"raw");
+        assert_eq!(manifest.compression, CompressionType::None);
+    }
+}
@@ -16,9 +16,15 @@ pub mod ultimate;
 pub mod concatenated;
 pub mod dictionary;
 pub mod ldpc;
+pub mod manifest;
+pub mod stream;
+pub mod vt_marker;
+pub mod product_code;
+pub mod registry;
+pub mod whitening;
 
 pub use encoder::{Encoder, EncoderConfig, EncoderType};
-pub use decoder::{Decoder, DecoderConfig};
+pub use decoder::{DecodeReport, Decoder, DecoderConfig};
 pub use reed_solomon::ReedSolomonCodec;
 pub use goldman_2013::{Goldman2013Encoder, Goldman2013Decoder};
 pub use grass_2015::{Grass2015Encoder, Grass2015Decoder};
@@ -30,9 +36,16 @@ pub use gc_optimizer::GcOptimizer;
 pub use enhanced_reed_solomon::EnhancedReedSolomonCodec;
 pub use enhanced_gc_aware::{EnhancedGcAwareEncoder, EnhancedGcAwareDecoder};
 pub use ultimate::{UltimateEncoder, UltimateDecoder, UltimateCodec, UltimateEncoderConfig};
-pub use concatenated::{ConcatenatedCodec, ConvolutionalCodec};
+pub use concatenated::{ConcatenatedCodec, ConvolutionalCodec, PuncturingPattern};
 pub use dictionary::{DictionaryCompressor, SequenceDictionaryCompressor, DictionaryStats};
-pub use ldpc::{LdpcCodec, SparseMatrix};
+pub use ldpc::{DegreeDistribution, LdpcAlgorithm, LdpcCodec, LdpcConfig, LdpcConstruction, LdpcDecodeReport, SparseMatrix};
+pub use manifest::{PoolManifest, SegmentInfo};
+pub use vt_marker::{VtMarkerDecoder, VtMarkerEncoder};
+pub use product_code::{ProductCodeConfig, ProductCodeDecoder, ProductCodeEncoder};
+pub use registry::{
+    codec_names, lookup_codec, register_codec, CodecCapabilities, CodecDescriptor, CodecParam, CodecRegistry, DnaCodec,
+};
+pub use stream::{FastaReader, FastaSink, FastaWriter, StreamSummary, DEFAULT_SEGMENT_SIZE};
 
 use crate::error::Result;
 use crate::sequence::DnaSequence;
@@ -91,6 +104,7 @@ mod tests {
                 crate::sequence::IupacBase::G,
                 crate::sequence::IupacBase::T,
             ],
+            ..Default::default()
         };
 
         let original = b"Hello, DNA world!";
@@ -0,0 +1,555 @@
+//! Code produit Reed-Solomon 2D (interne par oligo, externe par groupe)
+//!
+//! Les données sont rangées en matrice: une ligne par oligo, une colonne par
+//! position d'octet. Par groupe de `group_size` oligos de données:
+//! - le code externe, RS raccourci sur chaque colonne, ajoute
+//!   `parity_oligos` oligos de parité;
+//! - le code interne, RS sur chaque oligo (en-tête compris), corrige les
+//!   substitutions isolées.
+//!
+//! Quand les oligos portent les qualités de leurs bases (lectures FASTQ ou
+//! brins consensus), les octets peu fiables deviennent des effacements du code
+//! interne, qui corrige alors jusqu'à `INNER_ECC_BYTES` octets au lieu de la
+//! moitié.
+//!
+//! Un oligo perdu à la synthèse, ou que le code interne ne sait pas corriger,
+//! est absent du groupe; son rang étant connu par les index des autres
+//! oligos, il devient un effacement du code externe. Chaque groupe survit à
+//! la perte de `parity_oligos` brins quelconques.
+//!
+//! Format d'un oligo (avant RS interne, 2 bits par base):
+//! `[clé (1)] [groupe (2)] [rang (1)] [lignes de données du groupe (1)] [parité du groupe (1)] [données]`
+//!
+//! L'en-tête est blanchi par la clé, les données par la clé, le groupe et le
+//! rang (`codec::whitening`): padding et fichiers creux ne deviennent pas de
+//! longues séries de A. Si l'oligo obtenu viole malgré tout les contraintes
+//! de l'encodeur, la clé suivante est essayée.
+//!
+//! Le flux encodé est préfixé de sa longueur (u32 BE) puis complété de zéros
+//! jusqu'à une ligne entière.
+
+use crate::codec::encoder::is_synthesis_rejection;
+use crate::codec::reed_solomon::ReedSolomonCodec;
+use crate::codec::whitening::whiten;
+use crate::error::{DnaError, Result};
+use crate::reads::fastq::byte_error_probabilities;
+use crate::sequence::{DnaConstraints, DnaSequence, IupacBase};
+use std::collections::BTreeMap;
+
+/// Octets de ECC du code interne (corrige 2 octets erronés par oligo)
+pub const INNER_ECC_BYTES: usize = 4;
+
+/// Octets d'en-tête par oligo (clé, groupe, rang, lignes de données, parité)
+const HEADER_BYTES: usize = 6;
+
+/// Masque de l'octet de clé, pour que la clé 0 ne s'écrive pas AAAA
+const KEY_MASK: u8 = 0xA5;
+
+/// Probabilité d'erreur au-delà de laquelle un octet est effacé par le code interne
+const ERASURE_THRESHOLD: f64 = 0.2;
+
+/// Octets de données maximum par oligo
+pub const MAX_PAYLOAD_BYTES: usize = 255 - INNER_ECC_BYTES - HEADER_BYTES;
+
+/// Bases indexées par leur valeur 2 bits
+const BASES: [IupacBase; 4] = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+
+/// Paramètres du code produit
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductCodeConfig {
+    /// Octets de données par oligo
+    pub payload_bytes: usize,
+    /// Oligos de données par groupe
+    pub group_size: usize,
+    /// Oligos de parité externe par groupe (brins perdus tolérés)
+    pub parity_oligos: usize,
+}
+
+impl Default for ProductCodeConfig {
+    fn default() -> Self {
+        Self {
+            payload_bytes: 24,
+            group_size: 32,
+            parity_oligos: 4,
+        }
+    }
+}
+
+impl ProductCodeConfig {
+    /// Configuration dont la parité externe suit un facteur de redondance
+    ///
+    /// `redundancy = 1.25` ajoute un oligo de parité pour 4 oligos de données.
+    pub fn with_redundancy(mut self, redundancy: f64) -> Self {
+        let parity = (self.group_size as f64 * (redundancy - 1.0)).ceil();
+        self.parity_oligos = (parity.max(1.0) as usize).min(255 - self.group_size);
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.payload_bytes == 0 || self.payload_bytes > MAX_PAYLOAD_BYTES {
+            return Err(DnaError::Encoding(format!(
+                "Code produit: {} octets par oligo (1 à {})",
+                self.payload_bytes, MAX_PAYLOAD_BYTES
+            )));
+        }
+        if self.group_size == 0 || self.parity_oligos == 0 || self.group_size + self.parity_oligos > 255 {
+            return Err(DnaError::Encoding(format!(
+                "Code produit: groupe de {} + {} oligos (max 255)",
+                self.group_size, self.parity_oligos
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Encodeur du code produit
+pub struct ProductCodeEncoder {
+    config: ProductCodeConfig,
+    constraints: DnaConstraints,
+}
+
+impl ProductCodeEncoder {
+    /// Crée un encodeur dont les oligos respectent `constraints`
+    pub fn new(config: ProductCodeConfig, constraints: DnaConstraints) -> Self {
+        Self { config, constraints }
+    }
+
+    /// Encode des données en oligos protégés par le code produit
+    pub fn encode(&self, data: &[u8], scheme: &str) -> Result<Vec<DnaSequence>> {
+        self.config.validate()?;
+        let ProductCodeConfig { payload_bytes, group_size, parity_oligos } = self.config;
+
+        let len = u32::try_from(data.len())
+            .map_err(|_| DnaError::Encoding("Code produit: données supérieures à 4 Gio".to_string()))?;
+        let mut stream = Vec::with_capacity(4 + data.len() + payload_bytes);
+        stream.extend_from_slice(&len.to_be_bytes());
+        stream.extend_from_slice(data);
+        stream.resize(stream.len().div_ceil(payload_bytes) * payload_bytes, 0);
+
+        let rows: Vec<&[u8]> = stream.chunks(payload_bytes).collect();
+        let groups = rows.len().div_ceil(group_size);
+        if groups > u16::MAX as usize + 1 {
+            return Err(DnaError::Encoding(format!(
+                "Code produit: {} groupes dépassent l'index 16 bits",
+                groups
+            )));
+        }
+
+        let inner = ReedSolomonCodec::shortened(HEADER_BYTES + payload_bytes, INNER_ECC_BYTES)?;
+        let mut sequences = Vec::with_capacity(groups * (group_size + parity_oligos));
+
+        for (group, data_rows) in rows.chunks(group_size).enumerate() {
+            let outer = ReedSolomonCodec::shortened(data_rows.len(), parity_oligos)?;
+
+            // Parité externe: un mot de code par colonne
+            let mut parity_rows = vec![vec![0u8; payload_bytes]; parity_oligos];
+            for col in 0..payload_bytes {
+                let column: Vec<u8> = data_rows.iter().map(|row| row[col]).collect();
+                let codeword = outer.encode_block(&column);
+                for (row, &byte) in parity_rows.iter_mut().zip(&codeword[data_rows.len()..]) {
+                    row[col] = byte;
+                }
+            }
+
+            let all_rows = data_rows.iter().copied().chain(parity_rows.iter().map(Vec::as_slice));
+            for (rank, row) in all_rows.enumerate() {
+                let [group_hi, group_lo] = (group as u16).to_be_bytes();
+                let header = [group_hi, group_lo, rank as u8, data_rows.len() as u8, parity_oligos as u8];
+
+                let index = sequences.len();
+                sequences.push(DnaSequence::with_encoding_scheme(
+                    self.encode_oligo(&inner, header, row)?,
+                    String::from("product_rs"),
+                    index,
+                    payload_bytes,
+                    index as u64,
+                    scheme.to_string(),
+                ));
+            }
+        }
+
+        Ok(sequences)
+    }
+
+    /// Blanchit et protège un oligo, en changeant de clé tant qu'il viole les contraintes
+    fn encode_oligo(
+        &self,
+        inner: &ReedSolomonCodec,
+        header: [u8; HEADER_BYTES - 1],
+        row: &[u8],
+    ) -> Result<Vec<IupacBase>> {
+        let mut rejection = None;
+
+        for key in 0..=u8::MAX {
+            let mut payload = Vec::with_capacity(HEADER_BYTES + row.len());
+            payload.push(key);
+            payload.extend_from_slice(&header);
+            payload.extend_from_slice(row);
+            whiten_payload(&mut payload);
+
+            let bases = bytes_to_bases(&inner.encode_block(&payload));
+            match self.constraints.validate(&bases) {
+                Ok(()) => return Ok(bases),
+                Err(e) if is_synthesis_rejection(&e) => rejection = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(DnaError::Encoding(format!(
+            "Code produit: aucune clé de blanchiment ne respecte les contraintes ({})",
+            rejection.map(|e| e.to_string()).unwrap_or_default()
+        )))
+    }
+}
+
+/// Clé du flux de blanchiment des données: clé de l'oligo, groupe et rang
+fn row_key(payload: &[u8]) -> u64 {
+    u64::from_be_bytes([0, 0, 0, 1, payload[0], payload[1], payload[2], payload[3]])
+}
+
+/// Blanchit une charge en clair: la clé est masquée, l'en-tête et les données blanchis
+fn whiten_payload(payload: &mut [u8]) {
+    let (key, row) = (payload[0], row_key(payload));
+    whiten(&mut payload[HEADER_BYTES..], row);
+    whiten(&mut payload[1..HEADER_BYTES], key as u64);
+    payload[0] ^= KEY_MASK;
+}
+
+/// Inverse de `whiten_payload`
+fn unwhiten_payload(payload: &mut [u8]) {
+    payload[0] ^= KEY_MASK;
+    let key = payload[0];
+    whiten(&mut payload[1..HEADER_BYTES], key as u64);
+    let row = row_key(payload);
+    whiten(&mut payload[HEADER_BYTES..], row);
+}
+
+/// Oligos reçus d'un groupe, indexés par rang
+struct GroupRows {
+    data_rows: usize,
+    parity_oligos: usize,
+    rows: BTreeMap<usize, Vec<u8>>,
+}
+
+/// Décodeur du code produit
+///
+/// Les paramètres du code (taille des oligos, des groupes, parité) sont lus
+/// dans les oligos eux-mêmes: aucune configuration n'est nécessaire.
+pub struct ProductCodeDecoder;
+
+impl ProductCodeDecoder {
+    /// Crée un décodeur
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Décode un pool d'oligos du code produit
+    ///
+    /// Les qualités de `metadata.base_error_probabilities` guident le code
+    /// interne. Les oligos indécodables par le code interne sont écartés; parmi
+    /// plusieurs copies d'un même rang, la première valide est retenue. Les
+    /// rangs absents sont restaurés par le code externe.
+    pub fn decode(&self, sequences: &[DnaSequence]) -> Result<Vec<u8>> {
+        let mut groups: BTreeMap<usize, GroupRows> = BTreeMap::new();
+        let mut rejected = 0usize;
+        let mut payload_bytes = None;
+
+        for sequence in sequences {
+            let Some(payload) = decode_oligo(sequence) else {
+                rejected += 1;
+                continue;
+            };
+            // Tous les oligos du pool ont la même longueur que le premier décodé
+            if *payload_bytes.get_or_insert(payload.len() - HEADER_BYTES) != payload.len() - HEADER_BYTES {
+                rejected += 1;
+                continue;
+            }
+
+            let group = u16::from_be_bytes([payload[1], payload[2]]) as usize;
+            let (rank, data_rows, parity_oligos) = (payload[3] as usize, payload[4] as usize, payload[5] as usize);
+            let entry = groups.entry(group).or_insert_with(|| GroupRows {
+                data_rows,
+                parity_oligos,
+                rows: BTreeMap::new(),
+            });
+            if entry.data_rows != data_rows || entry.parity_oligos != parity_oligos {
+                rejected += 1;
+                continue;
+            }
+            entry.rows.entry(rank).or_insert_with(|| payload[HEADER_BYTES..].to_vec());
+        }
+
+        let (Some(payload_bytes), Some(&last)) = (payload_bytes, groups.keys().next_back()) else {
+            return Err(DnaError::Decoding(format!(
+                "Code produit: aucun oligo décodable ({} rejetés)",
+                rejected
+            )));
+        };
+        if rejected > 0 {
+            tracing::warn!("Code produit: {} oligos rejetés par le code interne", rejected);
+        }
+
+        let mut stream = Vec::new();
+        for group in 0..=last {
+            let rows = groups.get(&group).ok_or_else(|| {
+                DnaError::Decoding(format!("Code produit: groupe {} entièrement perdu", group))
+            })?;
+            stream.extend(decode_group(group, rows, payload_bytes)?);
+        }
+
+        if stream.len() < 4 {
+            return Err(DnaError::DataCorrupted);
+        }
+        let len = u32::from_be_bytes([stream[0], stream[1], stream[2], stream[3]]) as usize;
+        if stream.len() < 4 + len {
+            return Err(DnaError::InsufficientData {
+                need: 4 + len,
+                have: stream.len(),
+            });
+        }
+
+        Ok(stream[4..4 + len].to_vec())
+    }
+}
+
+impl Default for ProductCodeDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Restaure les lignes de données d'un groupe par le code externe
+///
+/// Les rangs absents sont passés comme effacements à
+/// `ReedSolomonCodec::decode_with_erasures`, colonne par colonne.
+fn decode_group(group: usize, rows: &GroupRows, payload_bytes: usize) -> Result<Vec<u8>> {
+    let total = rows.data_rows + rows.parity_oligos;
+    let missing: Vec<usize> = (0..total).filter(|rank| !rows.rows.contains_key(rank)).collect();
+
+    if missing.iter().all(|&rank| rank >= rows.data_rows) {
+        return Ok((0..rows.data_rows).flat_map(|rank| rows.rows[&rank].clone()).collect());
+    }
+    if missing.len() > rows.parity_oligos {
+        tracing::warn!("Code produit: groupe {}, rangs manquants {:?}", group, missing);
+        return Err(DnaError::InsufficientData {
+            need: rows.data_rows,
+            have: total - missing.len(),
+        });
+    }
+
+    let outer = ReedSolomonCodec::shortened(rows.data_rows, rows.parity_oligos)?;
+    let mut data = vec![0u8; rows.data_rows * payload_bytes];
+
+    for col in 0..payload_bytes {
+        // Mot de code au format de `ReedSolomonCodec::encode`: longueur puis bloc
+        let mut codeword = Vec::with_capacity(4 + total);
+        codeword.extend_from_slice(&(rows.data_rows as u32).to_be_bytes());
+        codeword.extend((0..total).map(|rank| rows.rows.get(&rank).map_or(0, |row| row[col])));
+
+        let column = outer.decode_with_erasures(&codeword, &missing)?;
+        for (rank, byte) in column.into_iter().enumerate() {
+            data[rank * payload_bytes + col] = byte;
+        }
+    }
+
+    Ok(data)
+}
+
+/// Décode le code interne d'un oligo; None s'il est incorrigible ou incohérent
+///
+/// Avec des qualités par base, les octets peu fiables sont d'abord effacés;
+/// en cas d'échec, le décodage sans effacement est tenté.
+fn decode_oligo(sequence: &DnaSequence) -> Option<Vec<u8>> {
+    let bases = &sequence.bases;
+    if !bases.len().is_multiple_of(4) || bases.len() / 4 <= HEADER_BYTES + INNER_ECC_BYTES {
+        return None;
+    }
+    let codeword = bases_to_bytes(bases)?;
+    let data_len = codeword.len() - INNER_ECC_BYTES;
+    let inner = ReedSolomonCodec::shortened(data_len, INNER_ECC_BYTES).ok()?;
+
+    let qualities = &sequence.metadata.base_error_probabilities;
+    let with_erasures = (qualities.len() == bases.len())
+        .then(|| {
+            // Mot de code au format de `ReedSolomonCodec::encode`: longueur puis bloc
+            let mut framed = (data_len as u32).to_be_bytes().to_vec();
+            framed.extend_from_slice(&codeword);
+            let mut reliability = vec![0.0; 4];
+            reliability.extend(byte_error_probabilities(qualities));
+            inner.decode_with_reliability(&framed, &reliability, ERASURE_THRESHOLD).ok()
+        })
+        .flatten();
+
+    let mut payload = match with_erasures {
+        Some(payload) => payload,
+        None => inner.correct_block(&codeword, &[]).ok()?,
+    };
+    payload.truncate(data_len);
+    unwhiten_payload(&mut payload);
+
+    let (rank, data_rows, parity_oligos) = (payload[3] as usize, payload[4] as usize, payload[5] as usize);
+    (data_rows > 0 && parity_oligos > 0 && rank < data_rows + parity_oligos).then_some(payload)
+}
+
+/// Octets → bases, 2 bits par base (poids fort en premier)
+fn bytes_to_bases(bytes: &[u8]) -> Vec<IupacBase> {
+    bytes
+        .iter()
+        .flat_map(|&byte| (0..4).rev().map(move |i| BASES[((byte >> (2 * i)) & 3) as usize]))
+        .collect()
+}
+
+/// Bases → octets; None si une base n'est pas A/C/G/T
+fn bases_to_bytes(bases: &[IupacBase]) -> Option<Vec<u8>> {
+    bases
+        .chunks(4)
+        .map(|chunk| {
+            chunk.iter().try_fold(0u8, |byte, base| {
+                let bits = BASES.iter().position(|b| b == base)? as u8;
+                Some((byte << 2) | bits)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    fn encode(data: &[u8], config: ProductCodeConfig) -> Vec<DnaSequence> {
+        ProductCodeEncoder::new(config, DnaConstraints::default())
+            .encode(data, "product_rs")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let original = sample(1000);
+        let pool = encode(&original, ProductCodeConfig::default());
+
+        // 1004 octets → 42 lignes de 24 octets → groupes de 32 et 10 lignes
+        assert_eq!(pool.len(), 32 + 4 + 10 + 4);
+        assert!(pool.iter().all(|s| s.bases.len() == (HEADER_BYTES + 24 + INNER_ECC_BYTES) * 4));
+
+        assert_eq!(ProductCodeDecoder::new().decode(&pool).unwrap(), original);
+    }
+
+    #[test]
+    fn test_whitened_oligos_respect_constraints() {
+        // Zéros et motif répétitif: sans blanchiment, de longues séries de A
+        let mut original = vec![0u8; 600];
+        original.extend(sample(400));
+        let pool = encode(&original, ProductCodeConfig::default());
+
+        let constraints = DnaConstraints::default();
+        for oligo in &pool {
+            oligo.validate(&constraints).unwrap();
+        }
+
+        assert_eq!(ProductCodeDecoder::new().decode(&pool).unwrap(), original);
+    }
+
+    #[test]
+    fn test_five_percent_strand_loss_is_recovered() {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let original: Vec<u8> = (0..4000).map(|_| rng.gen()).collect();
+        let config = ProductCodeConfig::default();
+        let mut pool = encode(&original, config.clone());
+
+        // Au plus `parity_oligos` pertes par groupe, données comme parité
+        let period = config.group_size + config.parity_oligos;
+        let lost = pool.len() * 5 / 100;
+        let mut dropped: Vec<usize> = (0..pool.len()).step_by(period / config.parity_oligos).collect();
+        dropped.shuffle(&mut rng);
+        dropped.truncate(lost);
+        dropped.sort_unstable_by(|a, b| b.cmp(a));
+        for index in dropped {
+            pool.remove(index);
+        }
+        pool.shuffle(&mut rng);
+
+        assert_eq!(ProductCodeDecoder::new().decode(&pool).unwrap(), original);
+    }
+
+    #[test]
+    fn test_inner_code_corrects_substitutions() {
+        let original = sample(300);
+        let mut pool = encode(&original, ProductCodeConfig::default());
+
+        // Une base fausse dans chaque oligo, et un oligo perdu
+        for (i, oligo) in pool.iter_mut().enumerate() {
+            let pos = (i * 7) % oligo.bases.len();
+            oligo.bases[pos] = if oligo.bases[pos] == IupacBase::A { IupacBase::G } else { IupacBase::A };
+        }
+        pool.remove(2);
+
+        assert_eq!(ProductCodeDecoder::new().decode(&pool).unwrap(), original);
+    }
+
+    #[test]
+    fn test_low_quality_positions_become_erasures() {
+        let original = sample(300);
+        let config = ProductCodeConfig {
+            parity_oligos: 1,
+            ..Default::default()
+        };
+        let mut pool = encode(&original, config);
+
+        // Trois octets faux dans deux oligos: au-delà du code interne (2 erreurs)
+        // comme du code externe (1 oligo perdu)
+        for oligo in &mut pool[..2] {
+            oligo.metadata.base_error_probabilities = vec![0.001; oligo.bases.len()];
+            for pos in [9, 41, 77] {
+                oligo.bases[pos] = if oligo.bases[pos] == IupacBase::A { IupacBase::G } else { IupacBase::A };
+                oligo.metadata.base_error_probabilities[pos] = 0.8;
+            }
+        }
+
+        // Les qualités désignent les octets à effacer: 3 effacements ≤ 4 octets de ECC
+        assert_eq!(ProductCodeDecoder::new().decode(&pool).unwrap(), original);
+
+        // Sans qualités, les deux oligos sont perdus
+        for oligo in &mut pool[..2] {
+            oligo.metadata.base_error_probabilities.clear();
+        }
+        assert!(ProductCodeDecoder::new().decode(&pool).is_err());
+    }
+
+    #[test]
+    fn test_too_many_losses_in_group_fail() {
+        let original = sample(300);
+        let config = ProductCodeConfig {
+            parity_oligos: 2,
+            ..Default::default()
+        };
+        let mut pool = encode(&original, config);
+        pool.drain(0..3);
+
+        assert!(matches!(
+            ProductCodeDecoder::new().decode(&pool),
+            Err(DnaError::InsufficientData { .. })
+        ));
+    }
+
+    #[test]
+    fn test_redundancy_sets_outer_parity() {
+        let config = ProductCodeConfig::default().with_redundancy(1.25);
+        assert_eq!(config.parity_oligos, 8);
+        assert_eq!(ProductCodeConfig::default().with_redundancy(1.0).parity_oligos, 1);
+
+        let invalid = ProductCodeConfig {
+            payload_bytes: MAX_PAYLOAD_BYTES + 1,
+            ..Default::default()
+        };
+        assert!(ProductCodeEncoder::new(invalid, DnaConstraints::default())
+            .encode(b"x", "product_rs")
+            .is_err());
+    }
+}
@@ -8,6 +8,7 @@
 
 use crate::error::{DnaError, Result};
 use reed_solomon::{Encoder, Decoder};
+use std::collections::HashMap;
 
 /// Taille max des données par bloc (255 total - 32 ECC)
 const MAX_DATA_BLOCK_SIZE: usize = 223;
@@ -63,6 +64,26 @@ impl ReedSolomonCodec {
         }
     }
 
+    /// Crée un code raccourci: blocs de `data_len` octets + `ecc_len` octets de ECC
+    ///
+    /// Utile quand les blocs sont courts (colonnes d'un code produit) et
+    /// qu'un bourrage à 255 octets serait prohibitif.
+    pub fn shortened(data_len: usize, ecc_len: usize) -> Result<Self> {
+        if data_len == 0 || data_len + ecc_len > 255 {
+            return Err(DnaError::Correction(format!(
+                "Reed-Solomon raccourci invalide: {} + {} octets (max 255)",
+                data_len, ecc_len
+            )));
+        }
+
+        Ok(Self {
+            encoder: Encoder::new(ecc_len),
+            decoder: Decoder::new(ecc_len),
+            ecc_len,
+            max_data_block: data_len,
+        })
+    }
+
     /// Encode les données avec Reed-Solomon ECC
     ///
     /// Les données sont divisées en blocs de max_data_block bytes,
@@ -205,6 +226,70 @@ impl ReedSolomonCodec {
         Ok(result)
     }
 
+    /// Décode en effaçant les octets les moins fiables
+    ///
+    /// `byte_error_probabilities[i]` est la probabilité que l'octet `i` de
+    /// `data` (préfixe de longueur compris) soit erroné. Les octets au-delà de
+    /// `erasure_threshold` deviennent des effacements, dans la limite de
+    /// `max_erasures_per_block` par bloc en gardant les moins fiables.
+    pub fn decode_with_reliability(
+        &self,
+        data: &[u8],
+        byte_error_probabilities: &[f64],
+        erasure_threshold: f64,
+    ) -> Result<Vec<u8>> {
+        let block_size = self.block_size();
+        let mut per_block: HashMap<usize, Vec<(usize, f64)>> = HashMap::new();
+
+        for (i, &p) in byte_error_probabilities.iter().enumerate().skip(4) {
+            if p > erasure_threshold && i < data.len() {
+                let pos = i - 4;
+                per_block.entry(pos / block_size).or_default().push((pos, p));
+            }
+        }
+
+        let mut erasures: Vec<usize> = per_block
+            .into_values()
+            .flat_map(|mut candidates| {
+                candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+                candidates.truncate(self.max_erasures_per_block());
+                candidates.into_iter().map(|(pos, _)| pos)
+            })
+            .collect();
+        erasures.sort_unstable();
+
+        self.decode_with_erasures(data, &erasures)
+    }
+
+    /// Encode un bloc isolé (au plus `max_data_block` octets) sans préfixe de longueur
+    ///
+    /// Retourne le mot de code `data + ecc`, à corriger avec `correct_block`.
+    pub fn encode_block(&self, block: &[u8]) -> Vec<u8> {
+        self.encoder.encode(block).to_vec()
+    }
+
+    /// Corrige un bloc isolé (data + ecc) et retourne le mot de code corrigé
+    ///
+    /// Utilisé par les décodeurs concaténés qui travaillent bloc par bloc.
+    /// `erasure_positions` sont relatives au début du bloc.
+    pub fn correct_block(&self, block: &[u8], erasure_positions: &[u8]) -> Result<Vec<u8>> {
+        let positions = if erasure_positions.is_empty() {
+            None
+        } else {
+            Some(erasure_positions)
+        };
+
+        let corrected = self.decoder.correct(block, positions).map_err(|_| {
+            DnaError::Correction(format!(
+                "Reed-Solomon: correction impossible pour un bloc de {} bytes ({} effacements)",
+                block.len(),
+                erasure_positions.len()
+            ))
+        })?;
+
+        Ok(self.encoder.encode(corrected.data()).to_vec())
+    }
+
     /// Vérifie si les données contiennent des erreurs (sans correction)
     pub fn is_corrupted(&self, data: &[u8]) -> bool {
         if data.is_empty() {
@@ -403,6 +488,41 @@ mod tests {
         assert_eq!(original.to_vec(), recovered);
     }
 
+    #[test]
+    fn test_unreliable_bytes_become_erasures() {
+        let codec = ReedSolomonCodec::with_ecc_len(8); // 4 erreurs ou 8 effacements
+        let original = b"Low quality bases become erasures instead of errors";
+
+        let mut encoded = codec.encode(original).unwrap();
+        let mut probabilities = vec![0.001; encoded.len()];
+        for pos in 10..16 {
+            encoded[pos] ^= 0xFF;
+            probabilities[pos] = 0.6;
+        }
+
+        assert_ne!(codec.decode(&encoded).ok(), Some(original.to_vec()));
+        let recovered = codec.decode_with_reliability(&encoded, &probabilities, 0.1).unwrap();
+        assert_eq!(original.to_vec(), recovered);
+    }
+
+    #[test]
+    fn test_shortened_code_with_erasures() {
+        let codec = ReedSolomonCodec::shortened(10, 4).unwrap();
+        assert!(ReedSolomonCodec::shortened(252, 4).is_err());
+
+        let column: Vec<u8> = (1..=10).collect();
+        let mut encoded = codec.encode(&column).unwrap();
+        assert_eq!(encoded.len(), 4 + 14);
+        assert_eq!(&encoded[4..], codec.encode_block(&column).as_slice());
+
+        // 4 effacements: la capacité maximale du code
+        for &pos in &[0usize, 3, 9, 12] {
+            encoded[4 + pos] = 0;
+        }
+        let recovered = codec.decode_with_erasures(&encoded, &[0, 3, 9, 12]).unwrap();
+        assert_eq!(recovered, column);
+    }
+
     #[test]
     fn test_too_many_errors() {
         let codec = ReedSolomonCodec::with_ecc_len(4); // Peut corriger max 2 erreurs
@@ -447,4 +567,22 @@ mod tests {
         let encoded2 = codec.encode(&data2).unwrap();
         assert_eq!(encoded2.len(), 4 + 255 * 2); // 4 bytes préfixe + 2 blocs
     }
+
+    #[test]
+    fn test_correct_block_returns_codeword() {
+        let codec = ReedSolomonCodec::new();
+        let encoded = codec.encode(b"Bloc isole").unwrap();
+        let block = &encoded[4..4 + codec.block_size()];
+
+        let mut corrupted = block.to_vec();
+        corrupted[3] ^= 0x5A;
+        corrupted[100] ^= 0x01;
+
+        let corrected = codec.correct_block(&corrupted, &[]).unwrap();
+        assert_eq!(corrected, block.to_vec());
+
+        // Effacements connus: positions relatives au bloc
+        let corrected = codec.correct_block(&corrupted, &[3, 100]).unwrap();
+        assert_eq!(corrected, block.to_vec());
+    }
 }
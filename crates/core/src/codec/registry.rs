@@ -0,0 +1,458 @@
+//! Registre des codecs ADN
+//!
+//! `DnaCodec` est l'interface commune des schémas d'encodage: nom et alias,
+//! capacités, paramètres lus dans `EncoderConfig`, encodage et décodage. Le
+//! registre global est la seule liste des schémas disponibles: la CLI, l'API
+//! web et la détection de schéma du `Decoder` le consultent. Un schéma défini
+//! hors de ce crate s'ajoute avec `register_codec`, sans autre modification.
+//!
+//! Les schémas historiques (`EncoderType`) sont enregistrés d'office.
+
+use crate::codec::decoder::{Decoder, DecoderConfig};
+use crate::codec::encoder::{Encoder, EncoderConfig, EncoderType};
+use crate::error::{DnaError, Result};
+use crate::sequence::DnaSequence;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+
+/// Capacités d'un codec
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodecCapabilities {
+    /// Corrige des insertions/délétions à l'intérieur d'un oligo
+    pub corrects_indels: bool,
+    /// Tolère la perte d'oligos (redondance entre oligos)
+    pub tolerates_strand_loss: bool,
+    /// Respecte les contraintes GC/homopolymères de la configuration
+    pub enforces_constraints: bool,
+    /// Encode les données brutes: la compression de l'`Encoder` n'est pas appliquée
+    pub raw_input: bool,
+}
+
+/// Type d'un paramètre de codec
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ParamKind {
+    Integer { min: u64, max: u64 },
+    Float { min: f64, max: f64 },
+    Boolean,
+}
+
+/// Paramètre d'un codec, lu dans le champ de même nom d'`EncoderConfig`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodecParam {
+    pub name: String,
+    pub description: String,
+    #[serde(flatten)]
+    pub kind: ParamKind,
+    pub default: serde_json::Value,
+}
+
+impl CodecParam {
+    /// Paramètre entier borné
+    pub fn integer(name: &str, description: &str, min: u64, max: u64, default: u64) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            kind: ParamKind::Integer { min, max },
+            default: default.into(),
+        }
+    }
+
+    /// Paramètre réel borné
+    pub fn float(name: &str, description: &str, min: f64, max: f64, default: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            kind: ParamKind::Float { min, max },
+            default: default.into(),
+        }
+    }
+}
+
+/// Description sérialisable d'un codec (liste des schémas de l'API web)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodecDescriptor {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub description: String,
+    pub capabilities: CodecCapabilities,
+    pub params: Vec<CodecParam>,
+}
+
+/// Interface commune des schémas d'encodage ADN
+///
+/// `encode` reçoit le flux déjà compressé (sauf `raw_input`) et produit les
+/// oligos de données; l'`Encoder` ajoute le manifeste. Les séquences produites
+/// doivent porter `name()` comme schéma d'encodage, pour la détection.
+pub trait DnaCodec: Send + Sync {
+    /// Nom du schéma, inscrit dans les en-têtes FASTA et le manifeste
+    fn name(&self) -> &str;
+
+    /// Autres noms acceptés par `CodecRegistry::get`
+    fn aliases(&self) -> &[&'static str] {
+        &[]
+    }
+
+    /// Description en une ligne
+    fn description(&self) -> &str;
+
+    /// Capacités du schéma
+    fn capabilities(&self) -> CodecCapabilities;
+
+    /// Paramètres d'`EncoderConfig` lus par le schéma
+    fn params_schema(&self) -> Vec<CodecParam> {
+        Vec::new()
+    }
+
+    /// Type historique correspondant, pour les schémas intégrés
+    fn encoder_type(&self) -> Option<EncoderType> {
+        None
+    }
+
+    /// Encode un flux en oligos de données
+    fn encode(&self, data: &[u8], config: &EncoderConfig) -> Result<Vec<DnaSequence>>;
+
+    /// Décode des oligos de données (sans manifeste) en flux
+    fn decode(&self, sequences: &[DnaSequence], config: &EncoderConfig, options: &DecoderConfig) -> Result<Vec<u8>>;
+
+    /// Reconnaît un pool sans manifeste dont le schéma n'est pas inscrit
+    ///
+    /// Appelé seulement si aucun codec ne porte le nom de schéma des séquences.
+    fn detect(&self, _sequences: &[DnaSequence]) -> bool {
+        false
+    }
+
+    /// Description sérialisable
+    fn descriptor(&self) -> CodecDescriptor {
+        CodecDescriptor {
+            name: self.name().to_string(),
+            aliases: self.aliases().iter().map(|a| a.to_string()).collect(),
+            description: self.description().to_string(),
+            capabilities: self.capabilities(),
+            params: self.params_schema(),
+        }
+    }
+}
+
+/// Registre des codecs disponibles
+#[derive(Clone, Default)]
+pub struct CodecRegistry {
+    codecs: Vec<Arc<dyn DnaCodec>>,
+}
+
+impl CodecRegistry {
+    /// Registre vide
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registre des schémas intégrés
+    pub fn with_builtin() -> Self {
+        Self {
+            codecs: EncoderType::ALL
+                .iter()
+                .map(|&encoder_type| Arc::new(BuiltinCodec::new(encoder_type)) as Arc<dyn DnaCodec>)
+                .collect(),
+        }
+    }
+
+    /// Registre global, initialisé avec les schémas intégrés
+    pub fn global() -> &'static RwLock<CodecRegistry> {
+        static GLOBAL: OnceLock<RwLock<CodecRegistry>> = OnceLock::new();
+        GLOBAL.get_or_init(|| RwLock::new(Self::with_builtin()))
+    }
+
+    /// Ajoute un codec; échoue si son nom ou un alias est déjà pris
+    pub fn register(&mut self, codec: Arc<dyn DnaCodec>) -> Result<()> {
+        let taken = std::iter::once(codec.name())
+            .chain(codec.aliases().iter().copied())
+            .find(|name| self.get(name).is_some());
+        if let Some(name) = taken {
+            return Err(DnaError::Encoding(format!("Codec déjà enregistré: {}", name)));
+        }
+
+        self.codecs.push(codec);
+        Ok(())
+    }
+
+    /// Codec par nom ou alias (casse et `-`/`_` indifférents)
+    pub fn get(&self, name: &str) -> Option<Arc<dyn DnaCodec>> {
+        let name = normalize(name);
+        self.codecs
+            .iter()
+            .find(|codec| {
+                normalize(codec.name()) == name || codec.aliases().iter().any(|alias| normalize(alias) == name)
+            })
+            .cloned()
+    }
+
+    /// Codec intégré d'un type historique
+    pub fn for_type(&self, encoder_type: EncoderType) -> Option<Arc<dyn DnaCodec>> {
+        self.codecs
+            .iter()
+            .find(|codec| codec.encoder_type() == Some(encoder_type))
+            .cloned()
+    }
+
+    /// Noms des codecs, dans l'ordre d'enregistrement
+    pub fn names(&self) -> Vec<String> {
+        self.codecs.iter().map(|codec| codec.name().to_string()).collect()
+    }
+
+    /// Descriptions des codecs
+    pub fn descriptors(&self) -> Vec<CodecDescriptor> {
+        self.codecs.iter().map(|codec| codec.descriptor()).collect()
+    }
+
+    /// Codec d'un pool sans manifeste
+    ///
+    /// Le schéma commun des séquences désigne le codec; à défaut, le premier
+    /// codec dont `detect` reconnaît le pool.
+    pub fn detect(&self, sequences: &[DnaSequence]) -> Option<Arc<dyn DnaCodec>> {
+        let scheme = &sequences.first()?.metadata.encoding_scheme;
+        if sequences.iter().all(|s| &s.metadata.encoding_scheme == scheme) {
+            if let Some(codec) = self.codecs.iter().find(|codec| codec.name() == scheme) {
+                return Some(codec.clone());
+            }
+        }
+
+        self.codecs.iter().find(|codec| codec.detect(sequences)).cloned()
+    }
+}
+
+/// Enregistre un codec dans le registre global
+pub fn register_codec(codec: Arc<dyn DnaCodec>) -> Result<()> {
+    CodecRegistry::global().write().register(codec)
+}
+
+/// Cherche un codec du registre global par nom ou alias
+pub fn lookup_codec(name: &str) -> Option<Arc<dyn DnaCodec>> {
+    CodecRegistry::global().read().get(name)
+}
+
+/// Noms des codecs du registre global
+pub fn codec_names() -> Vec<String> {
+    CodecRegistry::global().read().names()
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace('-', "_")
+}
+
+/// Schéma intégré, adossé à l'`Encoder` et au `Decoder`
+pub struct BuiltinCodec {
+    encoder_type: EncoderType,
+}
+
+impl BuiltinCodec {
+    /// Codec d'un type historique
+    pub fn new(encoder_type: EncoderType) -> Self {
+        Self { encoder_type }
+    }
+}
+
+impl DnaCodec for BuiltinCodec {
+    fn name(&self) -> &str {
+        self.encoder_type.scheme_name()
+    }
+
+    fn aliases(&self) -> &[&'static str] {
+        match self.encoder_type {
+            EncoderType::ErlichZielinski2017 => &["ez2017", "erlichzielinski2017"],
+            EncoderType::Goldman2013 => &["goldman2013"],
+            EncoderType::Grass2015 => &["grass2015"],
+            EncoderType::ProductCode => &["product_code"],
+            _ => &[],
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.encoder_type {
+            EncoderType::Fountain => "DNA Fountain: codes LT, distribution robust soliton",
+            EncoderType::ErlichZielinski2017 => "DNA Fountain d'Erlich & Zielinski (Science 2017), oligos GC-aware",
+            EncoderType::Goldman2013 => "Goldman et al. 2013: Huffman, rotation base 3, adressage 4 octets",
+            EncoderType::Goldman => "Goldman simplifié: 2 bits par base, sans contraintes",
+            EncoderType::Grass2015 => "Grass et al. 2015: Reed-Solomon, adressage 3 segments",
+            EncoderType::Adaptive => "Fontaine LT adaptée au type de données",
+            EncoderType::Base3 => "Encodage base 3",
+            EncoderType::VtMarker => "Code interne VT + marqueurs, corrige les indels",
+            EncoderType::ProductCode => "Code produit Reed-Solomon 2D, tolère la perte de brins",
+        }
+    }
+
+    fn capabilities(&self) -> CodecCapabilities {
+        let fountain = CodecCapabilities {
+            tolerates_strand_loss: true,
+            enforces_constraints: true,
+            ..Default::default()
+        };
+        match self.encoder_type {
+            EncoderType::Fountain | EncoderType::ErlichZielinski2017 | EncoderType::Adaptive => fountain,
+            EncoderType::Goldman2013 => CodecCapabilities {
+                enforces_constraints: true,
+                raw_input: true,
+                ..Default::default()
+            },
+            EncoderType::Grass2015 => CodecCapabilities {
+                raw_input: true,
+                ..Default::default()
+            },
+            EncoderType::Goldman | EncoderType::Base3 => CodecCapabilities::default(),
+            EncoderType::VtMarker => CodecCapabilities {
+                corrects_indels: true,
+                ..Default::default()
+            },
+            EncoderType::ProductCode => CodecCapabilities {
+                tolerates_strand_loss: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn params_schema(&self) -> Vec<CodecParam> {
+        let chunk_size = |max| CodecParam::integer("chunk_size", "Octets de données par oligo", 1, max, 32);
+        let redundancy = |min, max| CodecParam::float("redundancy", "Facteur de redondance", min, max, 1.5);
+        match self.encoder_type {
+            EncoderType::Fountain | EncoderType::Adaptive => vec![chunk_size(1024), redundancy(1.0, 10.0)],
+            EncoderType::ErlichZielinski2017 => vec![chunk_size(1024), redundancy(1.03, 1.07)],
+            EncoderType::Goldman | EncoderType::Base3 => vec![chunk_size(1024)],
+            EncoderType::Goldman2013 | EncoderType::Grass2015 => Vec::new(),
+            EncoderType::VtMarker => vec![chunk_size(crate::codec::vt_marker::MAX_PAYLOAD_BYTES as u64)],
+            EncoderType::ProductCode => vec![
+                chunk_size(crate::codec::product_code::MAX_PAYLOAD_BYTES as u64),
+                redundancy(1.0, 8.0),
+            ],
+        }
+    }
+
+    fn encoder_type(&self) -> Option<EncoderType> {
+        Some(self.encoder_type)
+    }
+
+    fn encode(&self, data: &[u8], config: &EncoderConfig) -> Result<Vec<DnaSequence>> {
+        let config = EncoderConfig {
+            encoder_type: self.encoder_type,
+            ..config.clone()
+        };
+        Encoder::new(config)?.encode_builtin(data)
+    }
+
+    fn decode(&self, sequences: &[DnaSequence], config: &EncoderConfig, options: &DecoderConfig) -> Result<Vec<u8>> {
+        Decoder::new(options.clone()).decode_builtin(self.encoder_type, sequences, &config.constraints)
+    }
+
+    fn detect(&self, sequences: &[DnaSequence]) -> bool {
+        // Droplets LT dont le schéma n'est pas inscrit (anciens pools)
+        self.encoder_type == EncoderType::Fountain && sequences.iter().all(|s| s.metadata.total_chunks > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::encoder::CompressionType;
+    use crate::sequence::IupacBase;
+
+    const BASES: [IupacBase; 4] = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+
+    /// Codec externe minimal: un octet par groupe de 4 bases, sans redondance
+    struct Quaternary;
+
+    impl DnaCodec for Quaternary {
+        fn name(&self) -> &str {
+            "quaternary"
+        }
+
+        fn description(&self) -> &str {
+            "2 bits par base"
+        }
+
+        fn capabilities(&self) -> CodecCapabilities {
+            CodecCapabilities::default()
+        }
+
+        fn encode(&self, data: &[u8], _config: &EncoderConfig) -> Result<Vec<DnaSequence>> {
+            let bases = data
+                .iter()
+                .flat_map(|&byte| (0..4).rev().map(move |i| BASES[((byte >> (2 * i)) & 3) as usize]))
+                .collect();
+            Ok(vec![DnaSequence::with_encoding_scheme(bases, "q".to_string(), 0, data.len(), 0, self.name().to_string())])
+        }
+
+        fn decode(&self, sequences: &[DnaSequence], _config: &EncoderConfig, _options: &DecoderConfig) -> Result<Vec<u8>> {
+            Ok(sequences[0]
+                .bases
+                .chunks(4)
+                .map(|chunk| chunk.iter().fold(0u8, |byte, base| (byte << 2) | BASES.iter().position(|b| b == base).unwrap_or(0) as u8))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_builtin_names_and_aliases() {
+        let registry = CodecRegistry::with_builtin();
+        assert_eq!(registry.names().len(), EncoderType::ALL.len());
+
+        for encoder_type in EncoderType::ALL {
+            let codec = registry.get(encoder_type.scheme_name()).unwrap();
+            assert_eq!(codec.encoder_type(), Some(encoder_type));
+        }
+        assert_eq!(registry.get("Goldman2013").unwrap().name(), "goldman_2013");
+        assert_eq!(registry.get("vt-marker").unwrap().name(), "vt_marker");
+        assert!(registry.get("inconnu").is_none());
+
+        let descriptor = registry.get("product_rs").unwrap().descriptor();
+        assert!(descriptor.capabilities.tolerates_strand_loss);
+        assert_eq!(descriptor.params[0].name, "chunk_size");
+    }
+
+    #[test]
+    fn test_register_rejects_duplicates() {
+        let mut registry = CodecRegistry::with_builtin();
+        registry.register(Arc::new(Quaternary)).unwrap();
+        assert!(registry.register(Arc::new(Quaternary)).is_err());
+        assert!(registry.register(Arc::new(BuiltinCodec::new(EncoderType::Goldman))).is_err());
+    }
+
+    #[test]
+    fn test_external_codec_roundtrip_through_encoder_and_decoder() {
+        register_codec(Arc::new(Quaternary)).ok();
+        let codec = lookup_codec("quaternary").unwrap();
+
+        let config = EncoderConfig {
+            compression_enabled: false,
+            compression_type: CompressionType::None,
+            ..Default::default()
+        };
+        let original = b"codec externe".to_vec();
+        let pool = Encoder::new(config).unwrap().with_codec(codec).encode(&original).unwrap();
+
+        // Manifeste puis détection par le nom de schéma
+        let decoder = Decoder::new(DecoderConfig::default());
+        assert_eq!(decoder.decode(&pool).unwrap(), original);
+
+        let payload: Vec<DnaSequence> = pool
+            .into_iter()
+            .filter(|s| !crate::codec::PoolManifest::is_header_oligo(s))
+            .collect();
+        let raw = Decoder::new(DecoderConfig {
+            auto_decompress: false,
+            ..Default::default()
+        });
+        assert_eq!(raw.decode(&payload).unwrap(), original);
+    }
+
+    #[test]
+    fn test_detect_by_scheme_then_structure() {
+        let registry = CodecRegistry::with_builtin();
+        let mut sequence = DnaSequence::with_encoding_scheme(Vec::new(), "f".to_string(), 0, 0, 0, "vt_marker".to_string());
+        assert_eq!(registry.detect(&[sequence.clone()]).unwrap().name(), "vt_marker");
+
+        sequence.metadata.encoding_scheme = "encoded".to_string();
+        assert!(registry.detect(&[sequence.clone()]).is_none());
+
+        sequence.metadata.total_chunks = 4;
+        assert_eq!(registry.detect(&[sequence]).unwrap().name(), "fountain");
+    }
+}
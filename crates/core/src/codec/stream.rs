@@ -0,0 +1,247 @@
+//! Encodage et décodage en flux par segments
+//!
+//! Les gros fichiers sont découpés en segments de taille fixe. Chaque segment
+//! est encodé comme un pool indépendant (manifeste + oligos de données), si
+//! bien qu'un seul segment réside en mémoire à la fois, à l'encodage comme au
+//! décodage. Le manifeste de chaque segment porte sa position (`SegmentInfo`)
+//! pour que le décodeur vérifie l'ordre et détecte un flux tronqué.
+
+use crate::error::{DnaError, Result};
+use crate::sequence::DnaSequence;
+use std::io::{BufRead, Read, Write};
+
+pub use crate::codec::manifest::SegmentInfo;
+
+/// Taille par défaut d'un segment (1 Mio)
+pub const DEFAULT_SEGMENT_SIZE: usize = 1 << 20;
+
+/// Destination des séquences produites par `Encoder::encode_stream`
+pub trait FastaSink {
+    /// Écrit une séquence
+    fn write_sequence(&mut self, sequence: &DnaSequence) -> Result<()>;
+
+    /// Vide les tampons éventuels en fin de flux
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl FastaSink for Vec<DnaSequence> {
+    fn write_sequence(&mut self, sequence: &DnaSequence) -> Result<()> {
+        self.push(sequence.clone());
+        Ok(())
+    }
+}
+
+/// Écrit les séquences au format FASTA dans un `Write`
+pub struct FastaWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> FastaWriter<W> {
+    /// Crée un écrivain FASTA
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Retourne l'écrivain sous-jacent
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> FastaSink for FastaWriter<W> {
+    fn write_sequence(&mut self, sequence: &DnaSequence) -> Result<()> {
+        self.inner.write_all(sequence.to_fasta().as_bytes())?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()?;
+        Ok(())
+    }
+}
+
+/// Lit les séquences d'un flux FASTA une par une
+pub struct FastaReader<R: BufRead> {
+    lines: std::io::Lines<R>,
+    pending_header: Option<String>,
+}
+
+impl<R: BufRead> FastaReader<R> {
+    /// Crée un lecteur FASTA
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            pending_header: None,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for FastaReader<R> {
+    type Item = Result<DnaSequence>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = self.pending_header.take().unwrap_or_default();
+
+        loop {
+            match self.lines.next() {
+                Some(Ok(line)) => {
+                    if line.starts_with('>') && !record.is_empty() {
+                        self.pending_header = Some(line + "\n");
+                        break;
+                    }
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    record.push_str(&line);
+                    record.push('\n');
+                }
+                Some(Err(e)) => return Some(Err(DnaError::Io(e))),
+                None => break,
+            }
+        }
+
+        if record.is_empty() {
+            None
+        } else {
+            Some(DnaSequence::from_fasta(&record))
+        }
+    }
+}
+
+/// Bilan d'un encodage ou d'un décodage en flux
+#[derive(Debug, Clone, Default)]
+pub struct StreamSummary {
+    /// Nombre de segments traités
+    pub segments: usize,
+    /// Octets du fichier original traités
+    pub bytes: u64,
+    /// Nombre de séquences écrites ou lues
+    pub sequences: usize,
+    /// Avertissements des décodages de segments, préfixés par leur numéro
+    pub warnings: Vec<String>,
+}
+
+/// Lit au plus `size` octets (moins uniquement en fin de flux)
+pub(crate) fn read_segment<R: Read>(reader: &mut R, size: usize) -> Result<Vec<u8>> {
+    let mut segment = Vec::with_capacity(size.min(DEFAULT_SEGMENT_SIZE));
+    reader.take(size as u64).read_to_end(&mut segment)?;
+    Ok(segment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{Decoder, DecoderConfig, Encoder, EncoderConfig, EncoderType};
+    use crate::codec::encoder::CompressionType;
+    use crate::codec::PoolManifest;
+    use crate::sequence::DnaConstraints;
+    use std::io::Cursor;
+
+    fn stream_encoder(segment_size: usize) -> Encoder {
+        let config = EncoderConfig {
+            encoder_type: EncoderType::Goldman,
+            chunk_size: 16,
+            compression_enabled: false,
+            compression_type: CompressionType::None,
+            constraints: DnaConstraints::new(0.0, 1.0, 16, 200),
+            ..Default::default()
+        };
+        Encoder::new(config).unwrap().with_segment_size(segment_size)
+    }
+
+    fn sample_data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    fn encode_to_fasta(encoder: &Encoder, data: &[u8]) -> (Vec<u8>, StreamSummary) {
+        let mut writer = FastaWriter::new(Vec::new());
+        let summary = encoder.encode_stream(Cursor::new(data), &mut writer).unwrap();
+        (writer.into_inner(), summary)
+    }
+
+    #[test]
+    fn test_fasta_reader_writer_roundtrip() {
+        let sequences = vec![
+            DnaSequence::from_str("ACGTACGT", "s1".to_string(), 0, 8, 0).unwrap(),
+            DnaSequence::from_str("TTGCA", "s2".to_string(), 1, 5, 0).unwrap(),
+        ];
+
+        let mut writer = FastaWriter::new(Vec::new());
+        for sequence in &sequences {
+            writer.write_sequence(sequence).unwrap();
+        }
+        let fasta = writer.into_inner();
+
+        let parsed: Vec<DnaSequence> = FastaReader::new(Cursor::new(fasta))
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].bases, sequences[0].bases);
+        assert_eq!(parsed[1].bases, sequences[1].bases);
+    }
+
+    #[test]
+    fn test_stream_roundtrip_multiple_segments() {
+        let data = sample_data(1000);
+        let encoder = stream_encoder(256);
+
+        let (fasta, encoded) = encode_to_fasta(&encoder, &data);
+        assert_eq!(encoded.segments, 4);
+        assert_eq!(encoded.bytes, 1000);
+
+        let decoder = Decoder::new(DecoderConfig::default());
+        let mut output = Vec::new();
+        let decoded = decoder.decode_stream(Cursor::new(fasta), &mut output).unwrap();
+
+        assert_eq!(output, data);
+        assert_eq!(decoded.segments, 4);
+        assert_eq!(decoded.sequences, encoded.sequences);
+    }
+
+    #[test]
+    fn test_stream_exact_multiple_of_segment_size() {
+        let data = sample_data(512);
+        let encoder = stream_encoder(256);
+
+        let mut pool = Vec::new();
+        let summary = encoder.encode_stream(Cursor::new(&data), &mut pool).unwrap();
+        assert_eq!(summary.segments, 2);
+        assert_eq!(summary.sequences, pool.len());
+    }
+
+    #[test]
+    fn test_stream_rejects_empty_input() {
+        let encoder = stream_encoder(256);
+        let mut pool: Vec<DnaSequence> = Vec::new();
+        assert!(encoder.encode_stream(Cursor::new(Vec::<u8>::new()), &mut pool).is_err());
+    }
+
+    #[test]
+    fn test_truncated_stream_is_detected() {
+        let data = sample_data(1000);
+        let encoder = stream_encoder(256);
+
+        let mut pool = Vec::new();
+        encoder.encode_stream(Cursor::new(&data), &mut pool).unwrap();
+
+        // Supprimer le dernier segment (à partir de son premier oligo de manifeste)
+        let mut start = pool.iter().rposition(PoolManifest::is_header_oligo).unwrap();
+        while start > 0 && PoolManifest::is_header_oligo(&pool[start - 1]) {
+            start -= 1;
+        }
+        pool.truncate(start);
+
+        let mut writer = FastaWriter::new(Vec::new());
+        for sequence in &pool {
+            writer.write_sequence(sequence).unwrap();
+        }
+
+        let decoder = Decoder::new(DecoderConfig::default());
+        let mut output = Vec::new();
+        let result = decoder.decode_stream(Cursor::new(writer.into_inner()), &mut output);
+        assert!(result.is_err());
+        assert_eq!(output, &data[..768]);
+    }
+}
@@ -273,6 +273,7 @@ mod tests {
                 IupacBase::G,
                 IupacBase::T,
             ],
+            ..Default::default()
         };
 
         let config = UltimateEncoderConfig {
@@ -0,0 +1,481 @@
+//! Code interne résistant aux insertions/délétions (VT + marqueurs)
+//!
+//! Chaque oligo est découpé en segments de 12 bases séparés par un marqueur
+//! fixe de 3 bases. Un segment porte 2 octets: les bits de poids fort des bases
+//! forment un mot de Varshamov–Tenengolts VT_0(12) pour le premier octet, les
+//! bits de poids faible un second mot pour le deuxième octet. Une délétion ou
+//! une insertion de base retire ou ajoute un bit au même rang dans les deux
+//! mots, que chaque décodeur VT corrige indépendamment.
+//!
+//! Les marqueurs resynchronisent le décodeur: un segment dont le marqueur
+//! suivant est décalé d'une base a subi une délétion ou une insertion, et
+//! l'erreur ne se propage pas aux segments suivants.
+//!
+//! Format d'un oligo (avant découpage en segments):
+//! `[clé (1)] [index (2)] [longueur (1)] [CRC8 (1)] [données (≤ 15)] [bourrage pair]`
+//!
+//! Le CRC8 porte sur l'oligo en clair. L'en-tête est ensuite blanchi par la
+//! clé, les données par la clé et l'index (`codec::whitening`), sans quoi un
+//! octet nul donnerait un segment de 12 A. Si l'oligo obtenu viole malgré
+//! tout les contraintes de l'encodeur, la clé suivante est essayée.
+
+use crate::codec::encoder::is_synthesis_rejection;
+use crate::codec::whitening::whiten;
+use crate::error::{DnaError, Result};
+use crate::sequence::{DnaConstraints, DnaSequence, IupacBase};
+use std::collections::BTreeMap;
+
+/// Nombre de bases d'un segment (longueur des mots VT)
+pub const SEGMENT_BASES: usize = 12;
+
+/// Marqueur de synchronisation placé après chaque segment
+pub const MARKER: [IupacBase; 3] = [IupacBase::T, IupacBase::G, IupacBase::C];
+
+/// Octets de données maximum par oligo (10 segments, 150 bases)
+pub const MAX_PAYLOAD_BYTES: usize = 15;
+
+/// Octets d'en-tête par oligo (clé, index, longueur, CRC8)
+const HEADER_BYTES: usize = 5;
+
+/// Masque de l'octet de clé, pour que la clé 0 ne donne pas un segment de A
+const KEY_MASK: u8 = 0xA5;
+
+/// Positions (1-indexées) des bits de contrôle VT: puissances de 2
+const CHECK_POSITIONS: [usize; 4] = [1, 2, 4, 8];
+
+/// Positions (1-indexées) des bits d'information
+const INFO_POSITIONS: [usize; 8] = [3, 5, 6, 7, 9, 10, 11, 12];
+
+/// Bases indexées par leur valeur 2 bits
+const BASES: [IupacBase; 4] = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+
+/// Encodeur VT + marqueurs
+pub struct VtMarkerEncoder {
+    payload_bytes: usize,
+    constraints: DnaConstraints,
+}
+
+impl VtMarkerEncoder {
+    /// Crée un encodeur découpant les données en oligos de `payload_bytes`
+    /// octets qui respectent `constraints`
+    pub fn new(payload_bytes: usize, constraints: DnaConstraints) -> Self {
+        Self {
+            payload_bytes: payload_bytes.clamp(1, MAX_PAYLOAD_BYTES),
+            constraints,
+        }
+    }
+
+    /// Encode des données en oligos résistants aux indels
+    pub fn encode(&self, data: &[u8], scheme: &str) -> Result<Vec<DnaSequence>> {
+        let chunks: Vec<&[u8]> = data.chunks(self.payload_bytes).collect();
+        if chunks.len() > u16::MAX as usize + 1 {
+            return Err(DnaError::Encoding(format!(
+                "VT-marqueurs: {} oligos dépassent l'index 16 bits",
+                chunks.len()
+            )));
+        }
+
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                Ok(DnaSequence::with_encoding_scheme(
+                    self.encode_valid_oligo(index as u16, chunk)?,
+                    String::from("vt_marker"),
+                    index,
+                    chunk.len(),
+                    index as u64,
+                    scheme.to_string(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Encode un oligo, en changeant de clé tant qu'il viole les contraintes
+    fn encode_valid_oligo(&self, index: u16, chunk: &[u8]) -> Result<Vec<IupacBase>> {
+        let mut rejection = None;
+
+        for key in 0..=u8::MAX {
+            let bases = encode_oligo(key, index, chunk);
+            match self.constraints.validate(&bases) {
+                Ok(()) => return Ok(bases),
+                Err(e) if is_synthesis_rejection(&e) => rejection = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(DnaError::Encoding(format!(
+            "VT-marqueurs: aucune clé de blanchiment ne respecte les contraintes ({})",
+            rejection.map(|e| e.to_string()).unwrap_or_default()
+        )))
+    }
+}
+
+/// Décodeur VT + marqueurs
+pub struct VtMarkerDecoder;
+
+impl VtMarkerDecoder {
+    /// Crée un décodeur
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Décode un pool d'oligos VT + marqueurs
+    ///
+    /// Les oligos indécodables (plus d'une indel par segment, substitution)
+    /// sont écartés; parmi plusieurs copies d'un même index, la première
+    /// valide est retenue.
+    pub fn decode(&self, sequences: &[DnaSequence]) -> Result<Vec<u8>> {
+        let mut chunks: BTreeMap<u16, Vec<u8>> = BTreeMap::new();
+        let mut rejected = 0usize;
+
+        for sequence in sequences {
+            match decode_oligo(&sequence.bases) {
+                Some((index, payload)) => {
+                    chunks.entry(index).or_insert(payload);
+                }
+                None => rejected += 1,
+            }
+        }
+
+        let expected = match chunks.keys().next_back() {
+            Some(&last) => last as usize + 1,
+            None => {
+                return Err(DnaError::Decoding(format!(
+                    "VT-marqueurs: aucun oligo décodable ({} rejetés)",
+                    rejected
+                )))
+            }
+        };
+
+        if chunks.len() < expected {
+            let missing: Vec<usize> = (0..expected)
+                .filter(|&i| !chunks.contains_key(&(i as u16)))
+                .collect();
+            tracing::warn!("VT-marqueurs: oligos manquants {:?} ({} rejetés)", missing, rejected);
+            return Err(DnaError::InsufficientData {
+                need: expected,
+                have: chunks.len(),
+            });
+        }
+
+        Ok(chunks.into_values().flatten().collect())
+    }
+}
+
+impl Default for VtMarkerDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Construit les bases d'un oligo blanchi par `key`
+fn encode_oligo(key: u8, index: u16, chunk: &[u8]) -> Vec<IupacBase> {
+    let mut payload = Vec::with_capacity(HEADER_BYTES + chunk.len() + 1);
+    payload.push(key);
+    payload.extend_from_slice(&index.to_be_bytes());
+    payload.push(chunk.len() as u8);
+    payload.push(0);
+    payload.extend_from_slice(chunk);
+    payload[4] = payload_crc8(&payload);
+    if !payload.len().is_multiple_of(2) {
+        payload.push(0);
+    }
+
+    let data_key = data_key(&payload);
+    whiten(&mut payload[HEADER_BYTES..], data_key);
+    whiten(&mut payload[1..HEADER_BYTES], key as u64);
+    payload[0] ^= KEY_MASK;
+
+    let mut bases = Vec::with_capacity(payload.len() / 2 * (SEGMENT_BASES + MARKER.len()));
+    for pair in payload.chunks(2) {
+        bases.extend(encode_segment(pair[0], pair[1]));
+        bases.extend_from_slice(&MARKER);
+    }
+    bases
+}
+
+/// Décode un oligo; None si un segment est indécodable ou si le CRC8 diffère
+fn decode_oligo(bases: &[IupacBase]) -> Option<(u16, Vec<u8>)> {
+    let mut payload = Vec::new();
+    for segment in split_segments(bases) {
+        let (high, low) = decode_segment(segment)?;
+        payload.push(high);
+        payload.push(low);
+    }
+
+    if payload.len() < HEADER_BYTES {
+        return None;
+    }
+    payload[0] ^= KEY_MASK;
+    let key = payload[0];
+    whiten(&mut payload[1..HEADER_BYTES], key as u64);
+    let data_key = data_key(&payload);
+    whiten(&mut payload[HEADER_BYTES..], data_key);
+
+    let len = payload[3] as usize;
+    if HEADER_BYTES + len > payload.len() {
+        return None;
+    }
+    payload.truncate(HEADER_BYTES + len);
+
+    let crc = payload[4];
+    payload[4] = 0;
+    if payload_crc8(&payload) != crc {
+        return None;
+    }
+
+    let index = u16::from_be_bytes([payload[1], payload[2]]);
+    Some((index, payload.split_off(HEADER_BYTES)))
+}
+
+/// Clé du flux de blanchiment des données: clé de l'oligo et index
+fn data_key(payload: &[u8]) -> u64 {
+    u64::from_be_bytes([0, 0, 0, 0, 1, payload[0], payload[1], payload[2]])
+}
+
+/// Découpe un oligo reçu en segments à l'aide des marqueurs
+///
+/// Pour chaque segment, le décalage (0, -1 ou +1 base) retenu est celui qui
+/// aligne le mieux le marqueur courant et le suivant (lui-même décalé d'au
+/// plus une base). À score égal, le plus petit décalage l'emporte.
+fn split_segments(bases: &[IupacBase]) -> Vec<&[IupacBase]> {
+    let period = SEGMENT_BASES + MARKER.len();
+    let count = (bases.len() + period / 2) / period;
+
+    let marker_score = |at: usize| {
+        MARKER
+            .iter()
+            .enumerate()
+            .filter(|&(k, m)| bases.get(at + k) == Some(m))
+            .count()
+    };
+    let shifted = |at: usize, delta: isize| at.saturating_add_signed(delta);
+
+    let mut segments = Vec::with_capacity(count);
+    let mut pos = 0usize;
+
+    for s in 0..count {
+        let shift = [0isize, -1, 1]
+            .into_iter()
+            .max_by_key(|&delta| {
+                let end = shifted(pos + SEGMENT_BASES, delta);
+                let next = if s + 1 < count {
+                    [0isize, -1, 1]
+                        .into_iter()
+                        .map(|d| marker_score(shifted(end + period, d)))
+                        .max()
+                        .unwrap_or(0)
+                } else {
+                    0
+                };
+                (marker_score(end) + next, -delta.abs(), delta)
+            })
+            .unwrap_or(0);
+
+        let end = shifted(pos + SEGMENT_BASES, shift).min(bases.len());
+        segments.push(&bases[pos.min(end)..end]);
+        pos = end + MARKER.len();
+    }
+
+    segments
+}
+
+/// Encode deux octets en un segment de 12 bases
+fn encode_segment(high: u8, low: u8) -> Vec<IupacBase> {
+    let high = vt_encode(high);
+    let low = vt_encode(low);
+    high.iter()
+        .zip(low.iter())
+        .map(|(&h, &l)| BASES[(h * 2 + l) as usize])
+        .collect()
+}
+
+/// Décode un segment (12 bases, ou 11/13 après une indel)
+fn decode_segment(segment: &[IupacBase]) -> Option<(u8, u8)> {
+    let values: Vec<u8> = segment
+        .iter()
+        .map(|b| BASES.iter().position(|x| x == b).unwrap_or(0) as u8)
+        .collect();
+    let high: Vec<u8> = values.iter().map(|v| v >> 1).collect();
+    let low: Vec<u8> = values.iter().map(|v| v & 1).collect();
+
+    Some((vt_decode(&high)?, vt_decode(&low)?))
+}
+
+/// Syndrome VT: somme des rangs (1-indexés) des bits à 1, modulo n + 1
+fn vt_syndrome(bits: &[u8]) -> usize {
+    bits.iter()
+        .enumerate()
+        .map(|(i, &b)| (i + 1) * b as usize)
+        .sum::<usize>()
+        % (SEGMENT_BASES + 1)
+}
+
+/// Encode un octet en mot VT_0(12) systématique
+///
+/// Les bits d'information occupent les rangs qui ne sont pas des puissances
+/// de 2; les bits de contrôle complètent le syndrome à 0.
+fn vt_encode(byte: u8) -> [u8; SEGMENT_BASES] {
+    let mut bits = [0u8; SEGMENT_BASES];
+    for (k, &pos) in INFO_POSITIONS.iter().enumerate() {
+        bits[pos - 1] = (byte >> (7 - k)) & 1;
+    }
+
+    let deficit = (SEGMENT_BASES + 1 - vt_syndrome(&bits)) % (SEGMENT_BASES + 1);
+    for (j, &pos) in CHECK_POSITIONS.iter().enumerate() {
+        bits[pos - 1] = ((deficit >> j) & 1) as u8;
+    }
+    bits
+}
+
+/// Décode un mot VT_0(12) reçu avec au plus une insertion ou une délétion
+///
+/// Un mot VT corrige une indel de façon unique: le premier candidat de
+/// syndrome nul est le mot émis. Une substitution (longueur correcte mais
+/// syndrome non nul) est détectée et rejetée.
+fn vt_decode(received: &[u8]) -> Option<u8> {
+    let codeword: Vec<u8> = match received.len() {
+        SEGMENT_BASES => (vt_syndrome(received) == 0).then(|| received.to_vec())?,
+        n if n + 1 == SEGMENT_BASES => (0..=n)
+            .flat_map(|p| [0u8, 1].into_iter().map(move |b| (p, b)))
+            .map(|(p, b)| {
+                let mut candidate = received.to_vec();
+                candidate.insert(p, b);
+                candidate
+            })
+            .find(|c| vt_syndrome(c) == 0)?,
+        n if n == SEGMENT_BASES + 1 => (0..n)
+            .map(|p| {
+                let mut candidate = received.to_vec();
+                candidate.remove(p);
+                candidate
+            })
+            .find(|c| vt_syndrome(c) == 0)?,
+        _ => return None,
+    };
+
+    Some(
+        INFO_POSITIONS
+            .iter()
+            .fold(0u8, |byte, &pos| (byte << 1) | codeword[pos - 1]),
+    )
+}
+
+/// CRC-8 (polynôme 0x07) de l'en-tête et des données
+fn payload_crc8(payload: &[u8]) -> u8 {
+    payload.iter().fold(0u8, |mut crc, &byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+        }
+        crc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vt_codewords_have_zero_syndrome() {
+        for byte in 0..=255u8 {
+            let word = vt_encode(byte);
+            assert_eq!(vt_syndrome(&word), 0);
+            assert_eq!(vt_decode(&word), Some(byte));
+        }
+    }
+
+    #[test]
+    fn test_vt_corrects_any_single_indel() {
+        for byte in [0x00u8, 0x5A, 0xA7, 0xFF] {
+            let word = vt_encode(byte);
+
+            for p in 0..SEGMENT_BASES {
+                let mut deleted = word.to_vec();
+                deleted.remove(p);
+                assert_eq!(vt_decode(&deleted), Some(byte));
+
+                for b in [0u8, 1] {
+                    let mut inserted = word.to_vec();
+                    inserted.insert(p, b);
+                    assert_eq!(vt_decode(&inserted), Some(byte));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_vt_detects_substitution() {
+        let mut word = vt_encode(0x3C);
+        word[5] ^= 1;
+        assert_eq!(vt_decode(&word), None);
+    }
+
+    #[test]
+    fn test_oligo_survives_one_indel_per_segment() {
+        let chunk: Vec<u8> = (0..MAX_PAYLOAD_BYTES as u8).map(|i| i.wrapping_mul(37)).collect();
+        let bases = encode_oligo(0, 513, &chunk);
+        let period = SEGMENT_BASES + MARKER.len();
+
+        // Délétion dans les segments pairs, insertion dans les impairs,
+        // en partant de la fin pour garder les positions valides
+        let mut noisy = bases.clone();
+        for s in (0..bases.len() / period).rev() {
+            let pos = s * period + (s * 5) % SEGMENT_BASES;
+            if s % 2 == 0 {
+                noisy.remove(pos);
+            } else {
+                let other = if noisy[pos] == IupacBase::A { IupacBase::G } else { IupacBase::A };
+                noisy.insert(pos, other);
+            }
+        }
+
+        assert_eq!(decode_oligo(&noisy), Some((513, chunk)));
+    }
+
+    #[test]
+    fn test_substitution_rejects_oligo() {
+        let mut bases = encode_oligo(0, 0, b"substitution");
+        bases[SEGMENT_BASES + MARKER.len() + 2] = match bases[SEGMENT_BASES + MARKER.len() + 2] {
+            IupacBase::A => IupacBase::C,
+            _ => IupacBase::A,
+        };
+        assert_eq!(decode_oligo(&bases), None);
+    }
+
+    #[test]
+    fn test_whitened_oligos_respect_constraints() {
+        // Octets nuls: sans blanchiment, chaque segment serait AAAAAAAAAAAA
+        let data = vec![0u8; 300];
+        let constraints = DnaConstraints::default();
+        let pool = VtMarkerEncoder::new(MAX_PAYLOAD_BYTES, constraints.clone())
+            .encode(&data, "vt_marker")
+            .unwrap();
+
+        for oligo in &pool {
+            oligo.validate(&constraints).unwrap();
+        }
+        assert_eq!(VtMarkerDecoder::new().decode(&pool).unwrap(), data);
+    }
+
+    #[test]
+    fn test_pool_roundtrip_with_shuffled_duplicates() {
+        let data: Vec<u8> = (0..97u8).collect();
+        let mut pool = VtMarkerEncoder::new(MAX_PAYLOAD_BYTES, DnaConstraints::default())
+            .encode(&data, "vt_marker")
+            .unwrap();
+
+        pool.reverse();
+        let copy = pool[1].clone();
+        pool.push(copy);
+
+        assert_eq!(VtMarkerDecoder::new().decode(&pool).unwrap(), data);
+
+        pool.retain(|s| s.metadata.chunk_index != 2);
+        assert!(matches!(
+            VtMarkerDecoder::new().decode(&pool),
+            Err(DnaError::InsufficientData { .. })
+        ));
+    }
+}
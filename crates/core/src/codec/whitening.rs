@@ -0,0 +1,49 @@
+//! Blanchiment des charges avant leur écriture en bases
+//!
+//! Un encodage direct à 2 bits par base transforme les octets nuls (padding,
+//! octets de poids fort d'un index, zones vides d'un fichier) en longues
+//! séries de A. Combiner la charge par XOR avec un flux pseudo-aléatoire
+//! dérivé d'une clé connue du décodeur (seed, index d'oligo) rend les oligos
+//! statistiquement équilibrés, et permet à un encodeur de changer de clé
+//! quand l'oligo obtenu viole malgré tout les contraintes.
+
+use rand::RngCore;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Blanchit (ou restaure) des octets par XOR avec un flux ChaCha8 initialisé par `key`
+///
+/// L'opération est son propre inverse.
+pub fn whiten(bytes: &mut [u8], key: u64) {
+    let mut stream = vec![0u8; bytes.len()];
+    ChaCha8Rng::seed_from_u64(key).fill_bytes(&mut stream);
+    for (byte, mask) in bytes.iter_mut().zip(stream) {
+        *byte ^= mask;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whiten_is_involutive() {
+        let original = vec![0u8; 32];
+        let mut bytes = original.clone();
+
+        whiten(&mut bytes, 7);
+        assert_ne!(bytes, original);
+
+        whiten(&mut bytes, 7);
+        assert_eq!(bytes, original);
+    }
+
+    #[test]
+    fn test_whiten_depends_on_key() {
+        let mut first = vec![0u8; 16];
+        let mut second = vec![0u8; 16];
+        whiten(&mut first, 1);
+        whiten(&mut second, 2);
+        assert_ne!(first, second);
+    }
+}
@@ -1,7 +1,339 @@
 //! Validation et gestion des contraintes ADN
+//!
+//! Outre le GC global, les homopolymères et la longueur, les règles de
+//! synthèse couvrent la température de fusion (plus proches voisins,
+//! SantaLucia 1998), les structures secondaires (énergie libre minimale d'un
+//! repliement de type Nussinov/Zuker simplifié), les motifs interdits et le
+//! GC local sur fenêtre glissante.
 
 use crate::error::{DnaError, Result};
-use crate::sequence::{DnaConstraints, IupacBase};
+use crate::sequence::{DnaConstraints, GcWindow, IupacBase};
+
+/// Constante des gaz parfaits (cal/K/mol)
+const GAS_CONSTANT: f64 = 1.987;
+
+/// Concentration en Na+ (M) pour le calcul de Tm
+const SODIUM_CONCENTRATION: f64 = 0.05;
+
+/// Concentration totale de brins (M) pour le calcul de Tm
+const STRAND_CONCENTRATION: f64 = 250e-9;
+
+/// Boucle minimale d'une épingle à cheveux
+const MIN_HAIRPIN_LOOP: usize = 3;
+
+/// Pénalité d'initiation d'une boucle terminale (kcal/mol)
+const HAIRPIN_PENALTY: f64 = 5.4;
+
+/// Pénalité de fermeture d'une boucle multiple (kcal/mol)
+const MULTILOOP_PENALTY: f64 = 3.4;
+
+/// Nombre maximal de tiges coupées par `repair_synthesis`: chaque coupe
+/// recalcule les tables de repliement en O(n³)
+const MAX_FOLD_REPAIRS: usize = 16;
+
+/// Paramètres plus proches voisins (ΔH kcal/mol, ΔS cal/K/mol, ΔG37 kcal/mol)
+/// de l'empilement 5'-first second-3' sur son complément
+fn nn_stack(first: IupacBase, second: IupacBase) -> Option<(f64, f64, f64)> {
+    use IupacBase::{A, C, G, T};
+
+    let params = match (first, second) {
+        (A, A) | (T, T) => (-7.9, -22.2, -1.00),
+        (A, T) => (-7.2, -20.4, -0.88),
+        (T, A) => (-7.2, -21.3, -0.58),
+        (C, A) | (T, G) => (-8.5, -22.7, -1.45),
+        (G, T) | (A, C) => (-8.4, -22.4, -1.44),
+        (C, T) | (A, G) => (-7.8, -21.0, -1.28),
+        (G, A) | (T, C) => (-8.2, -22.2, -1.30),
+        (C, G) => (-10.6, -27.2, -2.17),
+        (G, C) => (-9.8, -24.4, -2.24),
+        (G, G) | (C, C) => (-8.0, -19.9, -1.84),
+        _ => return None,
+    };
+    Some(params)
+}
+
+/// Température de fusion (°C) par le modèle des plus proches voisins
+///
+/// Paramètres unifiés de SantaLucia (1998), correction de sel sur l'entropie,
+/// 50 mM Na+ et 250 nM de brins. Les bases ambiguës sont ignorées.
+pub fn nearest_neighbor_tm(bases: &[IupacBase]) -> f64 {
+    if bases.len() < 2 {
+        return 0.0;
+    }
+
+    let mut enthalpy = 0.0;
+    let mut entropy = 0.0;
+    for pair in bases.windows(2) {
+        if let Some((dh, ds, _)) = nn_stack(pair[0], pair[1]) {
+            enthalpy += dh;
+            entropy += ds;
+        }
+    }
+
+    // Initiation selon la nature des paires terminales
+    for end in [bases[0], bases[bases.len() - 1]] {
+        if matches!(end, IupacBase::G | IupacBase::C) {
+            enthalpy += 0.1;
+            entropy -= 2.8;
+        } else {
+            enthalpy += 2.3;
+            entropy += 4.1;
+        }
+    }
+
+    entropy += 0.368 * (bases.len() - 1) as f64 * SODIUM_CONCENTRATION.ln();
+
+    enthalpy * 1000.0 / (entropy + GAS_CONSTANT * (STRAND_CONCENTRATION / 4.0).ln()) - 273.15
+}
+
+/// Retourne true si deux bases forment une paire Watson-Crick
+fn pairs_with(a: IupacBase, b: IupacBase) -> bool {
+    a.is_standard() && a.complement() == b
+}
+
+/// Tables de repliement: `paired[i][j]` énergie minimale avec (i, j) appariés,
+/// `free[i][j]` énergie minimale du fragment [i, j]
+struct FoldTables {
+    n: usize,
+    paired: Vec<f64>,
+    free: Vec<f64>,
+}
+
+impl FoldTables {
+    fn compute(bases: &[IupacBase]) -> Self {
+        let n = bases.len();
+        let mut paired = vec![f64::INFINITY; n * n];
+        let mut free = vec![0.0; n * n];
+
+        for span in (MIN_HAIRPIN_LOOP + 1)..n {
+            for i in 0..(n - span) {
+                let j = i + span;
+
+                if pairs_with(bases[i], bases[j]) {
+                    // Boucle terminale
+                    let mut best = HAIRPIN_PENALTY;
+
+                    // Empilement sur la paire intérieure
+                    if let Some((_, _, dg)) = nn_stack(bases[i], bases[i + 1]) {
+                        best = best.min(paired[(i + 1) * n + j - 1] + dg);
+                    }
+
+                    // Boucle multiple fermée par (i, j): deux branches stables au moins
+                    for k in (i + 1)..(j - 1) {
+                        let left = free[(i + 1) * n + k];
+                        let right = free[(k + 1) * n + j - 1];
+                        if left < 0.0 && right < 0.0 {
+                            best = best.min(left + right + MULTILOOP_PENALTY);
+                        }
+                    }
+
+                    paired[i * n + j] = best;
+                }
+
+                let mut best = free[(i + 1) * n + j].min(free[i * n + j - 1]).min(paired[i * n + j]);
+                for k in (i + 1)..j {
+                    best = best.min(free[i * n + k] + free[(k + 1) * n + j]);
+                }
+                free[i * n + j] = best;
+            }
+        }
+
+        Self { n, paired, free }
+    }
+
+    /// Énergie libre minimale de la séquence entière
+    fn energy(&self) -> f64 {
+        if self.n == 0 {
+            0.0
+        } else {
+            self.free[self.n - 1]
+        }
+    }
+
+    /// Paire fermant la structure la plus stable
+    fn strongest_pair(&self) -> Option<(usize, usize)> {
+        let n = self.n;
+        (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| self.paired[i * n + j] < 0.0)
+            .min_by(|&(a, b), &(c, d)| self.paired[a * n + b].total_cmp(&self.paired[c * n + d]))
+    }
+}
+
+/// Énergie libre minimale (kcal/mol) des structures secondaires d'un brin
+///
+/// Repliement simplifié à la Zuker: empilements plus proches voisins,
+/// pénalités constantes de boucle terminale et de boucle multiple, sans
+/// boucles internes. Retourne 0 si aucune structure n'est stable.
+pub fn min_free_energy(bases: &[IupacBase]) -> f64 {
+    FoldTables::compute(bases).energy()
+}
+
+/// Retourne true si `base` correspond au code IUPAC `pattern`
+fn iupac_matches(pattern: IupacBase, base: IupacBase) -> bool {
+    fn mask(base: IupacBase) -> u8 {
+        match base {
+            IupacBase::A => 0b0001,
+            IupacBase::C => 0b0010,
+            IupacBase::G => 0b0100,
+            IupacBase::T => 0b1000,
+            IupacBase::R => 0b0101,
+            IupacBase::Y => 0b1010,
+            IupacBase::S => 0b0110,
+            IupacBase::W => 0b1001,
+            IupacBase::K => 0b1100,
+            IupacBase::M => 0b0011,
+            IupacBase::B => 0b1110,
+            IupacBase::D => 0b1101,
+            IupacBase::H => 0b1011,
+            IupacBase::V => 0b0111,
+            IupacBase::N => 0b1111,
+        }
+    }
+
+    mask(base) & !mask(pattern) == 0
+}
+
+/// Retourne true si `motif` (ou son complément inverse) commence en `position`
+fn motif_at(bases: &[IupacBase], position: usize, motif: &[IupacBase]) -> bool {
+    if motif.is_empty() || position + motif.len() > bases.len() {
+        return false;
+    }
+
+    let window = &bases[position..position + motif.len()];
+    let forward = motif.iter().zip(window).all(|(&p, &b)| iupac_matches(p, b));
+    let reverse = motif
+        .iter()
+        .rev()
+        .zip(window)
+        .all(|(&p, &b)| iupac_matches(p.complement(), b));
+
+    forward || reverse
+}
+
+/// Première occurrence d'un motif interdit: (position, longueur, motif)
+pub fn find_forbidden_motif<'m>(
+    bases: &[IupacBase],
+    motifs: &'m [Vec<IupacBase>],
+) -> Option<(usize, usize, &'m [IupacBase])> {
+    (0..bases.len()).find_map(|position| {
+        motifs
+            .iter()
+            .find(|motif| motif_at(bases, position, motif))
+            .map(|motif| (position, motif.len(), motif.as_slice()))
+    })
+}
+
+/// Première fenêtre dont le GC sort de la plage: (position, ratio GC)
+pub fn find_local_gc_violation(bases: &[IupacBase], window: &GcWindow) -> Option<(usize, f64)> {
+    if window.size == 0 || bases.len() < window.size {
+        return None;
+    }
+
+    let mut gc = bases[..window.size].iter().filter(|b| b.is_gc()).count();
+    for start in 0..=(bases.len() - window.size) {
+        if start > 0 {
+            gc += bases[start + window.size - 1].is_gc() as usize;
+            gc -= bases[start - 1].is_gc() as usize;
+        }
+
+        let ratio = gc as f64 / window.size as f64;
+        if ratio < window.gc_min || ratio > window.gc_max {
+            return Some((start, ratio));
+        }
+    }
+
+    None
+}
+
+/// Erreur si un motif interdit ou une fenêtre GC se termine sur la dernière base
+fn check_suffix(constraints: &DnaConstraints, bases: &[IupacBase], window_gc: Option<usize>) -> Result<()> {
+    for motif in &constraints.forbidden_motifs {
+        if let Some(position) = bases.len().checked_sub(motif.len()) {
+            if motif_at(bases, position, motif) {
+                return Err(DnaError::ForbiddenMotif {
+                    motif: motif_string(motif),
+                    position,
+                });
+            }
+        }
+    }
+
+    if let Some(window) = &constraints.gc_window {
+        if window.size > 0 && bases.len() >= window.size {
+            let position = bases.len() - window.size;
+            let gc = window_gc.unwrap_or_else(|| bases[position..].iter().filter(|b| b.is_gc()).count());
+            let ratio = gc as f64 / window.size as f64;
+            if ratio < window.gc_min || ratio > window.gc_max {
+                return Err(DnaError::LocalGcOutOfRange {
+                    position,
+                    gc: ratio,
+                    min: window.gc_min,
+                    max: window.gc_max,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn motif_string(motif: &[IupacBase]) -> String {
+    motif.iter().map(|b| b.as_char()).collect()
+}
+
+/// Vérifie les règles de synthèse, des plus rapides aux plus coûteuses
+pub(crate) fn check_synthesis_rules(constraints: &DnaConstraints, bases: &[IupacBase]) -> Result<()> {
+    if let Some((position, _, motif)) = find_forbidden_motif(bases, &constraints.forbidden_motifs) {
+        return Err(DnaError::ForbiddenMotif {
+            motif: motif_string(motif),
+            position,
+        });
+    }
+
+    if let Some(window) = &constraints.gc_window {
+        if let Some((position, gc)) = find_local_gc_violation(bases, window) {
+            return Err(DnaError::LocalGcOutOfRange {
+                position,
+                gc,
+                min: window.gc_min,
+                max: window.gc_max,
+            });
+        }
+    }
+
+    if let Some((min, max)) = constraints.tm_range {
+        let tm = nearest_neighbor_tm(bases);
+        if tm < min || tm > max {
+            return Err(DnaError::MeltingTemperatureOutOfRange { tm, min, max });
+        }
+    }
+
+    if let Some(min) = constraints.min_fold_energy {
+        let energy = min_free_energy(bases);
+        if energy < min {
+            return Err(DnaError::SecondaryStructure { energy, min });
+        }
+    }
+
+    Ok(())
+}
+
+/// Règle de synthèse violée, avec de quoi la corriger
+enum SynthesisViolation {
+    Motif { position: usize, len: usize },
+    LocalGc { position: usize, too_low: bool },
+    Tm { too_low: bool },
+    Fold { position: usize },
+}
+
+/// Positions d'un intervalle, du centre vers les bords
+fn center_out(start: usize, len: usize) -> Vec<usize> {
+    let center = start as f64 + (len as f64 - 1.0) / 2.0;
+    let mut positions: Vec<usize> = (start..start + len).collect();
+    positions.sort_by(|a, b| (*a as f64 - center).abs().total_cmp(&(*b as f64 - center).abs()));
+    positions
+}
 
 /// Trouve la longueur maximale d'homopolymer dans une séquence
 pub fn find_max_homopolymer(bases: &[IupacBase]) -> usize {
@@ -37,6 +369,9 @@ pub struct IncrementalConstraintValidator {
     total_count: usize,
     current_homopolymer_run: usize,
     last_base: Option<IupacBase>,
+    // Bases reçues (motifs, GC local, Tm et structure secondaire)
+    bases: Vec<IupacBase>,
+    window_gc: usize,
 }
 
 impl DnaConstraintValidator {
@@ -67,6 +402,21 @@ impl DnaConstraintValidator {
         self.constraints.validate(bases)
     }
 
+    /// Vérifie les seules règles de synthèse (Tm, structure, motifs, GC local)
+    pub fn check_synthesis(&self, bases: &[IupacBase]) -> Result<()> {
+        check_synthesis_rules(&self.constraints, bases)
+    }
+
+    /// Température de fusion par plus proches voisins (°C)
+    pub fn melting_temperature(&self, bases: &[IupacBase]) -> f64 {
+        nearest_neighbor_tm(bases)
+    }
+
+    /// Énergie libre minimale des structures secondaires (kcal/mol)
+    pub fn fold_energy(&self, bases: &[IupacBase]) -> f64 {
+        min_free_energy(bases)
+    }
+
     /// Calcule le ratio GC d'une séquence
     pub fn compute_gc_ratio(&self, bases: &[IupacBase]) -> f64 {
         if bases.is_empty() {
@@ -145,6 +495,15 @@ impl DnaConstraintValidator {
             }
         }
 
+        // Motifs interdits et GC local se terminant sur la nouvelle base
+        if !self.constraints.forbidden_motifs.is_empty() || self.constraints.gc_window.is_some() {
+            let mut extended = bases.to_vec();
+            extended.push(new_base);
+            if check_suffix(&self.constraints, &extended, None).is_err() {
+                return false;
+            }
+        }
+
         // Pour le GC, on ne peut pas savoir à l'avance si ce sera OK
         // car on ne connaît pas la longueur finale
         true
@@ -242,13 +601,136 @@ impl DnaConstraintValidator {
 
         // Si le GC final est hors limites, essayer de corriger en remplaçant certaines bases
         if final_gc < self.constraints.gc_min || final_gc > self.constraints.gc_max {
-            return self.enforce_gc_with_retry(&result, bases);
+            result = self.enforce_gc_with_retry(&result, bases)?;
+        }
+
+        // Règles de synthèse: substitutions ciblées
+        if self.constraints.has_synthesis_rules() {
+            self.repair_synthesis(&mut result);
         }
 
         self.validate_sequence(&result)?;
         Ok(result)
     }
 
+    /// Première règle de synthèse violée
+    fn first_synthesis_violation(&self, bases: &[IupacBase]) -> Option<SynthesisViolation> {
+        if let Some((position, len, _)) = find_forbidden_motif(bases, &self.constraints.forbidden_motifs) {
+            return Some(SynthesisViolation::Motif { position, len });
+        }
+
+        if let Some(window) = &self.constraints.gc_window {
+            if let Some((position, gc)) = find_local_gc_violation(bases, window) {
+                return Some(SynthesisViolation::LocalGc {
+                    position,
+                    too_low: gc < window.gc_min,
+                });
+            }
+        }
+
+        if let Some((min, max)) = self.constraints.tm_range {
+            let tm = nearest_neighbor_tm(bases);
+            if tm < min || tm > max {
+                return Some(SynthesisViolation::Tm { too_low: tm < min });
+            }
+        }
+
+        if let Some(min) = self.constraints.min_fold_energy {
+            let tables = FoldTables::compute(bases);
+            if tables.energy() < min {
+                let (i, j) = tables.strongest_pair()?;
+                // Couper la tige en son milieu
+                let stem = (0..)
+                    .take_while(|&k| i + k < j - k && pairs_with(bases[i + k], bases[j - k]))
+                    .count();
+                return Some(SynthesisViolation::Fold { position: i + stem / 2 });
+            }
+        }
+
+        None
+    }
+
+    /// Corrige les règles de synthèse par substitutions ponctuelles
+    ///
+    /// Les substitutions par le complément (G↔C, A↔T) sont essayées en
+    /// premier: elles cassent un motif ou un appariement sans changer le GC.
+    /// Au-delà de `MAX_FOLD_REPAIRS` tiges coupées, la séquence est laissée
+    /// telle quelle et la validation finale la rejette. Retourne le nombre de
+    /// tiges coupées.
+    fn repair_synthesis(&self, bases: &mut [IupacBase]) -> usize {
+        let gc_bases = [IupacBase::G, IupacBase::C];
+        let at_bases = [IupacBase::A, IupacBase::T];
+        let mut fold_repairs = 0;
+
+        for _ in 0..bases.len() {
+            let Some(violation) = self.first_synthesis_violation(bases) else {
+                break;
+            };
+
+            let fixed = match violation {
+                SynthesisViolation::Motif { position, len } => center_out(position, len)
+                    .into_iter()
+                    .any(|p| self.substitute_any(bases, p)),
+                SynthesisViolation::Fold { position } => {
+                    let cut = fold_repairs < MAX_FOLD_REPAIRS && self.substitute_any(bases, position);
+                    fold_repairs += cut as usize;
+                    cut
+                }
+                SynthesisViolation::LocalGc { position, too_low } => {
+                    let size = self.constraints.gc_window.map_or(0, |w| w.size);
+                    let targets = if too_low { gc_bases } else { at_bases };
+                    let candidates: Vec<usize> = center_out(position, size)
+                        .into_iter()
+                        .filter(|&p| bases[p].is_gc() != too_low)
+                        .collect();
+                    candidates
+                        .into_iter()
+                        .any(|p| targets.iter().any(|&t| self.try_substitute(bases, p, t)))
+                }
+                SynthesisViolation::Tm { too_low } => {
+                    let targets = if too_low { gc_bases } else { at_bases };
+                    let candidates: Vec<usize> = center_out(0, bases.len())
+                        .into_iter()
+                        .filter(|&p| bases[p].is_gc() != too_low)
+                        .collect();
+                    candidates
+                        .into_iter()
+                        .any(|p| targets.iter().any(|&t| self.try_substitute(bases, p, t)))
+                }
+            };
+
+            if !fixed {
+                break;
+            }
+        }
+
+        fold_repairs
+    }
+
+    /// Substitue une base, par son complément de préférence
+    fn substitute_any(&self, bases: &mut [IupacBase], position: usize) -> bool {
+        let current = bases[position];
+        std::iter::once(current.complement())
+            .chain([IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T])
+            .any(|candidate| self.try_substitute(bases, position, candidate))
+    }
+
+    /// Remplace la base en `position` si cela n'allonge pas un homopolymère au-delà de la limite
+    fn try_substitute(&self, bases: &mut [IupacBase], position: usize, new_base: IupacBase) -> bool {
+        if bases[position] == new_base {
+            return false;
+        }
+
+        let left = bases[..position].iter().rev().take_while(|&&b| b == new_base).count();
+        let right = bases[position + 1..].iter().take_while(|&&b| b == new_base).count();
+        if left + right + 1 > self.constraints.max_homopolymer {
+            return false;
+        }
+
+        bases[position] = new_base;
+        true
+    }
+
     /// Corrige le GC content en remplaçant stratégiquement certaines bases
     fn enforce_gc_with_retry(&self, result: &[IupacBase], _original: &[IupacBase]) -> Result<Vec<IupacBase>> {
         let mut corrected = result.to_vec();
@@ -327,9 +809,6 @@ impl DnaConstraintValidator {
             }
         }
 
-        // Validation finale
-        self.validate_sequence(&corrected)?;
-
         Ok(corrected)
     }
 }
@@ -349,6 +828,8 @@ impl IncrementalConstraintValidator {
             total_count: 0,
             current_homopolymer_run: 0,
             last_base: None,
+            bases: Vec::new(),
+            window_gc: 0,
         }
     }
 
@@ -360,6 +841,8 @@ impl IncrementalConstraintValidator {
             total_count: 0,
             current_homopolymer_run: 0,
             last_base: None,
+            bases: Vec::new(),
+            window_gc: 0,
         }
     }
 
@@ -374,6 +857,8 @@ impl IncrementalConstraintValidator {
         self.total_count = 0;
         self.current_homopolymer_run = 0;
         self.last_base = None;
+        self.bases.clear();
+        self.window_gc = 0;
     }
 
     /// Ajoute une base et met à jour les statistiques incrémentales
@@ -412,6 +897,16 @@ impl IncrementalConstraintValidator {
             });
         }
 
+        // Motifs interdits et GC local sur la fenêtre qui se termine ici
+        self.bases.push(base);
+        if let Some(window) = self.constraints.gc_window {
+            self.window_gc += base.is_gc() as usize;
+            if window.size > 0 && self.bases.len() > window.size {
+                self.window_gc -= self.bases[self.bases.len() - 1 - window.size].is_gc() as usize;
+            }
+        }
+        check_suffix(&self.constraints, &self.bases, Some(self.window_gc))?;
+
         Ok(())
     }
 
@@ -458,6 +953,11 @@ impl IncrementalConstraintValidator {
             });
         }
 
+        // Tm et structure secondaire portent sur la séquence entière
+        if self.constraints.tm_range.is_some() || self.constraints.min_fold_energy.is_some() {
+            check_synthesis_rules(&self.constraints, &self.bases)?;
+        }
+
         Ok(())
     }
 
@@ -681,4 +1181,137 @@ mod tests {
         assert_eq!(stats.gc_ratio, 0.5);
         assert_eq!(stats.max_homopolymer, 2);
     }
+
+    fn parse(s: &str) -> Vec<IupacBase> {
+        s.chars().map(|c| IupacBase::from_char(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_nearest_neighbor_tm() {
+        // Amorce M13 inverse: ~57 °C à 50 mM Na+
+        let tm = nearest_neighbor_tm(&parse("AGCGGATAACAATTTCACACAGGA"));
+        assert!((55.0..60.0).contains(&tm), "Tm = {}", tm);
+
+        let gc_rich = nearest_neighbor_tm(&parse("GCGCGGCCGCGGCCGCGCGG"));
+        let at_rich = nearest_neighbor_tm(&parse("ATATTATAATTATATAATTA"));
+        assert!(gc_rich > tm && tm > at_rich);
+    }
+
+    #[test]
+    fn test_min_free_energy_detects_hairpin() {
+        // Tige GGGGC/GCCCC autour d'une boucle AAAA
+        let hairpin = parse("GGGGCAAAAGCCCC");
+        assert!(min_free_energy(&hairpin) < -2.0);
+        assert_eq!(min_free_energy(&parse("AAAAAAAAAAAAAA")), 0.0);
+
+        // Deux épingles indépendantes sont plus stables qu'une seule
+        let double = parse("GGGGCAAAAGCCCCTTGGGGCAAAAGCCCC");
+        assert!(min_free_energy(&double) < min_free_energy(&hairpin));
+    }
+
+    #[test]
+    fn test_forbidden_motifs_both_strands() {
+        let motifs = vec![parse("GCTAGT"), parse("GGNCC")];
+
+        assert_eq!(find_forbidden_motif(&parse("AAGCTAGTAA"), &motifs).map(|m| m.0), Some(2));
+        // Complément inverse de GCTAGT
+        assert_eq!(find_forbidden_motif(&parse("TTACTAGCTT"), &motifs).map(|m| m.0), Some(2));
+        // Code ambigu N
+        assert_eq!(find_forbidden_motif(&parse("ATGGACCTA"), &motifs).map(|m| m.0), Some(2));
+        assert!(find_forbidden_motif(&parse("ACGTACGTAC"), &motifs).is_none());
+    }
+
+    #[test]
+    fn test_local_gc_window() {
+        let window = GcWindow { size: 6, gc_min: 0.3, gc_max: 0.7 };
+        assert!(find_local_gc_violation(&parse("ACGTACGTACGT"), &window).is_none());
+        // Fenêtre ACGCGC riche en GC
+        let (position, gc) = find_local_gc_violation(&parse("ACGTACGCGCGCACGTAC"), &window).unwrap();
+        assert_eq!(position, 4);
+        assert!(gc > 0.7);
+    }
+
+    #[test]
+    fn test_validate_with_synthesis_rules() {
+        let bases = parse("ACGTGAATTCACGTACGTAC");
+        assert!(DnaConstraints::default().validate(&bases).is_ok());
+
+        let constraints = DnaConstraints::default().with_forbidden_motifs(vec![parse("GAATTC")]);
+        assert!(matches!(
+            constraints.validate(&bases),
+            Err(DnaError::ForbiddenMotif { position: 4, .. })
+        ));
+
+        let constraints = DnaConstraints::default().with_tm_range(70.0, 90.0);
+        assert!(matches!(
+            constraints.validate(&bases),
+            Err(DnaError::MeltingTemperatureOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_enforce_constraints_repairs_synthesis_rules() {
+        let constraints = DnaConstraints::new(0.3, 0.7, 3, 150)
+            .with_forbidden_motifs(vec![parse("GAATTC"), parse("GGATCC")])
+            .with_gc_window(10, 0.2, 0.8)
+            .with_min_fold_energy(-3.0);
+        let validator = DnaConstraintValidator::with_constraints(constraints);
+
+        let bases = parse("GAATTCACGGATCCTAGCGGGCATTTAGCCCGCATGAATTCAGT");
+        assert!(validator.check_synthesis(&bases).is_err());
+
+        let enforced = validator.enforce_constraints(&bases).unwrap();
+        assert_eq!(enforced.len(), bases.len());
+        assert!(validator.check_synthesis(&enforced).is_ok());
+        assert!(validator.validate_sequence(&enforced).is_ok());
+    }
+
+    #[test]
+    fn test_incremental_synthesis_rules() {
+        let constraints = DnaConstraints::new(0.0, 1.0, 3, 150).with_forbidden_motifs(vec![parse("GAATTC")]);
+        let mut validator = IncrementalConstraintValidator::with_constraints(constraints);
+        for base in parse("ACGTGAATT") {
+            validator.add_base(base).unwrap();
+        }
+        // Le motif se termine sur la base suivante
+        assert!(matches!(validator.add_base(IupacBase::C), Err(DnaError::ForbiddenMotif { .. })));
+
+        let constraints = DnaConstraints::new(0.0, 1.0, 3, 150).with_gc_window(4, 0.25, 0.75);
+        let mut validator = IncrementalConstraintValidator::with_constraints(constraints);
+        for base in parse("ATGCG") {
+            validator.add_base(base).unwrap();
+        }
+        // Fenêtre GCGC: 100% GC
+        assert!(matches!(validator.add_base(IupacBase::C), Err(DnaError::LocalGcOutOfRange { .. })));
+
+        let constraints = DnaConstraints::new(0.0, 1.0, 4, 150).with_min_fold_energy(-2.0);
+        let mut validator = IncrementalConstraintValidator::with_constraints(constraints);
+        assert!(validator.validate_sequence_incremental(&parse("ACACACACACAC")).is_ok());
+        assert!(matches!(
+            validator.validate_sequence_incremental(&parse("GGGGCAAAAGCCCC")),
+            Err(DnaError::SecondaryStructure { .. })
+        ));
+    }
+
+    #[test]
+    fn test_repair_synthesis_long_hairpin_is_bounded() {
+        // Épingle de 200 nt: 100 nt suivis de leur complément inverse
+        let half: Vec<IupacBase> = (0..100u32)
+            .map(|i| [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T][((i * 7 + i / 3) % 4) as usize])
+            .collect();
+        let mut bases = half.clone();
+        bases.extend(half.iter().rev().map(|b| b.complement()));
+
+        let constraints = DnaConstraints::new(0.0, 1.0, 3, 200).with_min_fold_energy(-3.0);
+        let validator = DnaConstraintValidator::with_constraints(constraints);
+
+        // Une coupe de tige par recalcul O(n³): la réparation s'arrête après
+        // MAX_FOLD_REPAIRS coupes et la validation finale rejette la séquence
+        let mut repaired = bases.clone();
+        assert_eq!(validator.repair_synthesis(&mut repaired), MAX_FOLD_REPAIRS);
+        assert!(matches!(
+            validator.enforce_constraints(&bases),
+            Err(DnaError::SecondaryStructure { .. })
+        ));
+    }
 }
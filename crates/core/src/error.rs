@@ -25,6 +25,18 @@ pub enum DnaError {
     #[error("GC content hors plage: {gc:.2} pas dans [{min:.2}, {max:.2}]")]
     GcContentOutOfRange { gc: f64, min: f64, max: f64 },
 
+    #[error("GC local hors plage: {gc:.2} pas dans [{min:.2}, {max:.2}] (fenêtre en position {position})")]
+    LocalGcOutOfRange { position: usize, gc: f64, min: f64, max: f64 },
+
+    #[error("Température de fusion hors plage: {tm:.1} °C pas dans [{min:.1}, {max:.1}]")]
+    MeltingTemperatureOutOfRange { tm: f64, min: f64, max: f64 },
+
+    #[error("Structure secondaire trop stable: ΔG {energy:.2} < {min:.2} kcal/mol")]
+    SecondaryStructure { energy: f64, min: f64 },
+
+    #[error("Motif interdit {motif} en position {position}")]
+    ForbiddenMotif { motif: String, position: usize },
+
     #[error("Erreur IO: {0}")]
     Io(#[from] std::io::Error),
 
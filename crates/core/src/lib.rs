@@ -7,15 +7,19 @@ pub mod codec;
 pub mod constraints;
 pub mod error;
 pub mod sequence;
+pub mod packed;
 pub mod logging;
 pub mod performance;
+pub mod reads;
 
 // Réexportations principales
 pub use error::{DnaError, Result};
-pub use sequence::{DnaSequence, DnaConstraints, IupacBase, SequenceId, SequenceMetadata};
+pub use sequence::{DnaSequence, DnaConstraints, GcWindow, IupacBase, SequenceId, SequenceMetadata};
+pub use packed::{PackedBases, PackedSequence};
 pub use codec::{Encoder, Decoder, EncoderConfig, DecoderConfig, ReedSolomonCodec};
 pub use constraints::{ConstraintChecker, DnaConstraintValidator, IncrementalConstraintValidator, IncrementalStats};
 pub use bio::{IlluminaBarcode, IlluminaAdapter, IlluminaSystem, IlluminaConfig, IlluminaValidator, AdapterType, BarcodePosition};
+pub use bio::{Primer, PrimerConfig, PrimerLibrary, PrimerPair};
 pub use logging::init_logging;
 // Les macros log_operation et log_error sont automatiquement exportées à la racine du crate
 pub use performance::{PerformanceCache, PerformanceOptimizer, HybridCache, AdvancedCacheManager, CacheStrategy};
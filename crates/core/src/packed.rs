@@ -0,0 +1,309 @@
+//! Représentation compacte des bases: 2 bits par base
+//!
+//! Les bases standard (A, C, G, T) sont rangées par quatre dans un octet. Les
+//! codes d'ambiguïté IUPAC (N, R, Y...) sont rares dans un pool encodé: ils
+//! occupent un emplacement nul dans le tableau de bits et sont décrits par une
+//! liste d'exceptions triée par position.
+
+use crate::error::{DnaError, Result};
+use crate::sequence::{DnaSequence, IupacBase, SequenceId, SequenceMetadata};
+use serde::{Deserialize, Serialize};
+
+/// Version du format binaire de `PackedBases::to_bytes`
+///
+/// Le premier octet n'est jamais une lettre ASCII, ce qui distingue un
+/// contenu compacté d'une chaîne de bases en clair.
+pub const PACKED_FORMAT_VERSION: u8 = 1;
+
+/// Bases ADN compactées à 2 bits par base
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Vec<u8>", into = "Vec<u8>")]
+pub struct PackedBases {
+    /// Nombre de bases
+    len: usize,
+    /// Quatre bases par octet, la première dans les bits de poids faible
+    words: Vec<u8>,
+    /// Bases ambiguës (position, base), triées par position
+    exceptions: Vec<(u32, IupacBase)>,
+}
+
+impl PackedBases {
+    /// Compacte une suite de bases
+    pub fn from_bases(bases: &[IupacBase]) -> Self {
+        let mut words = vec![0u8; bases.len().div_ceil(4)];
+        let mut exceptions = Vec::new();
+
+        for (i, &base) in bases.iter().enumerate() {
+            match code(base) {
+                Some(bits) => words[i / 4] |= bits << ((i % 4) * 2),
+                None => exceptions.push((i as u32, base)),
+            }
+        }
+
+        Self {
+            len: bases.len(),
+            words,
+            exceptions,
+        }
+    }
+
+    /// Nombre de bases
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Vérifie si la séquence est vide
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Nombre de codes d'ambiguïté stockés en exception
+    pub fn exception_count(&self) -> usize {
+        self.exceptions.len()
+    }
+
+    /// Base en position `index`
+    pub fn get(&self, index: usize) -> Option<IupacBase> {
+        if index >= self.len {
+            return None;
+        }
+
+        if let Ok(i) = self.exceptions.binary_search_by_key(&(index as u32), |&(pos, _)| pos) {
+            return Some(self.exceptions[i].1);
+        }
+
+        Some(decode(self.words[index / 4] >> ((index % 4) * 2)))
+    }
+
+    /// Itère sur les bases
+    pub fn iter(&self) -> impl Iterator<Item = IupacBase> + '_ {
+        let mut exceptions = self.exceptions.iter().peekable();
+        (0..self.len).map(move |i| match exceptions.peek() {
+            Some(&&(pos, base)) if pos as usize == i => {
+                exceptions.next();
+                base
+            }
+            _ => decode(self.words[i / 4] >> ((i % 4) * 2)),
+        })
+    }
+
+    /// Décompacte en un vecteur de bases
+    pub fn to_bases(&self) -> Vec<IupacBase> {
+        self.iter().collect()
+    }
+
+    /// Taille du format binaire en octets
+    pub fn byte_len(&self) -> usize {
+        9 + self.words.len() + 5 * self.exceptions.len()
+    }
+
+    /// Sérialise au format binaire
+    ///
+    /// `[version u8][bases u32 LE][exceptions u32 LE][bits][(position u32 LE, base ASCII u8)*]`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.byte_len());
+        bytes.push(PACKED_FORMAT_VERSION);
+        bytes.extend_from_slice(&(self.len as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.exceptions.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.words);
+        for &(pos, base) in &self.exceptions {
+            bytes.extend_from_slice(&pos.to_le_bytes());
+            bytes.push(base.as_char() as u8);
+        }
+        bytes
+    }
+
+    /// Désérialise le format binaire de `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let invalid = |reason: &str| DnaError::Serialization(format!("Bases compactées invalides: {}", reason));
+
+        if bytes.len() < 9 {
+            return Err(invalid("en-tête tronqué"));
+        }
+        if bytes[0] != PACKED_FORMAT_VERSION {
+            return Err(invalid(&format!("version {}", bytes[0])));
+        }
+
+        let len = u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as usize;
+        let count = u32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]) as usize;
+        let words_len = len.div_ceil(4);
+        if bytes.len() != 9 + words_len + 5 * count {
+            return Err(invalid("taille incohérente"));
+        }
+
+        let words = bytes[9..9 + words_len].to_vec();
+        let mut exceptions = Vec::with_capacity(count);
+        for chunk in bytes[9 + words_len..].chunks_exact(5) {
+            let pos = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            let base = IupacBase::from_char(chunk[4] as char)?;
+            if pos as usize >= len || exceptions.last().is_some_and(|&(last, _)| last >= pos) {
+                return Err(invalid("exceptions non triées ou hors séquence"));
+            }
+            exceptions.push((pos, base));
+        }
+
+        Ok(Self { len, words, exceptions })
+    }
+}
+
+impl From<&[IupacBase]> for PackedBases {
+    fn from(bases: &[IupacBase]) -> Self {
+        Self::from_bases(bases)
+    }
+}
+
+impl From<PackedBases> for Vec<u8> {
+    fn from(packed: PackedBases) -> Self {
+        packed.to_bytes()
+    }
+}
+
+impl TryFrom<Vec<u8>> for PackedBases {
+    type Error = DnaError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Séquence ADN dont les bases sont compactées
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackedSequence {
+    /// Bases compactées
+    pub bases: PackedBases,
+    /// ID unique
+    pub id: SequenceId,
+    /// Métadonnées
+    pub metadata: SequenceMetadata,
+}
+
+impl PackedSequence {
+    /// Décompacte en `DnaSequence`
+    pub fn unpack(&self) -> DnaSequence {
+        DnaSequence {
+            bases: self.bases.to_bases(),
+            id: self.id,
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+impl From<&DnaSequence> for PackedSequence {
+    fn from(sequence: &DnaSequence) -> Self {
+        Self {
+            bases: PackedBases::from_bases(&sequence.bases),
+            id: sequence.id,
+            metadata: sequence.metadata.clone(),
+        }
+    }
+}
+
+impl From<DnaSequence> for PackedSequence {
+    fn from(sequence: DnaSequence) -> Self {
+        Self {
+            bases: PackedBases::from_bases(&sequence.bases),
+            id: sequence.id,
+            metadata: sequence.metadata,
+        }
+    }
+}
+
+impl From<PackedSequence> for DnaSequence {
+    fn from(packed: PackedSequence) -> Self {
+        DnaSequence {
+            bases: packed.bases.to_bases(),
+            id: packed.id,
+            metadata: packed.metadata,
+        }
+    }
+}
+
+/// Code 2 bits d'une base standard
+fn code(base: IupacBase) -> Option<u8> {
+    match base {
+        IupacBase::A => Some(0),
+        IupacBase::C => Some(1),
+        IupacBase::G => Some(2),
+        IupacBase::T => Some(3),
+        _ => None,
+    }
+}
+
+fn decode(bits: u8) -> IupacBase {
+    match bits & 0b11 {
+        0 => IupacBase::A,
+        1 => IupacBase::C,
+        2 => IupacBase::G,
+        _ => IupacBase::T,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Vec<IupacBase> {
+        s.chars().map(|c| IupacBase::from_char(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_roundtrip_standard_bases() {
+        let bases = parse("ACGTTGCAAGCTAGCTTACGGATCCAGTC");
+        let packed = PackedBases::from_bases(&bases);
+
+        assert_eq!(packed.len(), bases.len());
+        assert_eq!(packed.exception_count(), 0);
+        assert_eq!(packed.to_bases(), bases);
+        assert_eq!(packed.get(3), Some(IupacBase::T));
+        assert_eq!(packed.get(bases.len()), None);
+    }
+
+    #[test]
+    fn test_ambiguity_codes_are_exceptions() {
+        let bases = parse("ACNGTRYACGTN");
+        let packed = PackedBases::from_bases(&bases);
+
+        assert_eq!(packed.exception_count(), 4);
+        assert_eq!(packed.get(2), Some(IupacBase::N));
+        assert_eq!(packed.get(5), Some(IupacBase::R));
+        assert_eq!(packed.to_bases(), bases);
+    }
+
+    #[test]
+    fn test_bytes_roundtrip_and_size() {
+        let bases = parse(&"ACGT".repeat(38));
+        let packed = PackedBases::from_bases(&bases);
+        let bytes = packed.to_bytes();
+
+        // 152 bases: 38 octets de bits + 9 octets d'en-tête, contre 152 en clair
+        assert_eq!(bytes.len(), 47);
+        assert_eq!(PackedBases::from_bytes(&bytes).unwrap(), packed);
+
+        let with_n = PackedBases::from_bases(&parse("ACGNT"));
+        assert_eq!(PackedBases::from_bytes(&with_n.to_bytes()).unwrap(), with_n);
+    }
+
+    #[test]
+    fn test_invalid_bytes_rejected() {
+        assert!(PackedBases::from_bytes(b"ACGT").is_err());
+
+        let mut bytes = PackedBases::from_bases(&parse("ACGTA")).to_bytes();
+        bytes.pop();
+        assert!(PackedBases::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_packed_sequence_roundtrip() {
+        let sequence = DnaSequence::new(parse("ACGTNACGT"), "test.txt".to_string(), 0, 9, 7);
+        let packed = PackedSequence::from(&sequence);
+        let unpacked = packed.unpack();
+
+        assert_eq!(unpacked.id, sequence.id);
+        assert_eq!(unpacked.bases, sequence.bases);
+        assert_eq!(unpacked.metadata.seed, 7);
+
+        let json = serde_json::to_string(&packed).unwrap();
+        let restored: PackedSequence = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.bases, packed.bases);
+    }
+}
@@ -133,6 +133,17 @@ impl HybridCache {
         None
     }
 
+    /// Ajoute des bases au cache, compactées à 2 bits par base
+    pub fn insert_bases(&self, key: u64, bases: &[crate::IupacBase]) -> crate::error::Result<()> {
+        self.insert(key, crate::PackedBases::from_bases(bases).to_bytes())
+    }
+
+    /// Récupère des bases ajoutées par `insert_bases`
+    pub fn get_bases(&self, key: u64) -> Option<Vec<crate::IupacBase>> {
+        let bytes = self.get(key)?;
+        crate::PackedBases::from_bytes(&bytes).ok().map(|packed| packed.to_bases())
+    }
+
     /// Nettoie le cache
     pub fn clear(&self) -> crate::error::Result<()> {
         self.memory_cache.clear();
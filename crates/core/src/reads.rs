@@ -0,0 +1,147 @@
+//! Traitement des lectures de séquençage
+//!
+//! Un séquençage réel produit de nombreuses copies bruitées de chaque oligo
+//! (substitutions, insertions, délétions), alors que les décodeurs de
+//! `crate::codec` attendent une copie exacte par oligo. Ce module fait le pont:
+//! - `cluster`: regroupement des lectures par MinHash sur les k-mers, confirmé par distance d'édition
+//! - `consensus`: brin consensus de chaque cluster par alignement multiple, avec confiance par position
+//! - `align`: distance d'édition bornée et alignement global
+//! - `fastq`: lecture FASTQ (brut ou gzip) avec qualités Phred
+
+pub mod align;
+pub mod cluster;
+pub mod consensus;
+pub mod fastq;
+
+pub use align::{bounded_edit_distance, edit_distance};
+pub use cluster::{ClusterConfig, ReadCluster, ReadClusterer};
+pub use consensus::{ConsensusBuilder, ConsensusConfig, ConsensusStrand};
+pub use fastq::{open_fastq, read_fastq, FastqReader, FastqRecord};
+
+use crate::sequence::DnaSequence;
+use rayon::prelude::*;
+
+/// Regroupe des lectures brutes et construit un brin consensus par cluster
+///
+/// Les clusters plus petits que `cluster_config.min_cluster_size` sont
+/// considérés comme du bruit et ignorés. Les brins sont rendus dans l'ordre
+/// d'apparition de leur cluster.
+pub fn consensus_pool(
+    reads: &[DnaSequence],
+    cluster_config: ClusterConfig,
+    consensus_config: ConsensusConfig,
+) -> Vec<ConsensusStrand> {
+    let min_size = cluster_config.min_cluster_size;
+    let clusters = ReadClusterer::new(cluster_config).cluster(reads);
+    let builder = ConsensusBuilder::new(consensus_config);
+
+    clusters
+        .par_iter()
+        .filter(|cluster| cluster.len() >= min_size)
+        .map(|cluster| builder.build(reads, cluster))
+        .collect()
+}
+
+/// Convertit des brins consensus en séquences prêtes pour `Decoder::decode`
+pub fn to_sequences(strands: &[ConsensusStrand]) -> Vec<DnaSequence> {
+    strands.iter().map(ConsensusStrand::to_sequence).collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::codec::encoder::CompressionType;
+    use crate::codec::{Decoder, DecoderConfig, Encoder, EncoderConfig, EncoderType};
+    use crate::sequence::{DnaConstraints, IupacBase};
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const BASES: [IupacBase; 4] = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+
+    /// Séquence aléatoire de bases standard
+    pub(crate) fn random_bases<R: Rng>(rng: &mut R, len: usize) -> Vec<IupacBase> {
+        (0..len).map(|_| BASES[rng.gen_range(0..4)]).collect()
+    }
+
+    /// Copie bruitée: substitutions, insertions et délétions à parts égales
+    pub(crate) fn mutate<R: Rng>(rng: &mut R, bases: &[IupacBase], rate: f64) -> Vec<IupacBase> {
+        let mut noisy = Vec::with_capacity(bases.len() + 4);
+        for &base in bases {
+            if !rng.gen_bool(rate) {
+                noisy.push(base);
+                continue;
+            }
+            match rng.gen_range(0..3) {
+                0 => noisy.push(*BASES.iter().filter(|&&b| b != base).nth(rng.gen_range(0..3)).unwrap()),
+                1 => {
+                    noisy.push(BASES[rng.gen_range(0..4)]);
+                    noisy.push(base);
+                }
+                _ => {}
+            }
+        }
+        noisy
+    }
+
+    #[test]
+    fn test_noisy_reads_decode_after_consensus() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2024);
+        let original: Vec<u8> = (0..120).map(|_| rng.gen()).collect();
+
+        // Schémas adressés par les bases: seed (fontaine par défaut, EZ 2017) ou index (Goldman 2013)
+        for encoder_type in [EncoderType::Fountain, EncoderType::ErlichZielinski2017, EncoderType::Goldman2013] {
+            let config = EncoderConfig {
+                encoder_type,
+                chunk_size: 16,
+                compression_enabled: false,
+                compression_type: CompressionType::None,
+                constraints: DnaConstraints::new(0.0, 1.0, 16, 200),
+                ..Default::default()
+            };
+            let pool = Encoder::new(config).unwrap().encode(&original).unwrap();
+
+            // 8 lectures bruitées par oligo, dans le désordre, sans métadonnées
+            // d'encodage (comme `FastqRecord::to_sequence` sur un en-tête de séquenceur)
+            let mut reads: Vec<DnaSequence> = pool
+                .iter()
+                .flat_map(|oligo| std::iter::repeat_n(oligo, 8))
+                .map(|oligo| DnaSequence::new(mutate(&mut rng, &oligo.bases, 0.02), String::new(), 0, 0, 0))
+                .collect();
+            reads.shuffle(&mut rng);
+
+            let cluster_config = ClusterConfig {
+                min_cluster_size: 3,
+                ..Default::default()
+            };
+            let strands = consensus_pool(&reads, cluster_config, ConsensusConfig::default());
+            assert_eq!(strands.len(), pool.len(), "{:?}", encoder_type);
+
+            let decoded = Decoder::new(DecoderConfig::default())
+                .decode(&to_sequences(&strands))
+                .unwrap();
+            assert_eq!(decoded, original, "{:?}", encoder_type);
+        }
+    }
+
+    #[test]
+    fn test_unaddressed_scheme_rejects_metadata_free_reads() {
+        let config = EncoderConfig {
+            encoder_type: EncoderType::Goldman,
+            chunk_size: 8,
+            compression_enabled: false,
+            compression_type: CompressionType::None,
+            constraints: DnaConstraints::new(0.0, 1.0, 32, 200),
+            ..Default::default()
+        };
+        let pool = Encoder::new(config).unwrap().encode(b"ordre connu des seules metadonnees").unwrap();
+        let reads: Vec<DnaSequence> = pool
+            .iter()
+            .map(|oligo| DnaSequence::new(oligo.bases.clone(), String::new(), 0, 0, 0))
+            .collect();
+
+        let error = Decoder::new(DecoderConfig::default()).decode(&reads).unwrap_err();
+        assert!(error.to_string().contains("goldman"), "{}", error);
+    }
+}
@@ -0,0 +1,148 @@
+//! Distance d'édition et alignement global entre lectures
+
+use crate::sequence::IupacBase;
+
+/// Opération d'un alignement global d'une lecture sur une référence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignOp {
+    /// Position de référence alignée sur une base de la lecture (identique ou substituée)
+    Match { reference: usize, read: usize },
+    /// Base de référence absente de la lecture
+    Deletion { reference: usize },
+    /// Base de la lecture absente de la référence
+    Insertion { read: usize },
+}
+
+/// Distance de Levenshtein entre deux séquences
+pub fn edit_distance(a: &[IupacBase], b: &[IupacBase]) -> usize {
+    bounded_edit_distance(a, b, a.len().max(b.len())).unwrap_or(a.len().max(b.len()))
+}
+
+/// Distance de Levenshtein si elle ne dépasse pas `max`, `None` sinon
+///
+/// Seule une bande de largeur `2 * max + 1` autour de la diagonale est
+/// calculée, avec arrêt anticipé dès qu'une ligne dépasse `max`.
+pub fn bounded_edit_distance(a: &[IupacBase], b: &[IupacBase], max: usize) -> Option<usize> {
+    let (n, m) = (a.len(), b.len());
+    if n.abs_diff(m) > max {
+        return None;
+    }
+
+    let inf = max + 1;
+    let mut prev: Vec<usize> = (0..=m).map(|j| j.min(inf)).collect();
+    let mut curr = vec![inf; m + 1];
+
+    for i in 1..=n {
+        let lo = i.saturating_sub(max).max(1);
+        let hi = (i + max).min(m);
+
+        curr[lo - 1] = if lo == 1 { i.min(inf) } else { inf };
+        let mut row_min = curr[lo - 1];
+
+        for j in lo..=hi {
+            let substitution = prev[j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let value = substitution.min(prev[j] + 1).min(curr[j - 1] + 1).min(inf);
+            curr[j] = value;
+            row_min = row_min.min(value);
+        }
+        if hi < m {
+            curr[hi + 1] = inf;
+        }
+
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    (prev[m] <= max).then_some(prev[m])
+}
+
+/// Alignement global (coûts unitaires) d'une lecture sur une référence
+pub fn align(reference: &[IupacBase], read: &[IupacBase]) -> Vec<AlignOp> {
+    let (n, m) = (reference.len(), read.len());
+    let width = m + 1;
+    let mut dp = vec![0usize; (n + 1) * width];
+
+    for i in 0..=n {
+        dp[i * width] = i;
+    }
+    for (j, cell) in dp.iter_mut().take(width).enumerate() {
+        *cell = j;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = dp[(i - 1) * width + j - 1] + usize::from(reference[i - 1] != read[j - 1]);
+            let deletion = dp[(i - 1) * width + j] + 1;
+            let insertion = dp[i * width + j - 1] + 1;
+            dp[i * width + j] = substitution.min(deletion).min(insertion);
+        }
+    }
+
+    // Remontée depuis le coin, en privilégiant les appariements
+    let mut ops = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        let here = dp[i * width + j];
+        if i > 0 && j > 0 && here == dp[(i - 1) * width + j - 1] + usize::from(reference[i - 1] != read[j - 1]) {
+            ops.push(AlignOp::Match { reference: i - 1, read: j - 1 });
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && here == dp[(i - 1) * width + j] + 1 {
+            ops.push(AlignOp::Deletion { reference: i - 1 });
+            i -= 1;
+        } else {
+            ops.push(AlignOp::Insertion { read: j - 1 });
+            j -= 1;
+        }
+    }
+
+    ops.reverse();
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bases(s: &str) -> Vec<IupacBase> {
+        s.chars().map(|c| IupacBase::from_char(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance(&bases("ACGT"), &bases("ACGT")), 0);
+        assert_eq!(edit_distance(&bases("ACGT"), &bases("AGGT")), 1);
+        assert_eq!(edit_distance(&bases("ACGT"), &bases("ACT")), 1);
+        assert_eq!(edit_distance(&bases("ACGT"), &bases("TACGTA")), 2);
+        assert_eq!(edit_distance(&bases(""), &bases("ACG")), 3);
+    }
+
+    #[test]
+    fn test_bounded_edit_distance() {
+        let a = bases("ACGTACGTACGT");
+        let b = bases("ACGAACGTCGTT");
+
+        let exact = edit_distance(&a, &b);
+        assert_eq!(bounded_edit_distance(&a, &b, exact), Some(exact));
+        assert_eq!(bounded_edit_distance(&a, &b, exact + 3), Some(exact));
+        assert_eq!(bounded_edit_distance(&a, &b, exact - 1), None);
+        assert_eq!(bounded_edit_distance(&a, &bases("AC"), 5), None);
+    }
+
+    #[test]
+    fn test_align_reports_indels() {
+        let reference = bases("ACGTAC");
+        let read = bases("ACTTACG");
+        let ops = align(&reference, &read);
+
+        let deletions = ops.iter().filter(|op| matches!(op, AlignOp::Deletion { .. })).count();
+        let insertions = ops.iter().filter(|op| matches!(op, AlignOp::Insertion { .. })).count();
+        let matches = ops.iter().filter(|op| matches!(op, AlignOp::Match { .. })).count();
+
+        // Chaque base de référence et de lecture apparaît exactement une fois
+        assert_eq!(matches + deletions, reference.len());
+        assert_eq!(matches + insertions, read.len());
+        assert_eq!(insertions, 1);
+    }
+}
@@ -0,0 +1,227 @@
+//! Regroupement des lectures par origine
+//!
+//! Chaque lecture reçoit une signature MinHash calculée sur ses k-mers. La
+//! signature est découpée en bandes (LSH): deux lectures partageant une bande
+//! sont candidates au même cluster, ce que confirme une distance d'édition
+//! bornée avec le centre du cluster. Le regroupement est glouton et
+//! déterministe: une lecture rejoint le premier cluster candidat assez proche,
+//! sinon elle devient le centre d'un nouveau cluster.
+
+use crate::reads::align::bounded_edit_distance;
+use crate::sequence::{DnaSequence, IupacBase};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Paramètres du regroupement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterConfig {
+    /// Taille des k-mers (au plus 32)
+    pub kmer_size: usize,
+    /// Nombre de bandes LSH
+    pub bands: usize,
+    /// Nombre de fonctions de hachage par bande
+    pub rows_per_band: usize,
+    /// Distance d'édition maximale au centre, en fraction de la longueur
+    pub max_edit_ratio: f64,
+    /// Taille minimale d'un cluster conservé par `consensus_pool`
+    pub min_cluster_size: usize,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            kmer_size: 8,
+            bands: 16,
+            rows_per_band: 2,
+            max_edit_ratio: 0.25,
+            min_cluster_size: 1,
+        }
+    }
+}
+
+/// Cluster de lectures issues d'un même oligo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadCluster {
+    /// Indice de la lecture centre (première lecture du cluster)
+    pub center: usize,
+    /// Indices de toutes les lectures du cluster, centre compris
+    pub members: Vec<usize>,
+}
+
+impl ReadCluster {
+    /// Nombre de lectures du cluster
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Retourne true si le cluster est vide
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+/// Regroupe des lectures bruitées par oligo d'origine
+pub struct ReadClusterer {
+    config: ClusterConfig,
+}
+
+impl ReadClusterer {
+    /// Crée un regroupeur
+    pub fn new(config: ClusterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Regroupe les lectures; les clusters sont rendus dans l'ordre d'apparition
+    pub fn cluster(&self, reads: &[DnaSequence]) -> Vec<ReadCluster> {
+        let mut clusters: Vec<ReadCluster> = Vec::new();
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+
+        for (index, read) in reads.iter().enumerate() {
+            let keys = self.band_keys(&read.bases);
+
+            // Clusters candidats, par nombre de bandes partagées décroissant
+            let mut shared: Vec<(usize, usize)> = Vec::new();
+            for key in &keys {
+                for &cluster_id in buckets.get(key).into_iter().flatten() {
+                    match shared.iter_mut().find(|(id, _)| *id == cluster_id) {
+                        Some((_, count)) => *count += 1,
+                        None => shared.push((cluster_id, 1)),
+                    }
+                }
+            }
+            shared.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+            let target = shared
+                .iter()
+                .filter_map(|&(cluster_id, _)| {
+                    let center = &reads[clusters[cluster_id].center].bases;
+                    let max = self.max_distance(center.len().max(read.bases.len()));
+                    bounded_edit_distance(center, &read.bases, max).map(|d| (d, cluster_id))
+                })
+                .min();
+
+            let cluster_id = match target {
+                Some((_, cluster_id)) => {
+                    clusters[cluster_id].members.push(index);
+                    cluster_id
+                }
+                None => {
+                    clusters.push(ReadCluster { center: index, members: vec![index] });
+                    clusters.len() - 1
+                }
+            };
+
+            // Les bandes de chaque membre élargissent le rappel du cluster
+            for key in keys {
+                let entry = buckets.entry(key).or_default();
+                if !entry.contains(&cluster_id) {
+                    entry.push(cluster_id);
+                }
+            }
+        }
+
+        clusters
+    }
+
+    /// Distance d'édition tolérée pour une longueur donnée
+    fn max_distance(&self, len: usize) -> usize {
+        (len as f64 * self.config.max_edit_ratio).ceil() as usize
+    }
+
+    /// Clés LSH d'une lecture (une par bande de la signature MinHash)
+    fn band_keys(&self, bases: &[IupacBase]) -> Vec<u64> {
+        let kmers = kmer_hashes(bases, self.config.kmer_size);
+        if kmers.is_empty() {
+            return Vec::new();
+        }
+
+        let rows = self.config.rows_per_band.max(1);
+        (0..self.config.bands)
+            .map(|band| {
+                (0..rows).fold(mix(band as u64), |key, row| {
+                    let seed = mix((band * rows + row) as u64 + 1);
+                    let min_hash = kmers.iter().map(|&k| mix(k ^ seed)).min().unwrap_or(0);
+                    mix(key ^ min_hash)
+                })
+            })
+            .collect()
+    }
+}
+
+/// Codes 2 bits des k-mers ne contenant que A, C, G, T
+fn kmer_hashes(bases: &[IupacBase], k: usize) -> Vec<u64> {
+    let k = k.clamp(1, 32);
+    let mask = if k == 32 { u64::MAX } else { (1u64 << (2 * k)) - 1 };
+
+    let mut kmers = Vec::with_capacity(bases.len());
+    let mut code = 0u64;
+    let mut valid = 0usize;
+
+    for &base in bases {
+        let bits = match base {
+            IupacBase::A => 0,
+            IupacBase::C => 1,
+            IupacBase::G => 2,
+            IupacBase::T => 3,
+            _ => {
+                valid = 0;
+                continue;
+            }
+        };
+        code = ((code << 2) | bits) & mask;
+        valid += 1;
+        if valid >= k {
+            kmers.push(code);
+        }
+    }
+
+    kmers
+}
+
+/// Mélange 64 bits (finaliseur SplitMix64)
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reads::tests::{mutate, random_bases};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_kmer_hashes_skip_ambiguous_bases() {
+        let bases: Vec<IupacBase> = "ACGTNACGT".chars().map(|c| IupacBase::from_char(c).unwrap()).collect();
+        assert_eq!(kmer_hashes(&bases, 4).len(), 2);
+        assert!(kmer_hashes(&bases, 5).is_empty());
+    }
+
+    #[test]
+    fn test_clusters_separate_distinct_oligos() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let originals: Vec<Vec<IupacBase>> = (0..5).map(|_| random_bases(&mut rng, 120)).collect();
+
+        // 6 copies bruitées de chaque oligo, mélangées
+        let mut reads = Vec::new();
+        for copy in 0..6 {
+            for (origin, bases) in originals.iter().enumerate() {
+                let noisy = mutate(&mut rng, bases, 0.03);
+                reads.push((origin, copy, DnaSequence::new(noisy, "reads".to_string(), origin, 0, 0)));
+            }
+        }
+        let sequences: Vec<DnaSequence> = reads.iter().map(|(_, _, s)| s.clone()).collect();
+
+        let clusters = ReadClusterer::new(ClusterConfig::default()).cluster(&sequences);
+        assert_eq!(clusters.len(), originals.len());
+
+        for cluster in &clusters {
+            assert_eq!(cluster.len(), 6);
+            let origin = reads[cluster.center].0;
+            assert!(cluster.members.iter().all(|&m| reads[m].0 == origin));
+        }
+    }
+}
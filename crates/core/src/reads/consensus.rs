@@ -0,0 +1,363 @@
+//! Brins consensus par alignement multiple
+//!
+//! Toutes les lectures d'un cluster sont alignées sur une référence (le centre
+//! du cluster, puis le consensus du tour précédent). Chaque position de la
+//! référence reçoit les votes A/C/G/T/délétion des lectures, et chaque
+//! intervalle entre deux positions les bases insérées. La base majoritaire est
+//! retenue; sa fréquence parmi les lectures donne la confiance de la position.
+//!
+//! Quand les lectures portent leurs qualités (`metadata.base_error_probabilities`),
+//! chaque vote pèse `1 - p`, et la probabilité d'erreur d'une base du consensus
+//! tient compte à la fois du désaccord entre lectures et de la probabilité que
+//! toutes les lectures qui la portent se trompent.
+
+use crate::reads::align::{align, AlignOp};
+use crate::reads::cluster::ReadCluster;
+use crate::sequence::{DnaSequence, IupacBase};
+use serde::{Deserialize, Serialize};
+
+/// Bases votables, dans l'ordre des compteurs
+const VOTE_BASES: [IupacBase; 4] = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+
+/// Indice du compteur de délétions
+const GAP: usize = 4;
+
+/// Paramètres du consensus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusConfig {
+    /// Nombre maximal de tours d'alignement
+    pub iterations: usize,
+    /// Nombre maximal de lectures alignées par cluster (les premières)
+    pub max_reads: usize,
+}
+
+impl Default for ConsensusConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 3,
+            max_reads: 64,
+        }
+    }
+}
+
+/// Brin consensus d'un cluster
+#[derive(Debug, Clone)]
+pub struct ConsensusStrand {
+    /// Bases du consensus
+    pub bases: Vec<IupacBase>,
+    /// Fraction des lectures qui soutiennent chaque base (0-1, pondérée par les qualités)
+    pub confidence: Vec<f64>,
+    /// Probabilité d'erreur de chaque base
+    pub error_probabilities: Vec<f64>,
+    /// Nombre de lectures du cluster
+    pub support: usize,
+    /// Lecture centre, dont les métadonnées sont reprises par `to_sequence`
+    pub template: DnaSequence,
+}
+
+impl ConsensusStrand {
+    /// Confiance moyenne du brin
+    pub fn mean_confidence(&self) -> f64 {
+        if self.confidence.is_empty() {
+            return 0.0;
+        }
+        self.confidence.iter().sum::<f64>() / self.confidence.len() as f64
+    }
+
+    /// Positions dont la confiance est strictement inférieure au seuil
+    ///
+    /// Ces positions sont les candidates naturelles aux effacements d'un
+    /// décodage Reed-Solomon.
+    pub fn low_confidence_positions(&self, threshold: f64) -> Vec<usize> {
+        self.confidence
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c < threshold)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Séquence consensus avec les métadonnées de la lecture centre
+    ///
+    /// Les probabilités d'erreur du consensus remplacent les qualités de la
+    /// lecture centre.
+    pub fn to_sequence(&self) -> DnaSequence {
+        let metadata = &self.template.metadata;
+        let mut sequence = DnaSequence::with_encoding_scheme(
+            self.bases.clone(),
+            metadata.original_file.clone(),
+            metadata.chunk_index,
+            metadata.chunk_size,
+            metadata.seed,
+            metadata.encoding_scheme.clone(),
+        );
+        sequence.metadata.total_chunks = metadata.total_chunks;
+        sequence.metadata.source_length = metadata.source_length;
+        sequence.metadata.base_error_probabilities = self.error_probabilities.clone();
+        sequence
+    }
+}
+
+/// Construit les brins consensus des clusters
+pub struct ConsensusBuilder {
+    config: ConsensusConfig,
+}
+
+impl ConsensusBuilder {
+    /// Crée un constructeur de consensus
+    pub fn new(config: ConsensusConfig) -> Self {
+        Self { config }
+    }
+
+    /// Construit le consensus d'un cluster de lectures
+    pub fn build(&self, reads: &[DnaSequence], cluster: &ReadCluster) -> ConsensusStrand {
+        let members: Vec<&DnaSequence> = cluster
+            .members
+            .iter()
+            .take(self.config.max_reads.max(1))
+            .map(|&i| &reads[i])
+            .collect();
+
+        let center = &reads[cluster.center];
+        let mut reference = center.bases.clone();
+        let mut confidence = vec![1.0; reference.len()];
+        let mut error_probabilities: Vec<f64> =
+            (0..reference.len()).map(|i| read_error_probability(center, i)).collect();
+
+        if members.len() > 1 {
+            for _ in 0..self.config.iterations.max(1) {
+                let round = consensus_round(&reference, &members);
+                let converged = round.bases == reference;
+                reference = round.bases;
+                confidence = round.confidence;
+                error_probabilities = round.error_probabilities;
+                if converged {
+                    break;
+                }
+            }
+        }
+
+        ConsensusStrand {
+            bases: reference,
+            confidence,
+            error_probabilities,
+            support: cluster.len(),
+            template: reads[cluster.center].clone(),
+        }
+    }
+}
+
+/// Votes d'une position: poids A, C, G, T, délétion, et pour chaque base le
+/// produit des probabilités d'erreur des lectures qui la portent
+#[derive(Clone, Copy)]
+struct Votes {
+    weights: [f64; 5],
+    all_wrong: [f64; 4],
+}
+
+impl Default for Votes {
+    fn default() -> Self {
+        Self {
+            weights: [0.0; 5],
+            all_wrong: [1.0; 4],
+        }
+    }
+}
+
+impl Votes {
+    /// Vote d'une lecture pour une base, pondéré par sa fiabilité
+    fn add(&mut self, base: IupacBase, error_probability: f64) {
+        if let Some(b) = vote_index(base) {
+            self.weights[b] += 1.0 - error_probability;
+            self.all_wrong[b] *= error_probability;
+        }
+    }
+
+    /// Base retenue, confiance et probabilité d'erreur (None pour une délétion)
+    fn decide(&self, total: f64) -> Option<(IupacBase, f64, f64)> {
+        let (best, weight) = majority(&self.weights);
+        if best == GAP {
+            return None;
+        }
+        let confidence = weight / total;
+        Some((VOTE_BASES[best], confidence, (1.0 - confidence).max(self.all_wrong[best])))
+    }
+}
+
+/// Résultat d'un tour de consensus
+struct Round {
+    bases: Vec<IupacBase>,
+    confidence: Vec<f64>,
+    error_probabilities: Vec<f64>,
+}
+
+/// Probabilité d'erreur d'une base d'une lecture (0 sans qualités)
+fn read_error_probability(read: &DnaSequence, position: usize) -> f64 {
+    read.metadata.base_error_probabilities.get(position).map_or(0.0, |p| p.clamp(0.0, 1.0))
+}
+
+/// Un tour de consensus: aligne les lectures sur la référence et vote
+fn consensus_round(reference: &[IupacBase], reads: &[&DnaSequence]) -> Round {
+    let total = reads.len() as f64;
+
+    // Votes par position de référence
+    let mut columns = vec![Votes::default(); reference.len()];
+    // Votes par intervalle (avant la position i) puis par rang d'insertion
+    let mut insertions: Vec<Vec<Votes>> = vec![Vec::new(); reference.len() + 1];
+
+    for read in reads {
+        let mut slot = 0;
+        let mut inserted = 0;
+
+        for op in align(reference, &read.bases) {
+            match op {
+                AlignOp::Match { reference: r, read: q } => {
+                    columns[r].add(read.bases[q], read_error_probability(read, q));
+                    slot = r + 1;
+                    inserted = 0;
+                }
+                AlignOp::Deletion { reference: r } => {
+                    columns[r].weights[GAP] += 1.0;
+                    slot = r + 1;
+                    inserted = 0;
+                }
+                AlignOp::Insertion { read: q } => {
+                    if insertions[slot].len() <= inserted {
+                        insertions[slot].push(Votes::default());
+                    }
+                    insertions[slot][inserted].add(read.bases[q], read_error_probability(read, q));
+                    inserted += 1;
+                }
+            }
+        }
+    }
+
+    let mut round = Round {
+        bases: Vec::with_capacity(reference.len()),
+        confidence: Vec::with_capacity(reference.len()),
+        error_probabilities: Vec::with_capacity(reference.len()),
+    };
+
+    for (slot, inserted) in insertions.iter().enumerate() {
+        // Une insertion n'est retenue que si la majorité des lectures la porte
+        let kept = inserted
+            .iter()
+            .filter_map(|votes| votes.decide(total))
+            .filter(|&(_, confidence, _)| confidence > 0.5);
+        let column = columns.get(slot).and_then(|votes| votes.decide(total));
+
+        for (base, confidence, error_probability) in kept.chain(column) {
+            round.bases.push(base);
+            round.confidence.push(confidence);
+            round.error_probabilities.push(error_probability);
+        }
+    }
+
+    round
+}
+
+/// Indice de vote d'une base (None pour les bases ambiguës)
+fn vote_index(base: IupacBase) -> Option<usize> {
+    VOTE_BASES.iter().position(|&b| b == base)
+}
+
+/// Indice et poids du compteur majoritaire (premier en cas d'égalité)
+fn majority(votes: &[f64]) -> (usize, f64) {
+    votes
+        .iter()
+        .enumerate()
+        .fold((0, 0.0), |best, (i, &weight)| if weight > best.1 { (i, weight) } else { best })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reads::tests::{mutate, random_bases};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn bases(s: &str) -> Vec<IupacBase> {
+        s.chars().map(|c| IupacBase::from_char(c).unwrap()).collect()
+    }
+
+    fn cluster_of(reads: &[DnaSequence]) -> ReadCluster {
+        ReadCluster { center: 0, members: (0..reads.len()).collect() }
+    }
+
+    #[test]
+    fn test_consensus_corrects_substitution_and_indels() {
+        let original = bases("ACGTTGCAACGTAGCT");
+        let variants = [
+            "ACGTTGCAACGTAGCT",
+            "ACGATGCAACGTAGCT", // substitution
+            "ACGTTGCACGTAGCT",  // délétion
+            "ACGTTGCAAACGTAGCT", // insertion
+            "ACGCTGCAACGTAGCT", // autre substitution à la même position
+        ];
+        let reads: Vec<DnaSequence> = variants
+            .iter()
+            .map(|v| DnaSequence::new(bases(v), "reads".to_string(), 0, 0, 0))
+            .collect();
+
+        // Le centre est volontairement une lecture erronée
+        let cluster = ReadCluster { center: 3, members: (0..reads.len()).collect() };
+        let strand = ConsensusBuilder::new(ConsensusConfig::default()).build(&reads, &cluster);
+
+        assert_eq!(strand.bases, original);
+        assert_eq!(strand.support, 5);
+        assert_eq!(strand.confidence.len(), original.len());
+        assert!(strand.confidence[3] < 1.0);
+        assert_eq!(strand.low_confidence_positions(0.7), vec![3]);
+    }
+
+    #[test]
+    fn test_consensus_of_noisy_random_copies() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let original = random_bases(&mut rng, 150);
+        let reads: Vec<DnaSequence> = (0..10)
+            .map(|_| DnaSequence::new(mutate(&mut rng, &original, 0.04), "reads".to_string(), 0, 0, 0))
+            .collect();
+
+        let strand = ConsensusBuilder::new(ConsensusConfig::default()).build(&reads, &cluster_of(&reads));
+        assert_eq!(strand.bases, original);
+        assert!(strand.mean_confidence() > 0.8);
+    }
+
+    #[test]
+    fn test_qualities_weight_the_votes() {
+        let original = bases("ACGTTGCAACGTAGCT");
+        let mut reads: Vec<DnaSequence> = ["ACGATGCAACGTAGCT", "ACGATGCAACGTAGCT", "ACGTTGCAACGTAGCT"]
+            .iter()
+            .map(|v| DnaSequence::new(bases(v), "reads".to_string(), 0, 0, 0))
+            .collect();
+
+        // Sans qualités, la substitution majoritaire l'emporte
+        let builder = ConsensusBuilder::new(ConsensusConfig::default());
+        assert_ne!(builder.build(&reads, &cluster_of(&reads)).bases, original);
+
+        // La substitution est lue avec une qualité Phred 1 dans les deux lectures
+        for read in &mut reads[..2] {
+            read.metadata.base_error_probabilities = vec![0.001; 16];
+            read.metadata.base_error_probabilities[3] = 0.8;
+        }
+        reads[2].metadata.base_error_probabilities = vec![0.001; 16];
+
+        let strand = builder.build(&reads, &cluster_of(&reads));
+        assert_eq!(strand.bases, original);
+        assert!(strand.error_probabilities[3] > 0.3);
+        assert!(strand.error_probabilities[0] < 0.01);
+        assert_eq!(strand.to_sequence().metadata.base_error_probabilities, strand.error_probabilities);
+    }
+
+    #[test]
+    fn test_single_read_cluster_keeps_read_and_metadata() {
+        let read = DnaSequence::with_encoding_scheme(bases("ACGTAC"), "f".to_string(), 4, 2, 9, "goldman".to_string());
+        let strand = ConsensusBuilder::new(ConsensusConfig::default()).build(&[read], &ReadCluster { center: 0, members: vec![0] });
+
+        assert_eq!(strand.confidence, vec![1.0; 6]);
+        let sequence = strand.to_sequence();
+        assert_eq!(sequence.metadata.chunk_index, 4);
+        assert_eq!(sequence.metadata.seed, 9);
+        assert_eq!(sequence.metadata.encoding_scheme, "goldman");
+    }
+}
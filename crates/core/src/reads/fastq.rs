@@ -0,0 +1,308 @@
+//! Lecture des fichiers FASTQ (bruts ou compressés gzip)
+//!
+//! Les qualités Phred sont conservées avec les bases et converties en
+//! probabilités d'erreur par base, par bit (2 bits par base) ou par octet
+//! (4 bases par octet), pour initialiser les LLR de `LdpcCodec` ou choisir les
+//! effacements de `ReedSolomonCodec`.
+
+use crate::error::{DnaError, Result};
+use crate::sequence::{DnaSequence, IupacBase};
+use flate2::read::MultiGzDecoder;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Décalage ASCII des qualités Phred (Illumina 1.8+, Sanger)
+pub const PHRED_OFFSET: u8 = 33;
+
+/// Signature des fichiers gzip
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Enregistrement FASTQ: bases et qualités Phred
+#[derive(Debug, Clone, PartialEq)]
+pub struct FastqRecord {
+    /// En-tête sans le `@` initial
+    pub header: String,
+    /// Bases lues
+    pub bases: Vec<IupacBase>,
+    /// Scores Phred (déjà décalés de `PHRED_OFFSET`)
+    pub qualities: Vec<u8>,
+}
+
+impl FastqRecord {
+    /// Probabilité d'erreur de chaque base
+    pub fn error_probabilities(&self) -> Vec<f64> {
+        self.qualities.iter().map(|&q| phred_to_error_probability(q)).collect()
+    }
+
+    /// Qualité Phred moyenne
+    pub fn mean_quality(&self) -> f64 {
+        if self.qualities.is_empty() {
+            return 0.0;
+        }
+        self.qualities.iter().map(|&q| q as f64).sum::<f64>() / self.qualities.len() as f64
+    }
+
+    /// Convertit l'enregistrement en séquence ADN
+    ///
+    /// Un en-tête au format de `DnaSequence::to_fasta` (`id|scheme:...`)
+    /// restaure les métadonnées d'encodage. Les qualités sont conservées dans
+    /// `metadata.base_error_probabilities`.
+    pub fn to_sequence(&self) -> Result<DnaSequence> {
+        let mut sequence = if self.header.contains("|scheme:") {
+            let bases: String = self.bases.iter().map(|b| b.as_char()).collect();
+            DnaSequence::from_fasta(&format!(">{}\n{}\n", self.header, bases))?
+        } else {
+            DnaSequence::new(self.bases.clone(), self.header.clone(), 0, 0, 0)
+        };
+
+        sequence.metadata.base_error_probabilities = self.error_probabilities();
+        Ok(sequence)
+    }
+}
+
+/// Probabilité d'erreur d'un score Phred: 10^(-Q/10)
+pub fn phred_to_error_probability(quality: u8) -> f64 {
+    10f64.powf(-(quality as f64) / 10.0)
+}
+
+/// Probabilités d'erreur par bit pour un codage 2 bits par base
+///
+/// Une substitution tire une des 3 autres bases: chaque bit de la base est
+/// faux avec une probabilité 2p/3.
+pub fn bit_error_probabilities(base_error_probabilities: &[f64]) -> Vec<f64> {
+    base_error_probabilities
+        .iter()
+        .flat_map(|&p| [2.0 * p / 3.0; 2])
+        .collect()
+}
+
+/// Probabilités d'erreur par octet pour un codage de 4 bases par octet
+pub fn byte_error_probabilities(base_error_probabilities: &[f64]) -> Vec<f64> {
+    base_error_probabilities
+        .chunks(4)
+        .map(|bases| 1.0 - bases.iter().map(|p| 1.0 - p).product::<f64>())
+        .collect()
+}
+
+/// Lit les enregistrements d'un flux FASTQ un par un
+pub struct FastqReader<R: BufRead> {
+    reader: R,
+    line_number: usize,
+}
+
+impl<R: BufRead> FastqReader<R> {
+    /// Crée un lecteur FASTQ
+    pub fn new(reader: R) -> Self {
+        Self { reader, line_number: 0 }
+    }
+
+    /// Lit une ligne sans fin de ligne; None en fin de flux
+    fn next_line(&mut self) -> Result<Option<String>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        self.line_number += 1;
+        Ok(Some(line.trim_end_matches(['\n', '\r']).to_string()))
+    }
+
+    /// Lit la ligne suivante d'un enregistrement commencé
+    fn expect_line(&mut self, what: &str) -> Result<String> {
+        self.next_line()?.ok_or_else(|| {
+            DnaError::Decoding(format!("FASTQ tronqué: {} manquant après la ligne {}", what, self.line_number))
+        })
+    }
+
+    fn read_record(&mut self) -> Result<Option<FastqRecord>> {
+        // Sauter les lignes vides entre enregistrements
+        let header = loop {
+            match self.next_line()? {
+                None => return Ok(None),
+                Some(line) if line.trim().is_empty() => continue,
+                Some(line) => break line,
+            }
+        };
+
+        let header = header.strip_prefix('@').ok_or_else(|| {
+            DnaError::Decoding(format!("FASTQ invalide ligne {}: en-tête sans '@'", self.line_number))
+        })?;
+
+        let bases = self
+            .expect_line("séquence")?
+            .chars()
+            .map(|c| IupacBase::from_char(c.to_ascii_uppercase()))
+            .collect::<Result<Vec<_>>>()?;
+
+        if !self.expect_line("séparateur")?.starts_with('+') {
+            return Err(DnaError::Decoding(format!(
+                "FASTQ invalide ligne {}: séparateur '+' attendu",
+                self.line_number
+            )));
+        }
+
+        let qualities = self
+            .expect_line("qualités")?
+            .bytes()
+            .map(|q| {
+                q.checked_sub(PHRED_OFFSET).ok_or_else(|| {
+                    DnaError::Decoding(format!("FASTQ invalide ligne {}: qualité hors plage", self.line_number))
+                })
+            })
+            .collect::<Result<Vec<u8>>>()?;
+
+        if qualities.len() != bases.len() {
+            return Err(DnaError::Decoding(format!(
+                "FASTQ invalide ligne {}: {} qualités pour {} bases",
+                self.line_number,
+                qualities.len(),
+                bases.len()
+            )));
+        }
+
+        Ok(Some(FastqRecord {
+            header: header.to_string(),
+            bases,
+            qualities,
+        }))
+    }
+}
+
+impl<R: BufRead> Iterator for FastqReader<R> {
+    type Item = Result<FastqRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Ouvre un fichier FASTQ, décompressé à la volée s'il est au format gzip
+///
+/// Le format est détecté par la signature du fichier, pas par son extension.
+pub fn open_fastq<P: AsRef<Path>>(path: P) -> Result<FastqReader<Box<dyn BufRead>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let is_gzip = reader.fill_buf()?.starts_with(&GZIP_MAGIC);
+
+    let reader: Box<dyn BufRead> = if is_gzip {
+        Box::new(BufReader::new(MultiGzDecoder::new(reader)))
+    } else {
+        Box::new(reader)
+    };
+
+    Ok(FastqReader::new(reader))
+}
+
+/// Lit tous les enregistrements d'un fichier FASTQ (brut ou gzip)
+pub fn read_fastq<P: AsRef<Path>>(path: P) -> Result<Vec<FastqRecord>> {
+    open_fastq(path)?.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::ReedSolomonCodec;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::{Cursor, Write};
+
+    const SAMPLE: &str = "@read1\nACGTN\n+\nII#5!\n\n@read2|scheme:goldman|seed:7|gc:0.50|len:4\nacgt\n+read2\nIIII\n";
+
+    fn parse(text: &str) -> Result<Vec<FastqRecord>> {
+        FastqReader::new(Cursor::new(text.as_bytes().to_vec())).collect()
+    }
+
+    #[test]
+    fn test_parse_fastq_records() {
+        let records = parse(SAMPLE).unwrap();
+        assert_eq!(records.len(), 2);
+
+        assert_eq!(records[0].header, "read1");
+        assert_eq!(records[0].bases[4], IupacBase::N);
+        assert_eq!(records[0].qualities, vec![40, 40, 2, 20, 0]);
+        assert_eq!(records[1].bases, vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T]);
+
+        let sequence = records[1].to_sequence().unwrap();
+        assert_eq!(sequence.metadata.encoding_scheme, "goldman");
+        assert_eq!(sequence.metadata.seed, 7);
+
+        // Les qualités suivent les bases
+        let sequence = records[0].to_sequence().unwrap();
+        assert_eq!(sequence.metadata.base_error_probabilities, records[0].error_probabilities());
+    }
+
+    #[test]
+    fn test_invalid_fastq_is_rejected() {
+        assert!(parse("@r\nACGT\n+\nIII\n").is_err());
+        assert!(parse("r\nACGT\n+\nIIII\n").is_err());
+        assert!(parse("@r\nACGT\n").is_err());
+    }
+
+    #[test]
+    fn test_phred_probabilities() {
+        assert!((phred_to_error_probability(10) - 0.1).abs() < 1e-12);
+        assert!((phred_to_error_probability(30) - 0.001).abs() < 1e-12);
+
+        let bits = bit_error_probabilities(&[0.3]);
+        assert_eq!(bits.len(), 2);
+        assert!((bits[0] - 0.2).abs() < 1e-12);
+
+        let bytes = byte_error_probabilities(&[0.0, 0.5, 0.0, 0.0, 0.0]);
+        assert_eq!(bytes.len(), 2);
+        assert!((bytes[0] - 0.5).abs() < 1e-12);
+        assert_eq!(bytes[1], 0.0);
+    }
+
+    #[test]
+    fn test_open_gzip_fastq() {
+        let path = std::env::temp_dir().join(format!("adn_reads_{}.fastq.gz", std::process::id()));
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(SAMPLE.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let records = read_fastq(&path);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(records.unwrap(), parse(SAMPLE).unwrap());
+    }
+
+    #[test]
+    fn test_low_quality_bases_become_rs_erasures() {
+        let codec = ReedSolomonCodec::with_ecc_len(8);
+        let original = b"quality-aware Reed-Solomon decoding".to_vec();
+        let encoded = codec.encode(&original).unwrap();
+
+        // Octets → bases (2 bits par base), qualité 35 partout
+        let mut bases: Vec<IupacBase> = encoded
+            .iter()
+            .flat_map(|&byte| (0..4).rev().map(move |i| [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T][((byte >> (2 * i)) & 3) as usize]))
+            .collect();
+        let mut qualities = vec![35u8; bases.len()];
+
+        // 6 octets touchés (> 4 erreurs corrigeables), bases signalées en qualité 2
+        for byte in 8..14 {
+            let pos = byte * 4 + 1;
+            bases[pos] = if bases[pos] == IupacBase::A { IupacBase::T } else { IupacBase::A };
+            qualities[pos] = 2;
+        }
+
+        let record = FastqRecord { header: "r".to_string(), bases, qualities };
+        let received: Vec<u8> = record
+            .bases
+            .chunks(4)
+            .map(|chunk| {
+                chunk.iter().fold(0u8, |byte, base| {
+                    let bits = match base {
+                        IupacBase::A => 0,
+                        IupacBase::C => 1,
+                        IupacBase::G => 2,
+                        _ => 3,
+                    };
+                    (byte << 2) | bits
+                })
+            })
+            .collect();
+
+        let probabilities = byte_error_probabilities(&record.error_probabilities());
+        let recovered = codec.decode_with_reliability(&received, &probabilities, 0.1).unwrap();
+        assert_eq!(recovered, original);
+    }
+}
@@ -80,6 +80,27 @@ impl IupacBase {
     pub fn is_gc(self) -> bool {
         matches!(self, IupacBase::G | IupacBase::C | IupacBase::S | IupacBase::B | IupacBase::V)
     }
+
+    /// Retourne la base complémentaire (codes ambigus inclus)
+    pub fn complement(self) -> Self {
+        match self {
+            IupacBase::A => IupacBase::T,
+            IupacBase::C => IupacBase::G,
+            IupacBase::G => IupacBase::C,
+            IupacBase::T => IupacBase::A,
+            IupacBase::R => IupacBase::Y,
+            IupacBase::Y => IupacBase::R,
+            IupacBase::S => IupacBase::S,
+            IupacBase::W => IupacBase::W,
+            IupacBase::K => IupacBase::M,
+            IupacBase::M => IupacBase::K,
+            IupacBase::B => IupacBase::V,
+            IupacBase::V => IupacBase::B,
+            IupacBase::D => IupacBase::H,
+            IupacBase::H => IupacBase::D,
+            IupacBase::N => IupacBase::N,
+        }
+    }
 }
 
 impl fmt::Display for IupacBase {
@@ -146,12 +167,28 @@ pub struct SequenceMetadata {
     pub max_homopolymer: usize,
     /// Entropie de Shannon
     pub entropy: f64,
-    /// Checksum SHA-256
+    /// Empreinte SHA-256 (hexadécimale) des bases de l'oligo
+    ///
+    /// Elle identifie la séquence conçue, pas la charge utile qu'elle porte:
+    /// l'intégrité des données décodées est vérifiée par le manifeste du pool
+    /// (SHA-256 du fichier et CRC32 par chunk).
     pub checksum: String,
     /// Seed utilisé pour la génération
     pub seed: u64,
     /// Schéma d'encodage utilisé
     pub encoding_scheme: String,
+    /// Nombre total de chunks source (codes fontaine, 0 sinon)
+    #[serde(default)]
+    pub total_chunks: usize,
+    /// Taille des données source en octets (codes fontaine, 0 sinon)
+    #[serde(default)]
+    pub source_length: usize,
+    /// Probabilité d'erreur de chaque base (qualités de séquençage), vide si inconnue
+    ///
+    /// Les qualités décrivent une lecture, pas l'oligo conçu: elles ne sont
+    /// jamais sérialisées, ni dans le journal, ni dans les archives ou la base.
+    #[serde(skip)]
+    pub base_error_probabilities: Vec<f64>,
 }
 
 impl SequenceMetadata {
@@ -206,7 +243,7 @@ impl SequenceMetadata {
             })
             .sum();
 
-        // Calcul du checksum
+        // Empreinte des bases (la charge utile n'est pas connue ici)
         let sequence_str: String = bases.iter().map(|b| b.as_char()).collect();
         let hash = Sha256::digest(sequence_str.as_bytes());
         let checksum = format!("{:x}", hash);
@@ -222,6 +259,9 @@ impl SequenceMetadata {
             checksum,
             seed,
             encoding_scheme,
+            total_chunks: 0,
+            source_length: 0,
+            base_error_probabilities: Vec::new(),
         }
     }
 }
@@ -239,6 +279,29 @@ pub struct DnaConstraints {
     pub max_sequence_length: usize,
     /// Bases autorisées
     pub allowed_bases: Vec<IupacBase>,
+    /// Plage de température de fusion en °C (plus proches voisins), non vérifiée si absente
+    #[serde(default)]
+    pub tm_range: Option<(f64, f64)>,
+    /// Énergie libre minimale tolérée des structures secondaires (kcal/mol, ex. -6.0)
+    #[serde(default)]
+    pub min_fold_energy: Option<f64>,
+    /// Motifs interdits sur les deux brins (sites de restriction, fragments d'adaptateurs)
+    #[serde(default)]
+    pub forbidden_motifs: Vec<Vec<IupacBase>>,
+    /// GC local sur fenêtre glissante
+    #[serde(default)]
+    pub gc_window: Option<GcWindow>,
+}
+
+/// Contrainte de GC local sur une fenêtre glissante
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GcWindow {
+    /// Taille de la fenêtre (bases)
+    pub size: usize,
+    /// Ratio GC minimum dans chaque fenêtre
+    pub gc_min: f64,
+    /// Ratio GC maximum dans chaque fenêtre
+    pub gc_max: f64,
 }
 
 impl Default for DnaConstraints {
@@ -249,6 +312,10 @@ impl Default for DnaConstraints {
             max_homopolymer: 3,
             max_sequence_length: 150, // Standard Illumina
             allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            tm_range: None,
+            min_fold_energy: None,
+            forbidden_motifs: Vec::new(),
+            gc_window: None,
         }
     }
 }
@@ -261,10 +328,47 @@ impl DnaConstraints {
             gc_max,
             max_homopolymer,
             max_sequence_length: max_length,
-            allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+            ..Default::default()
         }
     }
 
+    /// Impose une plage de température de fusion (°C)
+    pub fn with_tm_range(mut self, min: f64, max: f64) -> Self {
+        self.tm_range = Some((min, max));
+        self
+    }
+
+    /// Rejette les séquences dont la structure secondaire la plus stable descend sous `min` kcal/mol
+    pub fn with_min_fold_energy(mut self, min: f64) -> Self {
+        self.min_fold_energy = Some(min);
+        self
+    }
+
+    /// Interdit des motifs (codes IUPAC acceptés, ex. `GAATTC` pour EcoRI)
+    pub fn with_forbidden_motifs(mut self, motifs: Vec<Vec<IupacBase>>) -> Self {
+        self.forbidden_motifs = motifs;
+        self
+    }
+
+    /// Impose un GC local dans chaque fenêtre de `size` bases
+    pub fn with_gc_window(mut self, size: usize, gc_min: f64, gc_max: f64) -> Self {
+        self.gc_window = Some(GcWindow { size, gc_min, gc_max });
+        self
+    }
+
+    /// Retourne true si des règles de synthèse (Tm, structure, motifs, GC local) sont actives
+    pub fn has_synthesis_rules(&self) -> bool {
+        self.tm_range.is_some()
+            || self.min_fold_energy.is_some()
+            || !self.forbidden_motifs.is_empty()
+            || self.gc_window.is_some()
+    }
+
+    /// Vérifie uniquement les règles de synthèse
+    pub fn validate_synthesis(&self, bases: &[IupacBase]) -> Result<()> {
+        crate::constraints::check_synthesis_rules(self, bases)
+    }
+
     /// Valide une séquence selon les contraintes
     pub fn validate(&self, bases: &[IupacBase]) -> Result<()> {
         // Vérifier la longueur
@@ -313,6 +417,11 @@ impl DnaConstraints {
             }
         }
 
+        // Règles de synthèse (Tm, structure secondaire, motifs, GC local)
+        if self.has_synthesis_rules() {
+            self.validate_synthesis(bases)?;
+        }
+
         Ok(())
     }
 }
@@ -385,13 +494,24 @@ impl DnaSequence {
 
     /// Convertit au format FASTA
     pub fn to_fasta(&self) -> String {
+        // Les paramètres fontaine ne sont écrits que s'ils sont renseignés
+        let fountain = if self.metadata.total_chunks > 0 {
+            format!(
+                "|chunks:{}|bytes:{}",
+                self.metadata.total_chunks, self.metadata.source_length
+            )
+        } else {
+            String::new()
+        };
+
         format!(
-            ">{}|scheme:{}|seed:{}|gc:{:.2}|len:{}\n{}\n",
+            ">{}|scheme:{}|seed:{}|gc:{:.2}|len:{}{}\n{}\n",
             self.id,
             self.metadata.encoding_scheme,
             self.metadata.seed,
             self.metadata.gc_ratio * 100.0,
             self.bases.len(),
+            fountain,
             self
         )
     }
@@ -414,6 +534,8 @@ impl DnaSequence {
         let metadata_parts = header[1..].split('|').collect::<Vec<_>>();
         let mut scheme = "unknown".to_string();
         let mut seed = 0u64;
+        let mut total_chunks = 0usize;
+        let mut source_length = 0usize;
 
         for part in metadata_parts {
             if part.contains("scheme:") {
@@ -421,6 +543,10 @@ impl DnaSequence {
             } else if part.contains("seed:") {
                 let seed_str = part.split(':').nth(1).unwrap_or("0");
                 seed = seed_str.parse().unwrap_or(0);
+            } else if part.contains("chunks:") {
+                total_chunks = part.split(':').nth(1).unwrap_or("0").parse().unwrap_or(0);
+            } else if part.contains("bytes:") {
+                source_length = part.split(':').nth(1).unwrap_or("0").parse().unwrap_or(0);
             }
         }
 
@@ -432,7 +558,7 @@ impl DnaSequence {
             .collect::<Result<Vec<IupacBase>>>()?;
 
         // Créer les métadonnées
-        let metadata = SequenceMetadata::compute(
+        let mut metadata = SequenceMetadata::compute(
             &bases,
             String::from("fasta"),
             0,
@@ -440,6 +566,8 @@ impl DnaSequence {
             seed,
             scheme,
         );
+        metadata.total_chunks = total_chunks;
+        metadata.source_length = source_length;
 
         Ok(Self {
             bases,
@@ -560,5 +688,54 @@ mod tests {
         assert!(fasta.starts_with('>'));
         assert!(fasta.contains("seed:42"));
         assert!(fasta.contains("ACGT"));
+        assert!(!fasta.contains("chunks:"));
+    }
+
+    #[test]
+    fn test_fasta_fountain_parameters_roundtrip() {
+        let bases = vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+        let mut seq = DnaSequence::with_encoding_scheme(
+            bases, "test.txt".to_string(), 0, 1, 7, "fountain".to_string(),
+        );
+        seq.metadata.total_chunks = 12;
+        seq.metadata.source_length = 380;
+
+        let parsed = DnaSequence::from_fasta(&seq.to_fasta()).unwrap();
+
+        assert_eq!(parsed.metadata.encoding_scheme, "fountain");
+        assert_eq!(parsed.metadata.seed, 7);
+        assert_eq!(parsed.metadata.total_chunks, 12);
+        assert_eq!(parsed.metadata.source_length, 380);
+    }
+
+    #[test]
+    fn test_base_error_probabilities_are_not_persisted() {
+        let mut seq = DnaSequence::new(vec![IupacBase::A; 120], "read".to_string(), 0, 30, 0);
+        seq.metadata.base_error_probabilities = vec![0.001; 120];
+
+        let json = serde_json::to_string(&seq).unwrap();
+        assert!(!json.contains("base_error_probabilities"));
+        assert!(serde_json::from_str::<DnaSequence>(&json)
+            .unwrap()
+            .metadata
+            .base_error_probabilities
+            .is_empty());
+
+        let mut without = seq.clone();
+        without.metadata.base_error_probabilities.clear();
+        assert_eq!(
+            bincode::serialize(&seq).unwrap(),
+            bincode::serialize(&without).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_checksum_fingerprints_bases() {
+        let seq = DnaSequence::from_str("ACGTACGT", "test".to_string(), 0, 2, 0).unwrap();
+        assert_eq!(seq.metadata.checksum, format!("{:x}", Sha256::digest(b"ACGTACGT")));
+
+        // L'empreinte ne dépend que des bases, pas de la position du chunk
+        let other = DnaSequence::from_str("ACGTACGT", "test".to_string(), 1, 2, 0).unwrap();
+        assert_eq!(seq.metadata.checksum, other.metadata.checksum);
     }
 }
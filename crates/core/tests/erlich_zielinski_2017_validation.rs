@@ -7,9 +7,13 @@
 
 use adn_core::{Encoder, Decoder, EncoderConfig, DecoderConfig};
 use adn_core::codec::EncoderType;
+use adn_core::codec::gc_aware_encoding::MAX_PAYLOAD_BYTES;
+use adn_core::codec::manifest::split_pool;
+
+/// Longueur des oligos EZ 2017 (spécification papier)
+const OLIGO_LEN: usize = 152;
 
 #[test]
-#[ignore = "Requires lenient constraints - TODO: implement proper GC-balancing encoding"]
 /// Test 1: Validation des paramètres Robust Soliton
 ///
 /// Selon le papier EZ 2017:
@@ -20,34 +24,35 @@ fn test_ez2017_robust_soliton_parameters() {
         encoder_type: EncoderType::ErlichZielinski2017,
         chunk_size: 32,
         redundancy: 1.05,  // Dans la plage 1.03-1.07 recommandée
-        compression_enabled: true,
+        compression_enabled: false,  // Nombre de chunks connu à l'avance
         ..Default::default()
     };
 
     let encoder = Encoder::new(config).unwrap();
-    let data = b"Test data for EZ 2017 validation".to_vec();
+    let data: Vec<u8> = (0..1024u32).map(|i| (i * 7 % 251) as u8).collect();
 
     // L'encodage doit réussir
     let sequences = encoder.encode(&data).unwrap();
 
-    // On doit avoir des séquences générées
-    assert!(!sequences.is_empty(), "Aucune séquence générée");
+    // Les oligos de manifeste ne sont pas des gouttes
+    let (_, droplets) = split_pool(&sequences).unwrap();
+    assert!(!droplets.is_empty(), "Aucune goutte générée");
 
-    // Le nombre de séquences doit être proche de K * redundancy
-    let expected_droplets = (data.len() / 32) as f64 * 1.05;
-    let actual_droplets = sequences.len() as f64;
+    // Un oligo transporte au plus MAX_PAYLOAD_BYTES octets: K chunks
+    let num_chunks = data.len().div_ceil(MAX_PAYLOAD_BYTES);
+    let expected_droplets = num_chunks as f64 * 1.05;
+    let actual_droplets = droplets.len() as f64;
 
-    // Tolérance de ±10%
+    // Au moins K * redundancy, et une marge de rang plein limitée à +10%
     assert!(
-        actual_droplets >= expected_droplets * 0.9 && actual_droplets <= expected_droplets * 1.1,
-        "Nombre de gouttes {} hors de la plage attendue {}",
+        actual_droplets >= expected_droplets.ceil() && actual_droplets <= expected_droplets * 1.1,
+        "Nombre de gouttes {} hors de la plage attendue {:.1}",
         actual_droplets,
         expected_droplets
     );
 }
 
 #[test]
-#[ignore = "Requires lenient constraints - TODO: implement proper GC-balancing encoding"]
 /// Test 2: Validation des contraintes GC (40-60%)
 ///
 /// Selon le papier EZ 2017, toutes les séquences doivent respecter:
@@ -82,7 +87,6 @@ fn test_ez2017_gc_content_constraint() {
 }
 
 #[test]
-#[ignore = "Requires lenient constraints - TODO: implement proper GC-balancing encoding"]
 /// Test 3: Validation des homopolymères (<4)
 ///
 /// Selon le papier EZ 2017, aucun homopolymer ne doit dépasser 3 bases
@@ -113,7 +117,6 @@ fn test_ez2017_homopolymer_constraint() {
 }
 
 #[test]
-#[ignore = "Requires lenient constraints - TODO: implement proper GC-balancing encoding"]
 /// Test 4: Validation de la longueur des oligos (152nt ± tolérance)
 ///
 /// Selon le papier EZ 2017, les oligos doivent avoir ~152nt
@@ -145,7 +148,6 @@ fn test_ez2017_sequence_length() {
 }
 
 #[test]
-#[ignore = "Requires lenient constraints - TODO: implement proper GC-balancing encoding"]
 /// Test 5: Roundtrip complet avec EZ 2017
 ///
 /// Valide que les données peuvent être encodées puis décodées correctement
@@ -181,7 +183,6 @@ fn test_ez2017_roundtrip() {
 }
 
 #[test]
-#[ignore = "Requires lenient constraints - TODO: implement proper GC-balancing encoding"]
 /// Test 6: Validation de l'overhead théorique
 ///
 /// Selon le papier EZ 2017, l'overhead doit être proche de 1.03-1.07×
@@ -199,9 +200,10 @@ fn test_ez2017_overhead() {
 
     let encoder = Encoder::new(config).unwrap();
     let sequences = encoder.encode(&data).unwrap();
+    let (_, droplets) = split_pool(&sequences).unwrap();
 
-    // Calculer le nombre total de bases
-    let total_bases: usize = sequences.iter()
+    // Calculer le nombre total de bases des gouttes
+    let total_bases: usize = droplets.iter()
         .map(|s| s.bases.len())
         .sum();
 
@@ -210,16 +212,20 @@ fn test_ez2017_overhead() {
     let theoretical_min_bases = original_size * 4;
     let overhead = total_bases as f64 / theoretical_min_bases as f64;
 
-    // L'overhead doit être proche de la redondance configurée (1.05)
+    // Un oligo de 152nt transporte MAX_PAYLOAD_BYTES octets (header + padding GC):
+    // l'overhead attendu est ce surcoût de format multiplié par la redondance
+    // (1.03-1.07) et la marge nécessaire pour un système de rang plein
+    let layout_overhead = OLIGO_LEN as f64 / (MAX_PAYLOAD_BYTES * 4) as f64;
     assert!(
-        overhead >= 1.0 && overhead <= 1.15,
-        "Overhead {:.2} hors de la plage attendue (1.0-1.15)",
-        overhead
+        overhead >= layout_overhead && overhead <= layout_overhead * 1.15,
+        "Overhead {:.2} hors de la plage attendue ({:.2}-{:.2})",
+        overhead,
+        layout_overhead,
+        layout_overhead * 1.15
     );
 }
 
 #[test]
-#[ignore = "Requires lenient constraints - TODO: implement proper GC-balancing encoding"]
 /// Test 7: Validation de la densité d'information
 ///
 /// Selon le papier EZ 2017, la densité doit être ~1.92 bits/base
@@ -234,16 +240,17 @@ fn test_ez2017_information_density() {
 
     let encoder = Encoder::new(config).unwrap();
     let data = b"Repetitive data to test compression efficiency. ".repeat(10);
-    let compressed_len = data.len();
 
     let sequences = encoder.encode(&data).unwrap();
 
-    let total_bases: usize = sequences.iter()
+    // Le manifeste est un coût fixe par pool, hors schéma EZ 2017
+    let (_, droplets) = split_pool(&sequences).unwrap();
+    let total_bases: usize = droplets.iter()
         .map(|s| s.bases.len())
         .sum();
 
     // Bits par base = (original_bytes * 8) / total_bases
-    let bits_per_base = (compressed_len * 8) as f64 / total_bases as f64;
+    let bits_per_base = (data.len() * 8) as f64 / total_bases as f64;
 
     // La densité doit être > 1.5 bits/base (typiquement ~1.92 avec EZ 2017)
     assert!(
@@ -256,7 +263,6 @@ fn test_ez2017_information_density() {
 }
 
 #[test]
-#[ignore = "Requires lenient constraints - TODO: implement proper GC-balancing encoding"]
 /// Test 8: Tolérance à la perte de gouttes
 ///
 /// Une propriété clé de DNA Fountain est la capacité à décoder même
@@ -12,6 +12,7 @@ fn lenient_constraints() -> DnaConstraints {
         max_homopolymer: 100,  // Old Goldman can create very long runs without rotation
         max_sequence_length: 200,
         allowed_bases: vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T],
+        ..Default::default()
     }
 }
 
@@ -0,0 +1,37 @@
+//! Accès aléatoire par paires d'amorces dans un pool multi-fichiers
+
+use adn_core::bio::primers::reverse_complement;
+use adn_core::{Decoder, DecoderConfig, Encoder, EncoderConfig, PrimerConfig, PrimerLibrary};
+
+#[test]
+fn test_decode_one_file_from_mixed_pool() {
+    let files: Vec<(&str, Vec<u8>)> = vec![
+        ("rapport.txt", b"Premier fichier du run de synthese, stocke en ADN.".repeat(8)),
+        ("photo.raw", (0..700u32).map(|i| (i * 37 % 251) as u8).collect()),
+        ("notes.md", b"# Notes\nTroisieme fichier du meme pool.\n".repeat(5)),
+    ];
+
+    let mut library = PrimerLibrary::generate(PrimerConfig::default(), files.len()).unwrap();
+
+    // Synthèse: tous les fichiers dans un même pool
+    let mut pool = Vec::new();
+    for (name, data) in &files {
+        let pair = library.assign(name).unwrap().clone();
+        let encoder = Encoder::new(EncoderConfig::default()).unwrap().with_primer_pair(pair);
+        pool.extend(encoder.encode(data).unwrap());
+    }
+
+    // Mélange déterministe et lecture d'une partie des oligos en antisens
+    pool.reverse();
+    for sequence in pool.iter_mut().step_by(3) {
+        sequence.bases = reverse_complement(&sequence.bases);
+    }
+
+    let decoder = Decoder::new(DecoderConfig::default());
+    for (name, data) in &files {
+        let selected = library.select(name, &pool, 2).unwrap();
+        assert!(!selected.is_empty());
+        assert!(selected.len() < pool.len());
+        assert_eq!(&decoder.decode(&selected).unwrap(), data, "{}", name);
+    }
+}
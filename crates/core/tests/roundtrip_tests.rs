@@ -19,6 +19,7 @@ fn goldman_constraints() -> DnaConstraints {
             adn_core::IupacBase::G,
             adn_core::IupacBase::T,
         ],
+        ..Default::default()
     }
 }
 
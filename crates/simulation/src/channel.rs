@@ -1,10 +1,13 @@
 //! Canal de transmission ADN simulé
 
-use crate::error_model::ErrorModel;
+use crate::error_model::{ErrorModel, ErrorType};
 use crate::metrics::SimulationMetrics;
+use crate::profile::ErrorProfile;
+use crate::stages::{ChannelOutput, ChannelPipeline};
 use adn_core::{DnaSequence, IupacBase, Result};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
 /// Configuration du canal ADN
@@ -13,14 +16,18 @@ pub struct ChannelConfig {
     /// Modèle d'erreur
     pub error_model: ErrorModel,
 
-    /// Température (affecte les taux d'erreur)
+    /// Température de stockage en °C (vitesse de dépurination)
     pub temperature: f64,
 
-    /// pH (affecte les taux d'erreur)
+    /// pH de stockage (catalyse acide de la dépurination)
     pub ph: f64,
 
-    /// Durée de stockage en jours
+    /// Durée de stockage en jours (étape de dégradation)
     pub storage_duration_days: u32,
+
+    /// Profil empirique; remplace les taux uniformes de `error_model`
+    #[serde(default)]
+    pub profile: Option<ErrorProfile>,
 }
 
 impl Default for ChannelConfig {
@@ -30,10 +37,26 @@ impl Default for ChannelConfig {
             temperature: 25.0,  // 25°C
             ph: 7.0,            // pH neutre
             storage_duration_days: 30,
+            profile: None,
         }
     }
 }
 
+impl ChannelConfig {
+    /// Utilise un profil appris; les taux moyens sont repris dans `error_model`
+    pub fn with_profile(mut self, profile: ErrorProfile) -> Self {
+        let seed = self.error_model.seed;
+        self.error_model = profile.error_model().with_seed(seed);
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Charge un profil JSON sauvegardé par `ErrorProfile::save`
+    pub fn with_profile_file<P: AsRef<std::path::Path>>(self, path: P) -> Result<Self> {
+        Ok(self.with_profile(ErrorProfile::load(path)?))
+    }
+}
+
 /// Canal de transmission ADN simulé
 pub struct DnaChannel {
     config: ChannelConfig,
@@ -51,63 +74,71 @@ impl DnaChannel {
     }
 
     /// Simule la transmission avec erreurs
+    ///
+    /// Avec un profil empirique, les taux dépendent de la position et du
+    /// contexte et les indels peuvent couvrir plusieurs bases.
+    ///
+    /// Chaque base d'origine subit au plus un événement; la séquence reçue est
+    /// reconstruite au fil de l'eau pour que les indels ne décalent pas les
+    /// positions des bases suivantes.
     pub fn transmit(&mut self, sequence: &DnaSequence) -> Result<(DnaSequence, SimulationMetrics)> {
-        let mut corrupted = sequence.clone();
-        let mut metrics = SimulationMetrics::new();
-
-        // Extraire les taux avant les appels mutables
-        let sub_rate = self.config.error_model.substitution_rate;
-        let ins_rate = self.config.error_model.insertion_rate;
-        let del_rate = self.config.error_model.deletion_rate;
-        let total_rate = sub_rate + ins_rate + del_rate;
-
-        for (i, base) in sequence.bases.iter().enumerate() {
-            let roll: f64 = self.rng.gen();
-
-            if roll < sub_rate {
-                // Substitution
-                let new_base = self.substitute_base(*base);
-                corrupted.bases[i] = new_base;
-                metrics.substitutions += 1;
-            } else if roll < sub_rate + ins_rate {
-                // Insertion
-                let new_base = self.random_base();
-                corrupted.bases.insert(i, new_base);
-                metrics.insertions += 1;
-            } else if roll < total_rate {
-                // Délétion
-                corrupted.bases.remove(i);
-                metrics.deletions += 1;
-            }
-        }
+        Ok(transmit_with(&self.config, sequence, &mut self.rng))
+    }
 
-        metrics.total_bases = sequence.bases.len();
-        metrics.affected_bases = metrics.substitutions + metrics.insertions + metrics.deletions;
+    /// Transmet un lot de séquences pour un essai, en parallèle
+    ///
+    /// Chaque séquence tire ses erreurs de son propre flux
+    /// [`stream_rng`]`(seed, trial, index)`: le résultat ne dépend ni du
+    /// nombre de threads, ni de l'ordre d'exécution, ni des autres séquences
+    /// du lot. L'état du RNG séquentiel du canal n'est pas modifié.
+    pub fn transmit_batch(&self, sequences: &[DnaSequence], trial: u64) -> Result<Vec<(DnaSequence, SimulationMetrics)>> {
+        let seed = self.config.error_model.seed;
 
-        Ok((corrupted, metrics))
+        sequences
+            .par_iter()
+            .enumerate()
+            .map(|(index, sequence)| {
+                let mut rng = stream_rng(seed, trial, index as u64);
+                Ok(transmit_with(&self.config, sequence, &mut rng))
+            })
+            .collect()
     }
 
-    /// Substitue une base par une autre
-    fn substitute_base(&mut self, base: IupacBase) -> IupacBase {
-        let bases = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
-
-        // Choisir une base différente
-        let mut new_base = base;
-        while new_base == base {
-            new_base = bases[self.rng.gen_range(0..4)];
-        }
+    /// Métriques cumulées de chaque essai sur tout le lot, en parallèle
+    ///
+    /// Les couples (essai, séquence) sont répartis entre les threads; les
+    /// compteurs sont des entiers, la réduction est donc exacte quel que soit
+    /// l'ordre.
+    pub fn simulate_trials(&self, sequences: &[DnaSequence], trials: usize) -> Vec<SimulationMetrics> {
+        let seed = self.config.error_model.seed;
 
-        new_base
+        (0..trials)
+            .into_par_iter()
+            .map(|trial| {
+                sequences
+                    .par_iter()
+                    .enumerate()
+                    .map(|(index, sequence)| {
+                        let mut rng = stream_rng(seed, trial as u64, index as u64);
+                        transmit_with(&self.config, sequence, &mut rng).1
+                    })
+                    .reduce(SimulationMetrics::new, |mut total, metrics| {
+                        total.merge(&metrics);
+                        total
+                    })
+            })
+            .collect()
     }
 
-    /// Génère une base aléatoire
-    fn random_base(&mut self) -> IupacBase {
-        match self.rng.gen_range(0..4) {
-            0 => IupacBase::A,
-            1 => IupacBase::C,
-            2 => IupacBase::G,
-            _ => IupacBase::T,
-        }
+    /// Simule un run complet sur un pool d'oligos
+    ///
+    /// Synthèse, stockage dans les conditions de la configuration, PCR puis
+    /// séquençage Illumina: le résultat est un multiset de lectures. Chaque
+    /// appel tire un nouveau seed du canal.
+    pub fn sequence_pool(&mut self, oligos: &[DnaSequence]) -> Result<ChannelOutput> {
+        ChannelPipeline::from_config(&self.config)
+            .with_seed(self.rng.gen())
+            .run(oligos)
     }
 
     /// Simule plusieurs itérations
@@ -121,6 +152,89 @@ impl DnaChannel {
     }
 }
 
+/// Nombre de mots ChaCha réservés à chaque séquence d'un flux d'essai
+const WORDS_PER_SEQUENCE_LOG2: u32 = 40;
+
+/// Flux aléatoire indépendant d'un couple (essai, séquence)
+///
+/// Le seed fixe la clé ChaCha, l'essai choisit le flux (nonce) et la séquence
+/// une plage disjointe de 2^40 mots dans ce flux: deux couples différents ne
+/// partagent jamais de tirages.
+pub fn stream_rng(seed: u64, trial: u64, sequence: u64) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(trial);
+    rng.set_word_pos((sequence as u128) << WORDS_PER_SEQUENCE_LOG2);
+    rng
+}
+
+/// Transmission d'une séquence avec un RNG donné
+fn transmit_with(config: &ChannelConfig, sequence: &DnaSequence, rng: &mut ChaCha8Rng) -> (DnaSequence, SimulationMetrics) {
+    let mut corrupted = sequence.clone();
+    let mut metrics = SimulationMetrics::new();
+
+    if let Some(profile) = &config.profile {
+        corrupted.bases = profile.mutate(&sequence.bases, rng, &mut metrics);
+        metrics.total_bases = sequence.bases.len();
+        return (corrupted, metrics);
+    }
+
+    let sub_rate = config.error_model.substitution_rate;
+    let ins_rate = config.error_model.insertion_rate;
+    let del_rate = config.error_model.deletion_rate;
+    let total_rate = sub_rate + ins_rate + del_rate;
+
+    let length = sequence.bases.len();
+    let mut received = Vec::with_capacity(length);
+
+    for (position, base) in sequence.bases.iter().enumerate() {
+        let roll: f64 = rng.gen();
+
+        if roll < sub_rate {
+            // Substitution
+            received.push(substitute_base(*base, rng));
+            metrics.record(ErrorType::Substitution, position, length);
+        } else if roll < sub_rate + ins_rate {
+            // Insertion avant la base
+            received.push(random_base(rng));
+            received.push(*base);
+            metrics.record(ErrorType::Insertion, position, length);
+        } else if roll < total_rate {
+            // Délétion
+            metrics.record(ErrorType::Deletion, position, length);
+        } else {
+            received.push(*base);
+        }
+    }
+
+    corrupted.bases = received;
+    metrics.total_bases = length;
+
+    (corrupted, metrics)
+}
+
+/// Substitue une base par une autre
+fn substitute_base(base: IupacBase, rng: &mut ChaCha8Rng) -> IupacBase {
+    let bases = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+
+    // Choisir une base différente
+    let mut new_base = base;
+    while new_base == base {
+        new_base = bases[rng.gen_range(0..4)];
+    }
+
+    new_base
+}
+
+/// Génère une base aléatoire
+fn random_base(rng: &mut ChaCha8Rng) -> IupacBase {
+    match rng.gen_range(0..4) {
+        0 => IupacBase::A,
+        1 => IupacBase::C,
+        2 => IupacBase::G,
+        _ => IupacBase::T,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,4 +272,71 @@ mod tests {
 
         let (_corrupted, _metrics) = channel.transmit(&seq).unwrap();
     }
+
+    #[test]
+    fn test_indels_change_length_consistently() {
+        let config = ChannelConfig {
+            error_model: ErrorModel::new(0.0, 0.05, 0.05).with_seed(9),
+            ..Default::default()
+        };
+        let mut channel = DnaChannel::new(config);
+
+        let bases = vec![IupacBase::A; 400];
+        let seq = DnaSequence::new(bases, "test.txt".to_string(), 0, 400, 42);
+
+        let (corrupted, metrics) = channel.transmit(&seq).unwrap();
+        assert!(metrics.insertions > 0 && metrics.deletions > 0);
+        assert_eq!(
+            corrupted.bases.len(),
+            400 + metrics.insertions - metrics.deletions
+        );
+    }
+
+    #[test]
+    fn test_batch_is_independent_of_threads_and_batch_size() {
+        let config = ChannelConfig {
+            error_model: ErrorModel::new(0.05, 0.02, 0.02).with_seed(77),
+            ..Default::default()
+        };
+        let channel = DnaChannel::new(config);
+        let sequences: Vec<DnaSequence> = (0..64)
+            .map(|i| DnaSequence::new(vec![IupacBase::A; 100], "lot".to_string(), i, 64, 0))
+            .collect();
+
+        let run = |threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| channel.transmit_batch(&sequences, 3).unwrap())
+        };
+        let single = run(1);
+        let multi = run(4);
+        assert!(single.iter().zip(&multi).all(|(a, b)| a.0.bases == b.0.bases));
+
+        // Un sous-lot reproduit exactement le début du lot complet
+        let prefix = channel.transmit_batch(&sequences[..10], 3).unwrap();
+        assert!(prefix.iter().zip(&single).all(|(a, b)| a.0.bases == b.0.bases));
+
+        // Séquences identiques, flux différents
+        assert!(single.iter().any(|r| r.0.bases != single[0].0.bases));
+    }
+
+    #[test]
+    fn test_simulate_trials_matches_batches() {
+        let channel = DnaChannel::new(ChannelConfig::default());
+        let sequences: Vec<DnaSequence> = (0..20)
+            .map(|i| DnaSequence::new(vec![IupacBase::C; 50], "lot".to_string(), i, 20, 0))
+            .collect();
+
+        let trials = channel.simulate_trials(&sequences, 4);
+        assert_eq!(trials.len(), 4);
+
+        for (trial, metrics) in trials.iter().enumerate() {
+            let batch = channel.transmit_batch(&sequences, trial as u64).unwrap();
+            let substitutions: usize = batch.iter().map(|(_, m)| m.substitutions).sum();
+            assert_eq!(metrics.substitutions, substitutions);
+            assert_eq!(metrics.total_bases, 1000);
+        }
+    }
 }
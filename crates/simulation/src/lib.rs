@@ -3,7 +3,21 @@
 pub mod error_model;
 pub mod channel;
 pub mod metrics;
+pub mod stages;
+pub mod monte_carlo;
+pub mod profile;
 
 pub use error_model::{ErrorModel, ErrorType};
-pub use channel::{DnaChannel, ChannelConfig};
-pub use metrics::{SimulationMetrics, MetricsCollector};
+pub use channel::{stream_rng, DnaChannel, ChannelConfig};
+pub use metrics::{
+    ConfidenceInterval, ErrorEvent, MetricKind, MetricSummary, MetricsCollector, PositionHistogram,
+    SimulationMetrics, StatisticsReport,
+};
+pub use stages::{
+    ChannelOutput, ChannelPipeline, ChannelStage, DecayStage, Molecule, PcrStage,
+    SequencingProfile, SequencingStage, StageContext, SynthesisStage,
+};
+pub use monte_carlo::{
+    MonteCarlo, MonteCarloConfig, MonteCarloSummary, SweepParameter, SweepPoint, TrialOutcome,
+};
+pub use profile::{ErrorProfile, ProfileLearner};
@@ -1,7 +1,28 @@
 //! Métriques de simulation
-
+//!
+//! Au-delà des moyennes, le collecteur fournit percentiles, intervalles de
+//! confiance par bootstrap et histogrammes de position des erreurs le long de
+//! l'oligo, exportables en CSV et JSON pour les rapports de manipulation.
+
+use crate::error_model::ErrorType;
+use adn_core::{DnaError, Result};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 
+/// Erreur localisée sur la séquence d'origine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorEvent {
+    /// Type d'erreur
+    pub kind: ErrorType,
+
+    /// Position dans la séquence d'origine
+    pub position: usize,
+
+    /// Longueur de la séquence d'origine
+    pub length: usize,
+}
+
 /// Métriques collectées pendant une simulation
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SimulationMetrics {
@@ -19,6 +40,10 @@ pub struct SimulationMetrics {
 
     /// Nombre total de bases affectées
     pub affected_bases: usize,
+
+    /// Position de chaque erreur sur sa séquence d'origine
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<ErrorEvent>,
 }
 
 impl SimulationMetrics {
@@ -59,6 +84,27 @@ impl SimulationMetrics {
         self.deletions as f64 / self.total_bases as f64
     }
 
+    /// Compte une erreur et mémorise sa position
+    pub fn record(&mut self, kind: ErrorType, position: usize, length: usize) {
+        match kind {
+            ErrorType::Substitution => self.substitutions += 1,
+            ErrorType::Insertion => self.insertions += 1,
+            ErrorType::Deletion => self.deletions += 1,
+        }
+        self.affected_bases += 1;
+        self.events.push(ErrorEvent { kind, position, length });
+    }
+
+    /// Ajoute les compteurs et les erreurs d'une autre mesure
+    pub fn merge(&mut self, other: &SimulationMetrics) {
+        self.total_bases += other.total_bases;
+        self.substitutions += other.substitutions;
+        self.insertions += other.insertions;
+        self.deletions += other.deletions;
+        self.affected_bases += other.affected_bases;
+        self.events.extend_from_slice(&other.events);
+    }
+
     /// Formate les métriques en tableau
     pub fn format_table(&self) -> String {
         format!(
@@ -114,6 +160,7 @@ impl MetricsCollector {
             insertions: self.metrics.iter().map(|m| m.insertions).sum::<usize>() / n,
             deletions: self.metrics.iter().map(|m| m.deletions).sum::<usize>() / n,
             affected_bases: self.metrics.iter().map(|m| m.affected_bases).sum::<usize>() / n,
+            events: Vec::new(),
         }
     }
 
@@ -129,6 +176,7 @@ impl MetricsCollector {
             insertions: self.metrics.iter().map(|m| m.insertions).min().unwrap_or(0),
             deletions: self.metrics.iter().map(|m| m.deletions).min().unwrap_or(0),
             affected_bases: self.metrics.iter().map(|m| m.affected_bases).min().unwrap_or(0),
+            events: Vec::new(),
         }
     }
 
@@ -144,6 +192,7 @@ impl MetricsCollector {
             insertions: self.metrics.iter().map(|m| m.insertions).max().unwrap_or(0),
             deletions: self.metrics.iter().map(|m| m.deletions).max().unwrap_or(0),
             affected_bases: self.metrics.iter().map(|m| m.affected_bases).max().unwrap_or(0),
+            events: Vec::new(),
         }
     }
 
@@ -171,6 +220,7 @@ impl MetricsCollector {
             insertions: variance(ins_values).sqrt() as usize,
             deletions: variance(del_values).sqrt() as usize,
             affected_bases: 0,
+            events: Vec::new(),
         }
     }
 
@@ -188,6 +238,329 @@ impl MetricsCollector {
     pub fn clear(&mut self) {
         self.metrics.clear();
     }
+
+    /// Métriques collectées, dans l'ordre d'ajout
+    pub fn metrics(&self) -> &[SimulationMetrics] {
+        &self.metrics
+    }
+
+    /// Valeurs d'une métrique pour chaque simulation
+    pub fn values(&self, metric: MetricKind) -> Vec<f64> {
+        self.metrics.iter().map(|m| metric.value(m)).collect()
+    }
+
+    /// Percentile `p` (0 à 100) d'une métrique, par interpolation linéaire
+    pub fn percentile(&self, metric: MetricKind, p: f64) -> f64 {
+        let mut values = self.values(metric);
+        values.sort_by(|a, b| a.total_cmp(b));
+        percentile_sorted(&values, p)
+    }
+
+    /// Intervalle de confiance de la moyenne d'une métrique par bootstrap
+    ///
+    /// `resamples` rééchantillonnages avec remise; les bornes sont les
+    /// quantiles `(1 - confidence) / 2` et `(1 + confidence) / 2` des moyennes.
+    pub fn bootstrap_ci(&self, metric: MetricKind, confidence: f64, resamples: usize, seed: u64) -> ConfidenceInterval {
+        let values = self.values(metric);
+        let mean = mean(&values);
+        if values.len() < 2 || resamples == 0 {
+            return ConfidenceInterval { mean, lower: mean, upper: mean, confidence };
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut means: Vec<f64> = (0..resamples)
+            .map(|_| {
+                let sum: f64 = (0..values.len()).map(|_| values[rng.gen_range(0..values.len())]).sum();
+                sum / values.len() as f64
+            })
+            .collect();
+        means.sort_by(|a, b| a.total_cmp(b));
+
+        let tail = 100.0 * (1.0 - confidence) / 2.0;
+        ConfidenceInterval {
+            mean,
+            lower: percentile_sorted(&means, tail),
+            upper: percentile_sorted(&means, 100.0 - tail),
+            confidence,
+        }
+    }
+
+    /// Histogramme des positions relatives des erreurs le long de l'oligo
+    pub fn position_histogram(&self, bins: usize) -> PositionHistogram {
+        let mut histogram = PositionHistogram::new(bins);
+        for event in self.metrics.iter().flat_map(|m| &m.events) {
+            histogram.add(event);
+        }
+        histogram
+    }
+
+    /// Rapport statistique complet: résumé par métrique et histogramme
+    pub fn report(&self, bins: usize) -> StatisticsReport {
+        let summaries = MetricKind::ALL
+            .iter()
+            .map(|&metric| {
+                let mut values = self.values(metric);
+                values.sort_by(|a, b| a.total_cmp(b));
+                let mean = mean(&values);
+                let variance = if values.is_empty() {
+                    0.0
+                } else {
+                    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+                };
+
+                MetricSummary {
+                    metric,
+                    mean,
+                    std_dev: variance.sqrt(),
+                    min: values.first().copied().unwrap_or(0.0),
+                    p05: percentile_sorted(&values, 5.0),
+                    median: percentile_sorted(&values, 50.0),
+                    p95: percentile_sorted(&values, 95.0),
+                    max: values.last().copied().unwrap_or(0.0),
+                    ci95: self.bootstrap_ci(metric, 0.95, 1000, 42),
+                }
+            })
+            .collect();
+
+        StatisticsReport {
+            runs: self.metrics.len(),
+            summaries,
+            histogram: self.position_histogram(bins),
+        }
+    }
+
+    /// Une ligne CSV par simulation
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("run,total_bases,substitutions,insertions,deletions,affected_bases,error_rate\n");
+        for (i, m) in self.metrics.iter().enumerate() {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{:.6}\n",
+                i,
+                m.total_bases,
+                m.substitutions,
+                m.insertions,
+                m.deletions,
+                m.affected_bases,
+                m.error_rate()
+            ));
+        }
+        csv
+    }
+
+    /// Sérialise le collecteur en JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| DnaError::Serialization(e.to_string()))
+    }
+
+    /// Relit un collecteur sérialisé par `to_json`
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| DnaError::Serialization(e.to_string()))
+    }
+}
+
+/// Métrique scalaire extraite de `SimulationMetrics`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MetricKind {
+    /// Taux d'erreur total
+    ErrorRate,
+    /// Taux de substitution
+    SubstitutionRate,
+    /// Taux d'insertion
+    InsertionRate,
+    /// Taux de délétion
+    DeletionRate,
+}
+
+impl MetricKind {
+    /// Toutes les métriques, dans l'ordre des rapports
+    pub const ALL: [MetricKind; 4] = [
+        Self::ErrorRate,
+        Self::SubstitutionRate,
+        Self::InsertionRate,
+        Self::DeletionRate,
+    ];
+
+    /// Nom court (colonnes CSV)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ErrorRate => "error_rate",
+            Self::SubstitutionRate => "substitution_rate",
+            Self::InsertionRate => "insertion_rate",
+            Self::DeletionRate => "deletion_rate",
+        }
+    }
+
+    /// Valeur de la métrique
+    pub fn value(&self, metrics: &SimulationMetrics) -> f64 {
+        match self {
+            Self::ErrorRate => metrics.error_rate(),
+            Self::SubstitutionRate => metrics.substitution_rate(),
+            Self::InsertionRate => metrics.insertion_rate(),
+            Self::DeletionRate => metrics.deletion_rate(),
+        }
+    }
+}
+
+/// Intervalle de confiance d'une moyenne
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    /// Moyenne observée
+    pub mean: f64,
+
+    /// Borne inférieure
+    pub lower: f64,
+
+    /// Borne supérieure
+    pub upper: f64,
+
+    /// Niveau de confiance (0.95 = 95 %)
+    pub confidence: f64,
+}
+
+/// Erreurs par type et par tranche de position relative le long de l'oligo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionHistogram {
+    /// Nombre de tranches
+    pub bins: usize,
+
+    /// Substitutions par tranche
+    pub substitutions: Vec<usize>,
+
+    /// Insertions par tranche
+    pub insertions: Vec<usize>,
+
+    /// Délétions par tranche
+    pub deletions: Vec<usize>,
+}
+
+impl PositionHistogram {
+    /// Histogramme vide
+    pub fn new(bins: usize) -> Self {
+        let bins = bins.max(1);
+        Self {
+            bins,
+            substitutions: vec![0; bins],
+            insertions: vec![0; bins],
+            deletions: vec![0; bins],
+        }
+    }
+
+    /// Compte une erreur dans sa tranche
+    pub fn add(&mut self, event: &ErrorEvent) {
+        let bin = (event.position * self.bins / event.length.max(1)).min(self.bins - 1);
+        match event.kind {
+            ErrorType::Substitution => self.substitutions[bin] += 1,
+            ErrorType::Insertion => self.insertions[bin] += 1,
+            ErrorType::Deletion => self.deletions[bin] += 1,
+        }
+    }
+
+    /// Erreurs de tous types dans une tranche
+    pub fn total(&self, bin: usize) -> usize {
+        self.substitutions[bin] + self.insertions[bin] + self.deletions[bin]
+    }
+
+    /// Une ligne CSV par tranche (bornes en fraction de la longueur)
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("bin_start,bin_end,substitutions,insertions,deletions,total\n");
+        for bin in 0..self.bins {
+            csv.push_str(&format!(
+                "{:.3},{:.3},{},{},{},{}\n",
+                bin as f64 / self.bins as f64,
+                (bin + 1) as f64 / self.bins as f64,
+                self.substitutions[bin],
+                self.insertions[bin],
+                self.deletions[bin],
+                self.total(bin)
+            ));
+        }
+        csv
+    }
+}
+
+/// Résumé statistique d'une métrique
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSummary {
+    /// Métrique résumée
+    pub metric: MetricKind,
+    /// Moyenne
+    pub mean: f64,
+    /// Écart type
+    pub std_dev: f64,
+    /// Minimum
+    pub min: f64,
+    /// 5e percentile
+    pub p05: f64,
+    /// Médiane
+    pub median: f64,
+    /// 95e percentile
+    pub p95: f64,
+    /// Maximum
+    pub max: f64,
+    /// Intervalle de confiance à 95 % de la moyenne (bootstrap)
+    pub ci95: ConfidenceInterval,
+}
+
+/// Rapport statistique d'un ensemble de simulations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatisticsReport {
+    /// Nombre de simulations
+    pub runs: usize,
+
+    /// Résumé de chaque métrique
+    pub summaries: Vec<MetricSummary>,
+
+    /// Positions des erreurs le long de l'oligo
+    pub histogram: PositionHistogram,
+}
+
+impl StatisticsReport {
+    /// Résumés en CSV, une ligne par métrique
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("metric,mean,std_dev,min,p05,median,p95,max,ci95_lower,ci95_upper\n");
+        for s in &self.summaries {
+            csv.push_str(&format!(
+                "{},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6}\n",
+                s.metric.name(),
+                s.mean,
+                s.std_dev,
+                s.min,
+                s.p05,
+                s.median,
+                s.p95,
+                s.max,
+                s.ci95.lower,
+                s.ci95.upper
+            ));
+        }
+        csv
+    }
+
+    /// Sérialise le rapport en JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| DnaError::Serialization(e.to_string()))
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Percentile d'un échantillon déjà trié
+fn percentile_sorted(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0],
+        n => {
+            let rank = (p.clamp(0.0, 100.0) / 100.0) * (n - 1) as f64;
+            let low = rank.floor() as usize;
+            let high = rank.ceil() as usize;
+            sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -228,4 +601,59 @@ mod tests {
         let avg = collector.average();
         assert_eq!(avg.substitutions, 15);
     }
+
+    fn collector_with_rates(rates: &[usize]) -> MetricsCollector {
+        let mut collector = MetricsCollector::new();
+        for &substitutions in rates {
+            collector.add(SimulationMetrics {
+                total_bases: 100,
+                substitutions,
+                affected_bases: substitutions,
+                ..Default::default()
+            });
+        }
+        collector
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let collector = collector_with_rates(&[1, 2, 3, 4, 5]);
+        assert!((collector.percentile(MetricKind::SubstitutionRate, 50.0) - 0.03).abs() < 1e-12);
+        assert!((collector.percentile(MetricKind::SubstitutionRate, 25.0) - 0.02).abs() < 1e-12);
+        assert!((collector.percentile(MetricKind::ErrorRate, 100.0) - 0.05).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_bootstrap_ci_contains_mean() {
+        let collector = collector_with_rates(&[1, 3, 2, 5, 4, 2, 3, 6, 1, 3]);
+        let ci = collector.bootstrap_ci(MetricKind::SubstitutionRate, 0.95, 500, 7);
+        assert!(ci.lower <= ci.mean && ci.mean <= ci.upper);
+        assert!(ci.lower < ci.upper);
+        assert_eq!(ci, collector.bootstrap_ci(MetricKind::SubstitutionRate, 0.95, 500, 7));
+    }
+
+    #[test]
+    fn test_position_histogram_and_exports() {
+        let mut metrics = SimulationMetrics::new();
+        metrics.total_bases = 100;
+        metrics.record(ErrorType::Substitution, 5, 100);
+        metrics.record(ErrorType::Deletion, 95, 100);
+        metrics.record(ErrorType::Insertion, 99, 100);
+
+        let mut collector = MetricsCollector::new();
+        collector.add(metrics);
+
+        let histogram = collector.position_histogram(4);
+        assert_eq!(histogram.substitutions, vec![1, 0, 0, 0]);
+        assert_eq!(histogram.total(3), 2);
+
+        let report = collector.report(4);
+        assert_eq!(report.runs, 1);
+        assert_eq!(report.summaries.len(), MetricKind::ALL.len());
+        assert!(report.to_csv().starts_with("metric,mean"));
+        assert_eq!(collector.to_csv().lines().count(), 2);
+
+        let restored = MetricsCollector::from_json(&collector.to_json().unwrap()).unwrap();
+        assert_eq!(restored.metrics()[0].events.len(), 3);
+    }
 }
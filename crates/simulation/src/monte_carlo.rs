@@ -0,0 +1,526 @@
+//! Simulation Monte Carlo de bout en bout
+//!
+//! Encode un fichier, fait traverser le pool entier au canal (synthèse,
+//! stockage, PCR avec pertes, séquençage à couverture donnée), manifeste
+//! compris, puis traite les lectures comme le ferait un vrai run: sans
+//! métadonnées ni oligo d'origine, regroupées par MinHash et réduites à un
+//! brin consensus par cluster (`adn_core::reads::consensus_pool`) avant le
+//! vrai décodeur. Répété sur N essais, cela donne la probabilité de décodage,
+//! seul chiffre utile pour dimensionner la redondance.
+
+use crate::channel::ChannelConfig;
+use crate::metrics::{MetricsCollector, SimulationMetrics};
+use crate::stages::{
+    ChannelPipeline, DecayStage, PcrStage, SequencingProfile, SequencingStage, SynthesisStage,
+};
+use adn_core::codec::PoolManifest;
+use adn_core::reads::{self, ClusterConfig, ConsensusConfig};
+use adn_core::{Decoder, DecoderConfig, DnaError, DnaSequence, Encoder, EncoderConfig, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Configuration d'une simulation Monte Carlo
+#[derive(Debug, Clone)]
+pub struct MonteCarloConfig {
+    /// Configuration d'encodage (schéma, redondance, contraintes)
+    pub encoder: EncoderConfig,
+
+    /// Configuration du décodeur
+    pub decoder: DecoderConfig,
+
+    /// Canal: erreurs de séquençage et conditions de stockage
+    pub channel: ChannelConfig,
+
+    /// Étape de synthèse
+    pub synthesis: SynthesisStage,
+
+    /// Étape PCR (biais et pertes d'oligos)
+    pub pcr: PcrStage,
+
+    /// Nombre moyen de lectures par oligo
+    pub coverage: f64,
+
+    /// Nombre d'essais
+    pub trials: usize,
+
+    /// Regrouper les lectures et reconstruire un consensus par cluster avant décodage
+    pub consensus: bool,
+
+    /// Regroupement des lectures (MinHash puis distance d'édition)
+    pub clustering: ClusterConfig,
+
+    /// Construction des brins consensus
+    pub consensus_config: ConsensusConfig,
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        Self {
+            encoder: EncoderConfig::default(),
+            decoder: DecoderConfig::default(),
+            channel: ChannelConfig::default(),
+            synthesis: SynthesisStage::default(),
+            pcr: PcrStage::default(),
+            coverage: 10.0,
+            trials: 100,
+            consensus: true,
+            clustering: ClusterConfig::default(),
+            consensus_config: ConsensusConfig::default(),
+        }
+    }
+}
+
+impl MonteCarloConfig {
+    /// Définit la configuration d'encodage
+    pub fn with_encoder(mut self, encoder: EncoderConfig) -> Self {
+        self.encoder = encoder;
+        self
+    }
+
+    /// Définit le canal
+    pub fn with_channel(mut self, channel: ChannelConfig) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Définit la couverture de séquençage
+    pub fn with_coverage(mut self, coverage: f64) -> Self {
+        self.coverage = coverage;
+        self
+    }
+
+    /// Définit le nombre d'essais
+    pub fn with_trials(mut self, trials: usize) -> Self {
+        self.trials = trials;
+        self
+    }
+
+    /// Pipeline du canal pour un essai
+    fn pipeline(&self, trial: usize) -> ChannelPipeline {
+        let seed = self
+            .channel
+            .error_model
+            .seed
+            .wrapping_add((trial as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+
+        let mut sequencing = SequencingStage::new(
+            SequencingProfile::from_error_model(&self.channel.error_model),
+            self.coverage,
+        );
+        if let Some(profile) = &self.channel.profile {
+            sequencing = sequencing.with_empirical(profile.clone());
+        }
+
+        ChannelPipeline::new(seed)
+            .with_stage(self.synthesis.clone())
+            .with_stage(DecayStage::from_config(&self.channel))
+            .with_stage(self.pcr.clone())
+            .with_stage(sequencing)
+    }
+}
+
+/// Paramètre balayé par [`MonteCarlo::sweep`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SweepParameter {
+    /// Taux de substitution au séquençage
+    SubstitutionRate,
+    /// Taux d'insertion au séquençage
+    InsertionRate,
+    /// Taux de délétion au séquençage
+    DeletionRate,
+    /// Couverture moyenne
+    Coverage,
+    /// Facteur de redondance de l'encodeur
+    Redundancy,
+    /// Probabilité de perte d'un oligo en PCR
+    DropoutRate,
+    /// Température de stockage (°C)
+    Temperature,
+    /// Durée de stockage (jours)
+    StorageDays,
+}
+
+impl SweepParameter {
+    /// Applique une valeur du balayage à la configuration
+    pub fn apply(&self, config: &mut MonteCarloConfig, value: f64) {
+        match self {
+            Self::SubstitutionRate => config.channel.error_model.substitution_rate = value,
+            Self::InsertionRate => config.channel.error_model.insertion_rate = value,
+            Self::DeletionRate => config.channel.error_model.deletion_rate = value,
+            Self::Coverage => config.coverage = value,
+            Self::Redundancy => config.encoder.redundancy = value,
+            Self::DropoutRate => config.pcr.dropout_rate = value,
+            Self::Temperature => config.channel.temperature = value,
+            Self::StorageDays => config.channel.storage_duration_days = value.max(0.0).round() as u32,
+        }
+    }
+
+    /// Nom court, accepté par `FromStr`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SubstitutionRate => "substitution",
+            Self::InsertionRate => "insertion",
+            Self::DeletionRate => "deletion",
+            Self::Coverage => "coverage",
+            Self::Redundancy => "redundancy",
+            Self::DropoutRate => "dropout",
+            Self::Temperature => "temperature",
+            Self::StorageDays => "days",
+        }
+    }
+
+    /// Tous les paramètres balayables
+    pub const ALL: [SweepParameter; 8] = [
+        Self::SubstitutionRate,
+        Self::InsertionRate,
+        Self::DeletionRate,
+        Self::Coverage,
+        Self::Redundancy,
+        Self::DropoutRate,
+        Self::Temperature,
+        Self::StorageDays,
+    ];
+}
+
+impl fmt::Display for SweepParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SweepParameter {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|p| p.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|p| p.name()).collect();
+                format!("paramètre inconnu '{}', valeurs possibles: {}", s, names.join(", "))
+            })
+    }
+}
+
+/// Résultat d'un essai
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrialOutcome {
+    /// Les données décodées sont identiques à l'original
+    pub success: bool,
+
+    /// Bits erronés des données décodées (None si le décodeur a échoué)
+    pub bit_errors: Option<usize>,
+
+    /// Type d'erreur renvoyée par le décodeur (None s'il a rendu des données)
+    pub failure: Option<String>,
+
+    /// Nombre de lectures produites par le canal
+    pub reads: usize,
+
+    /// Oligos du pool (manifeste compris) sans aucune lecture
+    pub dropouts: usize,
+
+    /// Erreurs du canal cumulées sur toutes les lectures
+    pub metrics: SimulationMetrics,
+}
+
+/// Synthèse de N essais
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloSummary {
+    /// Nombre d'essais
+    pub trials: usize,
+
+    /// Essais décodés sans erreur
+    pub successes: usize,
+
+    /// Essais où le décodeur a renvoyé une erreur
+    pub decoder_failures: usize,
+
+    /// Échecs du décodeur par type d'erreur
+    pub failures: BTreeMap<String, usize>,
+
+    /// Taux d'erreur binaire résiduel moyen des essais décodés
+    pub residual_bit_error_rate: f64,
+
+    /// Densité nominale (bits de données par base synthétisée)
+    pub bits_per_base: f64,
+
+    /// Oligos de données dans le pool encodé
+    pub oligos: usize,
+
+    /// Nombre moyen d'oligos perdus par essai
+    pub mean_dropouts: f64,
+
+    /// Erreurs moyennes du canal par essai
+    pub channel_metrics: SimulationMetrics,
+
+    /// Détail des essais
+    pub outcomes: Vec<TrialOutcome>,
+}
+
+impl MonteCarloSummary {
+    /// Probabilité de décodage réussi
+    pub fn success_probability(&self) -> f64 {
+        if self.trials == 0 {
+            return 0.0;
+        }
+        self.successes as f64 / self.trials as f64
+    }
+
+    /// Densité effective: bits/base pondérés par la probabilité de succès
+    pub fn effective_bits_per_base(&self) -> f64 {
+        self.bits_per_base * self.success_probability()
+    }
+
+    /// Formate la synthèse en tableau
+    pub fn format_table(&self) -> String {
+        let failures: String = self
+            .failures
+            .iter()
+            .map(|(kind, count)| format!("│   {:<15}: {:>6}          │\n", kind, count))
+            .collect();
+
+        format!(
+            "┌────────────────────────────────────┐\n\
+             │ Simulation Monte Carlo             │\n\
+             ├────────────────────────────────────┤\n\
+             │ Essais           : {:>6}          │\n\
+             │ Succès           : {:>6} ({:>5.1}%)  │\n\
+             │ Échecs décodeur  : {:>6}          │\n\
+             {}\
+             │ BER résiduel     : {:>10.2e}      │\n\
+             │ Oligos perdus    : {:>8.2}        │\n\
+             │ Bits/base        : {:>8.3}        │\n\
+             │ Bits/base eff.   : {:>8.3}        │\n\
+             └────────────────────────────────────┘",
+            self.trials,
+            self.successes,
+            100.0 * self.success_probability(),
+            self.decoder_failures,
+            failures,
+            self.residual_bit_error_rate,
+            self.mean_dropouts,
+            self.bits_per_base,
+            self.effective_bits_per_base()
+        )
+    }
+}
+
+/// Point d'un balayage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepPoint {
+    /// Valeur du paramètre
+    pub value: f64,
+
+    /// Synthèse des essais à cette valeur
+    pub summary: MonteCarloSummary,
+}
+
+/// Pilote de simulation encode → canal → décode
+pub struct MonteCarlo {
+    config: MonteCarloConfig,
+}
+
+impl MonteCarlo {
+    /// Crée un pilote
+    pub fn new(config: MonteCarloConfig) -> Self {
+        Self { config }
+    }
+
+    /// Configuration courante
+    pub fn config(&self) -> &MonteCarloConfig {
+        &self.config
+    }
+
+    /// Encode `data` puis exécute tous les essais
+    pub fn run(&self, data: &[u8]) -> Result<MonteCarloSummary> {
+        let pool = Encoder::new(self.config.encoder.clone())?.encode(data)?;
+        self.run_pool(&pool, data)
+    }
+
+    /// Exécute tous les essais sur un pool déjà encodé
+    pub fn run_pool(&self, pool: &[DnaSequence], data: &[u8]) -> Result<MonteCarloSummary> {
+        // Chaque essai a son propre seed: l'exécution parallèle reste reproductible
+        let outcomes = (0..self.config.trials)
+            .into_par_iter()
+            .map(|trial| self.run_trial(pool, data, trial))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut failures = BTreeMap::new();
+        for failure in outcomes.iter().filter_map(|o| o.failure.as_ref()) {
+            *failures.entry(failure.clone()).or_insert(0) += 1;
+        }
+
+        let bases: usize = pool.iter().map(|s| s.bases.len()).sum();
+        let decoded: Vec<usize> = outcomes.iter().filter_map(|o| o.bit_errors).collect();
+        let mut collector = MetricsCollector::new();
+        for outcome in &outcomes {
+            collector.add(outcome.metrics.clone());
+        }
+
+        Ok(MonteCarloSummary {
+            trials: outcomes.len(),
+            successes: outcomes.iter().filter(|o| o.success).count(),
+            decoder_failures: outcomes.len() - decoded.len(),
+            failures,
+            residual_bit_error_rate: if decoded.is_empty() || data.is_empty() {
+                0.0
+            } else {
+                decoded.iter().sum::<usize>() as f64 / (decoded.len() * data.len() * 8) as f64
+            },
+            bits_per_base: if bases == 0 {
+                0.0
+            } else {
+                (data.len() * 8) as f64 / bases as f64
+            },
+            oligos: pool.iter().filter(|s| !PoolManifest::is_header_oligo(s)).count(),
+            mean_dropouts: if outcomes.is_empty() {
+                0.0
+            } else {
+                outcomes.iter().map(|o| o.dropouts).sum::<usize>() as f64 / outcomes.len() as f64
+            },
+            channel_metrics: collector.average(),
+            outcomes,
+        })
+    }
+
+    /// Balaye un paramètre: une simulation complète par valeur
+    pub fn sweep(&self, data: &[u8], parameter: SweepParameter, values: &[f64]) -> Result<Vec<SweepPoint>> {
+        values
+            .iter()
+            .map(|&value| {
+                let mut config = self.config.clone();
+                parameter.apply(&mut config, value);
+                let summary = MonteCarlo::new(config).run(data)?;
+                Ok(SweepPoint { value, summary })
+            })
+            .collect()
+    }
+
+    /// Un essai: tout le pool traverse le canal, lectures anonymes comprises
+    ///
+    /// Comme à la sortie d'un séquenceur, les lectures perdent les métadonnées
+    /// et l'oligo d'origine: seul le contenu des bases guide le regroupement
+    /// et le décodage.
+    fn run_trial(&self, pool: &[DnaSequence], data: &[u8], trial: usize) -> Result<TrialOutcome> {
+        let output = self.config.pipeline(trial).run(pool)?;
+
+        let anonymous: Vec<DnaSequence> = output
+            .reads
+            .iter()
+            .map(|read| DnaSequence::new(read.bases.clone(), String::new(), 0, 0, 0))
+            .collect();
+
+        let reads = if self.config.consensus {
+            let strands = reads::consensus_pool(
+                &anonymous,
+                self.config.clustering.clone(),
+                self.config.consensus_config.clone(),
+            );
+            reads::to_sequences(&strands)
+        } else {
+            anonymous
+        };
+
+        let (bit_errors, failure) = match Decoder::new(self.config.decoder.clone()).decode(&reads) {
+            Ok(decoded) => (Some(bit_errors(data, &decoded)), None),
+            Err(e) => (None, Some(failure_kind(&e).to_string())),
+        };
+
+        Ok(TrialOutcome {
+            success: bit_errors == Some(0),
+            bit_errors,
+            failure,
+            reads: output.reads.len(),
+            dropouts: output.dropouts(),
+            metrics: output.metrics,
+        })
+    }
+}
+
+/// Type d'une erreur du décodeur, clé du décompte des échecs
+fn failure_kind(error: &DnaError) -> &'static str {
+    match error {
+        DnaError::InsufficientData { .. } => "insufficient",
+        DnaError::ChecksumMismatch { .. } => "checksum",
+        DnaError::Decoding(_) => "decoding",
+        DnaError::Correction(_) => "correction",
+        DnaError::DataCorrupted => "corrupted",
+        DnaError::Encoding(_) => "encoding",
+        DnaError::Serialization(_) => "serialization",
+        DnaError::Io(_) => "io",
+        DnaError::ConstraintViolation(_)
+        | DnaError::InvalidBase(_)
+        | DnaError::SequenceTooLong { .. }
+        | DnaError::HomopolymerRun { .. }
+        | DnaError::GcContentOutOfRange { .. }
+        | DnaError::LocalGcOutOfRange { .. }
+        | DnaError::MeltingTemperatureOutOfRange { .. }
+        | DnaError::SecondaryStructure { .. }
+        | DnaError::ForbiddenMotif { .. } => "constraint",
+    }
+}
+
+/// Bits différents entre l'original et les données décodées
+///
+/// Les octets manquants ou en trop comptent chacun pour 8 bits erronés.
+fn bit_errors(original: &[u8], decoded: &[u8]) -> usize {
+    let common: usize = original
+        .iter()
+        .zip(decoded)
+        .map(|(a, b)| (a ^ b).count_ones() as usize)
+        .sum();
+    common + 8 * original.len().abs_diff(decoded.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_model::ErrorModel;
+
+    #[test]
+    fn test_bit_errors() {
+        assert_eq!(bit_errors(b"abc", b"abc"), 0);
+        assert_eq!(bit_errors(&[0b1010], &[0b0000]), 2);
+        assert_eq!(bit_errors(b"abc", b"ab"), 8);
+    }
+
+    #[test]
+    fn test_sweep_parameter_parsing() {
+        assert_eq!("coverage".parse::<SweepParameter>().unwrap(), SweepParameter::Coverage);
+        assert!("inconnu".parse::<SweepParameter>().is_err());
+
+        let mut config = MonteCarloConfig::default();
+        SweepParameter::Redundancy.apply(&mut config, 2.5);
+        assert_eq!(config.encoder.redundancy, 2.5);
+    }
+
+    #[test]
+    fn test_monte_carlo_success_and_failure() {
+        let data = b"Simulation Monte Carlo du pipeline complet.".repeat(6);
+        let config = MonteCarloConfig::default()
+            .with_channel(ChannelConfig {
+                error_model: ErrorModel::new(0.002, 0.0, 0.0).with_seed(1),
+                ..Default::default()
+            })
+            .with_trials(3);
+
+        let points = MonteCarlo::new(config)
+            .sweep(&data, SweepParameter::SubstitutionRate, &[0.002, 0.1])
+            .unwrap();
+
+        assert_eq!(points[0].summary.success_probability(), 1.0);
+        assert!(points[0].summary.effective_bits_per_base() > 0.0);
+        assert_eq!(points[1].summary.success_probability(), 0.0);
+
+        // Chaque échec du décodeur est classé par type d'erreur
+        let failed = &points[1].summary;
+        assert_eq!(failed.decoder_failures, 3);
+        assert_eq!(failed.failures.values().sum::<usize>(), 3);
+        assert!(failed.format_table().contains(failed.failures.keys().next().unwrap()));
+    }
+}
@@ -0,0 +1,500 @@
+//! Profils d'erreur appris sur des lectures réelles
+//!
+//! Les lectures d'un run de séquençage sont rattachées à leur oligo de
+//! référence (vote sur les k-mers puis distance d'édition bornée), alignées
+//! globalement, et chaque opération d'alignement est comptée: substitutions
+//! par position le long de l'oligo et par contexte (base précédente),
+//! longueurs des insertions et des délétions. Le profil obtenu se sauvegarde
+//! en JSON et se recharge dans un [`ChannelConfig`](crate::ChannelConfig).
+
+use crate::error_model::{ErrorModel, ErrorType};
+use crate::metrics::SimulationMetrics;
+use adn_core::bio::primers::reverse_complement;
+use adn_core::codec::stream::FastaReader;
+use adn_core::reads::align::{align, bounded_edit_distance, AlignOp};
+use adn_core::reads::open_fastq;
+use adn_core::{DnaError, DnaSequence, IupacBase, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+const BASES: [IupacBase; 4] = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+
+/// Contexte des bases sans base précédente standard (début d'oligo, IUPAC)
+const NO_CONTEXT: usize = 4;
+
+/// Taille des k-mers utilisés pour rattacher une lecture à sa référence
+const ASSIGN_KMER: usize = 8;
+
+/// Nombre de références candidates vérifiées par distance d'édition
+const ASSIGN_CANDIDATES: usize = 3;
+
+/// Profil d'erreur empirique
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorProfile {
+    /// Nombre de tranches de position le long de l'oligo
+    pub position_bins: usize,
+
+    /// Taux de substitution par tranche de position
+    pub substitution_by_position: Vec<f64>,
+
+    /// Taux d'insertion (événements par base) par tranche de position
+    pub insertion_by_position: Vec<f64>,
+
+    /// Taux de délétion (événements par base) par tranche de position
+    pub deletion_by_position: Vec<f64>,
+
+    /// P(base lue | base de référence, base précédente), indexé
+    /// `[contexte][référence][lue]` en ordre A, C, G, T; le contexte 4
+    /// regroupe le début d'oligo et les bases ambiguës
+    pub substitution_matrix: Vec<[[f64; 4]; 4]>,
+
+    /// Distribution des longueurs d'insertion (index 0 = 1 base)
+    pub insertion_lengths: Vec<f64>,
+
+    /// Distribution des longueurs de délétion (index 0 = 1 base)
+    pub deletion_lengths: Vec<f64>,
+
+    /// Taux de substitution global
+    pub substitution_rate: f64,
+
+    /// Bases insérées par base de référence
+    pub insertion_rate: f64,
+
+    /// Bases supprimées par base de référence
+    pub deletion_rate: f64,
+
+    /// Lectures alignées ayant servi à l'apprentissage
+    pub aligned_reads: usize,
+
+    /// Lectures écartées faute de référence proche
+    pub unassigned_reads: usize,
+}
+
+impl ErrorProfile {
+    /// Apprend un profil à partir des références et des lectures
+    ///
+    /// Une lecture est rattachée à la référence la plus proche, sur l'un ou
+    /// l'autre brin, si leur distance d'édition ne dépasse pas
+    /// `max_error_ratio` fois la longueur de la référence.
+    pub fn fit(references: &[DnaSequence], reads: &[DnaSequence], max_error_ratio: f64) -> Result<Self> {
+        let mut learner = ProfileLearner::new(10);
+        let index = ReferenceIndex::new(references);
+
+        for read in reads {
+            match index.assign(&read.bases, max_error_ratio) {
+                Some((reference, oriented)) => learner.add_alignment(&references[reference].bases, &oriented),
+                None => learner.unassigned += 1,
+            }
+        }
+
+        learner.finish()
+    }
+
+    /// Apprend un profil depuis un FASTA de références et un FASTA/FASTQ de lectures
+    pub fn fit_files<P: AsRef<Path>, Q: AsRef<Path>>(
+        references: P,
+        reads: Q,
+        max_error_ratio: f64,
+    ) -> Result<Self> {
+        let references = read_fasta(references)?;
+        let reads = read_sequences(reads)?;
+        Self::fit(&references, &reads, max_error_ratio)
+    }
+
+    /// Modèle à trois taux équivalent (mêmes nombres de bases affectées)
+    pub fn error_model(&self) -> ErrorModel {
+        ErrorModel::new(self.substitution_rate, self.insertion_rate, self.deletion_rate)
+    }
+
+    /// Sauvegarde le profil en JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| DnaError::Serialization(e.to_string()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Charge un profil JSON
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let profile: Self = serde_json::from_str(&json).map_err(|e| DnaError::Serialization(e.to_string()))?;
+
+        let bins = profile.position_bins;
+        if bins == 0
+            || profile.substitution_by_position.len() != bins
+            || profile.insertion_by_position.len() != bins
+            || profile.deletion_by_position.len() != bins
+            || profile.substitution_matrix.len() != NO_CONTEXT + 1
+        {
+            return Err(DnaError::Serialization("Profil d'erreur incohérent".to_string()));
+        }
+
+        Ok(profile)
+    }
+
+    /// Applique le profil à une séquence, au plus un événement par base d'origine
+    pub fn mutate<R: Rng>(&self, bases: &[IupacBase], rng: &mut R, metrics: &mut SimulationMetrics) -> Vec<IupacBase> {
+        let n = bases.len();
+        let mut mutated = Vec::with_capacity(n + 4);
+        let mut i = 0;
+
+        while i < n {
+            let bin = position_bin(i, n, self.position_bins);
+            let context = context_of(bases, i);
+            let reference = base_index(bases[i]);
+
+            let deletion = self.deletion_by_position[bin];
+            let insertion = self.insertion_by_position[bin];
+            let substitution = match reference {
+                Some(r) if self.substitution_rate > 0.0 => {
+                    let row_rate = 1.0 - self.substitution_matrix[context][r][r];
+                    (row_rate * self.substitution_by_position[bin] / self.substitution_rate).min(1.0)
+                }
+                _ => self.substitution_by_position[bin],
+            };
+
+            let roll: f64 = rng.gen();
+            if roll < deletion {
+                let length = sample_length(&self.deletion_lengths, rng).min(n - i);
+                for position in i..i + length {
+                    metrics.record(ErrorType::Deletion, position, n);
+                }
+                i += length;
+                continue;
+            } else if roll < deletion + insertion {
+                let length = sample_length(&self.insertion_lengths, rng);
+                mutated.extend((0..length).map(|_| BASES[rng.gen_range(0..4)]));
+                mutated.push(bases[i]);
+                for _ in 0..length {
+                    metrics.record(ErrorType::Insertion, i, n);
+                }
+            } else if roll < deletion + insertion + substitution {
+                mutated.push(self.substitute(context, reference, bases[i], rng));
+                metrics.record(ErrorType::Substitution, i, n);
+            } else {
+                mutated.push(bases[i]);
+            }
+            i += 1;
+        }
+
+        mutated
+    }
+
+    /// Tire la base lue à la place de `base` selon la matrice de contexte
+    fn substitute<R: Rng>(&self, context: usize, reference: Option<usize>, base: IupacBase, rng: &mut R) -> IupacBase {
+        if let Some(r) = reference {
+            let row = &self.substitution_matrix[context][r];
+            let off_diagonal: f64 = (0..4).filter(|&b| b != r).map(|b| row[b]).sum();
+            if off_diagonal > 0.0 {
+                let mut target = rng.gen::<f64>() * off_diagonal;
+                for b in (0..4).filter(|&b| b != r) {
+                    if target < row[b] {
+                        return BASES[b];
+                    }
+                    target -= row[b];
+                }
+            }
+        }
+
+        let others: Vec<IupacBase> = BASES.iter().copied().filter(|&b| b != base).collect();
+        others[rng.gen_range(0..others.len())]
+    }
+}
+
+/// Accumule les alignements lecture/référence
+#[derive(Debug, Clone)]
+pub struct ProfileLearner {
+    position_bins: usize,
+    bases: Vec<usize>,
+    substitutions: Vec<usize>,
+    insertions: Vec<usize>,
+    deletions: Vec<usize>,
+    matrix: Vec<[[usize; 4]; 4]>,
+    insertion_lengths: Vec<usize>,
+    deletion_lengths: Vec<usize>,
+    aligned: usize,
+    unassigned: usize,
+}
+
+impl ProfileLearner {
+    /// Crée un accumulateur avec `position_bins` tranches de position
+    pub fn new(position_bins: usize) -> Self {
+        let position_bins = position_bins.max(1);
+        Self {
+            position_bins,
+            bases: vec![0; position_bins],
+            substitutions: vec![0; position_bins],
+            insertions: vec![0; position_bins],
+            deletions: vec![0; position_bins],
+            matrix: vec![[[0; 4]; 4]; NO_CONTEXT + 1],
+            insertion_lengths: Vec::new(),
+            deletion_lengths: Vec::new(),
+            aligned: 0,
+            unassigned: 0,
+        }
+    }
+
+    /// Compte les événements de l'alignement global d'une lecture sur sa référence
+    pub fn add_alignment(&mut self, reference: &[IupacBase], read: &[IupacBase]) {
+        let n = reference.len();
+        if n == 0 {
+            return;
+        }
+        self.aligned += 1;
+
+        let mut insertion_run = 0;
+        let mut deletion_run = 0;
+        let mut next_reference = 0;
+
+        for op in align(reference, read) {
+            if !matches!(op, AlignOp::Insertion { .. }) && insertion_run > 0 {
+                self.record_insertion(next_reference.min(n - 1), n, insertion_run);
+                insertion_run = 0;
+            }
+            if !matches!(op, AlignOp::Deletion { .. }) && deletion_run > 0 {
+                record_length(&mut self.deletion_lengths, deletion_run);
+                deletion_run = 0;
+            }
+
+            match op {
+                AlignOp::Match { reference: i, read: j } => {
+                    let bin = position_bin(i, n, self.position_bins);
+                    self.bases[bin] += 1;
+                    if reference[i] != read[j] {
+                        self.substitutions[bin] += 1;
+                    }
+                    if let (Some(r), Some(b)) = (base_index(reference[i]), base_index(read[j])) {
+                        self.matrix[context_of(reference, i)][r][b] += 1;
+                    }
+                    next_reference = i + 1;
+                }
+                AlignOp::Deletion { reference: i } => {
+                    let bin = position_bin(i, n, self.position_bins);
+                    self.bases[bin] += 1;
+                    if deletion_run == 0 {
+                        self.deletions[bin] += 1;
+                    }
+                    deletion_run += 1;
+                    next_reference = i + 1;
+                }
+                AlignOp::Insertion { .. } => insertion_run += 1,
+            }
+        }
+
+        if insertion_run > 0 {
+            self.record_insertion(n - 1, n, insertion_run);
+        }
+        if deletion_run > 0 {
+            record_length(&mut self.deletion_lengths, deletion_run);
+        }
+    }
+
+    fn record_insertion(&mut self, position: usize, n: usize, length: usize) {
+        self.insertions[position_bin(position, n, self.position_bins)] += 1;
+        record_length(&mut self.insertion_lengths, length);
+    }
+
+    /// Construit le profil; erreur si aucune lecture n'a pu être alignée
+    pub fn finish(&self) -> Result<ErrorProfile> {
+        let total: usize = self.bases.iter().sum();
+        if self.aligned == 0 || total == 0 {
+            return Err(DnaError::InsufficientData { need: 1, have: 0 });
+        }
+
+        let per_bin = |counts: &[usize]| -> Vec<f64> {
+            counts
+                .iter()
+                .zip(&self.bases)
+                .map(|(&c, &b)| if b == 0 { 0.0 } else { c as f64 / b as f64 })
+                .collect()
+        };
+        let weighted = |lengths: &[usize]| -> usize { lengths.iter().enumerate().map(|(i, &c)| (i + 1) * c).sum() };
+
+        let substitution_rate = self.substitutions.iter().sum::<usize>() as f64 / total as f64;
+        let matrix = self
+            .matrix
+            .iter()
+            .map(|context| {
+                let mut rows = [[0.0; 4]; 4];
+                for (r, (counts, probabilities)) in context.iter().zip(rows.iter_mut()).enumerate() {
+                    let sum: usize = counts.iter().sum();
+                    for (b, (&count, p)) in counts.iter().zip(probabilities.iter_mut()).enumerate() {
+                        *p = if sum > 0 {
+                            count as f64 / sum as f64
+                        } else if b == r {
+                            1.0 - substitution_rate
+                        } else {
+                            substitution_rate / 3.0
+                        };
+                    }
+                }
+                rows
+            })
+            .collect();
+
+        Ok(ErrorProfile {
+            position_bins: self.position_bins,
+            substitution_by_position: per_bin(&self.substitutions),
+            insertion_by_position: per_bin(&self.insertions),
+            deletion_by_position: per_bin(&self.deletions),
+            substitution_matrix: matrix,
+            insertion_lengths: normalize(&self.insertion_lengths),
+            deletion_lengths: normalize(&self.deletion_lengths),
+            substitution_rate,
+            insertion_rate: weighted(&self.insertion_lengths) as f64 / total as f64,
+            deletion_rate: weighted(&self.deletion_lengths) as f64 / total as f64,
+            aligned_reads: self.aligned,
+            unassigned_reads: self.unassigned,
+        })
+    }
+}
+
+/// Index k-mer des références pour rattacher les lectures
+struct ReferenceIndex<'a> {
+    references: &'a [DnaSequence],
+    kmers: HashMap<&'a [IupacBase], Vec<usize>>,
+}
+
+impl<'a> ReferenceIndex<'a> {
+    fn new(references: &'a [DnaSequence]) -> Self {
+        let mut kmers: HashMap<&[IupacBase], Vec<usize>> = HashMap::new();
+        for (id, reference) in references.iter().enumerate() {
+            for kmer in reference.bases.windows(ASSIGN_KMER) {
+                let ids = kmers.entry(kmer).or_default();
+                if ids.last() != Some(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        Self { references, kmers }
+    }
+
+    /// Référence la plus proche et lecture orientée sur son brin
+    fn assign(&self, read: &[IupacBase], max_error_ratio: f64) -> Option<(usize, Vec<IupacBase>)> {
+        let mut best: Option<(usize, usize, Vec<IupacBase>)> = None;
+
+        for oriented in [read.to_vec(), reverse_complement(read)] {
+            let mut votes: HashMap<usize, usize> = HashMap::new();
+            for kmer in oriented.windows(ASSIGN_KMER) {
+                for &id in self.kmers.get(kmer).into_iter().flatten() {
+                    *votes.entry(id).or_default() += 1;
+                }
+            }
+            let mut candidates: Vec<(usize, usize)> = votes.into_iter().collect();
+            candidates.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+            for &(id, _) in candidates.iter().take(ASSIGN_CANDIDATES) {
+                let reference = &self.references[id].bases;
+                let max = (reference.len() as f64 * max_error_ratio).ceil() as usize;
+                let max = best.as_ref().map_or(max, |(_, d, _)| max.min(d.saturating_sub(1)));
+                if let Some(distance) = bounded_edit_distance(reference, &oriented, max) {
+                    best = Some((id, distance, oriented.clone()));
+                }
+            }
+        }
+
+        best.map(|(id, _, oriented)| (id, oriented))
+    }
+}
+
+fn base_index(base: IupacBase) -> Option<usize> {
+    BASES.iter().position(|&b| b == base)
+}
+
+fn context_of(bases: &[IupacBase], i: usize) -> usize {
+    if i == 0 {
+        return NO_CONTEXT;
+    }
+    base_index(bases[i - 1]).unwrap_or(NO_CONTEXT)
+}
+
+fn position_bin(i: usize, n: usize, bins: usize) -> usize {
+    (i * bins / n.max(1)).min(bins - 1)
+}
+
+fn record_length(lengths: &mut Vec<usize>, length: usize) {
+    if lengths.len() < length {
+        lengths.resize(length, 0);
+    }
+    lengths[length - 1] += 1;
+}
+
+fn normalize(counts: &[usize]) -> Vec<f64> {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return vec![1.0];
+    }
+    counts.iter().map(|&c| c as f64 / total as f64).collect()
+}
+
+fn sample_length<R: Rng>(distribution: &[f64], rng: &mut R) -> usize {
+    let mut target: f64 = rng.gen();
+    for (i, &p) in distribution.iter().enumerate() {
+        if target < p {
+            return i + 1;
+        }
+        target -= p;
+    }
+    distribution.len().max(1)
+}
+
+/// Lit les références d'un fichier FASTA
+fn read_fasta<P: AsRef<Path>>(path: P) -> Result<Vec<DnaSequence>> {
+    FastaReader::new(BufReader::new(File::open(path)?)).collect()
+}
+
+/// Lit des lectures FASTQ (brut ou gzip) ou FASTA selon le premier caractère
+fn read_sequences<P: AsRef<Path>>(path: P) -> Result<Vec<DnaSequence>> {
+    let mut reader = BufReader::new(File::open(&path)?);
+    let is_fasta = reader.fill_buf()?.first() == Some(&b'>');
+
+    if is_fasta {
+        return FastaReader::new(reader).collect();
+    }
+    open_fastq(path)?.map(|record| record.and_then(|r| r.to_sequence())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bases(s: &str) -> Vec<IupacBase> {
+        s.chars().map(|c| IupacBase::from_char(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_alignment_counts() {
+        let mut learner = ProfileLearner::new(2);
+        // Substitution C→T et délétion de 2 bases (GG)
+        learner.add_alignment(&bases("ACGTTGGACT"), &bases("ATGTTACT"));
+        let profile = learner.finish().unwrap();
+
+        assert_eq!(profile.aligned_reads, 1);
+        assert!((profile.substitution_rate - 0.1).abs() < 1e-9);
+        assert!((profile.deletion_rate - 0.2).abs() < 1e-9);
+        assert_eq!(profile.deletion_lengths, vec![0.0, 1.0]);
+        // Après un A, le C de référence est lu T une fois (position 1) et C une fois (position 8)
+        assert_eq!(profile.substitution_matrix[0][1][3], 0.5);
+        assert_eq!(profile.substitution_matrix[0][1][1], 0.5);
+    }
+
+    #[test]
+    fn test_length_distributions() {
+        let mut learner = ProfileLearner::new(1);
+        learner.add_alignment(&bases("ACGTACGTACGT"), &bases("ACGTACGT"));
+        learner.add_alignment(&bases("ACGTACGTACGT"), &bases("ACGTAACGTACGT"));
+        let profile = learner.finish().unwrap();
+
+        assert_eq!(profile.deletion_lengths, vec![0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(profile.insertion_lengths, vec![1.0]);
+        assert!((profile.deletion_rate - 4.0 / 24.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_empty_learner_fails() {
+        assert!(ProfileLearner::new(4).finish().is_err());
+    }
+}
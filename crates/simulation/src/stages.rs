@@ -0,0 +1,808 @@
+//! Étapes composables du canal ADN
+//!
+//! Un run de stockage ADN n'est pas un simple canal binaire symétrique: les
+//! oligos sont synthétisés en de nombreuses copies imparfaites, vieillissent
+//! pendant le stockage, sont amplifiés par PCR de façon biaisée puis
+//! séquencés avec des erreurs qui dépendent de la position et du contexte.
+//!
+//! Chaque phénomène est une [`ChannelStage`] qui transforme une population de
+//! [`Molecule`]s; un [`ChannelPipeline`] les enchaîne et produit un multiset de
+//! lectures ([`ChannelOutput`]) plutôt qu'une copie corrompue unique.
+
+use crate::channel::ChannelConfig;
+use crate::error_model::{ErrorModel, ErrorType};
+use crate::profile::ErrorProfile;
+use crate::metrics::SimulationMetrics;
+use adn_core::{DnaSequence, IupacBase, Result};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Constante des gaz parfaits (J/(mol·K))
+const GAS_CONSTANT: f64 = 8.314;
+
+/// Énergie d'activation de la dépurination (J/mol, Lindahl & Nyberg 1972)
+const DEPURINATION_ACTIVATION_ENERGY: f64 = 129_700.0;
+
+/// Constante de dépurination de référence par purine (s⁻¹)
+const DEPURINATION_REFERENCE_RATE: f64 = 4.0e-9;
+
+/// Température de référence de la constante (70 °C)
+const DEPURINATION_REFERENCE_KELVIN: f64 = 343.15;
+
+/// pH de référence de la constante
+const DEPURINATION_REFERENCE_PH: f64 = 7.4;
+
+/// Vitesse de désamination des cytosines relative à la dépurination
+const DEAMINATION_RATIO: f64 = 0.02;
+
+/// Molécule en circulation dans le canal
+#[derive(Debug, Clone)]
+pub struct Molecule {
+    /// Index de l'oligo d'origine dans le pool
+    pub source: usize,
+
+    /// Bases de la molécule
+    pub bases: Vec<IupacBase>,
+
+    /// Abondance relative (nombre de copies représentées)
+    pub abundance: f64,
+
+    /// Erreurs accumulées depuis l'oligo d'origine
+    pub metrics: SimulationMetrics,
+}
+
+impl Molecule {
+    /// Molécule intacte issue d'un oligo du pool
+    pub fn from_oligo(source: usize, sequence: &DnaSequence) -> Self {
+        Self {
+            source,
+            bases: sequence.bases.clone(),
+            abundance: 1.0,
+            metrics: SimulationMetrics {
+                total_bases: sequence.bases.len(),
+                ..SimulationMetrics::default()
+            },
+        }
+    }
+}
+
+/// Contexte partagé par les étapes d'un même run
+pub struct StageContext {
+    /// Générateur aléatoire du run
+    pub rng: ChaCha8Rng,
+
+    /// Nombre d'oligos distincts dans le pool d'entrée
+    pub pool_size: usize,
+}
+
+/// Étape du canal ADN
+pub trait ChannelStage: Send + Sync {
+    /// Nom court de l'étape
+    fn name(&self) -> &'static str;
+
+    /// Transforme la population de molécules
+    fn apply(&self, molecules: Vec<Molecule>, ctx: &mut StageContext) -> Vec<Molecule>;
+}
+
+/// Erreurs de synthèse chimique (phosphoramidite)
+///
+/// Chaque oligo est synthétisé en `copies` molécules indépendantes; les
+/// délétions dominent, par échec de couplage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SynthesisStage {
+    /// Copies synthétisées par oligo
+    pub copies: usize,
+
+    /// Taux de substitution (par base)
+    pub substitution_rate: f64,
+
+    /// Taux d'insertion (par base)
+    pub insertion_rate: f64,
+
+    /// Taux de délétion (par base)
+    pub deletion_rate: f64,
+}
+
+impl Default for SynthesisStage {
+    fn default() -> Self {
+        Self {
+            copies: 10,
+            substitution_rate: 0.0005,
+            insertion_rate: 0.0002,
+            deletion_rate: 0.001,
+        }
+    }
+}
+
+impl SynthesisStage {
+    /// Définit le nombre de copies par oligo
+    pub fn with_copies(mut self, copies: usize) -> Self {
+        self.copies = copies;
+        self
+    }
+
+    /// Définit les taux d'erreur de synthèse
+    pub fn with_rates(mut self, substitution: f64, insertion: f64, deletion: f64) -> Self {
+        self.substitution_rate = substitution;
+        self.insertion_rate = insertion;
+        self.deletion_rate = deletion;
+        self
+    }
+}
+
+impl ChannelStage for SynthesisStage {
+    fn name(&self) -> &'static str {
+        "synthesis"
+    }
+
+    fn apply(&self, molecules: Vec<Molecule>, ctx: &mut StageContext) -> Vec<Molecule> {
+        let mut synthesized = Vec::with_capacity(molecules.len() * self.copies);
+
+        for molecule in &molecules {
+            for _ in 0..self.copies {
+                let mut copy = molecule.clone();
+                copy.bases = mutate(
+                    &molecule.bases,
+                    |_, _| (self.substitution_rate, self.insertion_rate, self.deletion_rate),
+                    &mut ctx.rng,
+                    &mut copy.metrics,
+                );
+                synthesized.push(copy);
+            }
+        }
+
+        synthesized
+    }
+}
+
+/// Dégradation pendant le stockage
+///
+/// La dépurination suit une loi d'Arrhenius catalysée par l'acidité: chaque
+/// site abasique finit par couper le brin, qui n'est alors plus amplifiable.
+/// La désamination des cytosines (lues comme des thymines) accompagne la
+/// dépurination à une vitesse plus faible.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecayStage {
+    /// Température de stockage (°C)
+    pub temperature: f64,
+
+    /// pH de la solution
+    pub ph: f64,
+
+    /// Durée de stockage en jours
+    pub storage_duration_days: u32,
+}
+
+impl Default for DecayStage {
+    fn default() -> Self {
+        Self::from_config(&ChannelConfig::default())
+    }
+}
+
+impl DecayStage {
+    /// Conditions de stockage reprises de la configuration du canal
+    pub fn from_config(config: &ChannelConfig) -> Self {
+        Self {
+            temperature: config.temperature,
+            ph: config.ph,
+            storage_duration_days: config.storage_duration_days,
+        }
+    }
+
+    /// Constante de dépurination par purine (s⁻¹)
+    pub fn depurination_rate(&self) -> f64 {
+        let kelvin = self.temperature + 273.15;
+        let arrhenius = (-DEPURINATION_ACTIVATION_ENERGY / GAS_CONSTANT
+            * (1.0 / kelvin - 1.0 / DEPURINATION_REFERENCE_KELVIN))
+            .exp();
+        let acidity = 10f64.powf(DEPURINATION_REFERENCE_PH - self.ph);
+
+        DEPURINATION_REFERENCE_RATE * arrhenius * acidity
+    }
+
+    /// Probabilité qu'une purine donnée soit perdue pendant le stockage
+    pub fn purine_loss_probability(&self) -> f64 {
+        let seconds = self.storage_duration_days as f64 * 86_400.0;
+        1.0 - (-self.depurination_rate() * seconds).exp()
+    }
+
+    /// Fraction attendue de molécules intactes après stockage
+    pub fn survival_fraction(&self, bases: &[IupacBase]) -> f64 {
+        let purines = bases
+            .iter()
+            .filter(|b| matches!(b, IupacBase::A | IupacBase::G))
+            .count();
+        (1.0 - self.purine_loss_probability()).powi(purines as i32)
+    }
+}
+
+impl ChannelStage for DecayStage {
+    fn name(&self) -> &'static str {
+        "decay"
+    }
+
+    fn apply(&self, molecules: Vec<Molecule>, ctx: &mut StageContext) -> Vec<Molecule> {
+        let seconds = self.storage_duration_days as f64 * 86_400.0;
+        let deamination = 1.0 - (-self.depurination_rate() * DEAMINATION_RATIO * seconds).exp();
+
+        molecules
+            .into_iter()
+            .filter_map(|mut molecule| {
+                if ctx.rng.gen::<f64>() >= self.survival_fraction(&molecule.bases) {
+                    return None;
+                }
+
+                let length = molecule.bases.len();
+                for (position, base) in molecule.bases.iter_mut().enumerate() {
+                    if *base == IupacBase::C && ctx.rng.gen::<f64>() < deamination {
+                        *base = IupacBase::T;
+                        molecule.metrics.record(ErrorType::Substitution, position, length);
+                    }
+                }
+
+                Some(molecule)
+            })
+            .collect()
+    }
+}
+
+/// Amplification PCR
+///
+/// Chaque oligo reçoit une efficacité propre, pénalisée par l'écart de son GC
+/// à 50 %; l'abondance croît en `(1 + efficacité)^cycles`, ce qui amplifie
+/// exponentiellement les écarts de couverture. Une fraction des oligos
+/// n'amplifie pas du tout. Les erreurs de polymérase sont tirées une fois par
+/// molécule, qui représente toute sa lignée.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PcrStage {
+    /// Nombre de cycles
+    pub cycles: u32,
+
+    /// Efficacité moyenne par cycle
+    pub efficiency: f64,
+
+    /// Écart type de l'efficacité entre oligos
+    pub efficiency_sd: f64,
+
+    /// Pénalité d'efficacité par unité d'écart du GC à 0.5
+    pub gc_bias: f64,
+
+    /// Probabilité qu'un oligo n'amplifie pas
+    pub dropout_rate: f64,
+
+    /// Taux de substitution de la polymérase (par base et par cycle)
+    pub polymerase_error_rate: f64,
+}
+
+impl Default for PcrStage {
+    fn default() -> Self {
+        Self {
+            cycles: 20,
+            efficiency: 0.9,
+            efficiency_sd: 0.05,
+            gc_bias: 0.5,
+            dropout_rate: 0.001,
+            polymerase_error_rate: 1e-5,
+        }
+    }
+}
+
+impl PcrStage {
+    /// Définit le nombre de cycles
+    pub fn with_cycles(mut self, cycles: u32) -> Self {
+        self.cycles = cycles;
+        self
+    }
+
+    /// Définit l'efficacité moyenne et sa dispersion
+    pub fn with_efficiency(mut self, efficiency: f64, sd: f64) -> Self {
+        self.efficiency = efficiency;
+        self.efficiency_sd = sd;
+        self
+    }
+
+    /// Définit la probabilité d'échec d'amplification d'un oligo
+    pub fn with_dropout_rate(mut self, dropout_rate: f64) -> Self {
+        self.dropout_rate = dropout_rate;
+        self
+    }
+
+    fn draw_efficiency(&self, bases: &[IupacBase], rng: &mut ChaCha8Rng) -> Option<f64> {
+        if rng.gen::<f64>() < self.dropout_rate {
+            return None;
+        }
+
+        let gc = gc_ratio(bases);
+        let efficiency = self.efficiency - self.gc_bias * (gc - 0.5).abs()
+            + self.efficiency_sd * standard_normal(rng);
+
+        (efficiency > 0.0).then_some(efficiency.min(1.0))
+    }
+}
+
+impl ChannelStage for PcrStage {
+    fn name(&self) -> &'static str {
+        "pcr"
+    }
+
+    fn apply(&self, molecules: Vec<Molecule>, ctx: &mut StageContext) -> Vec<Molecule> {
+        let mut efficiencies: HashMap<usize, Option<f64>> = HashMap::new();
+        let per_base = self.polymerase_error_rate * self.cycles as f64 / 2.0;
+
+        molecules
+            .into_iter()
+            .filter_map(|mut molecule| {
+                let efficiency = *efficiencies
+                    .entry(molecule.source)
+                    .or_insert_with(|| self.draw_efficiency(&molecule.bases, &mut ctx.rng));
+                let efficiency = efficiency?;
+
+                molecule.abundance *= (1.0 + efficiency).powi(self.cycles as i32);
+                molecule.bases = mutate(
+                    &molecule.bases,
+                    |_, _| (per_base, 0.0, 0.0),
+                    &mut ctx.rng,
+                    &mut molecule.metrics,
+                );
+
+                Some(molecule)
+            })
+            .collect()
+    }
+}
+
+/// Profil d'erreur d'une technologie de séquençage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencingProfile {
+    /// Taux de substitution en début de lecture
+    pub substitution_start: f64,
+
+    /// Taux de substitution en fin de lecture
+    pub substitution_end: f64,
+
+    /// Taux d'insertion (par base)
+    pub insertion_rate: f64,
+
+    /// Taux de délétion (par base)
+    pub deletion_rate: f64,
+
+    /// Surcroît d'indels par base supplémentaire d'un homopolymère
+    pub homopolymer_factor: f64,
+
+    /// Multiplicateur des substitutions après un motif GG
+    pub gg_factor: f64,
+}
+
+impl Default for SequencingProfile {
+    fn default() -> Self {
+        Self::illumina()
+    }
+}
+
+impl SequencingProfile {
+    /// Illumina: substitutions croissantes le long de la lecture, erreurs
+    /// spécifiques après GG, indels rares
+    pub fn illumina() -> Self {
+        Self {
+            substitution_start: 0.001,
+            substitution_end: 0.01,
+            insertion_rate: 0.00002,
+            deletion_rate: 0.00002,
+            homopolymer_factor: 0.0,
+            gg_factor: 3.0,
+        }
+    }
+
+    /// Nanopore: indels dominants, fortement amplifiés dans les homopolymères
+    pub fn nanopore() -> Self {
+        Self {
+            substitution_start: 0.03,
+            substitution_end: 0.03,
+            insertion_rate: 0.02,
+            deletion_rate: 0.03,
+            homopolymer_factor: 0.5,
+            gg_factor: 1.0,
+        }
+    }
+
+    /// Profil uniforme reprenant les trois taux d'un modèle d'erreur
+    pub fn from_error_model(model: &ErrorModel) -> Self {
+        Self {
+            substitution_start: model.substitution_rate,
+            substitution_end: model.substitution_rate,
+            insertion_rate: model.insertion_rate,
+            deletion_rate: model.deletion_rate,
+            homopolymer_factor: 0.0,
+            gg_factor: 1.0,
+        }
+    }
+
+    /// Taux (substitution, insertion, délétion) à la position `i`
+    pub fn rates_at(&self, bases: &[IupacBase], i: usize) -> (f64, f64, f64) {
+        let progress = if bases.len() > 1 {
+            i as f64 / (bases.len() - 1) as f64
+        } else {
+            0.0
+        };
+        let mut substitution =
+            self.substitution_start + (self.substitution_end - self.substitution_start) * progress;
+        if i >= 2 && bases[i - 1] == IupacBase::G && bases[i - 2] == IupacBase::G {
+            substitution *= self.gg_factor;
+        }
+
+        let run = bases[..=i].iter().rev().take_while(|&&b| b == bases[i]).count();
+        let indel = 1.0 + self.homopolymer_factor * (run - 1) as f64;
+
+        (substitution, self.insertion_rate * indel, self.deletion_rate * indel)
+    }
+}
+
+/// Séquençage: échantillonnage des lectures proportionnel à l'abondance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencingStage {
+    /// Profil d'erreur de la technologie
+    pub profile: SequencingProfile,
+
+    /// Nombre moyen de lectures par oligo du pool
+    pub coverage: f64,
+
+    /// Profil appris sur un run réel, prioritaire sur `profile`
+    #[serde(default)]
+    pub empirical: Option<ErrorProfile>,
+}
+
+impl Default for SequencingStage {
+    fn default() -> Self {
+        Self::new(SequencingProfile::illumina(), 10.0)
+    }
+}
+
+impl SequencingStage {
+    /// Crée une étape de séquençage
+    pub fn new(profile: SequencingProfile, coverage: f64) -> Self {
+        Self {
+            profile,
+            coverage,
+            empirical: None,
+        }
+    }
+
+    /// Utilise un profil d'erreur empirique
+    pub fn with_empirical(mut self, profile: ErrorProfile) -> Self {
+        self.empirical = Some(profile);
+        self
+    }
+}
+
+impl ChannelStage for SequencingStage {
+    fn name(&self) -> &'static str {
+        "sequencing"
+    }
+
+    fn apply(&self, molecules: Vec<Molecule>, ctx: &mut StageContext) -> Vec<Molecule> {
+        let mut cumulative = Vec::with_capacity(molecules.len());
+        let mut total = 0.0;
+        for molecule in &molecules {
+            total += molecule.abundance.max(0.0);
+            cumulative.push(total);
+        }
+        if total <= 0.0 {
+            return Vec::new();
+        }
+
+        let reads = (self.coverage * ctx.pool_size as f64).round() as usize;
+        let mut sampled = Vec::with_capacity(reads);
+
+        for _ in 0..reads {
+            let target = ctx.rng.gen::<f64>() * total;
+            let index = cumulative
+                .partition_point(|&c| c <= target)
+                .min(molecules.len() - 1);
+            let template = &molecules[index];
+
+            let mut read = Molecule {
+                source: template.source,
+                bases: Vec::new(),
+                abundance: 1.0,
+                metrics: template.metrics.clone(),
+            };
+            read.bases = match &self.empirical {
+                Some(empirical) => empirical.mutate(&template.bases, &mut ctx.rng, &mut read.metrics),
+                None => mutate(
+                    &template.bases,
+                    |bases, i| self.profile.rates_at(bases, i),
+                    &mut ctx.rng,
+                    &mut read.metrics,
+                ),
+            };
+            sampled.push(read);
+        }
+
+        sampled
+    }
+}
+
+/// Lectures produites par un run du canal
+#[derive(Debug, Clone)]
+pub struct ChannelOutput {
+    /// Lectures, dans l'ordre d'échantillonnage
+    pub reads: Vec<DnaSequence>,
+
+    /// Index de l'oligo d'origine de chaque lecture
+    pub sources: Vec<usize>,
+
+    /// Nombre de lectures par oligo du pool
+    pub coverage: Vec<usize>,
+
+    /// Erreurs cumulées sur l'ensemble des lectures
+    pub metrics: SimulationMetrics,
+}
+
+impl ChannelOutput {
+    /// Nombre d'oligos sans aucune lecture
+    pub fn dropouts(&self) -> usize {
+        self.coverage.iter().filter(|&&c| c == 0).count()
+    }
+
+    /// Couverture moyenne par oligo
+    pub fn mean_coverage(&self) -> f64 {
+        if self.coverage.is_empty() {
+            return 0.0;
+        }
+        self.reads.len() as f64 / self.coverage.len() as f64
+    }
+}
+
+/// Enchaînement d'étapes du canal
+pub struct ChannelPipeline {
+    stages: Vec<Box<dyn ChannelStage>>,
+    seed: u64,
+}
+
+impl ChannelPipeline {
+    /// Crée un pipeline vide
+    pub fn new(seed: u64) -> Self {
+        Self {
+            stages: Vec::new(),
+            seed,
+        }
+    }
+
+    /// Pipeline complet synthèse → stockage → PCR → séquençage Illumina,
+    /// avec les conditions de stockage de la configuration
+    pub fn from_config(config: &ChannelConfig) -> Self {
+        Self::new(config.error_model.seed)
+            .with_stage(SynthesisStage::default())
+            .with_stage(DecayStage::from_config(config))
+            .with_stage(PcrStage::default())
+            .with_stage(SequencingStage::default())
+    }
+
+    /// Ajoute une étape en fin de pipeline
+    pub fn with_stage(mut self, stage: impl ChannelStage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Définit le seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Noms des étapes, dans l'ordre d'application
+    pub fn stage_names(&self) -> Vec<&'static str> {
+        self.stages.iter().map(|s| s.name()).collect()
+    }
+
+    /// Fait traverser le pool à toutes les étapes
+    ///
+    /// Sans étape de séquençage, chaque molécule restante devient une lecture.
+    pub fn run(&self, oligos: &[DnaSequence]) -> Result<ChannelOutput> {
+        let mut ctx = StageContext {
+            rng: ChaCha8Rng::seed_from_u64(self.seed),
+            pool_size: oligos.len(),
+        };
+
+        let mut molecules: Vec<Molecule> = oligos
+            .iter()
+            .enumerate()
+            .map(|(i, oligo)| Molecule::from_oligo(i, oligo))
+            .collect();
+
+        for stage in &self.stages {
+            molecules = stage.apply(molecules, &mut ctx);
+        }
+
+        let mut output = ChannelOutput {
+            reads: Vec::with_capacity(molecules.len()),
+            sources: Vec::with_capacity(molecules.len()),
+            coverage: vec![0; oligos.len()],
+            metrics: SimulationMetrics::new(),
+        };
+
+        for molecule in molecules {
+            let mut read = oligos[molecule.source].clone();
+            read.bases = molecule.bases;
+
+            output.coverage[molecule.source] += 1;
+            output.sources.push(molecule.source);
+            output.metrics.merge(&molecule.metrics);
+            output.reads.push(read);
+        }
+
+        Ok(output)
+    }
+}
+
+/// Applique des erreurs indépendantes, au plus un événement par base d'origine
+fn mutate<F>(
+    bases: &[IupacBase],
+    rates: F,
+    rng: &mut ChaCha8Rng,
+    metrics: &mut SimulationMetrics,
+) -> Vec<IupacBase>
+where
+    F: Fn(&[IupacBase], usize) -> (f64, f64, f64),
+{
+    let length = bases.len();
+    let mut mutated = Vec::with_capacity(length);
+
+    for (i, &base) in bases.iter().enumerate() {
+        let (sub, ins, del) = rates(bases, i);
+        let roll: f64 = rng.gen();
+
+        if roll < sub {
+            mutated.push(substitute_base(base, rng));
+            metrics.record(ErrorType::Substitution, i, length);
+        } else if roll < sub + ins {
+            mutated.push(random_base(rng));
+            mutated.push(base);
+            metrics.record(ErrorType::Insertion, i, length);
+        } else if roll < sub + ins + del {
+            metrics.record(ErrorType::Deletion, i, length);
+        } else {
+            mutated.push(base);
+        }
+    }
+
+    mutated
+}
+
+fn substitute_base(base: IupacBase, rng: &mut ChaCha8Rng) -> IupacBase {
+    let mut new_base = base;
+    while new_base == base {
+        new_base = random_base(rng);
+    }
+    new_base
+}
+
+fn random_base(rng: &mut ChaCha8Rng) -> IupacBase {
+    match rng.gen_range(0..4) {
+        0 => IupacBase::A,
+        1 => IupacBase::C,
+        2 => IupacBase::G,
+        _ => IupacBase::T,
+    }
+}
+
+fn gc_ratio(bases: &[IupacBase]) -> f64 {
+    if bases.is_empty() {
+        return 0.5;
+    }
+    let gc = bases
+        .iter()
+        .filter(|b| matches!(b, IupacBase::G | IupacBase::C))
+        .count();
+    gc as f64 / bases.len() as f64
+}
+
+/// Tirage N(0, 1) par Box-Muller
+fn standard_normal(rng: &mut ChaCha8Rng) -> f64 {
+    let u1: f64 = rng.gen::<f64>().max(f64::MIN_POSITIVE);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oligo(pattern: &str, repeat: usize, index: usize) -> DnaSequence {
+        let text = pattern.repeat(repeat);
+        DnaSequence::from_str(&text, "pool.bin".to_string(), index, text.len() / 4, 0).unwrap()
+    }
+
+    fn pool(n: usize) -> Vec<DnaSequence> {
+        (0..n).map(|i| oligo("ACGTTGCA", 15, i)).collect()
+    }
+
+    #[test]
+    fn test_pipeline_produces_read_multiset() {
+        let oligos = pool(20);
+        let pipeline = ChannelPipeline::new(7)
+            .with_stage(SynthesisStage::default())
+            .with_stage(PcrStage::default().with_dropout_rate(0.0))
+            .with_stage(SequencingStage::new(SequencingProfile::illumina(), 5.0));
+
+        let output = pipeline.run(&oligos).unwrap();
+        assert_eq!(output.reads.len(), 100);
+        assert_eq!(output.coverage.iter().sum::<usize>(), 100);
+        assert!(output.coverage.iter().any(|&c| c > 1));
+        assert!(output.metrics.total_bases >= 100 * 120);
+    }
+
+    #[test]
+    fn test_pipeline_is_deterministic() {
+        let oligos = pool(10);
+        let pipeline = ChannelPipeline::from_config(&ChannelConfig::default());
+
+        let a = pipeline.run(&oligos).unwrap();
+        let b = pipeline.run(&oligos).unwrap();
+        assert_eq!(a.sources, b.sources);
+        assert!(a.reads.iter().zip(&b.reads).all(|(x, y)| x.bases == y.bases));
+        assert_eq!(
+            pipeline.stage_names(),
+            vec!["synthesis", "decay", "pcr", "sequencing"]
+        );
+    }
+
+    #[test]
+    fn test_decay_follows_arrhenius_and_ph() {
+        let base = DecayStage {
+            temperature: 25.0,
+            ph: 7.0,
+            storage_duration_days: 365,
+        };
+        let hot = DecayStage { temperature: 60.0, ..base.clone() };
+        let acid = DecayStage { ph: 5.0, ..base.clone() };
+
+        assert!(hot.depurination_rate() > 50.0 * base.depurination_rate());
+        assert!((acid.depurination_rate() / base.depurination_rate() - 100.0).abs() < 1e-6);
+
+        let bases = oligo("AGAG", 30, 0).bases;
+        assert!(base.survival_fraction(&bases) > hot.survival_fraction(&bases));
+    }
+
+    #[test]
+    fn test_decay_loses_molecules_when_hot() {
+        let oligos = pool(50);
+        let decay = DecayStage {
+            temperature: 70.0,
+            ph: 6.0,
+            storage_duration_days: 60,
+        };
+        let output = ChannelPipeline::new(3).with_stage(decay).run(&oligos).unwrap();
+        assert!(output.reads.len() < oligos.len());
+    }
+
+    #[test]
+    fn test_pcr_dropout_removes_oligos() {
+        let oligos = pool(40);
+        let output = ChannelPipeline::new(11)
+            .with_stage(PcrStage::default().with_dropout_rate(1.0))
+            .with_stage(SequencingStage::default())
+            .run(&oligos)
+            .unwrap();
+        assert!(output.reads.is_empty());
+        assert_eq!(output.dropouts(), 40);
+    }
+
+    #[test]
+    fn test_sequencing_profiles() {
+        let illumina = SequencingProfile::illumina();
+        let bases = oligo("GGGA", 10, 0).bases;
+        let (early, _, _) = illumina.rates_at(&bases, 0);
+        let (late, _, _) = illumina.rates_at(&bases, bases.len() - 1);
+        let (after_gg, _, _) = illumina.rates_at(&bases, 2);
+        assert!(late > early);
+        assert!(after_gg > early * 2.0);
+
+        let nanopore = SequencingProfile::nanopore();
+        let (_, _, start_of_run) = nanopore.rates_at(&bases, 0);
+        let (_, _, in_run) = nanopore.rates_at(&bases, 2);
+        assert!(in_run > start_of_run);
+    }
+}
@@ -0,0 +1,51 @@
+//! Run complet synthèse → stockage → PCR → séquençage sur un pool encodé
+
+use adn_core::{Encoder, EncoderConfig};
+use adn_simulation::{
+    ChannelConfig, ChannelPipeline, DecayStage, DnaChannel, PcrStage, SequencingProfile,
+    SequencingStage, SynthesisStage,
+};
+
+#[test]
+fn test_reads_cover_encoded_pool() {
+    let data = b"Archive de test pour le canal composable.".repeat(10);
+    let oligos = Encoder::new(EncoderConfig::default()).unwrap().encode(&data).unwrap();
+
+    let mut channel = DnaChannel::new(ChannelConfig::default());
+    let output = channel.sequence_pool(&oligos).unwrap();
+
+    assert_eq!(output.reads.len(), output.sources.len());
+    assert!((output.mean_coverage() - 10.0).abs() < 1.0);
+    assert!(output.dropouts() < oligos.len() / 10 + 1);
+
+    // Une partie des lectures est exacte, les autres portent des erreurs
+    let exact = output
+        .reads
+        .iter()
+        .zip(&output.sources)
+        .filter(|(read, &source)| read.bases == oligos[source].bases)
+        .count();
+    assert!(exact > 0 && exact < output.reads.len());
+}
+
+#[test]
+fn test_nanopore_reads_are_noisier_than_illumina() {
+    let data = b"Comparaison des profils de sequencage.".repeat(6);
+    let oligos = Encoder::new(EncoderConfig::default()).unwrap().encode(&data).unwrap();
+
+    let run = |profile: SequencingProfile| {
+        ChannelPipeline::new(5)
+            .with_stage(SynthesisStage::default().with_copies(4))
+            .with_stage(DecayStage::from_config(&ChannelConfig::default()))
+            .with_stage(PcrStage::default())
+            .with_stage(SequencingStage::new(profile, 8.0))
+            .run(&oligos)
+            .unwrap()
+            .metrics
+    };
+
+    let illumina = run(SequencingProfile::illumina());
+    let nanopore = run(SequencingProfile::nanopore());
+    assert!(nanopore.error_rate() > 5.0 * illumina.error_rate());
+    assert!(nanopore.insertions + nanopore.deletions > illumina.insertions + illumina.deletions);
+}
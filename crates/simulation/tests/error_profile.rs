@@ -0,0 +1,48 @@
+//! Apprentissage d'un profil d'erreur sur des lectures simulées
+
+use adn_core::{DnaSequence, IupacBase};
+use adn_simulation::{ChannelConfig, DnaChannel, ErrorModel, ErrorProfile};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+const BASES: [IupacBase; 4] = [IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+
+#[test]
+fn test_fitted_profile_recovers_channel_rates() {
+    let mut rng = ChaCha8Rng::seed_from_u64(17);
+    let references: Vec<DnaSequence> = (0..30)
+        .map(|i| {
+            let bases = (0..120).map(|_| BASES[rng.gen_range(0..4)]).collect();
+            DnaSequence::new(bases, "run.fasta".to_string(), i, 30, 0)
+        })
+        .collect();
+
+    let mut channel = DnaChannel::new(ChannelConfig {
+        error_model: ErrorModel::new(0.02, 0.005, 0.01).with_seed(3),
+        ..Default::default()
+    });
+    let reads: Vec<DnaSequence> = references
+        .iter()
+        .flat_map(|reference| (0..5).map(|_| reference.clone()).collect::<Vec<_>>())
+        .map(|reference| channel.transmit(&reference).unwrap().0)
+        .collect();
+
+    let profile = ErrorProfile::fit(&references, &reads, 0.25).unwrap();
+    assert_eq!(profile.aligned_reads, reads.len());
+    assert!((0.014..0.026).contains(&profile.substitution_rate), "{}", profile.substitution_rate);
+    assert!((0.002..0.008).contains(&profile.insertion_rate), "{}", profile.insertion_rate);
+    assert!((0.006..0.014).contains(&profile.deletion_rate), "{}", profile.deletion_rate);
+
+    // Aller-retour JSON puis chargement dans la configuration du canal
+    let path = std::env::temp_dir().join("adn_simulation_error_profile.json");
+    profile.save(&path).unwrap();
+    let config = ChannelConfig::default().with_profile_file(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(config.profile.as_ref(), Some(&profile));
+    assert!((config.error_model.substitution_rate - profile.substitution_rate).abs() < 1e-12);
+
+    let mut channel = DnaChannel::new(config);
+    let (_, metrics) = channel.transmit(&references[0]).unwrap();
+    assert_eq!(metrics.total_bases, 120);
+}
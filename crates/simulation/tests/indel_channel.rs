@@ -0,0 +1,85 @@
+//! Code interne VT + marqueurs face aux insertions/délétions du canal simulé
+
+use adn_core::codec::encoder::CompressionType;
+use adn_core::codec::{EncoderType, PoolManifest, VtMarkerDecoder};
+use adn_core::{Decoder, DecoderConfig, DnaSequence, Encoder, EncoderConfig};
+use adn_simulation::{ChannelConfig, DnaChannel, ErrorModel};
+
+/// Transmet `copies` lectures de chaque oligo de données; les oligos de
+/// manifeste sont transmis tels quels
+fn sequence_pool(pool: &[DnaSequence], channel: &mut DnaChannel, copies: usize) -> (Vec<DnaSequence>, usize) {
+    let mut reads = Vec::new();
+    let mut indels = 0;
+
+    for oligo in pool {
+        if PoolManifest::is_header_oligo(oligo) {
+            reads.push(oligo.clone());
+            continue;
+        }
+        for _ in 0..copies {
+            let (read, metrics) = channel.transmit(oligo).unwrap();
+            indels += metrics.insertions + metrics.deletions;
+            reads.push(read);
+        }
+    }
+
+    (reads, indels)
+}
+
+#[test]
+fn test_vt_marker_pool_survives_indel_channel() {
+    let original: Vec<u8> = (0..400u32).map(|i| (i * 97 % 256) as u8).collect();
+
+    let config = EncoderConfig {
+        encoder_type: EncoderType::VtMarker,
+        chunk_size: 15,
+        compression_enabled: false,
+        compression_type: CompressionType::None,
+        ..Default::default()
+    };
+    let pool = Encoder::new(config.clone()).unwrap().encode(&original).unwrap();
+
+    // Blanchis, les oligos respectent les contraintes de synthèse
+    for oligo in pool.iter().filter(|s| !PoolManifest::is_header_oligo(s)) {
+        oligo.validate(&config.constraints).unwrap();
+    }
+
+    let mut channel = DnaChannel::new(ChannelConfig {
+        error_model: ErrorModel::new(0.0, 0.003, 0.003).with_seed(17),
+        ..Default::default()
+    });
+    let (reads, indels) = sequence_pool(&pool, &mut channel, 3);
+    assert!(indels > 0, "le canal doit injecter des indels");
+
+    let decoded = Decoder::new(DecoderConfig::default()).decode(&reads).unwrap();
+    assert_eq!(decoded, original);
+}
+
+#[test]
+fn test_vt_marker_oligo_corrects_channel_indel() {
+    let config = EncoderConfig {
+        encoder_type: EncoderType::VtMarker,
+        chunk_size: 15,
+        compression_enabled: false,
+        compression_type: CompressionType::None,
+        ..Default::default()
+    };
+    let original = b"single oligo".to_vec();
+    let pool = Encoder::new(config).unwrap().encode(&original).unwrap();
+
+    // Chercher une transmission avec exactement une indel et aucune substitution
+    let mut channel = DnaChannel::new(ChannelConfig {
+        error_model: ErrorModel::new(0.0, 0.004, 0.004).with_seed(3),
+        ..Default::default()
+    });
+    let oligo = pool.iter().find(|s| !PoolManifest::is_header_oligo(s)).unwrap();
+    let read = (0..1000)
+        .map(|_| channel.transmit(oligo).unwrap())
+        .find(|(_, m)| m.insertions + m.deletions == 1)
+        .map(|(read, _)| read)
+        .unwrap();
+    assert_ne!(read.bases.len(), oligo.bases.len());
+
+    let decoded = VtMarkerDecoder::new().decode(&[read]).unwrap();
+    assert_eq!(decoded, original);
+}
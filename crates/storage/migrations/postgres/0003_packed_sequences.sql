@@ -0,0 +1,11 @@
+-- Bases compactées à 2 bits par base (PackedBases)
+--
+-- sequence_format distingue les lignes existantes (0: bases en clair, ASCII)
+-- des lignes compactées (1), converties par SequenceRepository::pack_legacy_sequences.
+
+ALTER TABLE sequences
+    ALTER COLUMN sequence_data TYPE BYTEA USING convert_to(sequence_data, 'UTF8');
+
+ALTER TABLE sequences ADD COLUMN IF NOT EXISTS sequence_format SMALLINT NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_sequences_format ON sequences(sequence_format);
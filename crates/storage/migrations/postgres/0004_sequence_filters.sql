@@ -0,0 +1,28 @@
+-- Colonnes de filtrage et index pour le listage paginé des séquences
+--
+-- Les colonnes sont extraites des métadonnées JSONB existantes. La longueur des
+-- lignes compactées (sequence_format = 1) est lue dans l'en-tête de
+-- PackedBases: u32 little-endian aux octets 1 à 4.
+
+ALTER TABLE sequences
+    ADD COLUMN IF NOT EXISTS encoding_scheme TEXT,
+    ADD COLUMN IF NOT EXISTS original_file TEXT,
+    ADD COLUMN IF NOT EXISTS gc_ratio DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS sequence_length INTEGER;
+
+UPDATE sequences SET
+    encoding_scheme = metadata->>'encoding_scheme',
+    original_file = metadata->>'original_file',
+    gc_ratio = (metadata->>'gc_ratio')::DOUBLE PRECISION,
+    sequence_length = CASE sequence_format
+        WHEN 0 THEN length(sequence_data)
+        ELSE get_byte(sequence_data, 1)
+            + (get_byte(sequence_data, 2) << 8)
+            + (get_byte(sequence_data, 3) << 16)
+            + (get_byte(sequence_data, 4) << 24)
+    END;
+
+CREATE INDEX IF NOT EXISTS idx_sequences_scheme ON sequences(encoding_scheme, id);
+CREATE INDEX IF NOT EXISTS idx_sequences_file ON sequences(original_file, id);
+CREATE INDEX IF NOT EXISTS idx_sequences_gc ON sequences(gc_ratio);
+CREATE INDEX IF NOT EXISTS idx_sequences_length ON sequences(sequence_length);
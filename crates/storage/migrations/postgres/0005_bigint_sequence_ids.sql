@@ -0,0 +1,10 @@
+-- IDs de séquences sur 64 bits, comme SQLite et la base intégrée
+--
+-- Le dépôt lit et lie tous les IDs en i64; sequences.id était un SERIAL
+-- (INTEGER) et les colonnes qui le référencent aussi.
+
+ALTER TABLE sequences ALTER COLUMN id TYPE BIGINT;
+ALTER SEQUENCE sequences_id_seq AS BIGINT;
+
+ALTER TABLE search_index ALTER COLUMN sequence_id TYPE BIGINT;
+ALTER TABLE archive_sequences ALTER COLUMN sequence_id TYPE BIGINT;
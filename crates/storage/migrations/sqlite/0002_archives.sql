@@ -0,0 +1,28 @@
+-- Archives: encodages complets d'un fichier
+
+CREATE TABLE IF NOT EXISTS archives (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    uuid TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    original_hash TEXT NOT NULL,
+    original_size INTEGER NOT NULL,
+    encoder_config TEXT NOT NULL,
+    manifest TEXT,
+    sequence_count INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_archives_name ON archives(name);
+CREATE INDEX IF NOT EXISTS idx_archives_hash ON archives(original_hash);
+
+-- Appartenance ordonnée des séquences à une archive
+CREATE TABLE IF NOT EXISTS archive_sequences (
+    archive_id INTEGER NOT NULL,
+    sequence_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (archive_id, position),
+    FOREIGN KEY (archive_id) REFERENCES archives(id) ON DELETE CASCADE,
+    FOREIGN KEY (sequence_id) REFERENCES sequences(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_archive_sequences_sequence ON archive_sequences(sequence_id);
@@ -0,0 +1,10 @@
+-- Bases compactées à 2 bits par base (PackedBases)
+--
+-- SQLite ne modifie pas le type d'une colonne existante: avec l'affinité TEXT,
+-- sequence_data conserve les BLOB tels quels. sequence_format distingue les
+-- lignes existantes (0: bases en clair) des lignes compactées (1), converties
+-- par SequenceRepository::pack_legacy_sequences.
+
+ALTER TABLE sequences ADD COLUMN sequence_format SMALLINT NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_sequences_format ON sequences(sequence_format);
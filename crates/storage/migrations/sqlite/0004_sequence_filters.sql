@@ -0,0 +1,27 @@
+-- Colonnes de filtrage et index pour le listage paginé des séquences
+--
+-- Les colonnes sont extraites des métadonnées JSON existantes. La longueur des
+-- lignes compactées (sequence_format = 1) est lue dans l'en-tête de
+-- PackedBases: u32 little-endian aux octets 1 à 4.
+
+ALTER TABLE sequences ADD COLUMN encoding_scheme TEXT;
+ALTER TABLE sequences ADD COLUMN original_file TEXT;
+ALTER TABLE sequences ADD COLUMN gc_ratio REAL;
+ALTER TABLE sequences ADD COLUMN sequence_length INTEGER;
+
+UPDATE sequences SET
+    encoding_scheme = json_extract(metadata, '$.encoding_scheme'),
+    original_file = json_extract(metadata, '$.original_file'),
+    gc_ratio = json_extract(metadata, '$.gc_ratio'),
+    sequence_length = CASE sequence_format
+        WHEN 0 THEN length(CAST(sequence_data AS TEXT))
+        ELSE ((instr('0123456789ABCDEF', substr(hex(sequence_data), 3, 1)) - 1) * 16 + (instr('0123456789ABCDEF', substr(hex(sequence_data), 4, 1)) - 1)) * 1
+            + ((instr('0123456789ABCDEF', substr(hex(sequence_data), 5, 1)) - 1) * 16 + (instr('0123456789ABCDEF', substr(hex(sequence_data), 6, 1)) - 1)) * 256
+            + ((instr('0123456789ABCDEF', substr(hex(sequence_data), 7, 1)) - 1) * 16 + (instr('0123456789ABCDEF', substr(hex(sequence_data), 8, 1)) - 1)) * 65536
+            + ((instr('0123456789ABCDEF', substr(hex(sequence_data), 9, 1)) - 1) * 16 + (instr('0123456789ABCDEF', substr(hex(sequence_data), 10, 1)) - 1)) * 16777216
+    END;
+
+CREATE INDEX IF NOT EXISTS idx_sequences_scheme ON sequences(encoding_scheme, id);
+CREATE INDEX IF NOT EXISTS idx_sequences_file ON sequences(original_file, id);
+CREATE INDEX IF NOT EXISTS idx_sequences_gc ON sequences(gc_ratio);
+CREATE INDEX IF NOT EXISTS idx_sequences_length ON sequences(sequence_length);
@@ -0,0 +1,426 @@
+//! Repository des archives: encodages complets d'un fichier
+//!
+//! Une archive regroupe la configuration de l'encodeur, le manifeste du pool,
+//! l'empreinte SHA-256 du fichier original et la liste ordonnée des séquences
+//! produites, ce qui permet de lister, récupérer et décoder un fichier
+//! directement depuis la base.
+
+use crate::repository::{
+    bind_sequence_row, db_sequence_to_dna_sequence, sequence_columns, SequenceRow, INSERT_SEQUENCE,
+    INSERT_SEQUENCE_POSTGRES,
+};
+use crate::{DatabasePool, DbSequence, EmbeddedStore, Result, StorageError};
+use adn_core::codec::manifest::split_pool;
+use adn_core::codec::decoder::CompressionType;
+use adn_core::codec::encoder::CompressionType as EncoderCompression;
+use adn_core::codec::PoolManifest;
+use adn_core::{Decoder, DecoderConfig, DnaSequence, EncoderConfig};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, Row};
+use std::sync::Arc;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+const INSERT_ARCHIVE: &str =
+    "INSERT INTO archives (uuid, name, original_hash, original_size, encoder_config, manifest, sequence_count, created_at)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+     RETURNING id";
+
+const INSERT_MEMBER: &str =
+    "INSERT INTO archive_sequences (archive_id, sequence_id, position) VALUES ($1, $2, $3)";
+
+const DELETE_MEMBERS: &str =
+    "DELETE FROM sequences WHERE id IN (SELECT sequence_id FROM archive_sequences WHERE archive_id = $1)";
+
+const DELETE_ARCHIVE: &str = "DELETE FROM archives WHERE id = $1";
+
+/// Modèle d'archive pour la base de données
+#[derive(Debug, FromRow)]
+pub struct DbArchive {
+    pub id: i64,
+    pub uuid: String,
+    pub name: String,
+    pub original_hash: String,
+    pub original_size: i64,
+    pub encoder_config: String,
+    pub manifest: Option<String>,
+    pub sequence_count: i64,
+    pub created_at: String,  // Stocké comme ISO 8601 string
+}
+
+/// Archive stockée, avec configuration et manifeste désérialisés
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredArchive {
+    pub id: i64,
+    pub uuid: String,
+    /// Nom du fichier original
+    pub name: String,
+    /// SHA-256 du fichier original (hexadécimal)
+    pub original_hash: String,
+    /// Taille du fichier original en octets
+    pub original_size: u64,
+    /// Configuration de l'encodeur utilisée
+    pub encoder_config: EncoderConfig,
+    /// Manifeste du pool (None pour un pool sans oligos d'en-tête)
+    pub manifest: Option<PoolManifest>,
+    /// Nombre de séquences de l'archive (manifeste compris)
+    pub sequence_count: usize,
+    pub created_at: String,
+}
+
+/// Repository pour les opérations sur les archives
+pub struct ArchiveRepository {
+    pool: Arc<DatabasePool>,
+}
+
+impl ArchiveRepository {
+    /// Crée un nouveau repository
+    pub fn new(pool: Arc<DatabasePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Sauvegarde l'encodage complet d'un fichier
+    ///
+    /// L'archive, ses séquences et leur ordre sont écrits dans une seule
+    /// transaction. Le manifeste est extrait des oligos d'en-tête du pool.
+    #[instrument(skip(self, data, config, sequences), fields(sequences = sequences.len()))]
+    pub async fn save_archive(
+        &self,
+        name: &str,
+        data: &[u8],
+        config: &EncoderConfig,
+        sequences: &[DnaSequence],
+    ) -> Result<i64> {
+        let (manifest, _) = split_pool(sequences)?;
+        let manifest_json = manifest
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+        let config_json = serde_json::to_string(config)
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        let rows = sequences
+            .iter()
+            .map(SequenceRow::new)
+            .collect::<Result<Vec<_>>>()?;
+
+        let hash = sha256_hex(data);
+        let now = Utc::now().to_rfc3339();
+
+        let id = match &*self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let mut tx = pool.begin().await?;
+                let row = sqlx::query(INSERT_ARCHIVE)
+                    .bind(Uuid::new_v4().to_string())
+                    .bind(name)
+                    .bind(&hash)
+                    .bind(data.len() as i64)
+                    .bind(&config_json)
+                    .bind(&manifest_json)
+                    .bind(sequences.len() as i64)
+                    .bind(&now)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                let archive_id: i64 = row.try_get("id")?;
+
+                for (position, sequence_row) in rows.iter().enumerate() {
+                    let row = bind_sequence_row!(sqlx::query(INSERT_SEQUENCE), sequence_row, &now)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                    let sequence_id: i64 = row.try_get("id")?;
+
+                    sqlx::query(INSERT_MEMBER)
+                        .bind(archive_id)
+                        .bind(sequence_id)
+                        .bind(position as i64)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+
+                tx.commit().await?;
+                archive_id
+            }
+            DatabasePool::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                let row = sqlx::query(INSERT_ARCHIVE)
+                    .bind(Uuid::new_v4().to_string())
+                    .bind(name)
+                    .bind(&hash)
+                    .bind(data.len() as i64)
+                    .bind(&config_json)
+                    .bind(&manifest_json)
+                    .bind(sequences.len() as i64)
+                    .bind(&now)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                let archive_id: i64 = row.try_get("id")?;
+
+                for (position, sequence_row) in rows.iter().enumerate() {
+                    let row = bind_sequence_row!(sqlx::query(INSERT_SEQUENCE_POSTGRES), sequence_row, &now)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                    let sequence_id: i64 = row.try_get("id")?;
+
+                    sqlx::query(INSERT_MEMBER)
+                        .bind(archive_id)
+                        .bind(sequence_id)
+                        .bind(position as i64)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+
+                tx.commit().await?;
+                archive_id
+            }
+            DatabasePool::Embedded(store) => {
+                let archive = DbArchive {
+                    id: 0,
+                    uuid: Uuid::new_v4().to_string(),
+                    name: name.to_string(),
+                    original_hash: hash,
+                    original_size: data.len() as i64,
+                    encoder_config: config_json,
+                    manifest: manifest_json,
+                    sequence_count: sequences.len() as i64,
+                    created_at: now.clone(),
+                };
+                store
+                    .blocking(move |store| store.insert_archive(archive, &rows, &now))
+                    .await?
+            }
+        };
+
+        info!("Archive '{}' sauvegardée avec ID: {} ({} séquences)", name, id, sequences.len());
+        Ok(id)
+    }
+
+    /// Liste les archives, des plus récentes aux plus anciennes
+    #[instrument(skip(self))]
+    pub async fn list_archives(&self) -> Result<Vec<StoredArchive>> {
+        let query = "SELECT * FROM archives ORDER BY created_at DESC, id DESC";
+
+        let rows = match &*self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_as::<_, DbArchive>(query).fetch_all(pool).await?
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_as::<_, DbArchive>(query).fetch_all(pool).await?
+            }
+            DatabasePool::Embedded(store) => store.blocking(EmbeddedStore::archives).await?,
+        };
+
+        rows.into_iter().map(db_archive_to_stored).collect()
+    }
+
+    /// Récupère une archive par ID
+    #[instrument(skip(self))]
+    pub async fn get_archive(&self, id: i64) -> Result<Option<StoredArchive>> {
+        let query = "SELECT * FROM archives WHERE id = $1";
+
+        let row = match &*self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_as::<_, DbArchive>(query)
+                    .bind(id)
+                    .fetch_optional(pool)
+                    .await?
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_as::<_, DbArchive>(query)
+                    .bind(id)
+                    .fetch_optional(pool)
+                    .await?
+            }
+            DatabasePool::Embedded(store) => store.blocking(move |store| store.archive(id)).await?,
+        };
+
+        row.map(db_archive_to_stored).transpose()
+    }
+
+    /// Récupère les séquences d'une archive dans leur ordre d'encodage
+    #[instrument(skip(self))]
+    pub async fn get_archive_sequences(&self, id: i64) -> Result<Vec<DnaSequence>> {
+        let postgres = matches!(&*self.pool, DatabasePool::Postgres(_));
+        let select_members = format!(
+            "SELECT {} FROM sequences s
+             JOIN archive_sequences a ON a.sequence_id = s.id
+             WHERE a.archive_id = $1
+             ORDER BY a.position",
+            sequence_columns(postgres)
+        );
+
+        let rows = match &*self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_as::<_, DbSequence>(&select_members)
+                    .bind(id)
+                    .fetch_all(pool)
+                    .await?
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_as::<_, DbSequence>(&select_members)
+                    .bind(id)
+                    .fetch_all(pool)
+                    .await?
+            }
+            DatabasePool::Embedded(store) => {
+                store.blocking(move |store| store.archive_sequences(id)).await?
+            }
+        };
+
+        rows.into_iter().map(db_sequence_to_dna_sequence).collect()
+    }
+
+    /// Supprime une archive et ses séquences
+    #[instrument(skip(self))]
+    pub async fn delete_archive(&self, id: i64) -> Result<bool> {
+        let deleted = match &*self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let mut tx = pool.begin().await?;
+                sqlx::query(DELETE_MEMBERS).bind(id).execute(&mut *tx).await?;
+                let result = sqlx::query(DELETE_ARCHIVE).bind(id).execute(&mut *tx).await?;
+                tx.commit().await?;
+                result.rows_affected() > 0
+            }
+            DatabasePool::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                sqlx::query(DELETE_MEMBERS).bind(id).execute(&mut *tx).await?;
+                let result = sqlx::query(DELETE_ARCHIVE).bind(id).execute(&mut *tx).await?;
+                tx.commit().await?;
+                result.rows_affected() > 0
+            }
+            DatabasePool::Embedded(store) => store.blocking(move |store| store.delete_archive(id)).await?,
+        };
+
+        if deleted {
+            info!("Archive {} supprimée", id);
+        }
+        Ok(deleted)
+    }
+
+    /// Décode le fichier original depuis une archive stockée
+    ///
+    /// Les données décodées sont vérifiées contre l'empreinte SHA-256
+    /// enregistrée lors de la sauvegarde.
+    #[instrument(skip(self))]
+    pub async fn decode_archive(&self, id: i64) -> Result<Vec<u8>> {
+        let archive = self
+            .get_archive(id)
+            .await?
+            .ok_or_else(|| StorageError::ArchiveNotFound(id.to_string()))?;
+        let sequences = self.get_archive_sequences(id).await?;
+
+        let decoder = Decoder::new(DecoderConfig {
+            compression_type: match archive.encoder_config.compression_type {
+                EncoderCompression::Lz4 => CompressionType::Lz4,
+                EncoderCompression::Zstd => CompressionType::Zstd,
+                EncoderCompression::None => CompressionType::None,
+            },
+            ..Default::default()
+        });
+        let data = decoder.decode(&sequences)?;
+
+        let hash = sha256_hex(&data);
+        if hash != archive.original_hash {
+            return Err(StorageError::IntegrityError(format!(
+                "SHA-256 de l'archive {}: attendu {}, obtenu {}",
+                id, archive.original_hash, hash
+            )));
+        }
+
+        info!("Archive {} décodée ({} octets)", id, data.len());
+        Ok(data)
+    }
+}
+
+/// Convertit DbArchive en StoredArchive
+fn db_archive_to_stored(db_archive: DbArchive) -> Result<StoredArchive> {
+    let encoder_config = serde_json::from_str(&db_archive.encoder_config)
+        .map_err(|e| StorageError::DatabaseError(format!("Failed to parse encoder config: {}", e)))?;
+
+    let manifest = db_archive
+        .manifest
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()
+        .map_err(|e| StorageError::DatabaseError(format!("Failed to parse manifest: {}", e)))?;
+
+    Ok(StoredArchive {
+        id: db_archive.id,
+        uuid: db_archive.uuid,
+        name: db_archive.name,
+        original_hash: db_archive.original_hash,
+        original_size: db_archive.original_size as u64,
+        encoder_config,
+        manifest,
+        sequence_count: db_archive.sequence_count as usize,
+        created_at: db_archive.created_at,
+    })
+}
+
+/// Empreinte SHA-256 en hexadécimal
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DatabaseConfig, DatabaseManager, SequenceRepository};
+    use adn_core::Encoder;
+
+    async fn memory_pool() -> Arc<DatabasePool> {
+        let mut manager = DatabaseManager::new(DatabaseConfig::default());
+        manager.initialize().await.unwrap();
+        Arc::new(manager.pool().unwrap().clone())
+    }
+
+    #[tokio::test]
+    async fn test_archive_roundtrip() {
+        let pool = memory_pool().await;
+        let repo = ArchiveRepository::new(pool.clone());
+
+        let data = b"Archive ADN stockee puis decodee depuis la base".to_vec();
+        let config = EncoderConfig::default();
+        let sequences = Encoder::new(config.clone()).unwrap().encode(&data).unwrap();
+
+        let id = repo.save_archive("notes.txt", &data, &config, &sequences).await.unwrap();
+
+        let archive = repo.get_archive(id).await.unwrap().unwrap();
+        assert_eq!(archive.name, "notes.txt");
+        assert_eq!(archive.original_size, data.len() as u64);
+        assert_eq!(archive.sequence_count, sequences.len());
+        assert_eq!(archive.original_hash, sha256_hex(&data));
+        assert!(archive.manifest.is_some());
+
+        let stored = repo.get_archive_sequences(id).await.unwrap();
+        assert_eq!(stored.len(), sequences.len());
+        assert!(stored.iter().zip(&sequences).all(|(a, b)| a.bases == b.bases));
+
+        assert_eq!(repo.list_archives().await.unwrap().len(), 1);
+        assert_eq!(repo.decode_archive(id).await.unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn test_delete_archive_removes_sequences() {
+        let pool = memory_pool().await;
+        let repo = ArchiveRepository::new(pool.clone());
+
+        let data = b"temporaire".to_vec();
+        let config = EncoderConfig::default();
+        let sequences = Encoder::new(config.clone()).unwrap().encode(&data).unwrap();
+        let id = repo.save_archive("tmp.bin", &data, &config, &sequences).await.unwrap();
+
+        assert!(repo.delete_archive(id).await.unwrap());
+        assert!(!repo.delete_archive(id).await.unwrap());
+        assert!(repo.get_archive(id).await.unwrap().is_none());
+        assert!(matches!(
+            repo.decode_archive(id).await,
+            Err(StorageError::ArchiveNotFound(_))
+        ));
+
+        let sequences = SequenceRepository::new(pool);
+        assert_eq!(sequences.count_sequences().await.unwrap(), 0);
+    }
+}
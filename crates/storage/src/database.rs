@@ -1,7 +1,9 @@
 //! Module de base de données pour le stockage ADN
 
+use crate::embedded::EmbeddedStore;
 use sqlx::{Pool, Sqlite, Postgres};
 use async_trait::async_trait;
+use std::sync::Arc;
 use tracing::{info, instrument};
 
 /// Type de base de données supporté
@@ -9,12 +11,15 @@ use tracing::{info, instrument};
 pub enum DatabaseType {
     Sqlite,
     Postgres,
+    /// Base fichier intégrée, sans moteur SQL (voir `EmbeddedStore`)
+    Embedded,
 }
 
 /// Configuration de la base de données
 #[derive(Debug, Clone)]
 pub struct DatabaseConfig {
     pub db_type: DatabaseType,
+    /// URL sqlx, ou chemin du fichier (`:memory:` accepté) pour `Embedded`
     pub connection_string: String,
     pub max_connections: u32,
 }
@@ -59,6 +64,7 @@ impl DatabaseManager {
             match self.config.db_type {
                 DatabaseType::Sqlite => "SQLite",
                 DatabaseType::Postgres => "PostgreSQL",
+                DatabaseType::Embedded => "intégrée",
             }
         );
 
@@ -73,6 +79,14 @@ impl DatabaseManager {
                     Pool::<Postgres>::connect(&self.config.connection_string).await?
                 )
             }
+            DatabaseType::Embedded => {
+                let store = if self.config.connection_string == ":memory:" {
+                    EmbeddedStore::in_memory()
+                } else {
+                    EmbeddedStore::open(&self.config.connection_string)?
+                };
+                DatabasePool::Embedded(Arc::new(store))
+            }
         };
 
         self.pool = Some(pool);
@@ -110,6 +124,9 @@ impl DatabaseManager {
                 sqlx::migrate!("./migrations/postgres").run(pool).await
                     .map_err(|e| crate::StorageError::MigrationError(e.to_string()))?;
             }
+            DatabasePool::Embedded(store) => {
+                store.migrate().await?;
+            }
         }
 
         info!("Migrations exécutées avec succès");
@@ -128,6 +145,9 @@ impl DatabaseManager {
             DatabasePool::Postgres(pool) => {
                 sqlx::query("SELECT 1").execute(pool).await?;
             }
+            DatabasePool::Embedded(store) => {
+                store.health_check().await?;
+            }
         }
 
         Ok(())
@@ -140,6 +160,7 @@ impl DatabaseManager {
 pub enum DatabasePool {
     Sqlite(Pool<Sqlite>),
     Postgres(Pool<Postgres>),
+    Embedded(Arc<EmbeddedStore>),
 }
 
 impl DatabasePool {
@@ -154,6 +175,7 @@ impl DatabasePool {
                 let result = sqlx::query(query).execute(pool).await?;
                 Ok(result.rows_affected())
             }
+            DatabasePool::Embedded(_) => Err(sql_unsupported()),
         }
     }
 
@@ -169,6 +191,13 @@ impl DatabasePool {
                 // Note: Cela nécessite une conversion appropriée
                 unimplemented!("Conversion PostgreSQL vers SqliteRow non implémentée");
             }
+            DatabasePool::Embedded(_) => Err(sql_unsupported()),
         }
     }
+}
+
+fn sql_unsupported() -> crate::StorageError {
+    crate::StorageError::DatabaseError(
+        "Requêtes SQL non disponibles avec la base intégrée".to_string()
+    )
 }
\ No newline at end of file
@@ -0,0 +1,793 @@
+//! Stockage intégré: base fichier en ajout seul, sans moteur SQL
+//!
+//! Alternative à SQLite et PostgreSQL pour un usage local (CLI, tests): les
+//! tables `sequences` et `archives` sont tenues en mémoire et chaque
+//! transaction est ajoutée au fichier sous forme d'un enregistrement
+//! `[longueur u32][CRC32 u32][charge bincode]`, comme le journal du pool.
+//! Un enregistrement porte toutes les mutations d'une transaction: un
+//! enregistrement final tronqué est ignoré et sa transaction perdue en entier.
+//!
+//! Lorsque les mutations périmées dominent le fichier, l'état courant est
+//! réécrit dans un fichier temporaire puis renommé atomiquement.
+//!
+//! Les écritures (write, fsync, compactage) et l'attente du verrou sont
+//! bloquantes: les dépôts async passent par `EmbeddedStore::blocking`.
+
+use crate::database::DatabaseOperations;
+use crate::persistence::{read_frame, sync_dir, write_frame, Frame, FRAME_HEADER_LEN};
+use crate::repository::{SequenceFilter, SequenceRow, SEQUENCE_FORMAT_PACKED};
+use crate::{DbArchive, DbSequence, Result, StorageError};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+const STORE_MAGIC: &[u8; 8] = b"ADNSTOR1";
+
+/// Version du schéma des tables intégrées
+pub const EMBEDDED_SCHEMA_VERSION: u32 = 1;
+
+/// Nombre minimal de mutations périmées avant compactage automatique
+const COMPACTION_THRESHOLD: usize = 1024;
+
+/// Ligne de la table `sequences`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SequenceRecord {
+    uuid: String,
+    sequence_data: Vec<u8>,
+    sequence_format: i16,
+    metadata: String,
+    encoding_scheme: String,
+    original_file: String,
+    gc_ratio: f64,
+    sequence_length: i64,
+    created_at: String,
+    updated_at: String,
+}
+
+impl SequenceRecord {
+    fn new(row: &SequenceRow, now: &str) -> Self {
+        Self {
+            uuid: Uuid::new_v4().to_string(),
+            sequence_data: row.sequence_data.clone(),
+            sequence_format: SEQUENCE_FORMAT_PACKED,
+            metadata: row.metadata.clone(),
+            encoding_scheme: row.encoding_scheme.clone(),
+            original_file: row.original_file.clone(),
+            gc_ratio: row.gc_ratio,
+            sequence_length: row.length,
+            created_at: now.to_string(),
+            updated_at: now.to_string(),
+        }
+    }
+
+    fn to_db(&self, id: i64) -> DbSequence {
+        DbSequence {
+            id,
+            uuid: self.uuid.clone(),
+            sequence_data: self.sequence_data.clone(),
+            sequence_format: self.sequence_format,
+            metadata: self.metadata.clone(),
+            created_at: self.created_at.clone(),
+            updated_at: self.updated_at.clone(),
+        }
+    }
+
+    fn matches(&self, filter: &SequenceFilter) -> bool {
+        filter.matches(
+            &self.encoding_scheme,
+            &self.original_file,
+            self.gc_ratio,
+            self.sequence_length,
+            &self.created_at,
+        )
+    }
+}
+
+/// Ligne de la table `archives` et ses séquences membres
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ArchiveRecord {
+    uuid: String,
+    name: String,
+    original_hash: String,
+    original_size: i64,
+    encoder_config: String,
+    manifest: Option<String>,
+    sequence_count: i64,
+    created_at: String,
+    /// IDs des séquences dans leur ordre d'encodage
+    sequence_ids: Vec<i64>,
+}
+
+impl ArchiveRecord {
+    fn new(archive: DbArchive, sequence_ids: Vec<i64>) -> Self {
+        Self {
+            uuid: archive.uuid,
+            name: archive.name,
+            original_hash: archive.original_hash,
+            original_size: archive.original_size,
+            encoder_config: archive.encoder_config,
+            manifest: archive.manifest,
+            sequence_count: archive.sequence_count,
+            created_at: archive.created_at,
+            sequence_ids,
+        }
+    }
+
+    fn to_db(&self, id: i64) -> DbArchive {
+        DbArchive {
+            id,
+            uuid: self.uuid.clone(),
+            name: self.name.clone(),
+            original_hash: self.original_hash.clone(),
+            original_size: self.original_size,
+            encoder_config: self.encoder_config.clone(),
+            manifest: self.manifest.clone(),
+            sequence_count: self.sequence_count,
+            created_at: self.created_at.clone(),
+        }
+    }
+}
+
+/// Mutation enregistrée dans le fichier
+///
+/// Les lignes sont empruntées aux tables pour le compactage, possédées
+/// partout ailleurs; l'encodage est le même.
+#[derive(Debug, Serialize, Deserialize)]
+enum Mutation<'a> {
+    Schema(u32),
+    Counters { next_sequence_id: i64, next_archive_id: i64 },
+    PutSequence(i64, Cow<'a, SequenceRecord>),
+    DeleteSequence(i64),
+    PutArchive(i64, Cow<'a, ArchiveRecord>),
+    DeleteArchive(i64),
+}
+
+/// État courant des tables
+#[derive(Debug)]
+struct Tables {
+    schema_version: u32,
+    next_sequence_id: i64,
+    next_archive_id: i64,
+    sequences: BTreeMap<i64, SequenceRecord>,
+    archives: BTreeMap<i64, ArchiveRecord>,
+}
+
+impl Tables {
+    fn new() -> Self {
+        Self {
+            schema_version: 0,
+            next_sequence_id: 1,
+            next_archive_id: 1,
+            sequences: BTreeMap::new(),
+            archives: BTreeMap::new(),
+        }
+    }
+
+    fn apply(&mut self, mutation: Mutation<'_>) {
+        match mutation {
+            Mutation::Schema(version) => self.schema_version = version,
+            Mutation::Counters { next_sequence_id, next_archive_id } => {
+                self.next_sequence_id = self.next_sequence_id.max(next_sequence_id);
+                self.next_archive_id = self.next_archive_id.max(next_archive_id);
+            }
+            Mutation::PutSequence(id, record) => {
+                // Comme AUTOINCREMENT: un ID supprimé n'est jamais réattribué
+                self.next_sequence_id = self.next_sequence_id.max(id + 1);
+                self.sequences.insert(id, record.into_owned());
+            }
+            Mutation::DeleteSequence(id) => {
+                self.sequences.remove(&id);
+            }
+            Mutation::PutArchive(id, record) => {
+                self.next_archive_id = self.next_archive_id.max(id + 1);
+                self.archives.insert(id, record.into_owned());
+            }
+            Mutation::DeleteArchive(id) => {
+                self.archives.remove(&id);
+            }
+        }
+    }
+
+    /// Nombre de mutations nécessaires pour réécrire l'état courant
+    fn live_mutations(&self) -> usize {
+        2 + self.sequences.len() + self.archives.len()
+    }
+}
+
+struct Inner {
+    tables: Tables,
+    /// Fichier ouvert en ajout (None: base en mémoire)
+    file: Option<File>,
+    /// Taille valide du fichier
+    file_len: u64,
+    /// Mutations présentes dans le fichier
+    logged: usize,
+}
+
+/// Base intégrée: tables en mémoire adossées à un fichier en ajout seul
+///
+/// Sélectionnée par `DatabaseType::Embedded`; la chaîne de connexion est le
+/// chemin du fichier, ou `:memory:` pour une base sans fichier.
+pub struct EmbeddedStore {
+    path: Option<PathBuf>,
+    inner: Mutex<Inner>,
+}
+
+impl EmbeddedStore {
+    /// Ouvre ou crée la base `path` et rejoue ses transactions
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        fs::create_dir_all(parent_dir(&path))?;
+
+        // Reste d'un compactage interrompu: le fichier principal fait foi
+        let tmp = tmp_path(&path);
+        if tmp.exists() {
+            fs::remove_file(&tmp)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+
+        let mut tables = Tables::new();
+        let (logged, file_len) = replay(&mut file, &path, &mut tables)?;
+
+        info!(
+            "Base intégrée ouverte: {} ({} séquences, {} archives)",
+            path.display(),
+            tables.sequences.len(),
+            tables.archives.len()
+        );
+
+        Ok(Self {
+            path: Some(path),
+            inner: Mutex::new(Inner {
+                tables,
+                file: Some(file),
+                file_len,
+                logged,
+            }),
+        })
+    }
+
+    /// Crée une base en mémoire, perdue à la fermeture
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            inner: Mutex::new(Inner {
+                tables: Tables::new(),
+                file: None,
+                file_len: 0,
+                logged: 0,
+            }),
+        }
+    }
+
+    /// Chemin du fichier (None pour une base en mémoire)
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Exécute `op` sur la base hors des threads de l'exécuteur async
+    pub(crate) async fn blocking<T, F>(self: &Arc<Self>, op: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&EmbeddedStore) -> Result<T> + Send + 'static,
+    {
+        let store = Arc::clone(self);
+        tokio::task::spawn_blocking(move || op(&store))
+            .await
+            .map_err(|e| StorageError::DatabaseError(format!("Tâche de la base intégrée interrompue: {}", e)))?
+    }
+
+    /// Réécrit le fichier avec le seul état courant
+    pub fn compact(&self) -> Result<()> {
+        let mut inner = self.lock()?;
+        self.compact_locked(&mut inner)
+    }
+
+    pub(crate) fn insert_sequences(&self, rows: &[SequenceRow], now: &str) -> Result<Vec<i64>> {
+        let mut inner = self.lock()?;
+        let first = inner.tables.next_sequence_id;
+        let ids: Vec<i64> = (first..first + rows.len() as i64).collect();
+
+        let mutations = ids
+            .iter()
+            .zip(rows)
+            .map(|(&id, row)| Mutation::PutSequence(id, Cow::Owned(SequenceRecord::new(row, now))))
+            .collect();
+        self.commit(&mut inner, mutations)?;
+
+        Ok(ids)
+    }
+
+    pub(crate) fn sequence(&self, id: i64) -> Result<Option<DbSequence>> {
+        let inner = self.lock()?;
+        Ok(inner.tables.sequences.get(&id).map(|record| record.to_db(id)))
+    }
+
+    /// Séquences d'ID supérieur à `cursor` correspondant au filtre, par ID croissant
+    pub(crate) fn list_sequences(
+        &self,
+        filter: &SequenceFilter,
+        cursor: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<DbSequence>> {
+        let inner = self.lock()?;
+        let start = cursor.map_or(i64::MIN, |cursor| cursor.saturating_add(1));
+
+        Ok(inner
+            .tables
+            .sequences
+            .range(start..)
+            .filter(|(_, record)| record.matches(filter))
+            .take(limit)
+            .map(|(&id, record)| record.to_db(id))
+            .collect())
+    }
+
+    /// Équivalent de `metadata LIKE '%<query>'` (insensible à la casse ASCII)
+    pub(crate) fn search_sequences(&self, query: &str) -> Result<Vec<DbSequence>> {
+        let inner = self.lock()?;
+        let suffix = query.to_ascii_lowercase();
+
+        let mut rows: Vec<DbSequence> = inner
+            .tables
+            .sequences
+            .iter()
+            .filter(|(_, record)| record.metadata.to_ascii_lowercase().ends_with(&suffix))
+            .map(|(&id, record)| record.to_db(id))
+            .collect();
+        rows.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        Ok(rows)
+    }
+
+    pub(crate) fn delete_sequence(&self, id: i64) -> Result<bool> {
+        let mut inner = self.lock()?;
+        if !inner.tables.sequences.contains_key(&id) {
+            return Ok(false);
+        }
+
+        let mut mutations = vec![Mutation::DeleteSequence(id)];
+        // Équivalent du ON DELETE CASCADE de archive_sequences
+        for (&archive_id, archive) in &inner.tables.archives {
+            if archive.sequence_ids.contains(&id) {
+                let mut archive = archive.clone();
+                archive.sequence_ids.retain(|&sequence_id| sequence_id != id);
+                mutations.push(Mutation::PutArchive(archive_id, Cow::Owned(archive)));
+            }
+        }
+        self.commit(&mut inner, mutations)?;
+
+        Ok(true)
+    }
+
+    pub(crate) fn count_sequences(&self) -> Result<i64> {
+        let inner = self.lock()?;
+        Ok(inner.tables.sequences.len() as i64)
+    }
+
+    /// Insère une archive et ses séquences dans une seule transaction
+    ///
+    /// L'ID de `archive` est ignoré; l'ID attribué est retourné.
+    pub(crate) fn insert_archive(&self, archive: DbArchive, rows: &[SequenceRow], now: &str) -> Result<i64> {
+        let mut inner = self.lock()?;
+        let archive_id = inner.tables.next_archive_id;
+        let first = inner.tables.next_sequence_id;
+        let sequence_ids: Vec<i64> = (first..first + rows.len() as i64).collect();
+
+        let mut mutations: Vec<Mutation> = sequence_ids
+            .iter()
+            .zip(rows)
+            .map(|(&id, row)| Mutation::PutSequence(id, Cow::Owned(SequenceRecord::new(row, now))))
+            .collect();
+        mutations.push(Mutation::PutArchive(
+            archive_id,
+            Cow::Owned(ArchiveRecord::new(archive, sequence_ids)),
+        ));
+        self.commit(&mut inner, mutations)?;
+
+        Ok(archive_id)
+    }
+
+    /// Archives, des plus récentes aux plus anciennes
+    pub(crate) fn archives(&self) -> Result<Vec<DbArchive>> {
+        let inner = self.lock()?;
+        let mut rows: Vec<DbArchive> = inner
+            .tables
+            .archives
+            .iter()
+            .map(|(&id, record)| record.to_db(id))
+            .collect();
+        rows.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+
+        Ok(rows)
+    }
+
+    pub(crate) fn archive(&self, id: i64) -> Result<Option<DbArchive>> {
+        let inner = self.lock()?;
+        Ok(inner.tables.archives.get(&id).map(|record| record.to_db(id)))
+    }
+
+    /// Séquences d'une archive dans leur ordre d'encodage
+    pub(crate) fn archive_sequences(&self, id: i64) -> Result<Vec<DbSequence>> {
+        let inner = self.lock()?;
+        let Some(archive) = inner.tables.archives.get(&id) else {
+            return Ok(Vec::new());
+        };
+
+        Ok(archive
+            .sequence_ids
+            .iter()
+            .filter_map(|&sequence_id| {
+                inner
+                    .tables
+                    .sequences
+                    .get(&sequence_id)
+                    .map(|record| record.to_db(sequence_id))
+            })
+            .collect())
+    }
+
+    /// Supprime une archive et ses séquences dans une seule transaction
+    pub(crate) fn delete_archive(&self, id: i64) -> Result<bool> {
+        let mut inner = self.lock()?;
+        let Some(archive) = inner.tables.archives.get(&id) else {
+            return Ok(false);
+        };
+
+        let mut mutations: Vec<Mutation> = archive
+            .sequence_ids
+            .iter()
+            .map(|&sequence_id| Mutation::DeleteSequence(sequence_id))
+            .collect();
+        mutations.push(Mutation::DeleteArchive(id));
+        self.commit(&mut inner, mutations)?;
+
+        Ok(true)
+    }
+
+    /// Enregistre le schéma courant s'il est absent
+    fn migrate_schema(&self) -> Result<()> {
+        let mut inner = self.lock()?;
+        match inner.tables.schema_version {
+            EMBEDDED_SCHEMA_VERSION => Ok(()),
+            0 => {
+                self.commit(&mut inner, vec![Mutation::Schema(EMBEDDED_SCHEMA_VERSION)])?;
+                info!("Schéma intégré v{} initialisé", EMBEDDED_SCHEMA_VERSION);
+                Ok(())
+            }
+            version => Err(StorageError::MigrationError(format!(
+                "Schéma intégré v{} non supporté (attendu v{})",
+                version, EMBEDDED_SCHEMA_VERSION
+            ))),
+        }
+    }
+
+    fn check_file(&self) -> Result<()> {
+        let inner = self.lock()?;
+        if let Some(file) = &inner.file {
+            file.metadata()?;
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Inner>> {
+        self.inner
+            .lock()
+            .map_err(|_| StorageError::DatabaseError("Verrou de la base intégrée empoisonné".to_string()))
+    }
+
+    /// Écrit une transaction dans le fichier puis l'applique aux tables
+    ///
+    /// Un échec du compactage qui suit n'annule pas la transaction, déjà
+    /// durable: il est journalisé et retenté au prochain commit.
+    fn commit(&self, inner: &mut Inner, mutations: Vec<Mutation<'_>>) -> Result<()> {
+        if mutations.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(file) = inner.file.as_mut() {
+            let payload = bincode::serialize(&mutations)
+                .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+            let mut buffer = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+            write_frame(&mut buffer, &payload)?;
+
+            // Un seul write par transaction, suivi d'un fsync avant de la rendre visible
+            if let Err(e) = file.write_all(&buffer).and_then(|_| file.sync_data()) {
+                // Retire une écriture partielle pour ne pas masquer les transactions suivantes
+                let _ = file.set_len(inner.file_len);
+                return Err(e.into());
+            }
+            inner.file_len += buffer.len() as u64;
+        }
+
+        inner.logged += mutations.len();
+        for mutation in mutations {
+            inner.tables.apply(mutation);
+        }
+
+        let live = inner.tables.live_mutations();
+        if inner.logged > 2 * live + COMPACTION_THRESHOLD {
+            if let Err(e) = self.compact_locked(inner) {
+                warn!("Compactage de la base intégrée échoué: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn compact_locked(&self, inner: &mut Inner) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let tables = &inner.tables;
+        let tmp = tmp_path(path);
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        writer.write_all(STORE_MAGIC)?;
+
+        let header = [
+            Mutation::Schema(tables.schema_version),
+            Mutation::Counters {
+                next_sequence_id: tables.next_sequence_id,
+                next_archive_id: tables.next_archive_id,
+            },
+        ];
+        write_mutations(&mut writer, &header)?;
+        for (&id, record) in &tables.sequences {
+            write_mutations(&mut writer, &[Mutation::PutSequence(id, Cow::Borrowed(record))])?;
+        }
+        for (&id, record) in &tables.archives {
+            write_mutations(&mut writer, &[Mutation::PutArchive(id, Cow::Borrowed(record))])?;
+        }
+
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        drop(file);
+
+        // Ouvert avant le renommage: en cas d'échec, l'ancien fichier reste la base
+        let mut file = OpenOptions::new().read(true).append(true).open(&tmp)?;
+        let file_len = file.seek(SeekFrom::End(0))?;
+        fs::rename(&tmp, path)?;
+
+        inner.file_len = file_len;
+        inner.file = Some(file);
+        inner.logged = inner.tables.live_mutations();
+        sync_dir(parent_dir(path))?;
+
+        info!("Base intégrée compactée: {} ({} octets)", path.display(), inner.file_len);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DatabaseOperations for Arc<EmbeddedStore> {
+    async fn initialize(&self) -> Result<()> {
+        self.blocking(EmbeddedStore::migrate_schema).await
+    }
+
+    async fn migrate(&self) -> Result<()> {
+        self.blocking(EmbeddedStore::migrate_schema).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.blocking(EmbeddedStore::check_file).await
+    }
+}
+
+/// Rejoue les transactions et tronque une éventuelle fin corrompue
+///
+/// Retourne le nombre de mutations valides et la taille valide du fichier.
+fn replay(file: &mut File, path: &Path, tables: &mut Tables) -> Result<(usize, u64)> {
+    let length = file.metadata()?.len();
+
+    // Fichier neuf, ou interrompu avant la fin de son en-tête
+    if length < STORE_MAGIC.len() as u64 {
+        file.set_len(0)?;
+        file.write_all(STORE_MAGIC)?;
+        file.sync_all()?;
+        return Ok((0, STORE_MAGIC.len() as u64));
+    }
+
+    file.seek(SeekFrom::Start(0))?;
+    let mut reader = BufReader::new(&mut *file);
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != STORE_MAGIC {
+        return Err(StorageError::DatabaseError(format!(
+            "Fichier de base intégrée invalide: {}",
+            path.display()
+        )));
+    }
+
+    let mut valid_end = STORE_MAGIC.len() as u64;
+    let mut logged = 0;
+    loop {
+        let transaction = match read_frame(&mut reader)? {
+            Frame::Payload(payload) => bincode::deserialize::<Vec<Mutation>>(&payload)
+                .ok()
+                .map(|mutations| (mutations, FRAME_HEADER_LEN + payload.len())),
+            Frame::End => break,
+            Frame::Torn => None,
+        };
+
+        let Some((mutations, size)) = transaction else {
+            warn!(
+                "Base intégrée tronquée à {} octets ({} mutations valides)",
+                valid_end, logged
+            );
+            break;
+        };
+
+        logged += mutations.len();
+        for mutation in mutations {
+            tables.apply(mutation);
+        }
+        valid_end += size as u64;
+    }
+
+    drop(reader);
+    if valid_end < length {
+        file.set_len(valid_end)?;
+        file.sync_all()?;
+    }
+
+    Ok((logged, valid_end))
+}
+
+fn write_mutations<W: Write>(writer: &mut W, mutations: &[Mutation<'_>]) -> Result<()> {
+    let payload = bincode::serialize(mutations)
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+    write_frame(writer, &payload)?;
+    Ok(())
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArchiveRepository, DatabaseConfig, DatabaseManager, DatabaseType, SequenceRepository};
+    use adn_core::{DnaSequence, Encoder, EncoderConfig};
+    use std::sync::Arc;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("adn_embedded_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("catalogue.adb")
+    }
+
+    fn rows(count: usize) -> Vec<SequenceRow> {
+        (0..count)
+            .map(|i| {
+                let sequence = DnaSequence::from_str("ACGTNACGT", format!("{}.txt", i), 0, 9, i as u64).unwrap();
+                SequenceRow::new(&sequence).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reopen_replays_transactions() {
+        let path = temp_path("reopen");
+        {
+            let store = EmbeddedStore::open(&path).unwrap();
+            let ids = store.insert_sequences(&rows(3), "2024-01-01T00:00:00+00:00").unwrap();
+            assert_eq!(ids, vec![1, 2, 3]);
+            assert!(store.delete_sequence(3).unwrap());
+        }
+
+        let store = EmbeddedStore::open(&path).unwrap();
+        assert_eq!(store.count_sequences().unwrap(), 2);
+        assert!(store.sequence(3).unwrap().is_none());
+
+        // Un ID supprimé n'est pas réattribué
+        let ids = store.insert_sequences(&rows(1), "2024-01-01T00:00:00+00:00").unwrap();
+        assert_eq!(ids, vec![4]);
+    }
+
+    #[test]
+    fn test_torn_tail_is_ignored() {
+        let path = temp_path("torn");
+        {
+            let store = EmbeddedStore::open(&path).unwrap();
+            store.insert_sequences(&rows(2), "2024-01-01T00:00:00+00:00").unwrap();
+        }
+        let valid_len = fs::metadata(&path).unwrap().len();
+
+        // Transaction interrompue: en-tête annonçant plus d'octets que présents
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[200, 0, 0, 0, 1, 2, 3, 4, 5]).unwrap();
+        drop(file);
+
+        let store = EmbeddedStore::open(&path).unwrap();
+        assert_eq!(store.count_sequences().unwrap(), 2);
+        assert_eq!(fs::metadata(&path).unwrap().len(), valid_len);
+
+        store.insert_sequences(&rows(1), "2024-01-01T00:00:00+00:00").unwrap();
+        drop(store);
+        assert_eq!(EmbeddedStore::open(&path).unwrap().count_sequences().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_compaction_keeps_live_rows() {
+        let path = temp_path("compact");
+        let store = EmbeddedStore::open(&path).unwrap();
+        let ids = store.insert_sequences(&rows(20), "2024-01-01T00:00:00+00:00").unwrap();
+        for &id in &ids[..15] {
+            store.delete_sequence(id).unwrap();
+        }
+        let before = fs::metadata(&path).unwrap().len();
+
+        store.compact().unwrap();
+        assert!(fs::metadata(&path).unwrap().len() < before);
+        assert!(!tmp_path(&path).exists());
+
+        store.insert_sequences(&rows(1), "2024-01-01T00:00:00+00:00").unwrap();
+        drop(store);
+
+        let store = EmbeddedStore::open(&path).unwrap();
+        assert_eq!(store.count_sequences().unwrap(), 6);
+        assert!(store.sequence(21).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_repositories_on_embedded_backend() {
+        let path = temp_path("repositories");
+        let mut manager = DatabaseManager::new(DatabaseConfig {
+            db_type: DatabaseType::Embedded,
+            connection_string: path.to_string_lossy().into_owned(),
+            max_connections: 1,
+        });
+        manager.initialize().await.unwrap();
+        manager.health_check().await.unwrap();
+        let pool = Arc::new(manager.pool().unwrap().clone());
+
+        let data = b"Catalogue local sans moteur SQL".to_vec();
+        let config = EncoderConfig::default();
+        let sequences = Encoder::new(config.clone()).unwrap().encode(&data).unwrap();
+
+        let archives = ArchiveRepository::new(pool.clone());
+        let id = archives.save_archive("local.txt", &data, &config, &sequences).await.unwrap();
+        assert_eq!(archives.list_archives().await.unwrap().len(), 1);
+        assert_eq!(archives.decode_archive(id).await.unwrap(), data);
+
+        let repo = SequenceRepository::new(pool);
+        assert_eq!(repo.count_sequences().await.unwrap(), sequences.len() as i64);
+
+        let page = repo.list_sequences(&SequenceFilter::default(), None, 2).await.unwrap();
+        assert_eq!(page.sequences.len(), 2.min(sequences.len()));
+
+        assert!(archives.delete_archive(id).await.unwrap());
+        assert_eq!(repo.count_sequences().await.unwrap(), 0);
+        drop(archives);
+        drop(repo);
+        drop(manager);
+
+        // La suppression a été persistée
+        let store = EmbeddedStore::open(&path).unwrap();
+        assert_eq!(store.count_sequences().unwrap(), 0);
+        assert!(store.archives().unwrap().is_empty());
+    }
+}
@@ -21,6 +21,15 @@ pub enum StorageError {
 
     #[error("Erreur de migration: {0}")]
     MigrationError(String),
+
+    #[error("Archive non trouvée: {0}")]
+    ArchiveNotFound(String),
+
+    #[error("Erreur d'intégrité: {0}")]
+    IntegrityError(String),
+
+    #[error("Erreur de codage ADN: {0}")]
+    CodecError(String),
 }
 
 pub type Result<T> = std::result::Result<T, StorageError>;
@@ -35,4 +44,16 @@ impl From<anyhow::Error> for StorageError {
     fn from(err: anyhow::Error) -> Self {
         StorageError::DatabaseError(err.to_string())
     }
-}
\ No newline at end of file
+}
+
+impl From<adn_core::DnaError> for StorageError {
+    fn from(err: adn_core::DnaError) -> Self {
+        StorageError::CodecError(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(err: std::io::Error) -> Self {
+        StorageError::DatabaseError(err.to_string())
+    }
+}
@@ -1,8 +1,15 @@
 //! Indexation et recherche de séquences
+//!
+//! La recherche par similarité combine un index inversé de k-mers (graines
+//! exactes) et une vérification par distance d'édition semi-globale en bande
+//! autour des diagonales des graines.
 
-use adn_core::DnaSequence;
+use adn_core::{DnaSequence, IupacBase, PackedBases, PackedSequence};
 use std::collections::HashMap;
 
+/// Taille de k-mer par défaut de l'index inversé
+pub const DEFAULT_KMER_SIZE: usize = 12;
+
 /// Index de séquences pour la recherche rapide
 #[derive(Debug)]
 pub struct SequenceIndex {
@@ -14,14 +21,35 @@ pub struct SequenceIndex {
 
     /// Index par seed
     by_seed: HashMap<u64, String>,
+
+    /// Index inversé k-mer -> occurrences
+    kmers: HashMap<u64, Vec<Posting>>,
+
+    /// Séquences indexées par emplacement (None: emplacement libéré)
+    slots: Vec<Option<String>>,
+
+    /// Emplacements libérés réutilisables
+    free_slots: Vec<u32>,
+
+    /// Taille des k-mers (1 à 31)
+    kmer_size: usize,
 }
 
 /// Métadonnées d'indexation
 #[derive(Debug, Clone)]
 struct SequenceMeta {
-    _id: String,
-    _file: String,
-    _seed: u64,
+    file: String,
+    seed: u64,
+    slot: u32,
+    /// Bases compactées (2 bits par base), décompactées à la vérification
+    bases: PackedBases,
+}
+
+/// Occurrence d'un k-mer dans une séquence indexée
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Posting {
+    slot: u32,
+    offset: u32,
 }
 
 impl SequenceIndex {
@@ -31,23 +59,86 @@ impl SequenceIndex {
             by_id: HashMap::new(),
             by_file: HashMap::new(),
             by_seed: HashMap::new(),
+            kmers: HashMap::new(),
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+            kmer_size: DEFAULT_KMER_SIZE,
         }
     }
 
+    /// Définit la taille des k-mers (bornée à 1..=31)
+    ///
+    /// Des k-mers courts rendent l'index sensible aux lectures très bruitées,
+    /// au prix de listes d'occurrences plus longues.
+    pub fn with_kmer_size(mut self, kmer_size: usize) -> Self {
+        self.kmer_size = kmer_size.clamp(1, 31);
+        self.rebuild_kmers();
+        self
+    }
+
+    /// Taille des k-mers de l'index inversé
+    pub fn kmer_size(&self) -> usize {
+        self.kmer_size
+    }
+
+    /// Nombre de séquences indexées
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    /// Vérifie si l'index est vide
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+
     /// Insère une séquence dans l'index
     pub fn insert(&mut self, sequence: &DnaSequence) {
-        let id = sequence.id.to_string();
-        let file = sequence.metadata.original_file.clone();
-        let seed = sequence.metadata.seed;
+        self.insert_entry(
+            sequence.id.to_string(),
+            sequence.metadata.original_file.clone(),
+            sequence.metadata.seed,
+            PackedBases::from_bases(&sequence.bases),
+        );
+    }
+
+    /// Insère une séquence compactée dans l'index
+    pub fn insert_packed(&mut self, sequence: &PackedSequence) {
+        self.insert_entry(
+            sequence.id.to_string(),
+            sequence.metadata.original_file.clone(),
+            sequence.metadata.seed,
+            sequence.bases.clone(),
+        );
+    }
+
+    fn insert_entry(&mut self, id: String, file: String, seed: u64, bases: PackedBases) {
+        // Une réinsertion remplace l'entrée précédente
+        self.remove_id(&id);
+
+        let slot = match self.free_slots.pop() {
+            Some(slot) => {
+                self.slots[slot as usize] = Some(id.clone());
+                slot
+            }
+            None => {
+                self.slots.push(Some(id.clone()));
+                (self.slots.len() - 1) as u32
+            }
+        };
+
+        for (offset, kmer) in kmers(bases.iter(), self.kmer_size) {
+            self.kmers.entry(kmer).or_default().push(Posting { slot, offset: offset as u32 });
+        }
 
         let meta = SequenceMeta {
-            _id: id.clone(),
-            _file: file.clone(),
-            _seed: seed,
+            file: file.clone(),
+            seed,
+            slot,
+            bases,
         };
 
         // Index par ID
-        self.by_id.insert(id.clone(), meta.clone());
+        self.by_id.insert(id.clone(), meta);
 
         // Index par fichier
         self.by_file.entry(file).or_default().push(id.clone());
@@ -58,23 +149,145 @@ impl SequenceIndex {
 
     /// Supprime une séquence de l'index
     pub fn remove(&mut self, sequence: &DnaSequence) {
-        let id = sequence.id.to_string();
-        let file = sequence.metadata.original_file.clone();
-        let seed = sequence.metadata.seed;
+        self.remove_id(&sequence.id.to_string());
+    }
 
-        self.by_id.remove(&id);
-        self.by_seed.remove(&seed);
+    /// Supprime une séquence de l'index par ID
+    pub fn remove_id(&mut self, id: &str) {
+        let Some(meta) = self.by_id.remove(id) else {
+            return;
+        };
 
-        if let Some(entries) = self.by_file.get_mut(&file) {
-            entries.retain(|e| e != &id);
+        if self.by_seed.get(&meta.seed).is_some_and(|e| e == id) {
+            self.by_seed.remove(&meta.seed);
+        }
+
+        if let Some(entries) = self.by_file.get_mut(&meta.file) {
+            entries.retain(|e| e != id);
+            if entries.is_empty() {
+                self.by_file.remove(&meta.file);
+            }
+        }
+
+        for (_, kmer) in kmers(meta.bases.iter(), self.kmer_size) {
+            if let Some(postings) = self.kmers.get_mut(&kmer) {
+                postings.retain(|p| p.slot != meta.slot);
+                if postings.is_empty() {
+                    self.kmers.remove(&kmer);
+                }
+            }
+        }
+
+        self.slots[meta.slot as usize] = None;
+        self.free_slots.push(meta.slot);
+    }
+
+    /// Recherche les séquences proches d'une requête (chaîne de bases IUPAC)
+    ///
+    /// `threshold` est le taux d'erreur maximal toléré: une séquence est
+    /// retenue si une de ses sous-chaînes est à une distance d'édition
+    /// d'au plus `threshold * longueur de la requête`. Une requête qui n'est
+    /// pas une chaîne de bases ne retourne aucun résultat.
+    pub fn search(&self, query: &str, threshold: f64) -> Vec<SearchResult> {
+        let bases: Option<Vec<IupacBase>> = query
+            .trim()
+            .chars()
+            .map(|c| IupacBase::from_char(c.to_ascii_uppercase()).ok())
+            .collect();
+
+        match bases {
+            Some(bases) => self.search_bases(&bases, threshold),
+            None => Vec::new(),
         }
     }
 
-    /// Recherche des séquences par similarité de chaîne
-    pub fn search(&self, query: &str, _threshold: f64) -> Vec<String> {
+    /// Recherche les séquences proches d'une suite de bases
+    ///
+    /// Les résultats sont triés par distance croissante; chaque séquence
+    /// n'apparaît qu'une fois, avec son meilleur alignement.
+    pub fn search_bases(&self, query: &[IupacBase], threshold: f64) -> Vec<SearchResult> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let max_distance = (threshold.clamp(0.0, 1.0) * query.len() as f64).floor() as usize;
+        let mut best: HashMap<u32, (usize, usize)> = HashMap::new();
+
+        // Principe des tiroirs: avec au plus d erreurs, au moins un des
+        // floor(m / k) k-mers disjoints de la requête est intact (les bases
+        // ambiguës ne forment pas de k-mers et invalident la garantie)
+        let seeded = query.len() / self.kmer_size > max_distance
+            && query.iter().all(|base| base.is_standard());
+
+        if seeded {
+            let mut diagonals: HashMap<u32, Vec<isize>> = HashMap::new();
+            for (query_offset, kmer) in kmers(query.iter().copied(), self.kmer_size) {
+                for posting in self.kmers.get(&kmer).into_iter().flatten() {
+                    diagonals
+                        .entry(posting.slot)
+                        .or_default()
+                        .push(posting.offset as isize - query_offset as isize);
+                }
+            }
+
+            for (slot, mut diagonals) in diagonals {
+                let Some(meta) = self.slot_meta(slot) else { continue };
+                let target = meta.bases.to_bases();
+                diagonals.sort_unstable();
+                diagonals.dedup();
+
+                // Regroupe les diagonales voisines en une seule bande
+                let gap = 2 * max_distance as isize + 1;
+                let mut start = 0;
+                for i in 1..=diagonals.len() {
+                    if i == diagonals.len() || diagonals[i] - diagonals[i - 1] > gap {
+                        let lo = diagonals[start] - max_distance as isize;
+                        let hi = diagonals[i - 1] + max_distance as isize;
+                        if let Some(hit) = banded_distance(query, &target, lo, hi, max_distance) {
+                            keep_best(&mut best, slot, hit);
+                        }
+                        start = i;
+                    }
+                }
+            }
+        } else {
+            // Requête trop courte ou trop bruitée pour les graines: vérification exhaustive
+            for meta in self.by_id.values() {
+                let target = meta.bases.to_bases();
+                let hi = target.len() as isize;
+                if let Some(hit) = banded_distance(query, &target, -(max_distance as isize), hi, max_distance) {
+                    keep_best(&mut best, meta.slot, hit);
+                }
+            }
+        }
+
+        let mut results: Vec<SearchResult> = best
+            .into_iter()
+            .filter_map(|(slot, (distance, position))| {
+                let id = self.slots[slot as usize].clone()?;
+                let meta = &self.by_id[&id];
+                Some(SearchResult {
+                    file: meta.file.clone(),
+                    id,
+                    score: 1.0 - distance as f64 / query.len() as f64,
+                    distance,
+                    position,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        results
+    }
+
+    /// Recherche par sous-chaîne dans le nom de fichier d'origine
+    pub fn search_files(&self, query: &str) -> Vec<String> {
         let mut results = Vec::new();
 
-        // Recherche simple par sous-chaîne dans le nom de fichier
         for (file, ids) in &self.by_file {
             if file.contains(query) {
                 results.extend(ids.clone());
@@ -99,6 +312,26 @@ impl SequenceIndex {
         self.by_id.clear();
         self.by_file.clear();
         self.by_seed.clear();
+        self.kmers.clear();
+        self.slots.clear();
+        self.free_slots.clear();
+    }
+
+    fn slot_meta(&self, slot: u32) -> Option<&SequenceMeta> {
+        let id = self.slots.get(slot as usize)?.as_ref()?;
+        self.by_id.get(id)
+    }
+
+    fn rebuild_kmers(&mut self) {
+        self.kmers.clear();
+        for meta in self.by_id.values() {
+            for (offset, kmer) in kmers(meta.bases.iter(), self.kmer_size) {
+                self.kmers
+                    .entry(kmer)
+                    .or_default()
+                    .push(Posting { slot: meta.slot, offset: offset as u32 });
+            }
+        }
     }
 }
 
@@ -113,7 +346,128 @@ impl Default for SequenceIndex {
 pub struct SearchResult {
     pub id: String,
     pub file: String,
+    /// Similarité: 1 - distance / longueur de la requête
     pub score: f64,
+    /// Distance d'édition entre la requête et la sous-chaîne alignée
+    pub distance: usize,
+    /// Position de début de l'alignement dans la séquence indexée
+    pub position: usize,
+}
+
+/// Conserve le meilleur alignement (distance, puis position) d'une séquence
+fn keep_best(best: &mut HashMap<u32, (usize, usize)>, slot: u32, hit: (usize, usize)) {
+    best.entry(slot)
+        .and_modify(|current| *current = (*current).min(hit))
+        .or_insert(hit);
+}
+
+/// Code 2 bits d'une base standard
+fn base_code(base: IupacBase) -> Option<u64> {
+    match base {
+        IupacBase::A => Some(0),
+        IupacBase::C => Some(1),
+        IupacBase::G => Some(2),
+        IupacBase::T => Some(3),
+        _ => None,
+    }
+}
+
+/// K-mers (offset, code) d'une séquence; ceux contenant une base ambiguë sont ignorés
+fn kmers<I: IntoIterator<Item = IupacBase>>(bases: I, k: usize) -> Vec<(usize, u64)> {
+    let mask = (1u64 << (2 * k)) - 1;
+    let mut result = Vec::new();
+    let mut code = 0u64;
+    let mut valid = 0usize;
+
+    for (i, base) in bases.into_iter().enumerate() {
+        match base_code(base) {
+            Some(bits) => {
+                code = ((code << 2) | bits) & mask;
+                valid += 1;
+            }
+            None => valid = 0,
+        }
+        if valid >= k {
+            result.push((i + 1 - k, code));
+        }
+    }
+
+    result
+}
+
+/// Distance d'édition semi-globale en bande
+///
+/// La requête est alignée en entier sur une sous-chaîne quelconque de la
+/// cible. Seules les cellules dont la diagonale `j - i` est dans `[lo, hi]`
+/// sont calculées, avec arrêt anticipé dès qu'une ligne dépasse
+/// `max_distance`. Retourne `(distance, position de début dans la cible)`.
+fn banded_distance(
+    query: &[IupacBase],
+    target: &[IupacBase],
+    lo: isize,
+    hi: isize,
+    max_distance: usize,
+) -> Option<(usize, usize)> {
+    const INF: usize = usize::MAX / 2;
+    let (m, n) = (query.len() as isize, target.len() as isize);
+    let columns = |i: isize| (0.max(i + lo), n.min(i + hi));
+
+    // Cellule: (coût, début de l'alignement dans la cible)
+    let mut prev = vec![(INF, 0usize); n as usize + 1];
+    let mut curr = prev.clone();
+
+    let (mut prev_lo, mut prev_hi) = columns(0);
+    if prev_lo > prev_hi {
+        return None;
+    }
+    for j in prev_lo..=prev_hi {
+        prev[j as usize] = (0, j as usize);
+    }
+
+    for i in 1..=m {
+        let (row_lo, row_hi) = columns(i);
+        if row_lo > row_hi {
+            return None;
+        }
+
+        let base = query[i as usize - 1];
+        let mut row_min = INF;
+        for j in row_lo..=row_hi {
+            let ju = j as usize;
+            let mut cell = (INF, 0);
+
+            if j > prev_lo && j - 1 <= prev_hi {
+                let (cost, start) = prev[ju - 1];
+                cell = (cost + usize::from(base != target[ju - 1]), start);
+            }
+            if j >= prev_lo && j <= prev_hi {
+                let (cost, start) = prev[ju];
+                if cost + 1 < cell.0 {
+                    cell = (cost + 1, start);
+                }
+            }
+            if j > row_lo {
+                let (cost, start) = curr[ju - 1];
+                if cost + 1 < cell.0 {
+                    cell = (cost + 1, start);
+                }
+            }
+
+            curr[ju] = cell;
+            row_min = row_min.min(cell.0);
+        }
+
+        if row_min > max_distance {
+            return None;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+        (prev_lo, prev_hi) = (row_lo, row_hi);
+    }
+
+    (prev_lo..=prev_hi)
+        .map(|j| prev[j as usize])
+        .filter(|&(cost, _)| cost <= max_distance)
+        .min()
 }
 
 #[cfg(test)]
@@ -121,6 +475,10 @@ mod tests {
     use super::*;
     use adn_core::{IupacBase, DnaSequence};
 
+    fn sequence(bases: &str, file: &str, seed: u64) -> DnaSequence {
+        DnaSequence::from_str(bases, file.to_string(), 0, bases.len(), seed).unwrap()
+    }
+
     #[test]
     fn test_index_creation() {
         let index = SequenceIndex::new();
@@ -149,7 +507,74 @@ mod tests {
 
         index.insert(&seq);
 
-        let results = index.search("test", 0.5);
+        let results = index.search_files("test");
+        assert_eq!(results.len(), 1);
+    }
+
+    #[test]
+    fn test_exact_substring_hit() {
+        let mut index = SequenceIndex::new();
+        let target = sequence("ACGTTGCAAGCTAGCTTACGGATCCAGT", "a.txt", 1);
+        index.insert(&target);
+        index.insert(&sequence("TTTTGGGGCCCCAAAATTTTGGGGCCCC", "b.txt", 2));
+
+        let results = index.search("AAGCTAGCTTACGG", 0.0);
         assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, target.id.to_string());
+        assert_eq!(results[0].distance, 0);
+        assert_eq!(results[0].position, 7);
+        assert_eq!(results[0].score, 1.0);
+    }
+
+    #[test]
+    fn test_fuzzy_hits_are_ranked() {
+        let mut index = SequenceIndex::new().with_kmer_size(8);
+        let original = "ACGTTGCAAGCTAGCTTACGGATCCAGTCAGT";
+        let exact = sequence(original, "exact.txt", 1);
+        // Une substitution (position 10) et une délétion (position 20)
+        let noisy = sequence("ACGTTGCAAGGTAGCTTACGATCCAGTCAGT", "noisy.txt", 2);
+        index.insert(&exact);
+        index.insert(&noisy);
+
+        let results = index.search(original, 0.1);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, exact.id.to_string());
+        assert_eq!(results[0].distance, 0);
+        assert_eq!(results[1].id, noisy.id.to_string());
+        assert_eq!(results[1].distance, 2);
+        assert!(results[0].score > results[1].score);
+
+        // Hors seuil
+        assert_eq!(index.search(original, 0.03).len(), 1);
+    }
+
+    #[test]
+    fn test_short_query_falls_back_to_scan() {
+        let mut index = SequenceIndex::new().with_kmer_size(16);
+        index.insert(&sequence("GGGGACGTACGTCCCC", "a.txt", 1));
+
+        let results = index.search("ACGAACGT", 0.2);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].distance, 1);
+        assert_eq!(results[0].position, 4);
+    }
+
+    #[test]
+    fn test_remove_drops_kmers() {
+        let mut index = SequenceIndex::new();
+        let seq = sequence("ACGTTGCAAGCTAGCTTACGGATCCAGT", "a.txt", 1);
+        index.insert(&seq);
+        index.remove(&seq);
+
+        assert!(index.is_empty());
+        assert!(index.kmers.is_empty());
+        assert!(index.search("ACGTTGCAAGCTAGCT", 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_non_dna_query_returns_nothing() {
+        let mut index = SequenceIndex::new();
+        index.insert(&sequence("ACGTTGCAAGCTAGCTTACGGATCCAGT", "a.txt", 1));
+        assert!(index.search("hello", 0.5).is_empty());
     }
 }
@@ -1,13 +1,19 @@
 //! Gestion du stockage virtuel ADN
 
 pub mod pool;
+pub mod persistence;
 pub mod index;
 pub mod error;
 pub mod database;
 pub mod repository;
+pub mod archive;
+pub mod embedded;
 
 pub use pool::{DnaPool, PoolConfig};
+pub use persistence::PoolPersistence;
 pub use index::{SequenceIndex, SearchResult};
 pub use error::{StorageError, Result};
-pub use database::{DatabaseManager, DatabaseConfig, DatabaseType, DatabasePool};
-pub use repository::{SequenceRepository, DbSequence};
+pub use database::{DatabaseManager, DatabaseConfig, DatabaseType, DatabasePool, DatabaseOperations};
+pub use repository::{SequenceRepository, DbSequence, SequenceFilter, SequencePage};
+pub use archive::{ArchiveRepository, DbArchive, StoredArchive};
+pub use embedded::EmbeddedStore;
@@ -0,0 +1,504 @@
+//! Persistance incrémentale du pool: journal en ajout seul et instantanés
+//!
+//! Chaque modification du pool est ajoutée au journal sous forme
+//! d'enregistrement `[longueur u32][CRC32 u32][charge bincode]`, les bases
+//! étant compactées à 2 bits par base (`PackedSequence`). Lorsque le
+//! journal atteint `snapshot_interval` enregistrements, le pool complet est
+//! écrit dans un fichier temporaire puis renommé atomiquement en instantané,
+//! et le journal est remis à zéro.
+//!
+//! Au démarrage, l'instantané est chargé puis le journal rejoué. Un
+//! enregistrement final tronqué ou corrompu (écriture interrompue) marque la
+//! fin du journal, qui est tronqué à cet endroit. Le rejeu est idempotent:
+//! un arrêt entre le renommage de l'instantané et la remise à zéro du journal
+//! ne fait que rejouer des opérations déjà appliquées.
+
+use adn_core::codec::manifest::crc32;
+use adn_core::error::DnaError;
+use adn_core::{PackedSequence, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const SNAPSHOT_FILE: &str = "pool.snapshot";
+const SNAPSHOT_TMP_FILE: &str = "pool.snapshot.tmp";
+const JOURNAL_FILE: &str = "pool.journal";
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"ADNSNAP2";
+const JOURNAL_MAGIC: &[u8; 8] = b"ADNJRNL2";
+
+/// Taille maximale d'un enregistrement (garde-fou contre une longueur corrompue)
+const MAX_RECORD_LEN: usize = 64 * 1024 * 1024;
+
+/// Opération journalisée (écriture)
+#[derive(Serialize)]
+enum RecordRef<'a> {
+    Insert(&'a PackedSequence),
+    Remove(&'a str),
+    Clear,
+}
+
+/// Opération journalisée (lecture)
+#[derive(Deserialize)]
+enum Record {
+    Insert(Box<PackedSequence>),
+    Remove(String),
+    Clear,
+}
+
+/// Journal et instantanés d'un pool dans un répertoire de persistance
+#[derive(Debug)]
+pub struct PoolPersistence {
+    dir: PathBuf,
+    journal: File,
+    /// Taille valide du journal: fin du dernier enregistrement complet
+    journal_len: u64,
+    records: usize,
+    snapshot_interval: usize,
+    sync_writes: bool,
+}
+
+impl PoolPersistence {
+    /// Ouvre (ou crée) le répertoire de persistance et restaure son contenu
+    ///
+    /// Retourne le gestionnaire de persistance et les séquences restaurées
+    /// (instantané puis rejeu du journal).
+    pub fn open<P: AsRef<Path>>(
+        dir: P,
+        snapshot_interval: usize,
+        sync_writes: bool,
+    ) -> Result<(Self, HashMap<String, PackedSequence>)> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        // Instantané interrompu avant son renommage: inutilisable
+        let tmp = dir.join(SNAPSHOT_TMP_FILE);
+        if tmp.exists() {
+            fs::remove_file(&tmp)?;
+        }
+
+        let mut sequences = HashMap::new();
+        let snapshot = dir.join(SNAPSHOT_FILE);
+        if snapshot.exists() {
+            load_snapshot(&snapshot, &mut sequences)?;
+        }
+
+        let journal_path = dir.join(JOURNAL_FILE);
+        let mut journal = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&journal_path)?;
+
+        let (records, journal_len) = replay_journal(&mut journal, &mut sequences)?;
+        journal.seek(SeekFrom::Start(journal_len))?;
+
+        Ok((
+            Self {
+                dir,
+                journal,
+                journal_len,
+                records,
+                snapshot_interval: snapshot_interval.max(1),
+                sync_writes,
+            },
+            sequences,
+        ))
+    }
+
+    /// Journalise l'insertion (ou le remplacement) d'une séquence
+    pub fn log_insert(&mut self, sequence: &PackedSequence) -> Result<()> {
+        self.append(&RecordRef::Insert(sequence))
+    }
+
+    /// Journalise la suppression d'une séquence
+    pub fn log_remove(&mut self, id: &str) -> Result<()> {
+        self.append(&RecordRef::Remove(id))
+    }
+
+    /// Journalise le vidage du pool
+    pub fn log_clear(&mut self) -> Result<()> {
+        self.append(&RecordRef::Clear)
+    }
+
+    /// Nombre d'enregistrements du journal depuis le dernier instantané
+    pub fn journal_len(&self) -> usize {
+        self.records
+    }
+
+    /// Indique si le journal a atteint l'intervalle d'instantané
+    pub fn needs_snapshot(&self) -> bool {
+        self.records >= self.snapshot_interval
+    }
+
+    /// Écrit un instantané complet et remet le journal à zéro
+    ///
+    /// L'instantané est écrit dans un fichier temporaire synchronisé sur
+    /// disque, puis renommé atomiquement sur l'instantané précédent.
+    pub fn snapshot<'a, I>(&mut self, sequences: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a PackedSequence>,
+    {
+        let tmp = self.dir.join(SNAPSHOT_TMP_FILE);
+        {
+            let file = File::create(&tmp)?;
+            let mut writer = BufWriter::new(file);
+            writer.write_all(SNAPSHOT_MAGIC)?;
+            for sequence in sequences {
+                write_record(&mut writer, &RecordRef::Insert(sequence))?;
+            }
+            writer.flush()?;
+            writer.get_ref().sync_all()?;
+        }
+
+        fs::rename(&tmp, self.dir.join(SNAPSHOT_FILE))?;
+        sync_dir(&self.dir)?;
+
+        // Le journal ne contient plus que des opérations déjà présentes dans l'instantané
+        self.journal.set_len(0)?;
+        self.journal.seek(SeekFrom::Start(0))?;
+        self.journal.write_all(JOURNAL_MAGIC)?;
+        self.journal.sync_all()?;
+        self.journal_len = JOURNAL_MAGIC.len() as u64;
+        self.records = 0;
+
+        Ok(())
+    }
+
+    /// Force l'écriture du journal sur disque
+    pub fn sync(&mut self) -> Result<()> {
+        self.journal.sync_data()?;
+        Ok(())
+    }
+
+    fn append(&mut self, record: &RecordRef<'_>) -> Result<()> {
+        // Un seul write par enregistrement: pas de tampon perdu en cas d'arrêt du processus
+        let mut buffer = Vec::new();
+        write_record(&mut buffer, record)?;
+
+        let (journal, sync_writes) = (&mut self.journal, self.sync_writes);
+        let written = journal
+            .write_all(&buffer)
+            .and_then(|_| if sync_writes { journal.sync_data() } else { Ok(()) });
+        if let Err(e) = written {
+            // Retire une écriture partielle pour ne pas masquer les enregistrements suivants
+            let _ = journal.set_len(self.journal_len);
+            let _ = journal.seek(SeekFrom::Start(self.journal_len));
+            return Err(e.into());
+        }
+
+        self.journal_len += buffer.len() as u64;
+        self.records += 1;
+        Ok(())
+    }
+}
+
+/// Charge un instantané; toute corruption est une erreur
+fn load_snapshot(path: &Path, sequences: &mut HashMap<String, PackedSequence>) -> Result<()> {
+    let mut reader = BufReader::new(File::open(path)?);
+
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != SNAPSHOT_MAGIC {
+        return Err(DnaError::Serialization(format!(
+            "Instantané de pool invalide: {}",
+            path.display()
+        )));
+    }
+
+    loop {
+        match read_record(&mut reader)? {
+            ReadOutcome::Record(record, _) => match *record {
+                Record::Insert(sequence) => {
+                    sequences.insert(sequence.id.to_string(), *sequence);
+                }
+                _ => {
+                    return Err(DnaError::Serialization(
+                        "Instantané de pool: enregistrement inattendu".to_string(),
+                    ));
+                }
+            },
+            ReadOutcome::End => return Ok(()),
+            ReadOutcome::Torn => return Err(DnaError::DataCorrupted),
+        }
+    }
+}
+
+/// Rejoue le journal et tronque une éventuelle fin corrompue
+///
+/// Retourne le nombre d'enregistrements valides et la taille valide du journal.
+fn replay_journal(journal: &mut File, sequences: &mut HashMap<String, PackedSequence>) -> Result<(usize, u64)> {
+    journal.seek(SeekFrom::Start(0))?;
+    let length = journal.metadata()?.len();
+
+    // Journal neuf, ou interrompu avant la fin de son en-tête
+    if length < JOURNAL_MAGIC.len() as u64 {
+        journal.set_len(0)?;
+        journal.write_all(JOURNAL_MAGIC)?;
+        journal.sync_all()?;
+        return Ok((0, JOURNAL_MAGIC.len() as u64));
+    }
+
+    let mut reader = BufReader::new(&mut *journal);
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != JOURNAL_MAGIC {
+        return Err(DnaError::Serialization("Journal de pool invalide".to_string()));
+    }
+
+    let mut valid_end = JOURNAL_MAGIC.len() as u64;
+    let mut records = 0;
+    loop {
+        match read_record(&mut reader)? {
+            ReadOutcome::Record(record, size) => {
+                match *record {
+                    Record::Insert(sequence) => {
+                        sequences.insert(sequence.id.to_string(), *sequence);
+                    }
+                    Record::Remove(id) => {
+                        sequences.remove(&id);
+                    }
+                    Record::Clear => sequences.clear(),
+                }
+                valid_end += size as u64;
+                records += 1;
+            }
+            ReadOutcome::End => break,
+            ReadOutcome::Torn => {
+                tracing::warn!(
+                    "Journal de pool tronqué à {} octets ({} enregistrements valides)",
+                    valid_end,
+                    records
+                );
+                break;
+            }
+        }
+    }
+
+    drop(reader);
+    if valid_end < length {
+        journal.set_len(valid_end)?;
+        journal.sync_all()?;
+    }
+
+    Ok((records, valid_end))
+}
+
+/// Résultat de la lecture d'un enregistrement
+enum ReadOutcome {
+    /// Enregistrement valide et sa taille sur disque
+    Record(Box<Record>, usize),
+    /// Fin propre du fichier
+    End,
+    /// Enregistrement incomplet ou dont le CRC32 est invalide
+    Torn,
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &RecordRef<'_>) -> Result<()> {
+    let payload = bincode::serialize(record)
+        .map_err(|e| DnaError::Serialization(e.to_string()))?;
+
+    write_frame(writer, &payload)?;
+    Ok(())
+}
+
+fn read_record<R: Read>(reader: &mut R) -> Result<ReadOutcome> {
+    let payload = match read_frame(reader)? {
+        Frame::Payload(payload) => payload,
+        Frame::End => return Ok(ReadOutcome::End),
+        Frame::Torn => return Ok(ReadOutcome::Torn),
+    };
+
+    match bincode::deserialize(&payload) {
+        Ok(record) => Ok(ReadOutcome::Record(Box::new(record), FRAME_HEADER_LEN + payload.len())),
+        Err(_) => Ok(ReadOutcome::Torn),
+    }
+}
+
+/// Taille de l'en-tête d'un enregistrement: longueur et CRC32
+pub(crate) const FRAME_HEADER_LEN: usize = 8;
+
+/// Enregistrement brut lu depuis un fichier en ajout seul
+pub(crate) enum Frame {
+    /// Charge dont le CRC32 est valide
+    Payload(Vec<u8>),
+    /// Fin propre du fichier
+    End,
+    /// Enregistrement incomplet ou dont le CRC32 est invalide
+    Torn,
+}
+
+/// Écrit un enregistrement `[longueur u32][CRC32 u32][charge]`
+pub(crate) fn write_frame<W: Write>(writer: &mut W, payload: &[u8]) -> std::io::Result<()> {
+    writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+    writer.write_all(&crc32(payload).to_le_bytes())?;
+    writer.write_all(payload)
+}
+
+/// Lit un enregistrement écrit par `write_frame`
+pub(crate) fn read_frame<R: Read>(reader: &mut R) -> std::io::Result<Frame> {
+    let mut header = [0u8; FRAME_HEADER_LEN];
+    match read_full(reader, &mut header)? {
+        0 => return Ok(Frame::End),
+        FRAME_HEADER_LEN => {}
+        _ => return Ok(Frame::Torn),
+    }
+
+    let length = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let checksum = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    if length > MAX_RECORD_LEN {
+        return Ok(Frame::Torn);
+    }
+
+    let mut payload = vec![0u8; length];
+    if read_full(reader, &mut payload)? < length || crc32(&payload) != checksum {
+        return Ok(Frame::Torn);
+    }
+
+    Ok(Frame::Payload(payload))
+}
+
+/// Lit jusqu'à remplir `buffer` ou atteindre la fin du fichier
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Synchronise l'entrée de répertoire après un renommage (sans effet hors Unix)
+pub(crate) fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("adn_pool_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn sequence(bases: &str, seed: u64) -> PackedSequence {
+        adn_core::DnaSequence::from_str(bases, "test.txt".to_string(), 0, bases.len(), seed)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn test_journal_replay() {
+        let dir = temp_dir("replay");
+        let (a, b) = (sequence("ACGT", 1), sequence("TTGCA", 2));
+
+        {
+            let (mut persistence, restored) = PoolPersistence::open(&dir, 100, false).unwrap();
+            assert!(restored.is_empty());
+            persistence.log_insert(&a).unwrap();
+            persistence.log_insert(&b).unwrap();
+            persistence.log_remove(&a.id.to_string()).unwrap();
+        }
+
+        let (persistence, restored) = PoolPersistence::open(&dir, 100, false).unwrap();
+        assert_eq!(persistence.journal_len(), 3);
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[&b.id.to_string()].bases, b.bases);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_torn_tail_is_truncated() {
+        let dir = temp_dir("torn");
+        let a = sequence("ACGT", 1);
+
+        {
+            let (mut persistence, _) = PoolPersistence::open(&dir, 100, false).unwrap();
+            persistence.log_insert(&a).unwrap();
+        }
+        let journal = dir.join(JOURNAL_FILE);
+        let valid = fs::metadata(&journal).unwrap().len();
+
+        // Écriture interrompue: en-tête d'enregistrement partiel
+        let mut file = OpenOptions::new().append(true).open(&journal).unwrap();
+        file.write_all(&[42, 0, 0]).unwrap();
+        drop(file);
+
+        let (mut persistence, restored) = PoolPersistence::open(&dir, 100, false).unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(fs::metadata(&journal).unwrap().len(), valid);
+
+        // Les ajouts suivants restent lisibles
+        let b = sequence("GGCA", 2);
+        persistence.log_insert(&b).unwrap();
+        drop(persistence);
+        let (_, restored) = PoolPersistence::open(&dir, 100, false).unwrap();
+        assert_eq!(restored.len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_record_stops_replay() {
+        let dir = temp_dir("crc");
+        {
+            let (mut persistence, _) = PoolPersistence::open(&dir, 100, false).unwrap();
+            persistence.log_insert(&sequence("ACGT", 1)).unwrap();
+            persistence.log_insert(&sequence("TTGCA", 2)).unwrap();
+        }
+
+        // Altère le dernier octet de charge du second enregistrement
+        let journal = dir.join(JOURNAL_FILE);
+        let mut bytes = fs::read(&journal).unwrap();
+        let last = bytes.len() - 2;
+        bytes[last] ^= 0xFF;
+        fs::write(&journal, bytes).unwrap();
+
+        let (persistence, restored) = PoolPersistence::open(&dir, 100, false).unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(persistence.journal_len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_resets_journal() {
+        let dir = temp_dir("snapshot");
+        let sequences: Vec<PackedSequence> = (0..5).map(|i| sequence("ACGTAC", i)).collect();
+
+        {
+            let (mut persistence, _) = PoolPersistence::open(&dir, 3, false).unwrap();
+            for seq in &sequences[..3] {
+                persistence.log_insert(seq).unwrap();
+            }
+            assert!(persistence.needs_snapshot());
+            persistence.snapshot(&sequences[..3]).unwrap();
+            assert_eq!(persistence.journal_len(), 0);
+
+            for seq in &sequences[3..] {
+                persistence.log_insert(seq).unwrap();
+            }
+        }
+
+        assert!(!dir.join(SNAPSHOT_TMP_FILE).exists());
+        let (persistence, restored) = PoolPersistence::open(&dir, 3, false).unwrap();
+        assert_eq!(persistence.journal_len(), 2);
+        assert_eq!(restored.len(), 5);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,9 @@
 //! Pool de séquences ADN avec gestion de stockage
 
-use crate::index::SequenceIndex;
-use adn_core::{DnaConstraints, DnaSequence, Result};
+use crate::index::{SearchResult, SequenceIndex};
+use crate::persistence::PoolPersistence;
+use adn_core::error::DnaError;
+use adn_core::{DnaConstraints, DnaSequence, IupacBase, PackedSequence, PrimerPair, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
@@ -20,6 +22,21 @@ pub struct PoolConfig {
 
     /// Répertoire de persistance
     pub persistence_dir: Option<String>,
+
+    /// Nombre d'enregistrements du journal avant un nouvel instantané
+    #[serde(default = "default_snapshot_interval")]
+    pub snapshot_interval: usize,
+
+    /// Synchroniser le journal sur disque à chaque modification
+    ///
+    /// Sans synchronisation, les modifications survivent à un arrêt du
+    /// processus mais pas forcément à une coupure de courant.
+    #[serde(default)]
+    pub sync_writes: bool,
+}
+
+fn default_snapshot_interval() -> usize {
+    10_000
 }
 
 impl Default for PoolConfig {
@@ -29,28 +46,69 @@ impl Default for PoolConfig {
             max_size: 100000,
             persistence_enabled: false,
             persistence_dir: None,
+            snapshot_interval: default_snapshot_interval(),
+            sync_writes: false,
         }
     }
 }
 
 /// Pool de séquences ADN
+///
+/// Les séquences sont conservées compactées (2 bits par base) et
+/// décompactées à la lecture.
 #[derive(Debug)]
 pub struct DnaPool {
-    sequences: HashMap<String, DnaSequence>,
+    sequences: HashMap<String, PackedSequence>,
     index: SequenceIndex,
     config: PoolConfig,
+    persistence: Option<PoolPersistence>,
 }
 
 impl DnaPool {
-    /// Crée un nouveau pool
+    /// Crée un nouveau pool en mémoire
+    ///
+    /// La persistance configurée n'est activée que par `DnaPool::open`.
     pub fn new(config: PoolConfig) -> Self {
         Self {
             sequences: HashMap::new(),
             index: SequenceIndex::new(),
             config,
+            persistence: None,
         }
     }
 
+    /// Ouvre un pool, restauré depuis `persistence_dir` si la persistance est activée
+    ///
+    /// Le dernier instantané est chargé puis le journal rejoué; chaque
+    /// modification ultérieure est ajoutée au journal.
+    pub fn open(config: PoolConfig) -> Result<Self> {
+        if !config.persistence_enabled {
+            return Ok(Self::new(config));
+        }
+
+        let dir = config.persistence_dir.clone().ok_or_else(|| {
+            DnaError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "persistence_dir requis lorsque la persistance est activée",
+            ))
+        })?;
+
+        let (persistence, sequences) =
+            PoolPersistence::open(dir, config.snapshot_interval, config.sync_writes)?;
+
+        let mut index = SequenceIndex::new();
+        for seq in sequences.values() {
+            index.insert_packed(seq);
+        }
+
+        Ok(Self {
+            sequences,
+            index,
+            config,
+            persistence: Some(persistence),
+        })
+    }
+
     /// Ajoute une séquence au pool
     pub fn insert(&mut self, sequence: DnaSequence) -> Result<()> {
         // Valider les contraintes
@@ -58,30 +116,47 @@ impl DnaPool {
 
         let id = sequence.id.to_string();
 
-        // Insérer dans le pool
-        self.sequences.insert(id.clone(), sequence.clone());
+        let packed = PackedSequence::from(sequence);
+
+        // Journaliser avant d'appliquer
+        if let Some(persistence) = self.persistence.as_mut() {
+            persistence.log_insert(&packed)?;
+        }
 
         // Mettre à jour l'index
-        self.index.insert(&sequence);
+        self.index.insert_packed(&packed);
 
-        Ok(())
+        // Insérer dans le pool
+        self.sequences.insert(id, packed);
+
+        self.snapshot_if_due()
     }
 
     /// Récupère une séquence par ID
-    pub fn get(&self, id: &str) -> Option<&DnaSequence> {
-        self.sequences.get(id)
+    pub fn get(&self, id: &str) -> Option<DnaSequence> {
+        self.sequences.get(id).map(PackedSequence::unpack)
     }
 
     /// Supprime une séquence du pool
-    pub fn remove(&mut self, id: &str) -> Option<DnaSequence> {
-        let seq = self.sequences.remove(id)?;
-        self.index.remove(&seq);
-        Some(seq)
+    pub fn remove(&mut self, id: &str) -> Result<Option<DnaSequence>> {
+        if !self.sequences.contains_key(id) {
+            return Ok(None);
+        }
+
+        if let Some(persistence) = self.persistence.as_mut() {
+            persistence.log_remove(id)?;
+        }
+
+        let seq = self.sequences.remove(id).map(DnaSequence::from);
+        self.index.remove_id(id);
+
+        self.snapshot_if_due()?;
+        Ok(seq)
     }
 
     /// Retourne toutes les séquences
-    pub fn all(&self) -> Vec<&DnaSequence> {
-        self.sequences.values().collect()
+    pub fn all(&self) -> Vec<DnaSequence> {
+        self.sequences.values().map(PackedSequence::unpack).collect()
     }
 
     /// Retourne le nombre de séquences
@@ -95,22 +170,74 @@ impl DnaPool {
     }
 
     /// Recherche des séquences par similarité
-    pub fn search(&self, query: &str, threshold: f64) -> Vec<&DnaSequence> {
+    ///
+    /// `threshold` est le taux d'erreur maximal toléré par rapport à la
+    /// longueur de la requête (voir `SequenceIndex::search`).
+    pub fn search(&self, query: &str, threshold: f64) -> Vec<SearchResult> {
         self.index.search(query, threshold)
-            .into_iter()
-            .filter_map(|id| self.sequences.get(&id))
+    }
+
+    /// Retrouve l'oligo d'origine le plus probable d'une lecture bruitée
+    pub fn best_match(&self, read: &[IupacBase], threshold: f64) -> Option<(DnaSequence, SearchResult)> {
+        let result = self.index.search_bases(read, threshold).into_iter().next()?;
+        let sequence = self.get(&result.id)?;
+        Some((sequence, result))
+    }
+
+    /// PCR simulée: extrait les oligos d'un fichier, débarrassés de leurs amorces
+    pub fn amplify(&self, pair: &PrimerPair, max_mismatches: usize) -> Vec<DnaSequence> {
+        self.sequences
+            .values()
+            .flat_map(|packed| pair.amplify(std::iter::once(&packed.unpack()), max_mismatches))
             .collect()
     }
 
     /// Vide le pool
-    pub fn clear(&mut self) {
+    pub fn clear(&mut self) -> Result<()> {
+        if let Some(persistence) = self.persistence.as_mut() {
+            persistence.log_clear()?;
+        }
+
         self.sequences.clear();
         self.index = SequenceIndex::new();
+
+        self.snapshot_if_due()
     }
 
-    /// Sauvegarde le pool sur disque
+    /// Écrit un instantané du pool et remet le journal à zéro
+    ///
+    /// Sans effet si la persistance n'est pas active.
+    pub fn snapshot(&mut self) -> Result<()> {
+        if let Some(persistence) = self.persistence.as_mut() {
+            persistence.snapshot(self.sequences.values())?;
+        }
+        Ok(())
+    }
+
+    /// Force l'écriture du journal sur disque
+    pub fn sync(&mut self) -> Result<()> {
+        if let Some(persistence) = self.persistence.as_mut() {
+            persistence.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Compacte le journal dans un instantané lorsque l'intervalle est atteint
+    fn snapshot_if_due(&mut self) -> Result<()> {
+        if self.persistence.as_ref().is_some_and(|p| p.needs_snapshot()) {
+            self.snapshot()?;
+        }
+        Ok(())
+    }
+
+    /// Sauvegarde le pool sur disque (export JSON, bases en clair)
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let json = serde_json::to_string_pretty(&self.sequences)
+        let sequences: HashMap<&String, DnaSequence> = self
+            .sequences
+            .iter()
+            .map(|(id, packed)| (id, packed.unpack()))
+            .collect();
+        let json = serde_json::to_string_pretty(&sequences)
             .map_err(|e| adn_core::error::DnaError::Serialization(e.to_string()))?;
         std::fs::write(path, json)?;
         Ok(())
@@ -122,15 +249,19 @@ impl DnaPool {
         let sequences: HashMap<String, DnaSequence> = serde_json::from_str(&json)
             .map_err(|e| adn_core::error::DnaError::Serialization(e.to_string()))?;
 
-        self.sequences = sequences;
-
         // Reconstruire l'index
         self.index = SequenceIndex::new();
-        for seq in self.sequences.values() {
+        for seq in sequences.values() {
             self.index.insert(seq);
         }
 
-        Ok(())
+        self.sequences = sequences
+            .into_iter()
+            .map(|(id, seq)| (id, PackedSequence::from(seq)))
+            .collect();
+
+        // Le contenu chargé remplace l'état persistant
+        self.snapshot()
     }
 }
 
@@ -184,7 +315,7 @@ mod tests {
         let id = seq.id.to_string();
         pool.insert(seq).unwrap();
 
-        pool.remove(&id);
+        pool.remove(&id).unwrap();
         assert!(pool.is_empty());
     }
 
@@ -197,8 +328,89 @@ mod tests {
         let seq = DnaSequence::new(bases, "test.txt".to_string(), 0, 4, 42);
 
         pool.insert(seq).unwrap();
-        pool.clear();
+        pool.clear().unwrap();
 
         assert!(pool.is_empty());
     }
+
+    #[test]
+    fn test_amplify_selects_file() {
+        let config = PoolConfig {
+            constraints: DnaConstraints::new(0.0, 1.0, 20, 500),
+            ..Default::default()
+        };
+        let mut pool = DnaPool::new(config);
+
+        let library = adn_core::PrimerLibrary::generate(adn_core::PrimerConfig::default(), 2).unwrap();
+        let bases = vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+        for (i, pair) in library.pairs().iter().enumerate() {
+            let seq = DnaSequence::new(bases.clone(), format!("file{}.txt", i), 0, 4, 42);
+            pool.insert(pair.attach(&seq)).unwrap();
+        }
+
+        let selected = pool.amplify(&library.pairs()[1], 0);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].bases, bases);
+        assert_eq!(selected[0].metadata.original_file, "file1.txt");
+    }
+
+    #[test]
+    fn test_best_match_for_noisy_read() {
+        let config = PoolConfig {
+            constraints: DnaConstraints::new(0.0, 1.0, 20, 500),
+            ..Default::default()
+        };
+        let mut pool = DnaPool::new(config);
+
+        let oligos = [
+            "ACGTTGCAAGCTAGCTTACGGATCCAGTCAGTAGCT",
+            "TGCATGCAGTCAGTCCATGACTGACGTAGCATGCAA",
+        ];
+        for (i, oligo) in oligos.iter().enumerate() {
+            let seq = DnaSequence::from_str(oligo, format!("file{}.txt", i), 0, oligo.len(), i as u64).unwrap();
+            pool.insert(seq).unwrap();
+        }
+
+        // Lecture du second oligo avec une substitution et une insertion
+        let read = DnaSequence::from_str("TGCATGCAGTCTGTCCATGACTGAACGTAGCATGCAA", String::new(), 0, 37, 0).unwrap();
+        let (sequence, result) = pool.best_match(&read.bases, 0.1).unwrap();
+        assert_eq!(sequence.metadata.original_file, "file1.txt");
+        assert_eq!(result.distance, 2);
+        assert_eq!(result.position, 0);
+
+        assert_eq!(pool.search("GCTAGCTTACGGATCC", 0.0).len(), 1);
+    }
+
+    #[test]
+    fn test_open_replays_journal() {
+        let dir = std::env::temp_dir().join(format!("adn_pool_open_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = PoolConfig {
+            persistence_enabled: true,
+            persistence_dir: Some(dir.to_string_lossy().into_owned()),
+            snapshot_interval: 4,
+            ..Default::default()
+        };
+
+        let bases = vec![IupacBase::A, IupacBase::C, IupacBase::G, IupacBase::T];
+        let mut ids = Vec::new();
+        {
+            let mut pool = DnaPool::open(config.clone()).unwrap();
+            for i in 0..6 {
+                let seq = DnaSequence::new(bases.clone(), "test.txt".to_string(), i, 4, i as u64);
+                ids.push(seq.id.to_string());
+                pool.insert(seq).unwrap();
+            }
+            pool.remove(&ids[0]).unwrap();
+        }
+
+        // Instantané après 4 enregistrements, puis 3 enregistrements rejoués
+        let pool = DnaPool::open(config).unwrap();
+        assert_eq!(pool.len(), 5);
+        assert!(pool.get(&ids[0]).is_none());
+        assert!(pool.get(&ids[5]).is_some());
+        assert_eq!(pool.index.by_file("test.txt").len(), 5);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,18 +1,71 @@
 //! Repository pour les opérations de stockage ADN
 
-use crate::{DatabasePool, Result, StorageError};
-use adn_core::{DnaSequence, IupacBase};
+use crate::{DatabasePool, EmbeddedStore, Result, StorageError};
+use adn_core::{DnaSequence, IupacBase, PackedBases};
 use sqlx::{FromRow, Row};
 use uuid::Uuid;
 use tracing::{info, instrument};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+
+/// Format de `sequence_data`: bases IUPAC en clair (lignes antérieures à 0003)
+pub const SEQUENCE_FORMAT_TEXT: i16 = 0;
+
+/// Format de `sequence_data`: `PackedBases` (2 bits par base)
+pub const SEQUENCE_FORMAT_PACKED: i16 = 1;
+
+/// Insertion d'une séquence avec ses colonnes de filtrage
+pub(crate) const INSERT_SEQUENCE: &str =
+    "INSERT INTO sequences (uuid, sequence_data, sequence_format, metadata, encoding_scheme,
+                            original_file, gc_ratio, sequence_length, created_at, updated_at)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+     RETURNING id";
+
+/// `INSERT_SEQUENCE` pour PostgreSQL: uuid, métadonnées et dates sont liés comme texte
+pub(crate) const INSERT_SEQUENCE_POSTGRES: &str =
+    "INSERT INTO sequences (uuid, sequence_data, sequence_format, metadata, encoding_scheme,
+                            original_file, gc_ratio, sequence_length, created_at, updated_at)
+     VALUES ($1::uuid, $2, $3, $4::jsonb, $5, $6, $7, $8, $9::timestamptz, $10::timestamptz)
+     RETURNING id";
+
+/// Colonnes de `DbSequence` pour une requête sur `sequences s`
+///
+/// PostgreSQL stocke uuid, métadonnées et dates en UUID, JSONB et
+/// TIMESTAMPTZ: ils sont relus comme texte, les dates en RFC 3339 (UTC).
+pub(crate) fn sequence_columns(postgres: bool) -> &'static str {
+    if postgres {
+        "s.id, s.uuid::text AS uuid, s.sequence_data, s.sequence_format, s.metadata::text AS metadata,
+         to_char(s.created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS.US\"+00:00\"') AS created_at,
+         to_char(s.updated_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS.US\"+00:00\"') AS updated_at"
+    } else {
+        "s.*"
+    }
+}
+
+/// Lie les paramètres de `INSERT_SEQUENCE` (ou `INSERT_SEQUENCE_POSTGRES`) pour une `SequenceRow`
+macro_rules! bind_sequence_row {
+    ($query:expr, $row:expr, $now:expr) => {
+        $query
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&$row.sequence_data)
+            .bind($crate::repository::SEQUENCE_FORMAT_PACKED)
+            .bind(&$row.metadata)
+            .bind(&$row.encoding_scheme)
+            .bind(&$row.original_file)
+            .bind($row.gc_ratio)
+            .bind($row.length)
+            .bind($now)
+            .bind($now)
+    };
+}
+pub(crate) use bind_sequence_row;
 
 /// Modèle de séquence ADN pour la base de données
 #[derive(Debug, FromRow)]
 pub struct DbSequence {
     pub id: i64,
     pub uuid: String,
-    pub sequence_data: String,
+    pub sequence_data: Vec<u8>,
+    pub sequence_format: i16,
     pub metadata: String,
     pub created_at: String,  // Stocké comme ISO 8601 string
     pub updated_at: String,  // Stocké comme ISO 8601 string
@@ -32,53 +85,148 @@ impl SequenceRepository {
     /// Sauvegarde une séquence ADN
     #[instrument(skip(self, sequence))]
     pub async fn save_sequence(&self, sequence: &DnaSequence) -> Result<i64> {
-        let metadata_json = serde_json::to_string(&sequence.metadata)
-            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
-
-        let sequence_data = sequence.bases.iter()
-            .map(|base| base.as_char())
-            .collect::<String>();
-
+        let row = SequenceRow::new(sequence)?;
         let now = Utc::now().to_rfc3339();
 
-        let query =
-            "INSERT INTO sequences (uuid, sequence_data, metadata, created_at, updated_at)
-             VALUES ($1, $2, $3, $4, $5)
-             RETURNING id";
-
         let id = match &*self.pool {
             DatabasePool::Sqlite(pool) => {
-                let row = sqlx::query(query)
-                    .bind(Uuid::new_v4().to_string())
-                    .bind(sequence_data)
-                    .bind(metadata_json)
-                    .bind(&now)
-                    .bind(&now)
+                let row = bind_sequence_row!(sqlx::query(INSERT_SEQUENCE), row, &now)
                     .fetch_one(pool)
                     .await?;
                 row.try_get("id")?
             }
             DatabasePool::Postgres(pool) => {
-                let row = sqlx::query(query)
-                    .bind(Uuid::new_v4().to_string())
-                    .bind(sequence_data)
-                    .bind(metadata_json)
-                    .bind(&now)
-                    .bind(&now)
+                let row = bind_sequence_row!(sqlx::query(INSERT_SEQUENCE_POSTGRES), row, &now)
                     .fetch_one(pool)
                     .await?;
                 row.try_get("id")?
             }
+            DatabasePool::Embedded(store) => {
+                store
+                    .blocking(move |store| store.insert_sequences(std::slice::from_ref(&row), &now))
+                    .await?[0]
+            }
         };
 
         info!("Séquence sauvegardée avec ID: {}", id);
         Ok(id)
     }
 
+    /// Sauvegarde un lot de séquences dans une seule transaction
+    ///
+    /// Retourne les IDs dans l'ordre du lot; en cas d'erreur, aucune
+    /// séquence du lot n'est conservée.
+    #[instrument(skip(self, sequences), fields(count = sequences.len()))]
+    pub async fn save_batch(&self, sequences: &[DnaSequence]) -> Result<Vec<i64>> {
+        let rows = sequences
+            .iter()
+            .map(SequenceRow::new)
+            .collect::<Result<Vec<_>>>()?;
+        let now = Utc::now().to_rfc3339();
+
+        let mut ids = Vec::with_capacity(rows.len());
+        match &*self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let mut tx = pool.begin().await?;
+                for row in &rows {
+                    let inserted = bind_sequence_row!(sqlx::query(INSERT_SEQUENCE), row, &now)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                    ids.push(inserted.try_get("id")?);
+                }
+                tx.commit().await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                for row in &rows {
+                    let inserted = bind_sequence_row!(sqlx::query(INSERT_SEQUENCE_POSTGRES), row, &now)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                    ids.push(inserted.try_get("id")?);
+                }
+                tx.commit().await?;
+            }
+            DatabasePool::Embedded(store) => {
+                ids.extend(store.blocking(move |store| store.insert_sequences(&rows, &now)).await?);
+            }
+        }
+
+        info!("{} séquences sauvegardées", ids.len());
+        Ok(ids)
+    }
+
+    /// Liste les séquences correspondant à un filtre, par pages
+    ///
+    /// La pagination se fait par curseur sur l'ID (ordre croissant): passer
+    /// `next_cursor` de la page précédente pour obtenir la suivante. Les
+    /// insertions concurrentes ne décalent pas les pages déjà lues.
+    #[instrument(skip(self))]
+    pub async fn list_sequences(
+        &self,
+        filter: &SequenceFilter,
+        cursor: Option<i64>,
+        limit: usize,
+    ) -> Result<SequencePage> {
+        let limit = limit.max(1);
+        let postgres = matches!(&*self.pool, DatabasePool::Postgres(_));
+        let (clause, values) = filter.where_clause(cursor, postgres);
+        let query = format!(
+            "SELECT {} FROM sequences s WHERE {} ORDER BY id LIMIT {}",
+            sequence_columns(postgres),
+            clause,
+            limit + 1
+        );
+
+        let mut rows = match &*self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let mut q = sqlx::query_as::<_, DbSequence>(&query);
+                for value in &values {
+                    q = match value {
+                        FilterValue::Text(v) => q.bind(v),
+                        FilterValue::Float(v) => q.bind(*v),
+                        FilterValue::Int(v) => q.bind(*v),
+                    };
+                }
+                q.fetch_all(pool).await?
+            }
+            DatabasePool::Postgres(pool) => {
+                let mut q = sqlx::query_as::<_, DbSequence>(&query);
+                for value in &values {
+                    q = match value {
+                        FilterValue::Text(v) => q.bind(v),
+                        FilterValue::Float(v) => q.bind(*v),
+                        FilterValue::Int(v) => q.bind(*v),
+                    };
+                }
+                q.fetch_all(pool).await?
+            }
+            DatabasePool::Embedded(store) => {
+                let filter = filter.clone();
+                store
+                    .blocking(move |store| store.list_sequences(&filter, cursor, limit + 1))
+                    .await?
+            }
+        };
+
+        // Une ligne de plus que la page indique qu'une suite existe
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+        let next_cursor = if has_more { rows.last().map(|row| row.id) } else { None };
+
+        let mut sequences = Vec::with_capacity(rows.len());
+        for row in rows {
+            sequences.push((row.id, db_sequence_to_dna_sequence(row)?));
+        }
+
+        Ok(SequencePage { sequences, next_cursor })
+    }
+
     /// Récupère une séquence par ID
     #[instrument(skip(self))]
     pub async fn get_sequence(&self, id: i64) -> Result<Option<DnaSequence>> {
-        let query = "SELECT * FROM sequences WHERE id = $1";
+        let postgres = matches!(&*self.pool, DatabasePool::Postgres(_));
+        let query = format!("SELECT {} FROM sequences s WHERE id = $1", sequence_columns(postgres));
+        let query = query.as_str();
 
         let row = match &*self.pool {
             DatabasePool::Sqlite(pool) => {
@@ -93,10 +241,11 @@ impl SequenceRepository {
                     .fetch_optional(pool)
                     .await?
             }
+            DatabasePool::Embedded(store) => store.blocking(move |store| store.sequence(id)).await?,
         };
 
         match row {
-            Some(db_seq) => Ok(Some(self.db_sequence_to_dna_sequence(db_seq)?)),
+            Some(db_seq) => Ok(Some(db_sequence_to_dna_sequence(db_seq)?)),
             None => Ok(None),
         }
     }
@@ -104,10 +253,15 @@ impl SequenceRepository {
     /// Recherche des séquences par métadonnées
     #[instrument(skip(self))]
     pub async fn search_sequences(&self, query_str: &str) -> Result<Vec<DnaSequence>> {
-        let search_query =
-            "SELECT * FROM sequences
-             WHERE metadata LIKE $1
-             ORDER BY created_at DESC";
+        let postgres = matches!(&*self.pool, DatabasePool::Postgres(_));
+        let search_query = format!(
+            "SELECT {} FROM sequences s
+             WHERE {} LIKE $1
+             ORDER BY created_at DESC",
+            sequence_columns(postgres),
+            if postgres { "s.metadata::text" } else { "s.metadata" }
+        );
+        let search_query = search_query.as_str();
 
         let rows = match &*self.pool {
             DatabasePool::Sqlite(pool) => {
@@ -122,11 +276,15 @@ impl SequenceRepository {
                     .fetch_all(pool)
                     .await?
             }
+            DatabasePool::Embedded(store) => {
+                let query_str = query_str.to_string();
+                store.blocking(move |store| store.search_sequences(&query_str)).await?
+            }
         };
 
         let mut sequences = Vec::new();
         for row in rows {
-            sequences.push(self.db_sequence_to_dna_sequence(row)?);
+            sequences.push(db_sequence_to_dna_sequence(row)?);
         }
         Ok(sequences)
     }
@@ -145,6 +303,7 @@ impl SequenceRepository {
                 let result = sqlx::query(query).bind(id).execute(pool).await?;
                 Ok(result.rows_affected() > 0)
             }
+            DatabasePool::Embedded(store) => store.blocking(move |store| store.delete_sequence(id)).await,
         }
     }
 
@@ -162,35 +321,367 @@ impl SequenceRepository {
                 let row = sqlx::query(query).fetch_one(pool).await?;
                 Ok(row.try_get("count")?)
             }
+            DatabasePool::Embedded(store) => store.blocking(EmbeddedStore::count_sequences).await,
+        }
+    }
+
+    /// Compacte les séquences stockées en clair avant la migration 0003
+    ///
+    /// Les lignes sont converties par lots de `batch_size`, chaque lot dans
+    /// une transaction. Retourne le nombre de séquences converties.
+    #[instrument(skip(self))]
+    pub async fn pack_legacy_sequences(&self, batch_size: i64) -> Result<u64> {
+        let select =
+            "SELECT id, sequence_data FROM sequences WHERE sequence_format = $1 ORDER BY id LIMIT $2";
+        let update =
+            "UPDATE sequences SET sequence_data = $1, sequence_format = $2 WHERE id = $3";
+
+        let mut converted = 0u64;
+        loop {
+            let rows: Vec<(i64, Vec<u8>)> = match &*self.pool {
+                DatabasePool::Sqlite(pool) => {
+                    sqlx::query_as(select)
+                        .bind(SEQUENCE_FORMAT_TEXT)
+                        .bind(batch_size.max(1))
+                        .fetch_all(pool)
+                        .await?
+                }
+                DatabasePool::Postgres(pool) => {
+                    sqlx::query_as(select)
+                        .bind(SEQUENCE_FORMAT_TEXT)
+                        .bind(batch_size.max(1))
+                        .fetch_all(pool)
+                        .await?
+                }
+                // La base intégrée n'écrit que des bases compactées
+                DatabasePool::Embedded(_) => Vec::new(),
+            };
+
+            if rows.is_empty() {
+                break;
+            }
+
+            let packed = rows
+                .iter()
+                .map(|(id, data)| {
+                    let bases = decode_sequence_data(data, SEQUENCE_FORMAT_TEXT)?;
+                    Ok((*id, PackedBases::from_bases(&bases).to_bytes()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            match &*self.pool {
+                DatabasePool::Sqlite(pool) => {
+                    let mut tx = pool.begin().await?;
+                    for (id, data) in &packed {
+                        sqlx::query(update)
+                            .bind(data)
+                            .bind(SEQUENCE_FORMAT_PACKED)
+                            .bind(id)
+                            .execute(&mut *tx)
+                            .await?;
+                    }
+                    tx.commit().await?;
+                }
+                DatabasePool::Postgres(pool) => {
+                    let mut tx = pool.begin().await?;
+                    for (id, data) in &packed {
+                        sqlx::query(update)
+                            .bind(data)
+                            .bind(SEQUENCE_FORMAT_PACKED)
+                            .bind(id)
+                            .execute(&mut *tx)
+                            .await?;
+                    }
+                    tx.commit().await?;
+                }
+                DatabasePool::Embedded(_) => {}
+            }
+
+            converted += packed.len() as u64;
+        }
+
+        info!("{} séquences compactées", converted);
+        Ok(converted)
+    }
+}
+
+/// Colonnes d'une séquence à insérer
+pub(crate) struct SequenceRow {
+    pub(crate) sequence_data: Vec<u8>,
+    pub(crate) metadata: String,
+    pub(crate) encoding_scheme: String,
+    pub(crate) original_file: String,
+    pub(crate) gc_ratio: f64,
+    pub(crate) length: i64,
+}
+
+impl SequenceRow {
+    /// Bases compactées, métadonnées JSON et colonnes de filtrage
+    pub(crate) fn new(sequence: &DnaSequence) -> Result<Self> {
+        let metadata = serde_json::to_string(&sequence.metadata)
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        Ok(Self {
+            sequence_data: PackedBases::from_bases(&sequence.bases).to_bytes(),
+            metadata,
+            encoding_scheme: sequence.metadata.encoding_scheme.clone(),
+            original_file: sequence.metadata.original_file.clone(),
+            gc_ratio: sequence.metadata.gc_ratio,
+            length: sequence.bases.len() as i64,
+        })
+    }
+}
+
+/// Filtres de listage des séquences
+///
+/// Les bornes sont inclusives; un critère absent n'est pas appliqué.
+#[derive(Debug, Clone, Default)]
+pub struct SequenceFilter {
+    pub encoding_scheme: Option<String>,
+    pub original_file: Option<String>,
+    pub gc_min: Option<f64>,
+    pub gc_max: Option<f64>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl SequenceFilter {
+    /// Filtre par schéma d'encodage
+    pub fn with_encoding_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.encoding_scheme = Some(scheme.into());
+        self
+    }
+
+    /// Filtre par fichier d'origine
+    pub fn with_original_file(mut self, file: impl Into<String>) -> Self {
+        self.original_file = Some(file.into());
+        self
+    }
+
+    /// Filtre par ratio GC (0-1)
+    pub fn with_gc_range(mut self, min: f64, max: f64) -> Self {
+        self.gc_min = Some(min);
+        self.gc_max = Some(max);
+        self
+    }
+
+    /// Filtre par longueur en bases
+    pub fn with_length_range(mut self, min: usize, max: usize) -> Self {
+        self.min_length = Some(min);
+        self.max_length = Some(max);
+        self
+    }
+
+    /// Filtre par date de création
+    pub fn with_time_range(mut self, after: DateTime<Utc>, before: DateTime<Utc>) -> Self {
+        self.created_after = Some(after);
+        self.created_before = Some(before);
+        self
+    }
+
+    /// Évalue le filtre sur une ligne en mémoire (base intégrée)
+    ///
+    /// Même sémantique que `where_clause` côté SQLite: les dates RFC 3339
+    /// sont comparées en tant que chaînes.
+    pub(crate) fn matches(
+        &self,
+        encoding_scheme: &str,
+        original_file: &str,
+        gc_ratio: f64,
+        length: i64,
+        created_at: &str,
+    ) -> bool {
+        let rejected = self.encoding_scheme.as_deref().is_some_and(|scheme| scheme != encoding_scheme)
+            || self.original_file.as_deref().is_some_and(|file| file != original_file)
+            || self.gc_min.is_some_and(|min| gc_ratio < min)
+            || self.gc_max.is_some_and(|max| gc_ratio > max)
+            || self.min_length.is_some_and(|min| length < min as i64)
+            || self.max_length.is_some_and(|max| length > max as i64)
+            || self.created_after.is_some_and(|after| created_at < after.to_rfc3339().as_str())
+            || self.created_before.is_some_and(|before| created_at > before.to_rfc3339().as_str());
+
+        !rejected
+    }
+
+    /// Clause WHERE paramétrée (`$1`, `$2`...) et valeurs à lier dans l'ordre
+    fn where_clause(&self, cursor: Option<i64>, postgres: bool) -> (String, Vec<FilterValue>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        let mut push = |condition: &str, value: FilterValue| {
+            values.push(value);
+            conditions.push(condition.replace('?', &format!("${}", values.len())));
+        };
+
+        if let Some(cursor) = cursor {
+            push("id > ?", FilterValue::Int(cursor));
+        }
+        if let Some(scheme) = &self.encoding_scheme {
+            push("encoding_scheme = ?", FilterValue::Text(scheme.clone()));
+        }
+        if let Some(file) = &self.original_file {
+            push("original_file = ?", FilterValue::Text(file.clone()));
+        }
+        if let Some(min) = self.gc_min {
+            push("gc_ratio >= ?", FilterValue::Float(min));
+        }
+        if let Some(max) = self.gc_max {
+            push("gc_ratio <= ?", FilterValue::Float(max));
+        }
+        if let Some(min) = self.min_length {
+            push("sequence_length >= ?", FilterValue::Int(min as i64));
+        }
+        if let Some(max) = self.max_length {
+            push("sequence_length <= ?", FilterValue::Int(max as i64));
+        }
+
+        // SQLite compare les dates RFC 3339 en tant que chaînes
+        let timestamp = if postgres { "?::timestamptz" } else { "?" };
+        if let Some(after) = self.created_after {
+            push(&format!("created_at >= {}", timestamp), FilterValue::Text(after.to_rfc3339()));
+        }
+        if let Some(before) = self.created_before {
+            push(&format!("created_at <= {}", timestamp), FilterValue::Text(before.to_rfc3339()));
+        }
+
+        if conditions.is_empty() {
+            ("1 = 1".to_string(), values)
+        } else {
+            (conditions.join(" AND "), values)
         }
     }
+}
 
-    /// Convertit DbSequence en DnaSequence
-    fn db_sequence_to_dna_sequence(&self, db_seq: DbSequence) -> Result<DnaSequence> {
-        use adn_core::SequenceId;
+/// Valeur liée à une clause de filtre
+#[derive(Debug, Clone)]
+enum FilterValue {
+    Text(String),
+    Float(f64),
+    Int(i64),
+}
+
+/// Page de séquences et curseur de la page suivante
+#[derive(Debug)]
+pub struct SequencePage {
+    /// Séquences de la page avec leur ID en base
+    pub sequences: Vec<(i64, DnaSequence)>,
+    /// Curseur à passer à `list_sequences` pour la page suivante (None: dernière page)
+    pub next_cursor: Option<i64>,
+}
 
-        // Parse les bases
-        let bases: Vec<IupacBase> = db_seq.sequence_data
-            .chars()
-            .map(|c| {
-                IupacBase::from_char(c)
+/// Décode la colonne `sequence_data` selon son format
+fn decode_sequence_data(data: &[u8], format: i16) -> Result<Vec<IupacBase>> {
+    match format {
+        SEQUENCE_FORMAT_PACKED => PackedBases::from_bytes(data)
+            .map(|packed| packed.to_bases())
+            .map_err(|e| StorageError::DatabaseError(format!("Invalid packed bases: {}", e))),
+        SEQUENCE_FORMAT_TEXT => data
+            .iter()
+            .map(|&c| {
+                IupacBase::from_char(c as char)
                     .map_err(|e| StorageError::DatabaseError(format!("Invalid base: {}", e)))
             })
             .collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(|e| StorageError::DatabaseError(format!("Invalid base sequence: {}", e)))?;
+            .map_err(|e| StorageError::DatabaseError(format!("Invalid base sequence: {}", e))),
+        other => Err(StorageError::DatabaseError(format!(
+            "Unknown sequence format: {}",
+            other
+        ))),
+    }
+}
 
-        // Parse les métadonnées
-        let metadata = serde_json::from_str(&db_seq.metadata)
-            .map_err(|e| StorageError::DatabaseError(format!("Failed to parse metadata: {}", e)))?;
+/// Convertit DbSequence en DnaSequence
+pub(crate) fn db_sequence_to_dna_sequence(db_seq: DbSequence) -> Result<DnaSequence> {
+    use adn_core::SequenceId;
 
-        // Générer un ID de séquence depuis l'UUID string
-        let uuid = Uuid::parse_str(&db_seq.uuid)
-            .map_err(|e| StorageError::DatabaseError(format!("Invalid UUID: {}", e)))?;
+    // Parse les bases
+    let bases = decode_sequence_data(&db_seq.sequence_data, db_seq.sequence_format)?;
 
-        Ok(DnaSequence {
-            id: SequenceId::from_uuid(uuid),
-            bases,
-            metadata,
-        })
+    // Parse les métadonnées
+    let metadata = serde_json::from_str(&db_seq.metadata)
+        .map_err(|e| StorageError::DatabaseError(format!("Failed to parse metadata: {}", e)))?;
+
+    // Générer un ID de séquence depuis l'UUID string
+    let uuid = Uuid::parse_str(&db_seq.uuid)
+        .map_err(|e| StorageError::DatabaseError(format!("Invalid UUID: {}", e)))?;
+
+    Ok(DnaSequence {
+        id: SequenceId::from_uuid(uuid),
+        bases,
+        metadata,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DatabaseConfig, DatabaseManager};
+    use std::sync::Arc;
+
+    async fn memory_repository() -> SequenceRepository {
+        let mut manager = DatabaseManager::new(DatabaseConfig::default());
+        manager.initialize().await.unwrap();
+        SequenceRepository::new(Arc::new(manager.pool().unwrap().clone()))
+    }
+
+    fn sequence(bases: &str, file: &str) -> DnaSequence {
+        DnaSequence::from_str(bases, file.to_string(), 0, bases.len(), 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_save_batch_and_paginate() {
+        let repo = memory_repository().await;
+        let batch: Vec<DnaSequence> = (0..5).map(|_| sequence("ACGTNACGT", "a.txt")).collect();
+
+        let ids = repo.save_batch(&batch).await.unwrap();
+        assert_eq!(ids.len(), 5);
+        assert_eq!(repo.count_sequences().await.unwrap(), 5);
+
+        let filter = SequenceFilter::default();
+        let first = repo.list_sequences(&filter, None, 2).await.unwrap();
+        assert_eq!(first.sequences.len(), 2);
+        assert_eq!(first.sequences[0].1.bases, batch[0].bases);
+
+        let second = repo.list_sequences(&filter, first.next_cursor, 2).await.unwrap();
+        let third = repo.list_sequences(&filter, second.next_cursor, 2).await.unwrap();
+        assert_eq!(third.sequences.len(), 1);
+        assert!(third.next_cursor.is_none());
+
+        let listed: Vec<i64> = [first, second, third]
+            .into_iter()
+            .flat_map(|page| page.sequences.into_iter().map(|(id, _)| id))
+            .collect();
+        assert_eq!(listed, ids);
+    }
+
+    #[tokio::test]
+    async fn test_list_filters() {
+        let repo = memory_repository().await;
+        repo.save_batch(&[
+            sequence("ACGTACGT", "a.txt"),
+            sequence("GGGCCCGGGCCC", "a.txt"),
+            sequence("ATATATAT", "b.txt"),
+        ])
+        .await
+        .unwrap();
+
+        let by_file = SequenceFilter::default().with_original_file("a.txt");
+        assert_eq!(repo.list_sequences(&by_file, None, 10).await.unwrap().sequences.len(), 2);
+
+        let high_gc = by_file.clone().with_gc_range(0.9, 1.0);
+        let page = repo.list_sequences(&high_gc, None, 10).await.unwrap();
+        assert_eq!(page.sequences.len(), 1);
+        assert_eq!(page.sequences[0].1.bases.len(), 12);
+
+        let short = SequenceFilter::default().with_length_range(0, 8);
+        assert_eq!(repo.list_sequences(&short, None, 10).await.unwrap().sequences.len(), 2);
+
+        let scheme = SequenceFilter::default().with_encoding_scheme("unknown");
+        assert_eq!(repo.list_sequences(&scheme, None, 10).await.unwrap().sequences.len(), 3);
+
+        let future = Utc::now() + chrono::Duration::hours(1);
+        let later = SequenceFilter::default().with_time_range(future, future + chrono::Duration::hours(1));
+        assert!(repo.list_sequences(&later, None, 10).await.unwrap().sequences.is_empty());
     }
 }
@@ -0,0 +1,73 @@
+//! Dépôts de séquences et d'archives sur PostgreSQL
+//!
+//! Nécessite un serveur PostgreSQL et une base vide, désignée par
+//! `ADN_TEST_POSTGRES_URL`:
+//!
+//! ```text
+//! ADN_TEST_POSTGRES_URL=postgres://postgres@localhost/adn_test \
+//!     cargo test -p adn-storage --test postgres -- --ignored
+//! ```
+
+use adn_core::{DnaSequence, Encoder, EncoderConfig};
+use adn_storage::{
+    ArchiveRepository, DatabaseConfig, DatabaseManager, DatabasePool, DatabaseType, SequenceFilter,
+    SequenceRepository,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+async fn postgres_pool() -> Arc<DatabasePool> {
+    let url = std::env::var("ADN_TEST_POSTGRES_URL").expect("ADN_TEST_POSTGRES_URL non défini");
+    let mut manager = DatabaseManager::new(DatabaseConfig {
+        db_type: DatabaseType::Postgres,
+        connection_string: url,
+        max_connections: 2,
+    });
+    manager.initialize().await.unwrap();
+
+    let pool = manager.pool().unwrap().clone();
+    pool.execute("TRUNCATE archives, sequences RESTART IDENTITY CASCADE").await.unwrap();
+    Arc::new(pool)
+}
+
+#[tokio::test]
+#[ignore = "nécessite ADN_TEST_POSTGRES_URL"]
+async fn test_postgres_sequences_and_archives() {
+    let pool = postgres_pool().await;
+
+    // Séquences: insertion, lecture, filtres, recherche, suppression
+    let repo = SequenceRepository::new(pool.clone());
+    let sequence = DnaSequence::from_str("ACGTACGTAC", "notes.txt".to_string(), 0, 10, 3).unwrap();
+    let id = repo.save_sequence(&sequence).await.unwrap();
+    let ids = repo.save_batch(&[sequence.clone(), sequence.clone()]).await.unwrap();
+    assert_eq!(ids, vec![id + 1, id + 2]);
+
+    let stored = repo.get_sequence(id).await.unwrap().unwrap();
+    assert_eq!(stored.bases, sequence.bases);
+    assert_eq!(stored.metadata.original_file, "notes.txt");
+
+    let now = Utc::now();
+    let filter = SequenceFilter::default()
+        .with_original_file("notes.txt")
+        .with_time_range(now - Duration::hours(1), now + Duration::hours(1));
+    let page = repo.list_sequences(&filter, Some(id), 10).await.unwrap();
+    assert_eq!(page.sequences.iter().map(|(id, _)| *id).collect::<Vec<_>>(), ids);
+
+    assert_eq!(repo.search_sequences("}").await.unwrap().len(), 3);
+    assert_eq!(repo.pack_legacy_sequences(10).await.unwrap(), 0);
+    assert!(repo.delete_sequence(id).await.unwrap());
+    assert_eq!(repo.count_sequences().await.unwrap(), 2);
+
+    // Archives: appartenance ordonnée et décodage
+    let archives = ArchiveRepository::new(pool);
+    let data = b"Archive ADN stockee dans PostgreSQL".to_vec();
+    let config = EncoderConfig::default();
+    let pool_sequences = Encoder::new(config.clone()).unwrap().encode(&data).unwrap();
+
+    let archive_id = archives.save_archive("pg.txt", &data, &config, &pool_sequences).await.unwrap();
+    assert_eq!(archives.get_archive_sequences(archive_id).await.unwrap().len(), pool_sequences.len());
+    assert_eq!(archives.decode_archive(archive_id).await.unwrap(), data);
+
+    assert!(archives.delete_archive(archive_id).await.unwrap());
+    assert_eq!(repo.count_sequences().await.unwrap(), 2);
+}
@@ -127,6 +127,7 @@ async fn main() -> std::io::Result<()> {
             .service(routes::download_result)
             .service(routes::download_fasta)
             .service(routes::health_check)
+            .service(routes::api_codecs)
             .service(Files::new("/static", config.server.static_files.clone())
                 .show_files_listing())
     })
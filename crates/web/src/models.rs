@@ -3,8 +3,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use adn_core::{EncoderConfig, DecoderConfig};
-use adn_core::codec::EncoderType;
+use adn_core::{Encoder, EncoderConfig, DecoderConfig};
+use adn_core::codec::{codec_names, lookup_codec};
 use chrono::{DateTime, Utc};
 
 /// Message de progression pour les mises à jour temps réel
@@ -106,17 +106,47 @@ impl Default for EncodeRequest {
     }
 }
 
-impl From<EncodeRequest> for EncoderConfig {
-    fn from(req: EncodeRequest) -> Self {
+impl EncodeRequest {
+    /// Renseigne un paramètre depuis un champ texte du formulaire d'encodage
+    ///
+    /// Les champs inconnus sont ignorés; une valeur illisible est rejetée.
+    pub fn set_field(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        let invalid = |e: &dyn std::fmt::Display| format!("Valeur invalide pour {}: {} ({})", name, value, e);
+
+        match name {
+            "algorithm" => self.algorithm = Some(value.to_string()),
+            "redundancy" => self.redundancy = Some(value.parse().map_err(|e| invalid(&e))?),
+            "compression" => self.compression = Some(value.parse().map_err(|e| invalid(&e))?),
+            "chunk_size" => self.chunk_size = Some(value.parse().map_err(|e| invalid(&e))?),
+            "save_to_db" => self.save_to_db = Some(value.parse().map_err(|e| invalid(&e))?),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Construit l'encodeur d'une requête avec le codec du registre qu'elle désigne
+///
+/// Un nom d'algorithme inconnu du registre est rejeté.
+impl TryFrom<EncodeRequest> for Encoder {
+    type Error = String;
+
+    fn try_from(req: EncodeRequest) -> Result<Self, Self::Error> {
+        let codec = req
+            .algorithm
+            .as_deref()
+            .map(|name| {
+                lookup_codec(name).ok_or_else(|| {
+                    format!("Algorithme inconnu: {} (disponibles: {})", name, codec_names().join(", "))
+                })
+            })
+            .transpose()?;
+
         let mut config = EncoderConfig::default();
 
-        if let Some(algorithm) = req.algorithm {
-            config.encoder_type = match algorithm.to_lowercase().as_str() {
-                "goldman" => EncoderType::Goldman,
-                "adaptive" => EncoderType::Adaptive,
-                "base3" => EncoderType::Base3,
-                _ => EncoderType::Fountain,
-            };
+        if let Some(encoder_type) = codec.as_ref().and_then(|codec| codec.encoder_type()) {
+            config.encoder_type = encoder_type;
         }
 
         if let Some(redundancy) = req.redundancy {
@@ -131,7 +161,13 @@ impl From<EncodeRequest> for EncoderConfig {
             config.chunk_size = chunk_size;
         }
 
-        config
+        let encoder = Encoder::new(config)
+            .map_err(|e| format!("Erreur d'initialisation de l'encodeur: {}", e))?;
+
+        Ok(match codec {
+            Some(codec) => encoder.with_codec(codec),
+            None => encoder,
+        })
     }
 }
 
@@ -197,4 +233,47 @@ impl ErrorResponse {
             code,
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_request_rejects_unknown_algorithm() {
+        let req = EncodeRequest {
+            algorithm: Some("fontaine-magique".to_string()),
+            ..Default::default()
+        };
+
+        let err = Encoder::try_from(req).err().unwrap();
+        assert!(err.contains("fontaine-magique"));
+    }
+
+    #[test]
+    fn test_encode_request_reads_form_fields() {
+        let mut req = EncodeRequest::default();
+        req.set_field("algorithm", "goldman").unwrap();
+        req.set_field("redundancy", "2.5").unwrap();
+        req.set_field("compression", "false").unwrap();
+        req.set_field("file_name", "ignoré").unwrap();
+
+        assert_eq!(req.algorithm.as_deref(), Some("goldman"));
+        assert_eq!(req.redundancy, Some(2.5));
+        assert_eq!(req.compression, Some(false));
+        assert!(req.set_field("redundancy", "beaucoup").is_err());
+    }
+
+    #[test]
+    fn test_encode_request_uses_registry_codec() {
+        let req = EncodeRequest {
+            algorithm: Some("ez2017".to_string()),
+            chunk_size: Some(25),
+            ..Default::default()
+        };
+
+        let pool = Encoder::try_from(req).unwrap().encode(b"codec du registre").unwrap();
+        assert!(pool
+            .iter()
+            .any(|seq| seq.metadata.encoding_scheme == "erlich_zielinski_2017"));
+    }
+}
@@ -7,7 +7,7 @@ use tracing::{info, error};
 use uuid::Uuid;
 use chrono::Utc;
 
-use crate::models::{AppState, EncodeResponse, DecodeResponse, JobStatus, ErrorResponse};
+use crate::models::{AppState, EncodeRequest, EncodeResponse, DecodeResponse, JobStatus, ErrorResponse};
 
 pub mod streaming_routes;
 pub use streaming_routes::api_encode_stream;
@@ -76,23 +76,10 @@ pub async fn api_encode(
 ) -> impl Responder {
     info!("Nouvelle requête d'encodage");
 
-    let job_id = Uuid::new_v4().to_string();
-
-    // Créer un nouveau job
-    let mut jobs = data.jobs.write().await;
-    jobs.insert(job_id.clone(), crate::models::JobState::new(job_id.clone()));
-
-    // Mettre à jour le statut
-    if let Some(job) = jobs.get_mut(&job_id) {
-        job.status = JobStatus::Processing;
-        job.updated_at = Utc::now();
-    }
-
-    drop(jobs); // Libérer le verrou
-
     // Traiter le fichier uploadé AVANT de spawner (Multipart n'est pas Send)
     let mut file_data = Vec::new();
     let mut _file_name = None;
+    let mut request = EncodeRequest::default();
 
     while let Some(item) = payload.next().await {
         let field = match item {
@@ -124,8 +111,29 @@ pub async fn api_encode(
                     };
                     file_data.extend_from_slice(&data);
                 }
+                continue;
             }
         }
+
+        // Champ texte du formulaire: paramètre de l'encodage
+        let name = field.name().unwrap_or_default().to_string();
+        let mut value = Vec::new();
+        let mut field = field;
+        while let Some(chunk_result) = field.next().await {
+            match chunk_result {
+                Ok(d) => value.extend_from_slice(&d),
+                Err(e) => {
+                    error!("Erreur de champ {}: {}", name, e);
+                    return HttpResponse::BadRequest().json(ErrorResponse::new(
+                        format!("Erreur de champ {}: {}", name, e),
+                        400
+                    ));
+                }
+            }
+        }
+        if let Err(e) = request.set_field(&name, &String::from_utf8_lossy(&value)) {
+            return HttpResponse::BadRequest().json(ErrorResponse::new(e, 400));
+        }
     }
 
     if file_data.is_empty() {
@@ -135,6 +143,29 @@ pub async fn api_encode(
         ));
     }
 
+    // Encodeur demandé: un algorithme inconnu est rejeté avant de créer le job
+    let encoder = match adn_core::Encoder::try_from(request) {
+        Ok(encoder) => encoder,
+        Err(e) => {
+            error!("Requête d'encodage invalide: {}", e);
+            return HttpResponse::BadRequest().json(ErrorResponse::new(e, 400));
+        }
+    };
+
+    let job_id = Uuid::new_v4().to_string();
+
+    // Créer un nouveau job
+    let mut jobs = data.jobs.write().await;
+    jobs.insert(job_id.clone(), crate::models::JobState::new(job_id.clone()));
+
+    // Mettre à jour le statut
+    if let Some(job) = jobs.get_mut(&job_id) {
+        job.status = JobStatus::Processing;
+        job.updated_at = Utc::now();
+    }
+
+    drop(jobs); // Libérer le verrou
+
     // Traiter l'encodage en arrière-plan
     let data_clone = data.clone();
     let job_id_clone = job_id.clone();
@@ -142,6 +173,7 @@ pub async fn api_encode(
 
     tokio::spawn(async move {
         let result = process_encode_data_with_progress(
+            &encoder,
             &file_data,
             &data_clone,
             job_id_clone.clone(),
@@ -179,6 +211,7 @@ pub async fn api_encode(
 
 /// Traite les données d'encodage avec mises à jour de progression
 async fn process_encode_data_with_progress(
+    encoder: &adn_core::Encoder,
     file_data: &[u8],
     data: &web::Data<AppState>,
     job_id: String,
@@ -194,8 +227,6 @@ async fn process_encode_data_with_progress(
 
     // Encoder les données
     let start_time = std::time::Instant::now();
-    let encoder = adn_core::Encoder::new(adn_core::EncoderConfig::default())
-        .map_err(|e| format!("Erreur d'initialisation de l'encodeur: {}", e))?;
 
     // Pour les fichiers volumineux, simuler une progression
     // (l'encodeur actuel est synchrone et ne fournit pas de callbacks)
@@ -670,4 +701,17 @@ pub async fn health_check() -> impl Responder {
 
     }))
 
-}
\ No newline at end of file
+}
+
+
+/// Route listant les codecs du registre et leurs paramètres
+
+#[get("/api/codecs")]
+
+pub async fn api_codecs() -> impl Responder {
+
+    let descriptors = adn_core::codec::CodecRegistry::global().read().descriptors();
+
+    HttpResponse::Ok().json(descriptors)
+
+}
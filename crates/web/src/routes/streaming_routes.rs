@@ -5,11 +5,14 @@ use tracing::info;
 use uuid::Uuid;
 use chrono::Utc;
 use futures::{StreamExt, TryStreamExt};
-use std::io::{Error, ErrorKind};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind};
+use tokio::io::AsyncWriteExt;
 
 use crate::models::{AppState, EncodeResponse, JobStatus, ErrorResponse};
 use adn_core::codec::encoder::EncoderType;
 use adn_core::codec::encoder::CompressionType;
+use adn_core::codec::{FastaReader, FastaSink, FastaWriter, StreamSummary};
 
 /// Route pour l'API d'encodage en streaming (pour les gros fichiers)
 #[post("/api/encode/stream")]
@@ -73,29 +76,66 @@ pub async fn api_encode_stream(
     response
 }
 
+/// Taille des segments encodés indépendamment (bornant la mémoire utilisée)
+const STREAM_SEGMENT_SIZE: usize = 1 << 20;
+
+/// Destination FASTA qui accumule les statistiques d'encodage au fil de l'eau
+struct StatsSink {
+    writer: FastaWriter<BufWriter<File>>,
+    sequence_count: usize,
+    total_length: usize,
+    gc_count: usize,
+}
+
+impl FastaSink for StatsSink {
+    fn write_sequence(&mut self, sequence: &adn_core::DnaSequence) -> adn_core::Result<()> {
+        self.sequence_count += 1;
+        self.total_length += sequence.bases.len();
+        self.gc_count += sequence.bases.iter().filter(|b| b.is_gc()).count();
+        self.writer.write_sequence(sequence)
+    }
+
+    fn flush(&mut self) -> adn_core::Result<()> {
+        self.writer.flush()
+    }
+}
+
 /// Traite les données d'encodage en streaming
+///
+/// Le corps de la requête est copié sur disque par morceaux, puis encodé par
+/// segments avec `Encoder::encode_stream`: ni le fichier ni le pool complet
+/// ne sont jamais chargés en mémoire.
 async fn process_streaming_encode(
     payload: web::Payload,
     data: &web::Data<AppState>,
     job_id: String,
 ) -> Result<crate::models::EncodingStats, String> {
     let start_time = std::time::Instant::now();
-    
-    // Lire le streaming en chunks
-    let mut file_data = Vec::new();
+
+    let upload_dir = std::path::Path::new("uploads");
+    tokio::fs::create_dir_all(upload_dir)
+        .await
+        .map_err(|e| format!("Erreur de création du dossier: {}", e))?;
+    let upload_path = upload_dir.join(format!("{}.upload", job_id));
+    let fasta_path = upload_dir.join(format!("{}.fasta", job_id));
+
+    // Copier le stream sur disque, morceau par morceau
+    let mut upload = tokio::fs::File::create(&upload_path)
+        .await
+        .map_err(|e| format!("Erreur de création du fichier temporaire: {}", e))?;
     let mut bytes_received = 0usize;
-    
-    // Utiliser un buffer pour accumuler les données
+
     let mut stream = payload
         .map_err(|e| Error::new(ErrorKind::Other, format!("Erreur de streaming: {}", e)));
-    
-    // Lire le stream en chunks
+
     while let Some(chunk_result) = stream.next().await {
         match chunk_result {
             Ok(chunk) => {
-                file_data.extend_from_slice(&chunk);
+                upload.write_all(&chunk)
+                    .await
+                    .map_err(|e| format!("Erreur d'écriture du fichier temporaire: {}", e))?;
                 bytes_received += chunk.len();
-                
+
                 // Envoyer la progression toutes les 100KB
                 if bytes_received % 102400 == 0 {
                     if let Some(ref tx) = data.progress_tx {
@@ -108,19 +148,22 @@ async fn process_streaming_encode(
                 }
             }
             Err(e) => {
-                let error_msg = format!("Erreur de lecture du stream: {}", e);
-                return Err(error_msg);
+                let _ = tokio::fs::remove_file(&upload_path).await;
+                return Err(format!("Erreur de lecture du stream: {}", e));
             }
         }
     }
-    
-    if file_data.is_empty() {
+
+    upload.flush()
+        .await
+        .map_err(|e| format!("Erreur d'écriture du fichier temporaire: {}", e))?;
+    drop(upload);
+
+    if bytes_received == 0 {
+        let _ = tokio::fs::remove_file(&upload_path).await;
         return Err("Aucune donnée reçue".to_string());
     }
-    
-    // Traiter l'encodage
-    let file_size = file_data.len();
-    
+
     // Configurer l'encodeur avec des contraintes appropriées pour le streaming
     let config = adn_core::EncoderConfig {
         encoder_type: EncoderType::Fountain,
@@ -139,48 +182,71 @@ async fn process_streaming_encode(
                 adn_core::IupacBase::G,
                 adn_core::IupacBase::T,
             ],
+            ..Default::default()
         },
     };
-    
+
     let encoder = adn_core::Encoder::new(config)
-        .map_err(|e| format!("Erreur de création de l'encodeur: {}", e))?;
-    
-    let sequences = encoder.encode(&file_data)
-        .map_err(|e| format!("Erreur d'encodage: {}", e))?;
-    
+        .map_err(|e| format!("Erreur de création de l'encodeur: {}", e))?
+        .with_segment_size(STREAM_SEGMENT_SIZE);
+
+    // Encodage par segments dans un thread bloquant, directement vers le FASTA
+    let input_path = upload_path.clone();
+    let output_path = fasta_path.clone();
+    let encoded = web::block(move || -> Result<(StreamSummary, StatsSink), String> {
+        let input = File::open(&input_path)
+            .map_err(|e| format!("Erreur de lecture du fichier temporaire: {}", e))?;
+        let output = File::create(&output_path)
+            .map_err(|e| format!("Erreur de sauvegarde FASTA: {}", e))?;
+
+        let mut sink = StatsSink {
+            writer: FastaWriter::new(BufWriter::new(output)),
+            sequence_count: 0,
+            total_length: 0,
+            gc_count: 0,
+        };
+        let summary = encoder.encode_stream(BufReader::new(input), &mut sink)
+            .map_err(|e| format!("Erreur d'encodage: {}", e))?;
+
+        Ok((summary, sink))
+    })
+    .await;
+
+    let _ = tokio::fs::remove_file(&upload_path).await;
+    let (summary, sink) = encoded
+        .map_err(|e| format!("Erreur d'encodage: {}", e))??;
+
     let encoding_time = start_time.elapsed().as_millis() as u64;
-    
+
     // Calculer les statistiques
-    let total_length: usize = sequences.iter().map(|s| s.bases.len()).sum();
-    let avg_length = total_length as f64 / sequences.len() as f64;
-
-    let gc_count: usize = sequences.iter()
-        .flat_map(|s| s.bases.iter())
-        .filter(|b| b.is_gc())
-        .count();
-
-    let gc_ratio = gc_count as f64 / total_length as f64;
-    let bits_per_base = (file_data.len() * 8) as f64 / total_length as f64;
-    let compression_ratio = file_data.len() as f64 / total_length as f64;
-    
-    // Sauvegarder le fichier FASTA
-    crate::routes::save_fasta_file(&sequences, &job_id).await
-        .map_err(|e| format!("Erreur de sauvegarde FASTA: {}", e))?;
-    
-    // Sauvegarder dans la base de données si activé
+    let file_size = summary.bytes as usize;
+    let total_length = sink.total_length;
+    let avg_length = total_length as f64 / sink.sequence_count as f64;
+    let gc_ratio = sink.gc_count as f64 / total_length as f64;
+    let bits_per_base = (file_size * 8) as f64 / total_length as f64;
+    let compression_ratio = file_size as f64 / total_length as f64;
+
+    // Sauvegarder dans la base de données si activé, en relisant le FASTA séquence par séquence
     if let Some(db) = &data.database {
         let pool = db.pool().unwrap();
         let repo = adn_storage::SequenceRepository::new(std::sync::Arc::new(pool.clone()));
 
-        for seq in &sequences {
-            if let Err(e) = repo.save_sequence(seq).await {
-                tracing::error!("Erreur de sauvegarde dans la base de données: {}", e);
+        let fasta = File::open(&fasta_path)
+            .map_err(|e| format!("Erreur de lecture FASTA: {}", e))?;
+        for seq in FastaReader::new(BufReader::new(fasta)) {
+            match seq {
+                Ok(seq) => {
+                    if let Err(e) = repo.save_sequence(&seq).await {
+                        tracing::error!("Erreur de sauvegarde dans la base de données: {}", e);
+                    }
+                }
+                Err(e) => tracing::error!("Erreur de relecture FASTA: {}", e),
             }
         }
     }
-    
+
     Ok(crate::models::EncodingStats {
-        sequence_count: sequences.len(),
+        sequence_count: sink.sequence_count,
         avg_length,
         gc_ratio,
         bits_per_base,
@@ -189,4 +255,4 @@ async fn process_streaming_encode(
         compression_ratio,
         encoding_time_ms: encoding_time,
     })
-}
\ No newline at end of file
+}